@@ -0,0 +1,122 @@
+//! An example program that locks its own SOL in SolLock via CPI
+#![deny(missing_docs)]
+#![forbid(unsafe_code)]
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::UnixTimestamp,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// The seed of the vault PDA holding the program's SOL
+pub const VAULT_SEED: &[u8] = b"vault";
+
+/// An example program instruction
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+pub enum ExampleInstruction {
+    /// Lock lamports from the vault in a new SolLock account owned by the vault
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Vault account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[]` System program account
+    ///   3. `[]` SolLock Config account
+    ///   4. `[WRITE]` SolLock Stats account
+    ///   5. `[]` SolLock program account
+    Lock {
+        /// The index of the new SolLock account
+        acc_index: u64,
+        /// The number of lamports to lock
+        lamports: u64,
+        /// The time the lamports should be locked until
+        deadline: UnixTimestamp,
+    },
+
+    /// Unlock a SolLock account owned by the vault back into the vault
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Vault account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[WRITE]` SolLock Treasury account
+    ///   3. `[WRITE]` SolLock Stats account
+    ///   4. `[]` SolLock program account
+    Unlock {
+        /// The index of the SolLock account
+        acc_index: u64,
+    },
+}
+
+/// Derive the address and bump seed of the vault
+pub fn get_vault(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_SEED], program_id)
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// Instruction processor
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = ExampleInstruction::try_from_slice(instruction_data)?;
+
+    let account_info_iter = &mut accounts.iter();
+    let vault_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let (vault_key, vault_bump_seed) = get_vault(program_id);
+    if vault_key != *vault_info.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    match instruction {
+        ExampleInstruction::Lock {
+            acc_index,
+            lamports,
+            deadline,
+        } => {
+            msg!("Example::Lock");
+
+            let system_account_info = next_account_info(account_info_iter)?;
+            let config_info = next_account_info(account_info_iter)?;
+            let stats_info = next_account_info(account_info_iter)?;
+            let sol_lock_program_info = next_account_info(account_info_iter)?;
+
+            sol_lock::cpi::create_and_lock(
+                sol_lock_program_info.clone(),
+                vault_info.clone(),
+                sol_lock_account_info.clone(),
+                system_account_info.clone(),
+                config_info.clone(),
+                stats_info.clone(),
+                acc_index,
+                lamports,
+                deadline,
+                &[&[VAULT_SEED, &[vault_bump_seed]]],
+            )
+        }
+        ExampleInstruction::Unlock { acc_index } => {
+            msg!("Example::Unlock");
+
+            let treasury_info = next_account_info(account_info_iter)?;
+            let stats_info = next_account_info(account_info_iter)?;
+            let sol_lock_program_info = next_account_info(account_info_iter)?;
+
+            sol_lock::cpi::unlock(
+                sol_lock_program_info.clone(),
+                vault_info.clone(),
+                sol_lock_account_info.clone(),
+                treasury_info.clone(),
+                stats_info.clone(),
+                acc_index,
+                &[&[VAULT_SEED, &[vault_bump_seed]]],
+            )
+        }
+    }
+}
@@ -0,0 +1,31 @@
+#!/usr/bin/env bash
+# Check the crate with the feature combinations downstream crates depend on,
+# then run the tests with and without the allowlist
+set -euo pipefail
+cd "$(dirname "$0")"
+
+feature_sets=(
+  ""
+  "no-entrypoint"
+  "cpi"
+  "client"
+  "serde"
+  "test-utils"
+  "allowlist"
+  "minimal-logs"
+  "cpi,allowlist"
+  "client,allowlist"
+  "cpi,serde,test-utils"
+)
+
+for features in "${feature_sets[@]}"; do
+  echo "cargo check --no-default-features --features '${features}'"
+  cargo check --lib --no-default-features --features "${features}"
+done
+
+if command -v cargo-hack > /dev/null; then
+  cargo hack check --lib --feature-powerset --exclude-features test-bpf
+fi
+
+cargo test --lib
+cargo test --lib --features allowlist
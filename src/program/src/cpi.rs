@@ -0,0 +1,70 @@
+//! Helpers for programs invoking SolLock instructions
+
+use solana_program::{
+    account_info::AccountInfo, clock::UnixTimestamp, entrypoint::ProgramResult,
+    program::invoke_signed,
+};
+
+use crate::instruction;
+
+/// Create a SolLock account owned by `owner` and lock lamports in it
+///
+/// `signer_seeds` are the seeds of `owner` if it is a program address.
+/// With the allowlist feature, `allowlist_entry` is the AllowlistEntry account of `owner`.
+#[allow(clippy::too_many_arguments)]
+pub fn create_and_lock<'a>(
+    sol_lock_program: AccountInfo<'a>,
+    owner: AccountInfo<'a>,
+    sol_lock_account: AccountInfo<'a>,
+    system_program: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    stats: AccountInfo<'a>,
+    #[cfg(feature = "allowlist")] allowlist_entry: AccountInfo<'a>,
+    acc_index: u64,
+    lamports: u64,
+    deadline: UnixTimestamp,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_signed(
+        &instruction::create_and_lock(
+            sol_lock_program.key,
+            owner.key,
+            acc_index,
+            lamports,
+            deadline,
+            None,
+            "",
+            None,
+        ),
+        &[
+            owner,
+            sol_lock_account,
+            system_program,
+            config,
+            stats,
+            #[cfg(feature = "allowlist")]
+            allowlist_entry,
+            sol_lock_program,
+        ],
+        signer_seeds,
+    )
+}
+
+/// Unlock a SolLock account owned by `owner`, crediting the lamports to `owner`
+///
+/// `signer_seeds` are the seeds of `owner` if it is a program address.
+pub fn unlock<'a>(
+    sol_lock_program: AccountInfo<'a>,
+    owner: AccountInfo<'a>,
+    sol_lock_account: AccountInfo<'a>,
+    treasury: AccountInfo<'a>,
+    stats: AccountInfo<'a>,
+    acc_index: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_signed(
+        &instruction::unlock(sol_lock_program.key, owner.key, acc_index, None, None, None),
+        &[owner, sol_lock_account, treasury, stats, sol_lock_program],
+        signer_seeds,
+    )
+}
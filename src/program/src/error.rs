@@ -34,6 +34,164 @@ pub enum SolLockError {
     ConflictingPayerInfo,
     #[error("ConflictingReceiverInfo")]
     ConflictingReceiverInfo,
+    #[error("InvalidStakeAccount")]
+    InvalidStakeAccount,
+    #[error("StakeNotDeactivated")]
+    StakeNotDeactivated,
+    #[error("AccountNotLocked")]
+    AccountNotLocked,
+    #[error("LockRequirementsNotMet")]
+    LockRequirementsNotMet,
+    #[error("PendingOwnerMismatch")]
+    PendingOwnerMismatch,
+    #[error("MissingUnlockAuthority")]
+    MissingUnlockAuthority,
+    #[error("ReceiverMismatch")]
+    ReceiverMismatch,
+    #[error("NonceMismatch")]
+    NonceMismatch,
+    #[error("CrankingDisabled")]
+    CrankingDisabled,
+    #[error("InvalidAutoExtendPeriod")]
+    InvalidAutoExtendPeriod,
+    #[error("InsufficientVestedFunds")]
+    InsufficientVestedFunds,
+    #[error("InvalidVestingSchedule")]
+    InvalidVestingSchedule,
+    #[error("DepositCapExceeded")]
+    DepositCapExceeded,
+    #[error("ConflictingSchedules")]
+    ConflictingSchedules,
+    #[error("InvalidPreimage")]
+    InvalidPreimage,
+    #[error("HashLockExpired")]
+    HashLockExpired,
+    #[error("EscrowExpired")]
+    EscrowExpired,
+    #[error("DisputeExpired")]
+    DisputeExpired,
+    #[error("DepositsClosed")]
+    DepositsClosed,
+    #[error("CrowdfundEnded")]
+    CrowdfundEnded,
+    #[error("RefundPeriodActive")]
+    RefundPeriodActive,
+    #[error("RefundUnavailable")]
+    RefundUnavailable,
+    #[error("OwnerStillActive")]
+    OwnerStillActive,
+    #[error("InvalidBeneficiaryShares")]
+    InvalidBeneficiaryShares,
+    #[error("InvalidMultisig")]
+    InvalidMultisig,
+    #[error("MultisigThresholdNotMet")]
+    MultisigThresholdNotMet,
+    #[error("InvalidGuardians")]
+    InvalidGuardians,
+    #[error("GuardianThresholdNotMet")]
+    GuardianThresholdNotMet,
+    #[error("RecoveryPending")]
+    RecoveryPending,
+    #[error("NoPendingRecovery")]
+    NoPendingRecovery,
+    #[error("InvalidPriceFeed")]
+    InvalidPriceFeed,
+    #[error("StalePrice")]
+    StalePrice,
+    #[error("PriceTargetNotReached")]
+    PriceTargetNotReached,
+    #[error("DeadlineTooFar")]
+    DeadlineTooFar,
+    #[error("AmountTooSmall")]
+    AmountTooSmall,
+    #[error("ProgramPaused")]
+    ProgramPaused,
+    #[error("NotUpgradeAuthority")]
+    NotUpgradeAuthority,
+    #[error("NotAllowlisted")]
+    NotAllowlisted,
+    #[error("InvalidLabel")]
+    InvalidLabel,
+    #[error("InvalidMemo")]
+    InvalidMemo,
+    #[error("CommitmentLocked")]
+    CommitmentLocked,
+    #[error("AccountNeedsMigration")]
+    AccountNeedsMigration,
+    #[error("AccountAlreadyMigrated")]
+    AccountAlreadyMigrated,
+    #[error("NoReallocNeeded")]
+    NoReallocNeeded,
+    #[error("ReceiptAlreadyMinted")]
+    ReceiptAlreadyMinted,
+    #[error("ReceiptNotBurned")]
+    ReceiptNotBurned,
+    #[error("NoReceiptMinted")]
+    NoReceiptMinted,
+    #[error("PositionAlreadyMinted")]
+    PositionAlreadyMinted,
+    #[error("PositionOutstanding")]
+    PositionOutstanding,
+    #[error("NoPositionMinted")]
+    NoPositionMinted,
+    #[error("NotPositionHolder")]
+    NotPositionHolder,
+    #[error("NoRewardsToWithdraw")]
+    NoRewardsToWithdraw,
+    #[error("NoRewardsToCompound")]
+    NoRewardsToCompound,
+    #[error("InvalidValidatorAllowlist")]
+    InvalidValidatorAllowlist,
+    #[error("ValidatorNotAllowed")]
+    ValidatorNotAllowed,
+    #[error("SplitStakeOutstanding")]
+    SplitStakeOutstanding,
+    #[error("InvalidSplitAmount")]
+    InvalidSplitAmount,
+    #[error("LstNotRedeemed")]
+    LstNotRedeemed,
+    #[error("NotConvertedToLst")]
+    NotConvertedToLst,
+    #[error("LendingNotWithdrawn")]
+    LendingNotWithdrawn,
+    #[error("NotDepositedInLending")]
+    NotDepositedInLending,
+    #[error("InvalidLendingReserve")]
+    InvalidLendingReserve,
+    #[error("LendingCollateralNotHeld")]
+    LendingCollateralNotHeld,
+    #[error("RentReserveNotKept")]
+    RentReserveNotKept,
+    #[error("YieldBackendActive")]
+    YieldBackendActive,
+    #[error("CloseRequiresFullWithdrawal")]
+    CloseRequiresFullWithdrawal,
+    #[error("NotRentExempt")]
+    NotRentExempt,
+    #[error("NameRecordsOutstanding")]
+    NameRecordsOutstanding,
+    #[error("RefundsPending")]
+    RefundsPending,
+    #[error("BeneficiaryClaimOpen")]
+    BeneficiaryClaimOpen,
+    #[error("InvalidStateForLock")]
+    InvalidStateForLock,
+    #[error("InvalidStateForUnlock")]
+    InvalidStateForUnlock,
+    #[error("InvalidStateForAddSolAndLock")]
+    InvalidStateForAddSolAndLock,
+    #[error("InvalidStateForUnstake")]
+    InvalidStateForUnstake,
+    #[error("InvalidStateForOwnerChange")]
+    InvalidStateForOwnerChange,
+    #[error("InvalidStateForPriceCheck")]
+    InvalidStateForPriceCheck,
+    #[error("ArithmeticOverflow")]
+    ArithmeticOverflow,
+    #[error("UnexpectedState")]
+    UnexpectedState,
+    #[error("AccountFieldNotSet")]
+    AccountFieldNotSet,
 }
 
 impl From<SolLockError> for ProgramError {
@@ -84,6 +242,121 @@ impl PrintProgramError for SolLockError {
             SolLockError::ConflictingReceiverInfo => {
                 msg!("Error: A receiver account was passed but has_receiver was false, or a receiver account wasn't passed but has_receiver was true.")
             }
+            SolLockError::InvalidStakeAccount => {
+                msg!("Error: The stake account is missing or does not hold a delegated stake.")
+            }
+            SolLockError::StakeNotDeactivated => {
+                msg!("Error: Cannot withdraw from the stake account until its stake is fully deactivated.")
+            }
+            SolLockError::AccountNotLocked => msg!("Error: Account is not locked."),
+            SolLockError::LockRequirementsNotMet => {
+                msg!("Error: Account is locked for less time or fewer lamports than required.")
+            }
+            SolLockError::PendingOwnerMismatch => {
+                msg!("Error: The new owner was not proposed by the current owner.")
+            }
+            SolLockError::MissingUnlockAuthority => {
+                msg!("Error: The account has an unlock authority, but it wasn't passed.")
+            }
+            SolLockError::ReceiverMismatch => {
+                msg!("Error: The receiver does not match the receiver stored in the account.")
+            }
+            SolLockError::NonceMismatch => {
+                msg!("Error: The nonce does not match the nonce stored in the account.")
+            }
+            SolLockError::CrankingDisabled => {
+                msg!("Error: The account does not allow permissionless unlocking.")
+            }
+            SolLockError::InvalidAutoExtendPeriod => {
+                msg!("Error: The auto-extension period must be positive.")
+            }
+            SolLockError::InsufficientVestedFunds => {
+                msg!("Error: Account has not vested enough lamports to withdraw.")
+            }
+            SolLockError::InvalidVestingSchedule => {
+                msg!("Error: The vesting cliff must be between the vesting start and the deadline.")
+            }
+            SolLockError::DepositCapExceeded => {
+                msg!("Error: The deposit would exceed the deposit cap of the account.")
+            }
+            SolLockError::ConflictingSchedules => {
+                msg!("Error: An account cannot both vest and stream.")
+            }
+            SolLockError::InvalidPreimage => {
+                msg!("Error: The preimage does not match the hash lock of the account.")
+            }
+            SolLockError::HashLockExpired => {
+                msg!("Error: The hash lock cannot be claimed after the deadline.")
+            }
+            SolLockError::EscrowExpired => {
+                msg!("Error: The escrow cannot be claimed after the deadline.")
+            }
+            SolLockError::DisputeExpired => {
+                msg!("Error: The dispute cannot be resolved after the deadline.")
+            }
+            SolLockError::DepositsClosed => {
+                msg!("Error: The account only accepts deposits from its owner.")
+            }
+            SolLockError::CrowdfundEnded => msg!("Error: The crowdfund does not accept contributions after its deadline."),
+            SolLockError::RefundPeriodActive => msg!("Error: The account cannot be unlocked while contributors can get refunds."),
+            SolLockError::RefundUnavailable => msg!("Error: Contributions can only be refunded during the refund period of a crowdfund that missed its target."),
+            SolLockError::OwnerStillActive => msg!("Error: The owner sent a heartbeat too recently for the beneficiary to claim."),
+            SolLockError::InvalidBeneficiaryShares => msg!("Error: Beneficiary shares must be distinct, non-zero, at most four, and sum to 10000 basis points."),
+            SolLockError::InvalidMultisig => msg!("Error: The multisig threshold must be between 1 and the number of distinct signers, at most five."),
+            SolLockError::MultisigThresholdNotMet => msg!("Error: Not enough multisig signers co-signed the unlock."),
+            SolLockError::InvalidGuardians => msg!("Error: The guardian threshold must be between 1 and the number of distinct guardians, at most five, with a positive recovery delay."),
+            SolLockError::GuardianThresholdNotMet => msg!("Error: Not enough guardians signed the recovery."),
+            SolLockError::RecoveryPending => msg!("Error: A recovery by the guardians is pending."),
+            SolLockError::NoPendingRecovery => msg!("Error: No recovery by the guardians is pending."),
+            SolLockError::InvalidPriceFeed => msg!("Error: The account is not the Pyth price account of the SolLock account."),
+            SolLockError::StalePrice => msg!("Error: The price is not being traded or was published too long ago."),
+            SolLockError::PriceTargetNotReached => msg!("Error: The price has not reached the price target."),
+            SolLockError::DeadlineTooFar => msg!("Error: The deadline is further in the future than the maximum lock duration."),
+            SolLockError::AmountTooSmall => msg!("Error: The account would hold fewer lamports than the minimum lock amount."),
+            SolLockError::ProgramPaused => msg!("Error: The program is paused, so SolLock accounts can't be funded or locked."),
+            SolLockError::NotUpgradeAuthority => msg!("Error: The signer is not the upgrade authority of the program."),
+            SolLockError::NotAllowlisted => msg!("Error: The owner is not on the allowlist of the program."),
+            SolLockError::InvalidLabel => msg!("Error: The label must be between 1 and 32 bytes long."),
+            SolLockError::InvalidMemo => msg!("Error: The memo must be at most 64 bytes long."),
+            SolLockError::CommitmentLocked => msg!("Error: The commitment cannot be changed while the account is locked."),
+            SolLockError::AccountNeedsMigration => msg!("Error: The account uses an older layout and must be migrated."),
+            SolLockError::AccountAlreadyMigrated => msg!("Error: The account already uses the current layout."),
+            SolLockError::NoReallocNeeded => msg!("Error: The account already has the size of the current layout."),
+            SolLockError::ReceiptAlreadyMinted => msg!("Error: A receipt token was already minted for the account."),
+            SolLockError::ReceiptNotBurned => msg!("Error: The receipt token of the account must be burned first."),
+            SolLockError::NoReceiptMinted => msg!("Error: No receipt token was minted for the account."),
+            SolLockError::PositionAlreadyMinted => msg!("Error: A position NFT was already minted for the account."),
+            SolLockError::PositionOutstanding => msg!("Error: The account can only be unlocked by the holder of its position NFT."),
+            SolLockError::NoPositionMinted => msg!("Error: No position NFT was minted for the account."),
+            SolLockError::NotPositionHolder => msg!("Error: The signer does not hold the position NFT of the account."),
+            SolLockError::NoRewardsToWithdraw => msg!("Error: The stake account holds no rewards above the locked principal."),
+            SolLockError::NoRewardsToCompound => msg!("Error: The stake account earned no rewards since they were last compounded."),
+            SolLockError::InvalidValidatorAllowlist => msg!("Error: The validator allowlist must hold distinct vote accounts, at most MAX_ALLOWED_VALIDATORS of them."),
+            SolLockError::ValidatorNotAllowed => msg!("Error: The account does not allow staking with the vote account."),
+            SolLockError::SplitStakeOutstanding => msg!("Error: The split stake account of the account must be unstaked or merged first."),
+            SolLockError::InvalidSplitAmount => msg!("Error: The split amount must be positive and less than the staked lamports."),
+            SolLockError::LstNotRedeemed => msg!("Error: The locked lamports must be redeemed from the stake pool first."),
+            SolLockError::NotConvertedToLst => msg!("Error: The locked lamports are not converted into a liquid staking token."),
+            SolLockError::LendingNotWithdrawn => msg!("Error: The locked lamports must be withdrawn from the lending reserve first."),
+            SolLockError::NotDepositedInLending => msg!("Error: The locked lamports are not deposited into a lending reserve."),
+            SolLockError::InvalidLendingReserve => msg!("Error: The lending reserve does not lend out SOL or does not match the accounts passed."),
+            SolLockError::LendingCollateralNotHeld => msg!("Error: The collateral tokens of the lending deposit are no longer held by the account alone."),
+            SolLockError::RentReserveNotKept => msg!("Error: The withdrawal would dip into the rent-exempt reserve of the account."),
+            SolLockError::YieldBackendActive => msg!("Error: The locked lamports must be withdrawn from their yield backend first."),
+            SolLockError::CloseRequiresFullWithdrawal => msg!("Error: Closing the account requires removing all of its lamports."),
+            SolLockError::NotRentExempt => msg!("Error: The withdrawal would leave the account below the rent-exempt minimum, remove all lamports and close it instead."),
+            SolLockError::NameRecordsOutstanding => msg!("Error: Name records still point at the account, repoint them first."),
+            SolLockError::RefundsPending => msg!("Error: Contributions to the account can still be refunded."),
+            SolLockError::BeneficiaryClaimOpen => msg!("Error: The beneficiary can claim the account, so it cannot be cranked."),
+            SolLockError::InvalidStateForLock => msg!("Error: The account must be ReadyUnlocked to be locked."),
+            SolLockError::InvalidStateForUnlock => msg!("Error: The account must be Locked to be unlocked."),
+            SolLockError::InvalidStateForAddSolAndLock => msg!("Error: The account must be HasDeadline or ReadyUnlocked to add SOL and lock it."),
+            SolLockError::InvalidStateForUnstake => msg!("Error: The account must be Staked to be unstaked."),
+            SolLockError::InvalidStateForOwnerChange => msg!("Error: The owner cannot change while the account is Staked or Disputed."),
+            SolLockError::InvalidStateForPriceCheck => msg!("Error: The account must be Locked to check its price target."),
+            SolLockError::ArithmeticOverflow => msg!("Error: An arithmetic operation on lamports overflowed."),
+            SolLockError::UnexpectedState => msg!("Error: The account is in a state the instruction does not handle."),
+            SolLockError::AccountFieldNotSet => msg!("Error: A field required by the state of the account is not set."),
         }
     }
 }
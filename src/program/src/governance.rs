@@ -0,0 +1,53 @@
+//! The voter weight addin interface of SPL Governance, so that Realms can weight
+//! voters by the voting power of their SolLock accounts
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{clock::Slot, pubkey::Pubkey};
+
+/// The seed prefix of voter weight records, followed by the realm, governing token mint
+/// and governing token owner
+pub const VOTER_WEIGHT_RECORD_SEED: &[u8] = b"voter-weight-record";
+
+/// The discriminator SPL Governance expects at the start of voter weight records
+pub const VOTER_WEIGHT_RECORD_DISCRIMINATOR: [u8; 8] = *b"2ef99b4b";
+
+/// The size of a voter weight record, with room for all of its optional fields
+pub const VOTER_WEIGHT_RECORD_SIZE: usize = 8 + 32 + 32 + 32 + 8 + (1 + 8) + (1 + 1) + (1 + 32) + 8;
+
+/// The governance action a voter weight was computed for
+#[derive(Clone, Copy, Debug, PartialEq, BorshDeserialize, BorshSerialize)]
+pub enum VoterWeightAction {
+    /// Cast a vote
+    CastVote,
+    /// Comment on a proposal
+    CommentProposal,
+    /// Create a governance
+    CreateGovernance,
+    /// Create a proposal
+    CreateProposal,
+    /// Sign off a proposal
+    SignOffProposal,
+}
+
+/// The voter weight record of the SPL Governance addin interface
+#[derive(Debug, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct VoterWeightRecord {
+    /// VOTER_WEIGHT_RECORD_DISCRIMINATOR
+    pub account_discriminator: [u8; 8],
+    /// The realm the record is for
+    pub realm: Pubkey,
+    /// The governing token mint of the realm the record is for
+    pub governing_token_mint: Pubkey,
+    /// The voter, who owns the SolLock accounts the weight is computed from
+    pub governing_token_owner: Pubkey,
+    /// The voting power of the voter
+    pub voter_weight: u64,
+    /// The slot after which the weight is stale, so it must be refreshed in the same slot
+    /// as it is used
+    pub voter_weight_expiry: Option<Slot>,
+    /// The action the weight is restricted to, if any
+    pub weight_action: Option<VoterWeightAction>,
+    /// The target of the action the weight is restricted to, if any
+    pub weight_action_target: Option<Pubkey>,
+    /// Reserved for future use by the interface
+    pub reserved: [u8; 8],
+}
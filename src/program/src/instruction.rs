@@ -1,17 +1,46 @@
 //! The definitions for SolLock instructions
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::clock::UnixTimestamp;
+use solana_program::{
+    bpf_loader_upgradeable,
+    clock::{Epoch, UnixTimestamp},
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    stake, system_program,
+    sysvar::{clock, rent, stake_history},
+};
+
+use crate::{
+    lending::{LendingReserveAccounts, LENDING_PROGRAM_ID},
+    memo::MEMO_PROGRAM_ID,
+    processor::{
+        get_allowlist_entry, get_config, get_contribution, get_index_counter, get_label_acc_index,
+        get_lst_funding_account, get_position_mint, get_receipt_mint, get_sol_lock_account,
+        get_split_stake_account, get_stake_account, get_stats, get_treasury,
+        get_voter_weight_record,
+    },
+    stake_pool::{StakePoolAccounts, STAKE_POOL_PROGRAM_ID},
+    state::{BeneficiaryShare, Config},
+    token::{
+        get_associated_token_address, ASSOCIATED_TOKEN_PROGRAM_ID, NATIVE_MINT, TOKEN_PROGRAM_ID,
+    },
+};
 
 /// CreateAccount instruction data
 #[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CreateAccount {
     /// The index of the new account
     pub acc_index: u64,
+    /// Whether a Sol Payer account was passed
+    pub has_payer: bool,
+    /// A name or memo for wallets and explorers to show, at most MAX_MEMO_LEN bytes, or empty
+    pub memo: String,
 }
 
 /// AddSol instruction data
 #[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddSol {
     /// The index of the account to access
     pub acc_index: u64,
@@ -23,6 +52,7 @@ pub struct AddSol {
 
 /// RemoveSol instruction data
 #[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RemoveSol {
     /// The index of the account to access
     pub acc_index: u64,
@@ -30,10 +60,15 @@ pub struct RemoveSol {
     pub lamports: u64,
     /// Whether a Sol Receiver account was passed
     pub has_receiver: bool,
+    /// A memo to log with the SPL Memo program, for whoever keys off the memos of transfers
+    pub memo: Option<String>,
+    /// Whether to close the account, which requires removing all of its lamports
+    pub close: bool,
 }
 
 /// SetDeadline instruction data
 #[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetDeadline {
     /// The index of the account to access
     pub acc_index: u64,
@@ -43,22 +78,31 @@ pub struct SetDeadline {
 
 /// Lock instruction data
 #[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Lock {
     /// The index of the account to access
     pub acc_index: u64,
+    /// The hash of an off-chain document the lock commits to, if any
+    pub commitment: Option<[u8; 32]>,
 }
 
 /// Unlock instruction data
 #[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Unlock {
     /// The index of the account to access
     pub acc_index: u64,
     /// Whether a Sol Receiver account was passed
     pub has_receiver: bool,
+    /// The nonce of the account, required when the unlock authority signs
+    pub nonce: Option<u64>,
+    /// A memo to log with the SPL Memo program, for whoever keys off the memos of transfers
+    pub memo: Option<String>,
 }
 
 /// Stake instruction data
 #[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Stake {
     /// The index of the account to access
     pub acc_index: u64,
@@ -66,139 +110,3256 @@ pub struct Stake {
 
 /// Unstake instruction data
 #[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Unstake {
     /// The index of the account to access
     pub acc_index: u64,
 }
 
-/// A SolLock instruction
+/// TopUpRent instruction data
 #[derive(Debug, BorshDeserialize, BorshSerialize)]
-pub enum SolLockInstruction {
-    /// Create a new SolLock account
-    /// Requires that the account does not exist.
-    /// Creates the account with uninitialized data
-    ///
-    /// # Account references
-    ///   0. `[SIGNER]` Owner account
-    ///   1. `[WRITE]` SolLock account
-    ///   2. `[]` System program account
-    CreateAccount(CreateAccount),
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TopUpRent {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
 
-    /// Add Sol to a SolLock account to prepare for locking
-    /// Requires that the account is in one of states {Uninitialized, HasFunds, HasDeadline, ReadyUnlocked, Locked}
-    /// That is, this instruction can be used in any state.
-    ///
-    ///
-    /// Transitions:
-    /// Uninitialized -> HasFunds
-    /// HasFunds -> HasFunds
-    /// HasDeadline -> ReadyUnlocked
-    /// ReadyUnlocked -> ReadyUnlocked
-    /// Locked -> Locked
-    ///
-    /// By default, the lamports will be debited from the Owner account.
-    /// If a Sol Payer account is passed, the lamports will be debited from it instead.
-    ///
-    /// # Account references
-    ///   0. `[SIGNER, WRITE]` Owner account
-    ///   1. `[WRITE]` SolLock account
-    ///   2. `[]` System program account
-    ///   3. `[SIGNER, WRITE]` (Optional) Sol Payer account
-    AddSol(AddSol),
+/// CreateAndLock instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateAndLock {
+    /// The index of the new account
+    pub acc_index: u64,
+    /// The number of lamports to lock
+    pub lamports: u64,
+    /// The time the lamports should be locked until
+    pub deadline: UnixTimestamp,
+    /// Whether a Sol Payer account was passed
+    pub has_payer: bool,
+    /// A name or memo for wallets and explorers to show, at most MAX_MEMO_LEN bytes, or empty
+    pub memo: String,
+    /// The hash of an off-chain document the lock commits to, if any
+    pub commitment: Option<[u8; 32]>,
+}
 
-    /// Remove Sol from an unlocked SolLock account
-    /// Requires that the account is in one of states {HasFunds, ReadyUnlocked}
-    /// Requires that the number of lamports to remove is less than or equal to the number in the account
-    ///
-    /// Transitions:
-    /// HasFunds -> HasFunds
-    ///     Occurs when the number of lamports to remove is less than the number in the account
-    /// HasFunds -> Uninitialized
-    ///     Occurs when the number of lamports to remove is equal to the number in the account
-    /// ReadyUnlocked -> ReadyUnlocked
-    ///     Occurs when the number of lamports to remove is less than the number in the account
-    /// ReadyUnlocked -> HasDeadline
-    ///     Occurs when the number of lamports to remove is equal to the number in the account
-    ///
-    /// If a Sol Receiver account is passed and has_receiver is true,
-    /// the lamports will be credited to the Sol Receiver account instead.
-    ///
-    /// # Account references
-    ///   0. `[SIGNER, WRITE]` Owner account
-    ///   1. `[WRITE]` SolLock account
-    ///   2. `[WRITE]` (Optional) Sol Receiver account
-    RemoveSol(RemoveSol),
+/// SetDeadlineMany instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetDeadlineMany {
+    /// The indices of the accounts to access, in the order the accounts are passed
+    pub acc_indices: Vec<u64>,
+    /// The deadline to use
+    pub deadline: UnixTimestamp,
+}
 
-    /// Set deadline on a SolLock account
-    /// Requires that the account is in one of states {Uninitialized, HasFunds, HasDeadline, ReadyUnlocked, Locked}
-    /// That is, this instruction can be used in any state.
-    ///
-    /// Transitions:
-    /// Uninitialized -> HasDeadline
-    /// HasFunds -> ReadyUnlocked
-    /// HasDeadline -> HasDeadline
-    /// ReadyUnlocked -> ReadyUnlocked
-    /// Locked -> Locked
-    ///     Requires that the new deadline is greater than or equal to the current deadline
-    ///
-    /// # Account references
-    ///   0. `[SIGNER]` Owner account
-    ///   1. `[WRITE]` SolLock account
-    SetDeadline(SetDeadline),
+/// AddSolAndLock instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AddSolAndLock {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The number of lamports to lock
+    pub lamports: u64,
+    /// The hash of an off-chain document the lock commits to, if any
+    pub commitment: Option<[u8; 32]>,
+}
 
-    /// Lock a SolLock account
-    /// Requires that the account is in the state ReadyUnlocked
-    ///
-    /// Transitions:
-    /// ReadyUnlocked -> Locked
-    ///
-    /// # Account references
-    ///   0. `[SIGNER]` Owner account
-    ///   1. `[WRITE]` SolLock account
-    Lock(Lock),
+/// SetName instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetName {
+    /// The index of the account the name should refer to
+    pub acc_index: u64,
+    /// The hash of the label
+    pub label_hash: [u8; 32],
+    /// Whether a Sol Payer account was passed
+    pub has_payer: bool,
+}
 
-    /// Unlock a SolLock account
-    /// Requires that the account is in state Locked
-    ///
-    /// Transitions:
-    /// Locked -> Uninitialized
-    ///     Requires that the current time is greater than or equal to the deadline.
-    ///     Transfers the lamports out of the SolLock account into the owner account
-    ///     or the Sol Receiver account and sets the SolLock account's state to Uninitialized.
-    ///
-    /// By default, the lamports will be credited to the Owner account.
-    /// If a Sol Receiver account is passed and has_receiver is true,
-    /// the lamports will be credited to the Sol Receiver account instead.
-    ///
-    /// # Account references
-    ///   0. `[SIGNER, WRITE]` Owner account
-    ///   1. `[WRITE]` SolLock account
-    ///   2. `[WRITE]` (Optional) Sol Receiver account
-    Unlock(Unlock),
+/// UnlockAndClose instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnlockAndClose {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// Whether a Sol Receiver account was passed
+    pub has_receiver: bool,
+    /// The nonce of the account, required when the unlock authority signs
+    pub nonce: Option<u64>,
+}
 
-    /// Stake the funds in a SolLock account
-    /// Requires that the account is in state Locked
-    /// Requires that the Stake program account is not currently staked
-    ///
-    /// Transitions:
-    /// Locked -> Staked
-    ///
-    /// # Account references
-    ///   0. `[SIGNER]` Owner account
-    ///   1. `[WRITE]` SolLock account
-    ///   2. `[]` Stake program account
-    Stake(Stake),
+/// ProposeOwner instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProposeOwner {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The proposed owner, or None to cancel a pending proposal
+    pub new_owner: Option<Pubkey>,
+}
 
-    /// Unstake the funds in a SolLock account
-    /// Requires that the account is in state Staked
-    /// Requires that the Stake program account is not currently staked
-    ///
-    /// Transitions:
-    /// Staked -> Locked
-    ///
-    /// # Account references
-    ///   0. `[SIGNER]` Owner account
-    ///   1. `[WRITE]` SolLock account
-    ///   2. `[]` Stake program account
-    Unstake(Unstake),
+/// AcceptOwner instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AcceptOwner {
+    /// The index of the account to take over
+    pub acc_index: u64,
+    /// The index of the new owner's account to move it to
+    pub new_acc_index: u64,
+}
+
+/// SetUnlockAuthority instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetUnlockAuthority {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The key allowed to unlock the account, or None to let the owner unlock it
+    pub unlock_authority: Option<Pubkey>,
+}
+
+/// SetReceiver instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetReceiver {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The account unlocked lamports must be credited to, or None to allow any receiver
+    pub receiver: Option<Pubkey>,
+}
+
+/// SetRecoveryPointer instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetRecoveryPointer {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// Hash or other pointer to off-chain recovery documentation, or None to clear it
+    pub recovery_pointer: Option<[u8; 32]>,
+}
+
+/// SetCrankTip instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetCrankTip {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// Lamports paid to whoever unlocks the account after its deadline,
+    /// or None to only let the owner unlock it
+    pub crank_tip: Option<u64>,
+}
+
+/// Relock instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Relock {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The amount of lamports to add, can be 0 if the account already has funds
+    pub lamports: u64,
+    /// The time the lamports should be locked until
+    pub deadline: UnixTimestamp,
+    /// The hash of an off-chain document the lock commits to, if any
+    pub commitment: Option<[u8; 32]>,
+}
+
+/// SetAutoExtend instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetAutoExtend {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// Period the deadline rolls forward by if the account is not unlocked in time,
+    /// or None to disable auto-extension
+    pub auto_extend: Option<UnixTimestamp>,
+}
+
+/// SetVesting instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetVesting {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The time the lamports start vesting, linearly until the deadline,
+    /// or None to release them all at the deadline
+    pub vesting_start: Option<UnixTimestamp>,
+    /// The time before which no lamports can be withdrawn, or None for no cliff
+    pub vesting_cliff: Option<UnixTimestamp>,
+}
+
+/// SetDepositCap instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetDepositCap {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The maximum number of lamports the account can hold, or None for no limit
+    pub deposit_cap: Option<u64>,
+    /// Whether deposits over the cap only deposit the part up to the cap instead of failing
+    pub refund_excess: bool,
+}
+
+/// SetOpenDeposits instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetOpenDeposits {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// Whether anyone can contribute lamports to the account
+    pub open_deposits: bool,
+}
+
+/// Contribute instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Contribute {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The number of lamports to contribute
+    pub lamports: u64,
+}
+
+/// SetRaiseTarget instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetRaiseTarget {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The lamports contributors must raise by the deadline, or None for no target
+    pub raise_target: Option<u64>,
+    /// How long after the deadline contributors can get refunds if the target was missed,
+    /// set if and only if raise_target is set
+    pub refund_period: Option<UnixTimestamp>,
+}
+
+/// RefundContribution instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RefundContribution {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// SetBeneficiary instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetBeneficiary {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The account allowed to claim the lamports if the owner stops sending heartbeats,
+    /// or None to remove the dead man's switch
+    pub beneficiary: Option<Pubkey>,
+    /// How long after the deadline or the last heartbeat the beneficiary can claim the
+    /// lamports, set if and only if beneficiary is set
+    pub heartbeat_interval: Option<UnixTimestamp>,
+}
+
+/// SetBeneficiaryShares instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetBeneficiaryShares {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// How the lamports claimed by the beneficiary are split, or empty to remove the split
+    pub shares: Vec<BeneficiaryShare>,
+}
+
+/// SetMultisig instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetMultisig {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The accounts that can co-sign unlocks, or empty to remove the multisig
+    pub signers: Vec<Pubkey>,
+    /// How many signers must co-sign unlocks, or 0 to remove the multisig
+    pub threshold: u8,
+}
+
+/// SetGuardians instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetGuardians {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The accounts that can recover the account, or empty to remove the guardians
+    pub guardians: Vec<Pubkey>,
+    /// How many guardians must sign a recovery, or 0 to remove the guardians
+    pub threshold: u8,
+    /// How long the owner can veto a recovery, or None to remove the guardians
+    pub recovery_delay: Option<UnixTimestamp>,
+}
+
+/// InitiateRecovery instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InitiateRecovery {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The key that becomes the pending owner of the account
+    pub new_owner: Pubkey,
+}
+
+/// VetoRecovery instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VetoRecovery {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// SetPriceCondition instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetPriceCondition {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The Pyth price account to read the price from, or None to remove the price condition
+    pub price_feed: Option<Pubkey>,
+    /// The price at which the account can be unlocked, in the units of the price feed
+    pub price_target: Option<i64>,
+    /// Whether the deadline must also have passed, instead of either one
+    pub requires_deadline: bool,
+}
+
+/// SetEpochDeadline instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetEpochDeadline {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The epoch the account is locked until, or None to remove the epoch deadline
+    pub epoch_deadline: Option<Epoch>,
+}
+
+/// SetDeadlineRelative instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetDeadlineRelative {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// How many seconds after the current on-chain time the deadline is
+    pub seconds: u64,
+}
+
+/// InitializeConfig instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InitializeConfig {
+    /// The initial config
+    pub config: Config,
+}
+
+/// UpdateConfig instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UpdateConfig {
+    /// The new config, which may hand the config over to a new admin
+    pub config: Config,
+}
+
+/// WithdrawTreasury instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WithdrawTreasury {
+    /// The number of lamports to withdraw
+    pub lamports: u64,
+}
+
+/// AddToAllowlist instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AddToAllowlist {
+    /// The owner to allow
+    pub owner: Pubkey,
+}
+
+/// RemoveFromAllowlist instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RemoveFromAllowlist {
+    /// The owner to disallow
+    pub owner: Pubkey,
+}
+
+/// CreateNextAccount instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateNextAccount {
+    /// Whether a Sol Payer account was passed
+    pub has_payer: bool,
+    /// A name or memo for wallets and explorers to show, at most MAX_MEMO_LEN bytes, or empty
+    pub memo: String,
+}
+
+/// CreateLabeledAccount instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateLabeledAccount {
+    /// The label of the new account, which its index is derived from
+    pub label: String,
+    /// Whether a Sol Payer account was passed
+    pub has_payer: bool,
+    /// A name or memo for wallets and explorers to show, at most MAX_MEMO_LEN bytes, or empty
+    pub memo: String,
+}
+
+/// Migrate instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Migrate {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// Realloc instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Realloc {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// GetVersion instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GetVersion {}
+
+/// The return data of the GetVersion instruction
+#[derive(Debug, PartialEq, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VersionInfo {
+    /// The semantic version of the program, e.g. "1.0.0"
+    pub program_version: String,
+    /// The version of the SolLock account layout, see ACCOUNT_VERSION
+    pub account_version: u8,
+}
+
+/// InitializeReceiptMint instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InitializeReceiptMint {}
+
+/// MintReceipt instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MintReceipt {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// BurnReceipt instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BurnReceipt {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// MintPosition instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MintPosition {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// UnlockPosition instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnlockPosition {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// GetVotingPower instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GetVotingPower {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// CreateVoterWeightRecord instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateVoterWeightRecord {
+    /// The realm to vote in
+    pub realm: Pubkey,
+    /// The governing token mint of the realm
+    pub governing_token_mint: Pubkey,
+}
+
+/// UpdateVoterWeightRecord instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UpdateVoterWeightRecord {
+    /// The index of the account to weight the voter by
+    pub acc_index: u64,
+    /// The realm to vote in
+    pub realm: Pubkey,
+    /// The governing token mint of the realm
+    pub governing_token_mint: Pubkey,
+}
+
+/// WithdrawRewards instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WithdrawRewards {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// Compound instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Compound {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// SetValidatorAllowlist instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetValidatorAllowlist {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The vote accounts the locked lamports can be delegated to, or empty to allow any
+    pub validators: Vec<Pubkey>,
+}
+
+/// SplitStake instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SplitStake {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The lamports to split off the stake account
+    pub lamports: u64,
+}
+
+/// MergeStake instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MergeStake {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// ConvertToLst instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConvertToLst {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// RedeemLst instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RedeemLst {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// DepositToLending instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DepositToLending {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// WithdrawFromLending instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WithdrawFromLending {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// What Reconcile does with the lamports of a SolLock account that are not tracked by it
+#[derive(Clone, Copy, Debug, PartialEq, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SurplusAction {
+    /// Only log the discrepancy
+    Log,
+    /// Transfer the untracked lamports to the owner
+    Sweep,
+    /// Deposit the untracked lamports into the account, as AddSol would
+    Absorb,
+}
+
+/// Reconcile instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Reconcile {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// What to do with the untracked lamports
+    pub action: SurplusAction,
+}
+
+/// CheckPrice instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CheckPrice {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// Heartbeat instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Heartbeat {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// ClaimAsBeneficiary instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClaimAsBeneficiary {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// UnlockToClaim instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnlockToClaim {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The nonce of the account, required if it is unlocked by its unlock authority
+    pub nonce: Option<u64>,
+}
+
+/// ClaimUnlocked instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClaimUnlocked {}
+
+/// SetStream instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetStream {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// Lamports per second that become claimable once the account is locked,
+    /// or None to stop streaming
+    pub stream_rate: Option<u64>,
+}
+
+/// Claim instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Claim {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// SetHashLock instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetHashLock {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The SHA-256 hash whose preimage lets the counterparty claim the lamports,
+    /// or None to remove the hash lock
+    pub hash_lock: Option<[u8; 32]>,
+    /// The account allowed to claim the lamports, set if and only if hash_lock is set
+    pub counterparty: Option<Pubkey>,
+}
+
+/// ClaimWithPreimage instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClaimWithPreimage {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The preimage of the hash lock
+    pub preimage: Vec<u8>,
+}
+
+/// SetEscrow instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetEscrow {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// The account allowed to claim the lamports before the deadline,
+    /// or None to remove the escrow
+    pub counterparty: Option<Pubkey>,
+    /// The account allowed to resolve disputes, or None if the escrow can't be disputed
+    pub arbiter: Option<Pubkey>,
+    /// How long the arbiter has to resolve a dispute, set if and only if arbiter is set
+    pub dispute_period: Option<UnixTimestamp>,
+}
+
+/// ClaimEscrow instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClaimEscrow {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// RefundEscrow instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RefundEscrow {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// RaiseDispute instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RaiseDispute {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// Resolve instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Resolve {
+    /// The index of the account to access
+    pub acc_index: u64,
+    /// Whether the lamports go to the counterparty, or back to the owner
+    pub to_counterparty: bool,
+}
+
+/// CrankUnlock instruction data
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CrankUnlock {
+    /// The index of the account to access
+    pub acc_index: u64,
+}
+
+/// A SolLock instruction
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SolLockInstruction {
+    /// Create a new SolLock account
+    /// Requires that the account does not exist.
+    /// Requires that the memo is at most MAX_MEMO_LEN bytes, and stores it in the account.
+    /// Creates the account with uninitialized data
+    ///
+    /// By default, the Owner account pays for the account.
+    /// If a Sol Payer account is passed and has_payer is true, it pays instead,
+    /// and the Owner account doesn't need to sign.
+    ///
+    /// If the program is built with the allowlist feature, requires that the owner was
+    /// allowed by the admin with AddToAllowlist, and the AllowlistEntry account of the owner
+    /// comes before the Sol Payer account.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account (only `[]` if a Sol Payer account is passed)
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[]` System program account
+    ///   3. `[]` AllowlistEntry account of the owner, only with the allowlist feature
+    ///   4. `[SIGNER, WRITE]` (Optional) Sol Payer account
+    CreateAccount(CreateAccount),
+
+    /// Add Sol to a SolLock account to prepare for locking
+    /// Requires that the program is not paused.
+    /// Requires that the account is in one of states {Uninitialized, HasFunds, HasDeadline, ReadyUnlocked, Locked}
+    /// That is, this instruction can be used in any state.
+    /// Requires that the account holds at least the min_lock_lamports of the config after the deposit.
+    /// Requires that the Sol Payer account is not the SolLock account.
+    ///
+    ///
+    /// Transitions:
+    /// Uninitialized -> HasFunds
+    /// HasFunds -> HasFunds
+    /// HasDeadline -> ReadyUnlocked
+    /// ReadyUnlocked -> ReadyUnlocked
+    /// Locked -> Locked
+    ///
+    /// By default, the lamports will be debited from the Owner account.
+    /// If a Sol Payer account is passed and has_payer is true, the lamports will be debited
+    /// from it instead, and the Owner account only needs to sign.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account (only `[SIGNER]` if a Sol Payer account is passed)
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[]` System program account
+    ///   3. `[]` Config account
+    ///   4. `[WRITE]` Stats account
+    ///   5. `[SIGNER, WRITE]` (Optional) Sol Payer account
+    AddSol(AddSol),
+
+    /// Remove Sol from an unlocked SolLock account
+    /// Requires that the account is in one of states {HasFunds, ReadyUnlocked}
+    /// Requires that the number of lamports to remove is less than or equal to the number in the account
+    /// Requires that the Sol Receiver account is not the SolLock account
+    /// Requires that the account balance stays at or above its rent reserve, and rent-exempt
+    /// Requires that all lamports are removed if close is true
+    ///
+    /// Transitions:
+    /// HasFunds -> HasFunds
+    ///     Occurs when the number of lamports to remove is less than the number in the account
+    /// HasFunds -> Uninitialized
+    ///     Occurs when the number of lamports to remove is equal to the number in the account
+    /// ReadyUnlocked -> ReadyUnlocked
+    ///     Occurs when the number of lamports to remove is less than the number in the account
+    /// ReadyUnlocked -> HasDeadline
+    ///     Occurs when the number of lamports to remove is equal to the number in the account
+    /// Locked -> Locked
+    ///     Occurs when the account is vesting and the lamports to remove have vested
+    /// Locked -> Initialized
+    ///     Occurs when the account is vesting and all its lamports have vested and are removed
+    ///
+    /// If a Sol Receiver account is passed and has_receiver is true,
+    /// the lamports will be credited to the Sol Receiver account instead.
+    /// Vested lamports can only be removed by the owner if the account has no unlock authority,
+    /// and must be credited to the stored receiver if the account has one. If the account has
+    /// a multisig, they also need at least threshold of its signers as signer accounts.
+    /// The protocol fee frozen into the account when it was locked is deducted from the
+    /// vested lamports, as with Unlock, and credited to the Treasury account.
+    ///
+    /// If close is true, the account must have no outstanding tokens or yield backend,
+    /// and its whole balance, including the rent reserve, is transferred and its data cleared,
+    /// so the account is closed.
+    ///
+    /// If a memo is passed, it is logged with the SPL Memo program after the transfer.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[WRITE]` Treasury account
+    ///   3. `[WRITE]` Stats account
+    ///   4. `[WRITE]` (Optional) Sol Receiver account, only if has_receiver is true
+    ///   5. `[]` (Optional) SPL Memo program account, only if a memo is passed
+    ///   6.. `[SIGNER]` (Optional) Multisig signer accounts, only to remove vested lamports
+    RemoveSol(RemoveSol),
+
+    /// Set deadline on a SolLock account
+    /// Requires that the account is in one of states {Uninitialized, HasFunds, HasDeadline, ReadyUnlocked, Locked}
+    /// That is, this instruction can be used in any state.
+    /// Requires that the deadline is at most the max_lock_duration of the config from now.
+    ///
+    /// Transitions:
+    /// Uninitialized -> HasDeadline
+    /// HasFunds -> ReadyUnlocked
+    /// HasDeadline -> HasDeadline
+    /// ReadyUnlocked -> ReadyUnlocked
+    /// Locked -> Locked
+    ///     Requires that the new deadline is greater than or equal to the current deadline
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[]` Config account
+    SetDeadline(SetDeadline),
+
+    /// Lock a SolLock account
+    /// Requires that the program is not paused.
+    /// Freezes the fee_bps of the config into the account as the protocol fee of its unlocks.
+    /// Stores the commitment hash, which cannot change until the account is unlocked.
+    /// Requires that the account is in the state ReadyUnlocked
+    /// Requires that the account holds at least the min_lock_lamports of the config
+    ///
+    /// Transitions:
+    /// ReadyUnlocked -> Locked
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[]` Config account
+    ///   3. `[WRITE]` Stats account
+    Lock(Lock),
+
+    /// Unlock a SolLock account
+    /// Requires that the account is in state Locked
+    /// Requires that the Sol Receiver account is not the SolLock account
+    ///
+    /// Transitions:
+    /// Locked -> Uninitialized
+    ///     Requires that the current time is greater than or equal to the deadline.
+    ///     Transfers the lamports out of the SolLock account into the owner account
+    ///     or the Sol Receiver account and sets the SolLock account's state to Uninitialized.
+    /// Locked -> Locked
+    ///     Occurs when the account is vesting and the current time is less than the deadline.
+    ///     Transfers the vested lamports that were not withdrawn yet.
+    ///
+    /// By default, the lamports will be credited to the Owner account.
+    /// If a Sol Receiver account is passed and has_receiver is true,
+    /// the lamports will be credited to the Sol Receiver account instead.
+    ///
+    /// If the account has a stored receiver, the lamports must be credited to it,
+    /// so it must be passed as the Sol Receiver account unless it is the owner.
+    ///
+    /// If the account has an unlock authority, the unlock authority must sign instead of the owner,
+    /// and nonce must match the nonce of the account, which is then incremented.
+    /// This prevents captured unlock authorizations from being replayed.
+    ///
+    /// If the account has a price condition, its price target must have been reached
+    /// as recorded by CheckPrice, which lets it unlock before the deadline unless
+    /// the condition also requires the deadline.
+    ///
+    /// If the account has an epoch deadline, the current epoch must also be greater than
+    /// or equal to it.
+    ///
+    /// The protocol fee frozen into the account when it was locked is deducted from the
+    /// unlocked lamports and credited to the Treasury account.
+    ///
+    /// The unlocked lamports are only taken from the account balance above its rent reserve,
+    /// so the account stays rent-exempt.
+    ///
+    /// If a memo is passed, it is logged with the SPL Memo program after the transfer.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account (only `[WRITE]` if the account has an unlock authority)
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[WRITE]` Treasury account
+    ///   3. `[WRITE]` Stats account
+    ///   4. `[WRITE]` (Optional) Sol Receiver account, only if has_receiver is true
+    ///   5. `[]` (Optional) SPL Memo program account, only if a memo is passed
+    ///   6. `[SIGNER]` (Optional) Unlock authority account, only if the account has one
+    ///   7.. `[SIGNER]` (Optional) Multisig signer accounts, at least the threshold of them
+    Unlock(Unlock),
+
+    /// Stake the funds in a SolLock account
+    /// Requires that the account is in state Locked
+    /// Requires that the vote account is allowed by the validator allowlist of the account,
+    /// if it has one
+    ///
+    /// The locked lamports are moved into the stake account derived from the SolLock account,
+    /// which is its staker and withdrawer authority, and delegated to the vote account.
+    /// They stay locked as the principal of the account until it is unstaked.
+    ///
+    /// Transitions:
+    /// Locked -> Staked
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[WRITE]` Stake account, derived from the SolLock account
+    ///   3. `[]` Vote account
+    ///   4. `[]` Clock sysvar account
+    ///   5. `[]` Rent sysvar account
+    ///   6. `[]` Stake history sysvar account
+    ///   7. `[]` Stake config account
+    ///   8. `[]` Stake program account
+    ///   9. `[]` System program account
+    Stake(Stake),
+
+    /// Unstake the funds in a SolLock account
+    /// Requires that the account is in state Staked
+    /// Requires that the SolLock account is the staker and withdrawer authority of the stake account
+    ///
+    /// Unstaking happens in two phases, each performed by a separate Unstake instruction:
+    /// 1. If the stake account has not been deactivated yet, its stake is deactivated.
+    ///    The account stays in state Staked.
+    /// 2. Once the stake is fully deactivated (after the cooldown), all lamports in the stake account
+    ///    are withdrawn back into the SolLock account.
+    ///
+    /// The stake account can also be the split stake account, which unstakes part of the
+    /// locked lamports while the rest stays staked. The stake account itself can only be
+    /// withdrawn from once the split stake account was unstaked or merged back.
+    ///
+    /// Transitions:
+    /// Staked -> Staked
+    ///     Occurs when the stake is deactivated, or the split stake account is withdrawn from
+    /// Staked -> Locked
+    ///     Occurs when the lamports are withdrawn. Requires that the stake is fully deactivated.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[WRITE]` Stake account
+    ///   3. `[]` Clock sysvar account
+    ///   4. `[]` Stake history sysvar account
+    ///   5. `[]` Stake program account
+    Unstake(Unstake),
+
+    /// Top up the rent-exempt reserve of a SolLock account
+    /// Can be called by anyone, and can be used in any initialized state.
+    /// Does not change the account state or the number of locked lamports.
+    ///
+    /// The rent reserve is the SolLock account balance minus the locked lamports it holds,
+    /// so lamports deposited into a yield backend do not count towards it.
+    /// Only the lamports needed to raise the reserve to the current rent-exempt minimum are transferred,
+    /// so nothing is transferred if the account is already rent-exempt.
+    /// The rent-exempt minimum is recorded as the rent reserve of the account, which RemoveSol
+    /// and Unlock never transfer out unless closing the account.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Payer account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[]` System program account
+    TopUpRent(TopUpRent),
+
+    /// Create a new SolLock account and lock lamports in it
+    /// Requires that the program is not paused.
+    /// Freezes the fee_bps of the config into the account as the protocol fee of its unlocks.
+    /// Stores the commitment hash, which cannot change until the account is unlocked.
+    /// Requires that the account does not exist.
+    /// Requires that the deadline is at most the max_lock_duration of the config from now.
+    /// Requires that at least the min_lock_lamports of the config are locked.
+    /// Equivalent to CreateAccount, SetDeadline, AddSol and Lock, but the account is created
+    /// with a single system program call which also transfers the lamports to lock.
+    ///
+    /// Transitions:
+    /// (none) -> Locked
+    ///
+    /// By default, the lamports are debited from the Owner account.
+    /// If a Sol Payer account is passed and has_payer is true, they are debited from it instead,
+    /// and the Owner account doesn't need to sign. This allows locking SOL as a gift for someone else.
+    ///
+    /// If the program is built with the allowlist feature, requires that the owner was
+    /// allowed by the admin with AddToAllowlist, and the AllowlistEntry account of the owner
+    /// comes before the Sol Payer account.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account (only `[]` if a Sol Payer account is passed)
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[]` System program account
+    ///   3. `[]` Config account
+    ///   4. `[WRITE]` Stats account
+    ///   5. `[]` AllowlistEntry account of the owner, only with the allowlist feature
+    ///   6. `[SIGNER, WRITE]` (Optional) Sol Payer account
+    CreateAndLock(CreateAndLock),
+
+    /// Set the same deadline on several SolLock accounts of an owner
+    /// Applies SetDeadline to every SolLock account passed, with the same requirements and transitions.
+    /// Fails without changing any account if any of them cannot be updated.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[]` Config account
+    ///   2..N. `[WRITE]` SolLock accounts, one per index in acc_indices
+    SetDeadlineMany(SetDeadlineMany),
+
+    /// Add Sol to a SolLock account and lock it
+    /// Requires that the program is not paused.
+    /// Freezes the fee_bps of the config into the account as the protocol fee of its unlocks.
+    /// Stores the commitment hash, which cannot change until the account is unlocked.
+    /// Requires that the account is in one of states {HasDeadline, ReadyUnlocked}
+    /// Equivalent to AddSol followed by Lock, so the account cannot be left in ReadyUnlocked
+    /// if locking fails.
+    ///
+    /// Transitions:
+    /// HasDeadline -> Locked
+    /// ReadyUnlocked -> Locked
+    ///
+    /// The lamports are debited from the Owner account.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[]` System program account
+    ///   3. `[]` Config account
+    ///   4. `[WRITE]` Stats account
+    AddSolAndLock(AddSolAndLock),
+
+    /// Point a human-readable name of the owner at a SolLock account
+    /// Requires that the SolLock account is initialized.
+    /// Creates the NameRecord account if it does not exist, otherwise repoints it.
+    /// Does not change the state of the SolLock account.
+    ///
+    /// The NameRecord account is the program address derived from
+    /// ["name", owner, label_hash], so each owner has their own namespace.
+    /// SolLock accounts count the NameRecords pointing at them, so AcceptOwner cannot
+    /// leave them dangling. Repointing a NameRecord requires the SolLock account it
+    /// pointed at, unless that account is closed.
+    ///
+    /// By default, the Owner account pays for a new NameRecord account.
+    /// If a Sol Payer account is passed and has_payer is true, it pays instead.
+    /// The Owner account signs either way, as the name is in its namespace.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account (only `[SIGNER]` if a Sol Payer account is passed)
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[WRITE]` NameRecord account
+    ///   3. `[]` System program account
+    ///   4. `[SIGNER, WRITE]` (Optional) Sol Payer account
+    ///   5. `[WRITE]` SolLock account the NameRecord pointed at, if repointing it
+    ///      (account 4 if no Sol Payer account is passed)
+    SetName(SetName),
+
+    /// Unlock a SolLock account and close it
+    /// Requires that the account is in state Locked
+    /// Requires that the current time is greater than or equal to the deadline.
+    /// Requires that the Sol Receiver account is not the SolLock account.
+    ///
+    /// Transitions:
+    /// Locked -> Uninitialized
+    ///     Transfers the locked lamports and the rent reserve out of the SolLock account
+    ///     and clears its data, so the account is closed.
+    ///
+    /// By default, the lamports will be credited to the Owner account.
+    /// If a Sol Receiver account is passed and has_receiver is true,
+    /// the lamports will be credited to the Sol Receiver account instead.
+    ///
+    /// If the account has a stored receiver, the lamports must be credited to it,
+    /// so it must be passed as the Sol Receiver account unless it is the owner.
+    ///
+    /// If the account has an unlock authority, the unlock authority must sign instead of the owner,
+    /// and nonce must match the nonce of the account, which is then incremented.
+    /// This prevents captured unlock authorizations from being replayed.
+    ///
+    /// The protocol fee frozen into the account when it was locked is deducted from the
+    /// unlocked lamports and credited to the Treasury account.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account (only `[WRITE]` if the account has an unlock authority)
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[WRITE]` Treasury account
+    ///   3. `[WRITE]` Stats account
+    ///   4. `[WRITE]` (Optional) Sol Receiver account, only if has_receiver is true
+    ///   5. `[SIGNER]` (Optional) Unlock authority account, only if the account has one
+    ///   6.. `[SIGNER]` (Optional) Multisig signer accounts, at least the threshold of them
+    UnlockAndClose(UnlockAndClose),
+
+    /// Propose a new owner for a SolLock account
+    /// Can be used in any initialized state and does not change the account state.
+    /// The transfer only completes once the proposed owner sends AcceptOwner,
+    /// so a mistyped pubkey can never take over the account.
+    /// Proposing None cancels a pending proposal.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ProposeOwner(ProposeOwner),
+
+    /// Accept the ownership of a SolLock account
+    /// Requires that the signer is the pending owner of the account
+    /// Requires that the account is not in state Staked
+    /// Requires that the recovery delay has passed if the guardians proposed the owner
+    /// Requires that no accounts derived from the SolLock address would be orphaned:
+    /// no position NFT, yield backend or split stake account is outstanding, no NameRecord
+    /// points at the account, and no contributions can still be refunded.
+    ///
+    /// Because SolLock accounts are derived from their owner, the account is moved
+    /// to the SolLock account of the new owner at new_acc_index, which must not exist yet.
+    /// The data and locked lamports are moved to the new account, which is funded by the new owner.
+    /// The old account is closed and the rest of its balance, its rent reserve, is returned to the old owner.
+    /// The state of the account is unchanged.
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[SIGNER, WRITE]` New owner account
+    ///   3. `[WRITE]` New SolLock account
+    ///   4. `[]` System program account
+    AcceptOwner(AcceptOwner),
+
+    /// Set the key allowed to unlock a SolLock account instead of the owner
+    /// Can be used in any initialized state and does not change the account state.
+    /// The unlocked lamports are still credited to the owner (or the Sol Receiver account),
+    /// so a cold wallet can own the account while a hot wallet unlocks it.
+    /// Setting None lets the owner unlock the account again.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetUnlockAuthority(SetUnlockAuthority),
+
+    /// Set the account unlocked lamports must be credited to
+    /// Requires that the account is not in one of states {Locked, Staked},
+    /// so the payout destination of a locked account is guaranteed.
+    /// Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetReceiver(SetReceiver),
+
+    /// Set a pointer to off-chain recovery documentation for a SolLock account,
+    /// such as the hash of an encrypted document or of a URI where it can be found.
+    /// Requires that the account is not in one of states {Locked, Staked}.
+    /// Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetRecoveryPointer(SetRecoveryPointer),
+
+    /// Set the tip paid to whoever unlocks a SolLock account with CrankUnlock.
+    /// Setting a tip allows anyone to unlock the account once its deadline has passed.
+    /// Requires that the account is not in one of states {Locked, Staked},
+    /// so the tip cannot change while the account is locked.
+    /// Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetCrankTip(SetCrankTip),
+
+    /// Unlock a SolLock account on behalf of its owner
+    /// Requires that the account has a crank tip, and the same as Unlock otherwise.
+    /// Requires that the beneficiary of the account, if any, cannot claim it yet: once the
+    /// owner missed a heartbeat, only the beneficiary or the owner can take the lamports.
+    /// The locked lamports are credited to the stored receiver, or to the Owner account
+    /// if the account has no receiver, except for the crank tip which is credited to the Cranker
+    /// and the protocol fee which is credited to the Treasury account.
+    ///
+    /// Transitions:
+    /// Locked -> Initialized
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Cranker account
+    ///   1. `[WRITE]` Owner account
+    ///   2. `[WRITE]` SolLock account
+    ///   3. `[WRITE]` Treasury account
+    ///   4. `[WRITE]` Stats account
+    ///   5. `[WRITE]` (Optional) Receiver account, required if the account has a receiver
+    CrankUnlock(CrankUnlock),
+
+    /// Lock a SolLock account again, e.g. after it was unlocked
+    /// Requires that the program is not paused.
+    /// Freezes the fee_bps of the config into the account as the protocol fee of its unlocks.
+    /// Stores the commitment hash, which cannot change until the account is unlocked.
+    /// Requires that the account is not in one of states {Locked, Staked}.
+    /// Equivalent to AddSol, SetDeadline and Lock in one step, keeping the existing account
+    /// and its settings, such as its receiver and unlock authority.
+    /// Requires that the account has funds after adding the lamports.
+    ///
+    /// Transitions:
+    /// Initialized -> Locked
+    /// HasFunds -> Locked
+    /// HasDeadline -> Locked
+    /// ReadyUnlocked -> Locked
+    ///
+    /// The lamports are debited from the Owner account.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[]` System program account
+    ///   3. `[]` Config account
+    ///   4. `[WRITE]` Stats account
+    Relock(Relock),
+
+    /// Make a SolLock account extend itself when it isn't unlocked in time
+    /// If the account is not unlocked within AUTO_EXTEND_GRACE_PERIOD after its deadline,
+    /// the deadline rolls forward by the auto-extension period, as many times as needed,
+    /// the next time the account is unlocked.
+    /// Requires that the period is positive if set.
+    /// Requires that the account is not in one of states {Locked, Staked}.
+    /// Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetAutoExtend(SetAutoExtend),
+
+    /// Make the lamports of a SolLock account vest linearly until its deadline
+    /// Once locked, the vested part of the lamports can be withdrawn before the deadline
+    /// with Unlock, which withdraws all vested lamports, or RemoveSol, which withdraws
+    /// a given amount of them. The account stays Locked until the deadline.
+    /// If a cliff is set, nothing can be withdrawn before it, and the lamports that vested
+    /// since the vesting start can all be withdrawn at the cliff.
+    /// Requires that the cliff is not set without a vesting start, and that it is not before
+    /// the vesting start nor after the deadline, if the account has one.
+    /// Requires that the account is not in one of states {Locked, Staked}.
+    /// Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetVesting(SetVesting),
+
+    /// Set the maximum number of lamports a SolLock account can hold
+    /// Applies to AddSol, AddSolAndLock and Relock. By default, deposits that would exceed
+    /// the cap fail. If refund_excess is true, only the part of the deposit up to the cap
+    /// is debited from the payer instead, so the excess stays with the payer.
+    /// Lowering the cap below the current balance only prevents further deposits.
+    /// Requires that the account is not in one of states {Locked, Staked, Disputed},
+    /// so the cap cannot change while the lamports are locked.
+    /// Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetDepositCap(SetDepositCap),
+
+    /// Unlock a SolLock account into a claim ticket for the receiver
+    /// Same as Unlock, except that the lamports are credited to the claim ticket of the receiver
+    /// instead of the receiver itself. The receiver then claims them with ClaimUnlocked,
+    /// which suits receivers that are programs or smart wallets with deposit hooks.
+    /// Creates the claim ticket if it does not exist yet, paid for by the Payer account.
+    ///
+    /// Transitions:
+    /// Locked -> Initialized
+    /// Locked -> Locked
+    ///     Occurs when the account is vesting and the current time is less than the deadline.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account (only `[]` if the account has an unlock authority)
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[]` Receiver account, which must be the stored receiver if the account has one
+    ///   3. `[WRITE]` Claim ticket account, derived from the Receiver account
+    ///   4. `[SIGNER, WRITE]` Payer account
+    ///   5. `[]` System program account
+    ///   6. `[WRITE]` Treasury account
+    ///   7. `[WRITE]` Stats account
+    ///   8. `[SIGNER]` (Optional) Unlock authority account
+    ///   9.. `[SIGNER]` (Optional) Multisig signer accounts, at least the threshold of them
+    UnlockToClaim(UnlockToClaim),
+
+    /// Claim the lamports unlocked into a claim ticket
+    /// Transfers all claimable lamports of the claim ticket to the Receiver account.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Receiver account
+    ///   1. `[WRITE]` Claim ticket account, derived from the Receiver account
+    ClaimUnlocked(ClaimUnlocked),
+
+    /// Make a SolLock account stream its lamports to its receiver
+    /// From the time the account is locked, stream_rate lamports per second become claimable
+    /// with Claim, until all lamports have streamed. The rest can still be unlocked
+    /// at the deadline as usual.
+    /// Requires that the account does not vest.
+    /// Requires that the account is not in one of states {Locked, Staked}.
+    /// Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetStream(SetStream),
+
+    /// Claim the lamports a streaming SolLock account has streamed and that weren't claimed yet
+    /// Requires that the account is in state Locked and streams.
+    ///
+    /// Transitions:
+    /// Locked -> Locked
+    /// Locked -> Initialized
+    ///     Occurs when all lamports of the account have streamed and are claimed
+    ///
+    /// The protocol fee frozen into the account when it was locked is deducted from the
+    /// claimed lamports and credited to the Treasury account.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Receiver account, the stored receiver or the owner if there is none
+    ///   1. `[]` Owner account
+    ///   2. `[WRITE]` SolLock account
+    ///   3. `[WRITE]` Treasury account
+    ///   4. `[WRITE]` Stats account
+    Claim(Claim),
+
+    /// Turn a SolLock account into a hash time-lock
+    /// Before the deadline, the counterparty can claim all lamports of the locked account
+    /// with ClaimWithPreimage by revealing the preimage of the hash lock. After the deadline,
+    /// the owner can unlock the lamports as usual, which refunds them.
+    /// Requires that hash_lock and counterparty are either both set or both None.
+    /// Requires that the account is not in one of states {Locked, Staked}.
+    /// Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetHashLock(SetHashLock),
+
+    /// Claim the lamports of a hash-locked SolLock account as its counterparty
+    /// Requires that the account is in state Locked and has a hash lock.
+    /// Requires that the current time is less than the deadline,
+    /// and that the SHA-256 hash of the preimage matches the hash lock.
+    ///
+    /// Removes the hash lock, so it cannot be reused.
+    ///
+    /// The protocol fee frozen into the account when it was locked is deducted from the
+    /// claimed lamports and credited to the Treasury account.
+    ///
+    /// Transitions:
+    /// Locked -> Initialized
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Counterparty account
+    ///   1. `[]` Owner account
+    ///   2. `[WRITE]` SolLock account
+    ///   3. `[WRITE]` Treasury account
+    ///   4. `[WRITE]` Stats account
+    ClaimWithPreimage(ClaimWithPreimage),
+
+    /// Turn a SolLock account into a two-party escrow
+    /// Before the deadline, the counterparty can accept the escrow by claiming all lamports
+    /// of the locked account with ClaimEscrow. After the deadline, if the counterparty has
+    /// not claimed them, the owner can take them back with RefundEscrow.
+    /// If an arbiter is set, either party can raise a dispute before the deadline, after which
+    /// only the arbiter can direct the lamports to either party during the dispute period.
+    /// Removes any hash lock of the account.
+    /// Requires that arbiter and dispute_period are either both set or both None,
+    /// that dispute_period is positive, and that an arbiter is only set with a counterparty.
+    /// Requires that the account is not in one of states {Locked, Staked, Disputed}.
+    /// Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetEscrow(SetEscrow),
+
+    /// Claim the lamports of an escrow SolLock account as its counterparty
+    /// Requires that the account is in state Locked and is an escrow.
+    /// Requires that the current time is less than the deadline.
+    ///
+    /// Removes the escrow.
+    ///
+    /// The protocol fee frozen into the account when it was locked is deducted from the
+    /// claimed lamports and credited to the Treasury account.
+    ///
+    /// Transitions:
+    /// Locked -> Initialized
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Counterparty account
+    ///   1. `[]` Owner account
+    ///   2. `[WRITE]` SolLock account
+    ///   3. `[WRITE]` Treasury account
+    ///   4. `[WRITE]` Stats account
+    ClaimEscrow(ClaimEscrow),
+
+    /// Take back the lamports of an escrow SolLock account the counterparty has not claimed
+    /// Requires that the account is in one of states {Locked, Disputed} and is an escrow.
+    /// Requires that the current time is greater than or equal to the deadline.
+    ///
+    /// Removes the escrow.
+    ///
+    /// The protocol fee frozen into the account when it was locked is deducted from the
+    /// refunded lamports and credited to the Treasury account.
+    ///
+    /// Transitions:
+    /// Locked -> Initialized
+    /// Disputed -> Initialized
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[WRITE]` Treasury account
+    ///   3. `[WRITE]` Stats account
+    RefundEscrow(RefundEscrow),
+
+    /// Dispute an escrow SolLock account as its owner or counterparty
+    /// Requires that the account is in state Locked and is an escrow with an arbiter.
+    /// Requires that the current time is less than the deadline.
+    ///
+    /// Sets the deadline to the current time plus the dispute period. Until then, only the
+    /// arbiter can release the lamports with Resolve. After it, the owner can take them back
+    /// with RefundEscrow.
+    ///
+    /// Transitions:
+    /// Locked -> Disputed
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner or counterparty account
+    ///   1. `[]` Owner account
+    ///   2. `[WRITE]` SolLock account
+    RaiseDispute(RaiseDispute),
+
+    /// Direct the lamports of a disputed escrow SolLock account to either party as its arbiter
+    /// Requires that the account is in state Disputed.
+    /// Requires that the current time is less than the deadline.
+    ///
+    /// Removes the escrow.
+    ///
+    /// The protocol fee frozen into the account when it was locked is deducted from the
+    /// released lamports and credited to the Treasury account.
+    ///
+    /// Transitions:
+    /// Disputed -> Initialized
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Arbiter account
+    ///   1. `[WRITE]` Owner account
+    ///   2. `[WRITE]` SolLock account
+    ///   3. `[WRITE]` Counterparty account
+    ///   4. `[WRITE]` Treasury account
+    ///   5. `[WRITE]` Stats account
+    Resolve(Resolve),
+
+    /// Allow or forbid anyone to contribute lamports to a SolLock account with Contribute,
+    /// turning it into a crowdfunding vault
+    /// Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetOpenDeposits(SetOpenDeposits),
+
+    /// Add Sol to an open-deposit SolLock account as any account
+    /// Requires that the program is not paused.
+    /// Requires that the account allows open deposits.
+    /// Requires that the current time is less than the deadline if the account has a raise target.
+    /// Applies the deposit cap and state transitions of AddSol,
+    /// so the deposit cap is the maximum raise of a crowdfund.
+    ///
+    /// The total contributed by the contributor is recorded in their Contribution account,
+    /// derived from the SolLock account and the contributor, which is created on their
+    /// first contribution and paid for by the contributor.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Contributor account
+    ///   1. `[]` Owner account
+    ///   2. `[WRITE]` SolLock account
+    ///   3. `[WRITE]` Contribution account
+    ///   4. `[]` System program account
+    ///   5. `[]` Config account
+    ///   6. `[WRITE]` Stats account
+    Contribute(Contribute),
+
+    /// Set the raise target and refund period of a crowdfunding SolLock account
+    /// If contributors raised less than the target by the deadline, each of them can get
+    /// their contribution back with RefundContribution until the end of the refund period,
+    /// and the account cannot be unlocked until then.
+    /// Requires that raise_target and refund_period are either both set or both None,
+    /// and that refund_period is positive.
+    /// Requires that the account is not in one of states {Locked, Staked, Disputed}.
+    /// Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetRaiseTarget(SetRaiseTarget),
+
+    /// Get back the lamports contributed to a crowdfund that missed its raise target
+    /// Requires that the account is in state Locked, missed its raise target,
+    /// and that the current time is within the refund period after the deadline.
+    ///
+    /// Transitions:
+    /// Locked -> Locked (if lamports remain)
+    /// Locked -> Initialized (if no lamports remain)
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Contributor account
+    ///   1. `[]` Owner account
+    ///   2. `[WRITE]` SolLock account
+    ///   3. `[WRITE]` Contribution account
+    ///   4. `[WRITE]` Stats account
+    RefundContribution(RefundContribution),
+
+    /// Set up a dead man's switch on a SolLock account
+    /// The owner proves they are still active by sending Heartbeat instructions. If no
+    /// heartbeat arrives within heartbeat_interval after the deadline or the last heartbeat,
+    /// whichever is later, the beneficiary can claim the lamports with ClaimAsBeneficiary.
+    /// The owner can still unlock the account as usual after the deadline.
+    /// Requires that beneficiary and heartbeat_interval are either both set or both None,
+    /// and that heartbeat_interval is positive.
+    /// Requires that the account is not in one of states {Locked, Staked, Disputed}.
+    /// Clears the last heartbeat. Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetBeneficiary(SetBeneficiary),
+
+    /// Prove that the owner of a SolLock account with a dead man's switch is still active
+    /// Requires that the account has a beneficiary.
+    /// Sets the last heartbeat to the current time. Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    Heartbeat(Heartbeat),
+
+    /// Claim the lamports of a SolLock account whose owner stopped sending heartbeats
+    /// Requires that the account is in state Locked and has a beneficiary.
+    /// Requires that the current time is greater than or equal to the deadline or the last
+    /// heartbeat, whichever is later, plus the heartbeat interval.
+    ///
+    /// If the account has beneficiary shares, the lamports are split between the share
+    /// accounts instead, and the last share account receives the rounding remainder.
+    ///
+    /// If the account has a multisig, at least threshold of its signers must co-sign.
+    ///
+    /// The protocol fee frozen into the account when it was locked is deducted from the
+    /// claimed lamports before they are split and credited to the Treasury account.
+    ///
+    /// Transitions:
+    /// Locked -> Initialized
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Beneficiary account
+    ///   1. `[]` Owner account
+    ///   2. `[WRITE]` SolLock account
+    ///   3. `[WRITE]` Treasury account
+    ///   4. `[WRITE]` Stats account
+    ///   5.. `[WRITE]` Share accounts, in the order of the beneficiary shares, if any
+    ///   5+S.. `[SIGNER]` (Optional) Multisig signer accounts, after the S share accounts
+    ClaimAsBeneficiary(ClaimAsBeneficiary),
+
+    /// Split the lamports claimed by the beneficiary of a SolLock account between accounts
+    /// Requires that the shares are distinct, non-zero, at most MAX_BENEFICIARY_SHARES,
+    /// and sum to TOTAL_SHARE_BPS basis points, or that there are no shares.
+    /// Requires that the account is not in one of states {Locked, Staked, Disputed}.
+    /// Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetBeneficiaryShares(SetBeneficiaryShares),
+
+    /// Require M of N multisig signers to co-sign every unlock of a SolLock account
+    /// Applies to Unlock, UnlockAndClose, UnlockToClaim, MintPosition, ClaimAsBeneficiary and
+    /// RemoveSol of vested lamports, which then need at least threshold of the signers as
+    /// signer accounts after all their other accounts.
+    /// Requires that the signers are distinct and at most MAX_MULTISIG_SIGNERS, and that
+    /// threshold is between 1 and the number of signers, or that there are neither.
+    /// Requires that the account is not in one of states {Locked, Staked, Disputed}.
+    /// Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetMultisig(SetMultisig),
+
+    /// Set the guardians that can recover a SolLock account if its owner key is lost
+    /// Requires that the guardians are distinct and at most MAX_GUARDIANS, that threshold
+    /// is between 1 and the number of guardians, and that recovery_delay is positive,
+    /// or that there are neither.
+    /// Requires that no recovery is pending.
+    /// Can be used in any initialized state and does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetGuardians(SetGuardians),
+
+    /// Propose a new owner for a SolLock account on behalf of its guardians
+    /// Requires that at least threshold of the guardians sign.
+    /// The new owner becomes the pending owner, and can only send AcceptOwner once
+    /// recovery_delay has passed. Until then, the owner can cancel it with VetoRecovery.
+    /// Does not need the owner's signature and does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2.. `[SIGNER]` Guardian accounts, at least the threshold of them
+    InitiateRecovery(InitiateRecovery),
+
+    /// Cancel a recovery started by the guardians of a SolLock account
+    /// Requires that a recovery is pending.
+    /// Clears the pending owner and does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    VetoRecovery(VetoRecovery),
+
+    /// Let a SolLock account be unlocked once a Pyth price reaches a target
+    /// The price target is checked with CheckPrice. Once it was reached, the account can be
+    /// unlocked before its deadline, or only after it if requires_deadline is true.
+    /// Requires that price_feed and price_target are set together, and that
+    /// requires_deadline is false if they are not set.
+    /// Requires that the account is not in one of states {Locked, Staked, Disputed}.
+    /// Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetPriceCondition(SetPriceCondition),
+
+    /// Check the price target of a SolLock account against its Pyth price account
+    /// Anyone can send it, and it records that the price target was reached.
+    /// Requires that the account is in state Locked and has a price condition.
+    /// Requires that the price account is owned by the Pyth oracle program.
+    /// Requires that the price is being traded, was published at most MAX_PRICE_AGE
+    /// seconds ago and is at least the price target.
+    /// Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[]` Pyth price account
+    CheckPrice(CheckPrice),
+
+    /// Set the epoch a SolLock account is locked until, such as the end of a staking period
+    /// Once locked, the account can only be unlocked from that epoch on, in addition to
+    /// the other unlock conditions.
+    /// Requires that the account is not in one of states {Locked, Staked, Disputed}.
+    /// Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetEpochDeadline(SetEpochDeadline),
+
+    /// Set deadline on a SolLock account to a number of seconds after the current on-chain time
+    /// Same as SetDeadline otherwise, including its requirements and transitions.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[]` Config account
+    SetDeadlineRelative(SetDeadlineRelative),
+
+    /// Create the Config account holding the program-wide settings
+    /// Until it exists, the default config applies: no fee, MAX_LOCK_DURATION,
+    /// MIN_LOCK_LAMPORTS and not paused.
+    /// Also creates the Treasury account, which collects the protocol fees of unlocks,
+    /// and the Stats account, which counts the locks made from then on.
+    /// Requires that the signer is the upgrade authority of the program, which pays for the accounts.
+    /// Requires that the admin is set, that fee_bps is at most 10000 and that
+    /// max_lock_duration is positive.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Upgrade authority account
+    ///   1. `[WRITE]` Config account
+    ///   2. `[WRITE]` Treasury account
+    ///   3. `[WRITE]` Stats account
+    ///   4. `[]` ProgramData account of the program
+    ///   5. `[]` System program account
+    InitializeConfig(InitializeConfig),
+
+    /// Replace the program-wide settings
+    /// Requires that the signer is the admin of the current config.
+    /// Requires the same of the new config as InitializeConfig.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Admin account
+    ///   1. `[WRITE]` Config account
+    UpdateConfig(UpdateConfig),
+
+    /// Withdraw protocol fees collected by the treasury
+    /// Requires that the signer is the admin of the config.
+    /// Requires that lamports is positive and leaves the treasury rent-exempt.
+    /// Requires that the Destination account is not owned by the program.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Admin account
+    ///   1. `[]` Config account
+    ///   2. `[WRITE]` Treasury account
+    ///   3. `[WRITE]` Destination account
+    WithdrawTreasury(WithdrawTreasury),
+
+    /// Allow an owner to create SolLock accounts when the program is built with the
+    /// allowlist feature
+    /// Requires that the signer is the admin of the config, which pays for the AllowlistEntry account.
+    /// Requires that the owner is not allowed yet.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Admin account
+    ///   1. `[]` Config account
+    ///   2. `[WRITE]` AllowlistEntry account of the owner
+    ///   3. `[]` System program account
+    AddToAllowlist(AddToAllowlist),
+
+    /// Disallow an owner from creating SolLock accounts when the program is built with the
+    /// allowlist feature
+    /// Requires that the signer is the admin of the config.
+    /// Closes the AllowlistEntry account, crediting its lamports to the admin.
+    /// Existing SolLock accounts of the owner are not affected.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Admin account
+    ///   1. `[]` Config account
+    ///   2. `[WRITE]` AllowlistEntry account of the owner
+    RemoveFromAllowlist(RemoveFromAllowlist),
+
+    /// Create a new SolLock account at the next index of the owner
+    /// Same as CreateAccount, except that the index is the next_index of the IndexCounter
+    /// account of the owner, or 0 if it does not exist yet, so clients don't pick indexes.
+    /// Creates the IndexCounter account if it does not exist, paid for like the SolLock account,
+    /// and increments its next_index.
+    /// Sets the assigned index as return data, as 8 little-endian bytes.
+    ///
+    /// The SolLock account must be derived from the index the counter holds when the
+    /// instruction runs, so the instruction fails if another account was created in between.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account (only `[]` if a Sol Payer account is passed)
+    ///   1. `[WRITE]` IndexCounter account of the owner
+    ///   2. `[WRITE]` SolLock account
+    ///   3. `[]` System program account
+    ///   4. `[]` AllowlistEntry account of the owner, only with the allowlist feature
+    ///   5. `[SIGNER, WRITE]` (Optional) Sol Payer account
+    CreateNextAccount(CreateNextAccount),
+
+    /// Create a new SolLock account identified by a label of the owner
+    /// Same as CreateAccount, except that the index is derived from the label with
+    /// get_label_acc_index, and the label is stored in the account. The account is then used
+    /// like any other, with that index.
+    /// Requires that the label is between 1 and MAX_LABEL_LEN bytes of UTF-8.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account (only `[]` if a Sol Payer account is passed)
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[]` System program account
+    ///   3. `[]` AllowlistEntry account of the owner, only with the allowlist feature
+    ///   4. `[SIGNER, WRITE]` (Optional) Sol Payer account
+    CreateLabeledAccount(CreateLabeledAccount),
+
+    /// Migrate a SolLock account from an older layout to the current one
+    /// Requires that the account does not already use the current layout.
+    /// Grows the account to SOL_LOCK_ACCOUNT_SIZE if needed, with the Payer account paying for
+    /// the additional rent, and upgrades its data in place. New fields take their default values,
+    /// except the rent reserve and the bump seed of the account address, which are recorded.
+    /// Anyone can migrate an account, as migrating doesn't change what it holds.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Payer account
+    ///   1. `[]` Owner account
+    ///   2. `[WRITE]` SolLock account
+    ///   3. `[]` System program account
+    Migrate(Migrate),
+
+    /// Grow a SolLock account created with an older, smaller layout to SOL_LOCK_ACCOUNT_SIZE
+    /// Requires that the account is smaller than SOL_LOCK_ACCOUNT_SIZE.
+    /// The new bytes are zeroed, apart from the recorded rent reserve and bump seed of the
+    /// account address, and the data is otherwise left as is, so accounts from an
+    /// older layout version still need Migrate afterwards.
+    /// NameRecords set before the Realloc are not counted in the grown account.
+    ///
+    /// The additional rent is debited from the Owner account, so the locked lamports
+    /// are untouched.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[]` System program account
+    Realloc(Realloc),
+
+    /// Get the version of the program
+    /// Sets the return data to the borsh-serialized VersionInfo, so clients can check which
+    /// features a deployment supports, e.g. by simulating the instruction.
+    ///
+    /// # Account references
+    ///   (none)
+    GetVersion(GetVersion),
+
+    /// Create the receipt token mint of the program
+    /// Requires that the signer is the admin of the config, which pays for the mint account.
+    /// The mint has RECEIPT_MINT_DECIMALS decimals, no freeze authority, and is its own
+    /// mint authority, so only MintReceipt can mint receipt tokens.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Admin account
+    ///   1. `[]` Config account
+    ///   2. `[WRITE]` Receipt mint account
+    ///   3. `[]` SPL Token program account
+    ///   4. `[]` System program account
+    InitializeReceiptMint(InitializeReceiptMint),
+
+    /// Mint receipt tokens 1:1 against the locked lamports of a SolLock account
+    /// Requires that the account is in one of states {Locked, Staked}.
+    /// Requires that no receipt token is outstanding for the account.
+    /// Lamports added to the account afterwards are not covered by the receipt.
+    ///
+    /// While receipt tokens are outstanding, no lamports can leave the account, e.g. with
+    /// Unlock or Claim, until they are burned with BurnReceipt.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[WRITE]` Receipt mint account
+    ///   3. `[WRITE]` Destination token account of the receipt mint
+    ///   4. `[]` SPL Token program account
+    MintReceipt(MintReceipt),
+
+    /// Burn the receipt tokens minted for a SolLock account, so its lamports can leave it again
+    /// Requires that receipt tokens were minted for the account.
+    /// Burns exactly the number of tokens minted with MintReceipt.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account, which must be the authority of the source token account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[WRITE]` Receipt mint account
+    ///   3. `[WRITE]` Source token account of the receipt mint
+    ///   4. `[]` SPL Token program account
+    BurnReceipt(BurnReceipt),
+
+    /// Mint a position NFT for a SolLock account to the owner, making the position transferable
+    /// Requires that the account is in one of states {Locked, Staked}.
+    /// Requires that no position NFT is outstanding for the account.
+    /// Creates the position mint of the account with 0 decimals if it does not exist yet, and
+    /// the associated token account of the owner for it, both paid for by the owner.
+    ///
+    /// While the NFT is outstanding, only its holder can unlock the account, with UnlockPosition.
+    /// Minting hands over the unlock, so it needs the same approval as Unlock: the unlock
+    /// authority of the account if it has one, and the multisig signers if it has a multisig.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[WRITE]` Position mint account, derived from the SolLock account
+    ///   3. `[WRITE]` Associated token account of the owner for the position mint
+    ///   4. `[]` SPL Token program account
+    ///   5. `[]` SPL Associated Token Account program account
+    ///   6. `[]` System program account
+    ///   7. `[SIGNER]` (Optional) Unlock authority account, only if the account has one
+    ///   8.. `[SIGNER]` (Optional) Multisig signer accounts, at least the threshold of them
+    MintPosition(MintPosition),
+
+    /// Unlock a SolLock account as the holder of its position NFT
+    /// Same as Unlock, except that the lamports are credited to the holder, which must
+    /// sign and hold the NFT in the token account. The unlock authority, multisig
+    /// and stored receiver of the account don't apply.
+    /// Burns the NFT once the account is unlocked. A partial withdrawal of vested lamports
+    /// leaves it outstanding.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Holder account
+    ///   1. `[]` Owner account
+    ///   2. `[WRITE]` SolLock account
+    ///   3. `[WRITE]` Position mint account
+    ///   4. `[WRITE]` Token account of the holder holding the position NFT
+    ///   5. `[]` SPL Token program account
+    ///   6. `[WRITE]` Treasury account
+    ///   7. `[WRITE]` Stats account
+    UnlockPosition(UnlockPosition),
+
+    /// Get the voting power of a SolLock account
+    /// The voting power is the locked lamports weighted by the remaining lock time, relative to
+    /// MAX_VOTING_POWER_DURATION, so it decays to 0 at the deadline. Accounts that are not in
+    /// one of states {Locked, Staked} have no voting power.
+    /// Sets the return data to the borsh-serialized u64 voting power, and snapshots it in the
+    /// account together with the current time, so governance programs can read it from there.
+    /// Anyone can take a snapshot.
+    ///
+    /// # Account references
+    ///   0. `[]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    GetVotingPower(GetVotingPower),
+
+    /// Create the SPL Governance voter weight record of an owner for a realm
+    /// The record is owned by the program and starts with a weight of 0, so a realm
+    /// configured with the program as its voter weight addin can read it once it is updated
+    /// with UpdateVoterWeightRecord.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account
+    ///   1. `[WRITE]` Voter weight record account, derived from the realm, mint and owner
+    ///   2. `[]` System program account
+    CreateVoterWeightRecord(CreateVoterWeightRecord),
+
+    /// Update the voter weight record of an owner to the voting power of one of their
+    /// SolLock accounts
+    /// The weight expires at the end of the current slot, so it must be updated in the same
+    /// transaction as the governance action that uses it. Anyone can update a record.
+    ///
+    /// # Account references
+    ///   0. `[]` Owner account
+    ///   1. `[]` SolLock account
+    ///   2. `[WRITE]` Voter weight record account
+    UpdateVoterWeightRecord(UpdateVoterWeightRecord),
+
+    /// Withdraw the stake rewards of a staked SolLock account to the owner
+    /// Requires that the account is in state Staked
+    /// Requires that the SolLock account is the withdrawer authority of the stake account
+    ///
+    /// The rewards are the lamports of the stake account above the locked principal.
+    /// Only the part of them that is not delegated can be withdrawn, so the principal stays
+    /// locked and staked until the deadline.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account
+    ///   1. `[]` SolLock account
+    ///   2. `[WRITE]` Stake account
+    ///   3. `[]` Clock sysvar account
+    ///   4. `[]` Stake history sysvar account
+    ///   5. `[]` Stake program account
+    WithdrawRewards(WithdrawRewards),
+
+    /// Compound the stake rewards of a staked SolLock account into its locked principal
+    /// Requires that the account is in state Staked
+    /// Requires that the account has a crank tip, which allows anyone to compound it
+    /// Requires that the SolLock account is the withdrawer authority of the stake account
+    ///
+    /// The stake program adds inflation rewards to the delegation as they are paid, so they
+    /// keep earning. Compounding locks them, together with any lamports that were sent to the
+    /// stake account, as part of the principal, so WithdrawRewards can no longer take them
+    /// and they count towards the voting power of the account. Lamports that are not
+    /// delegated yet are delegated when the account is next staked.
+    ///
+    /// The crank tip is withdrawn from the undelegated rewards and credited to the Cranker.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Cranker account
+    ///   1. `[]` Owner account
+    ///   2. `[WRITE]` SolLock account
+    ///   3. `[WRITE]` Stake account
+    ///   4. `[]` Clock sysvar account
+    ///   5. `[]` Stake history sysvar account
+    ///   6. `[]` Stake program account
+    ///   7. `[WRITE]` Stats account
+    Compound(Compound),
+
+    /// Restrict the vote accounts the locked lamports of a SolLock account can be staked with
+    /// Requires that the validators are distinct and at most MAX_ALLOWED_VALIDATORS.
+    /// An empty list allows any vote account.
+    /// Requires that the account is not in one of states {Locked, Staked, Disputed},
+    /// so the delegation policy cannot change while the account is locked.
+    /// Does not change the account state.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    SetValidatorAllowlist(SetValidatorAllowlist),
+
+    /// Split part of the stake of a SolLock account into its split stake account
+    /// Requires that the account is in state Staked
+    /// Requires that the account has no split stake account yet
+    /// Requires that lamports is positive and less than the lamports of the stake account,
+    /// and that both accounts stay above the rent-exempt reserve and minimum delegation
+    ///
+    /// The split stake account keeps the delegation and the SolLock account as its authority,
+    /// so it can be unstaked on its own with Unstake, e.g. to unlock part of the lamports
+    /// right at the deadline. The locked lamports don't change.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[WRITE]` Stake account
+    ///   3. `[WRITE]` Split stake account, derived from the SolLock account
+    ///   4. `[]` Stake program account
+    ///   5. `[]` System program account
+    SplitStake(SplitStake),
+
+    /// Merge a secondary stake account into the stake account of a SolLock account
+    /// Requires that the account is in state Staked
+    /// Requires that the SolLock account is the staker and withdrawer authority of both
+    /// stake accounts, and that the stake program can merge them, e.g. that they are
+    /// delegated to the same vote account
+    ///
+    /// Merging the split stake account undoes SplitStake. Any other stake account, e.g. one
+    /// funded by a later deposit and authorized to the SolLock account, adds its lamports to
+    /// the locked lamports. Either way, the account keeps tracking a single stake account.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[WRITE]` Stake account
+    ///   3. `[WRITE]` Source stake account
+    ///   4. `[]` Clock sysvar account
+    ///   5. `[]` Stake history sysvar account
+    ///   6. `[]` Stake program account
+    MergeStake(MergeStake),
+
+    /// Convert the locked lamports of a SolLock account into a liquid staking token, as an
+    /// alternative to Stake without its epoch cooldown
+    /// Requires that the account is in state Locked and not converted yet
+    /// Requires that the stake pool is owned by the SPL Stake Pool program
+    ///
+    /// Deposits the locked lamports into the stake pool, minting its pool tokens to the
+    /// associated token account of the SolLock account, which is created and paid for by the
+    /// owner if needed. The locked lamports must be redeemed with RedeemLst before any of them
+    /// can leave the account.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[WRITE]` Funding account, derived from the SolLock account
+    ///   3. `[WRITE]` Stake pool account
+    ///   4. `[]` Stake pool withdraw authority account
+    ///   5. `[WRITE]` Stake pool reserve stake account
+    ///   6. `[WRITE]` Stake pool manager fee account
+    ///   7. `[WRITE]` Pool mint account
+    ///   8. `[WRITE]` Associated token account of the SolLock account for the pool mint
+    ///   9. `[]` SPL Stake Pool program account
+    ///   10. `[]` SPL Token program account
+    ///   11. `[]` SPL Associated Token Account program account
+    ///   12. `[]` System program account
+    ConvertToLst(ConvertToLst),
+
+    /// Redeem the liquid staking tokens of a SolLock account for SOL, which stays locked
+    /// Requires that the account was converted with ConvertToLst
+    ///
+    /// Withdraws SOL from the reserve of the stake pool for all pool tokens of the account.
+    /// The locked lamports become the redeemed lamports, including the rewards earned while
+    /// converted, net of the fees of the stake pool.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[WRITE]` Stake pool account
+    ///   3. `[]` Stake pool withdraw authority account
+    ///   4. `[WRITE]` Stake pool reserve stake account
+    ///   5. `[WRITE]` Stake pool manager fee account
+    ///   6. `[WRITE]` Pool mint account
+    ///   7. `[WRITE]` Associated token account of the SolLock account for the pool mint
+    ///   8. `[]` Clock sysvar account
+    ///   9. `[]` Stake history sysvar account
+    ///   10. `[]` Stake program account
+    ///   11. `[]` SPL Stake Pool program account
+    ///   12. `[]` SPL Token program account
+    RedeemLst(RedeemLst),
+
+    /// Lend the locked lamports of a SolLock account out from a lending reserve
+    /// Requires that the account is in state Locked, and not staked, converted or lent out yet
+    /// Requires that the reserve is owned by the lending program and lends out wrapped SOL
+    /// Requires that the reserve was refreshed earlier in the same transaction
+    ///
+    /// Wraps the locked lamports and deposits them into the reserve, minting its collateral
+    /// tokens to the associated token account of the SolLock account. The collateral tokens
+    /// are never deposited into an obligation, so the locked lamports cannot be borrowed
+    /// against or liquidated. The owner pays for the token accounts of the SolLock account and
+    /// is refunded the rent of the wrapped SOL account. The locked lamports must be withdrawn
+    /// with WithdrawFromLending before any of them can leave the account.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[WRITE]` Reserve account
+    ///   3. `[WRITE]` Reserve liquidity supply account
+    ///   4. `[WRITE]` Reserve collateral mint account
+    ///   5. `[]` Lending market account
+    ///   6. `[]` Lending market authority account
+    ///   7. `[WRITE]` Associated token account of the SolLock account for wrapped SOL
+    ///   8. `[WRITE]` Associated token account of the SolLock account for the collateral mint
+    ///   9. `[]` Wrapped SOL mint account
+    ///   10. `[]` Clock sysvar account
+    ///   11. `[]` Lending program account
+    ///   12. `[]` SPL Token program account
+    ///   13. `[]` SPL Associated Token Account program account
+    ///   14. `[]` System program account
+    DepositToLending(DepositToLending),
+
+    /// Withdraw the locked lamports of a SolLock account from a lending reserve, which stay locked
+    /// Requires that the account was lent out with DepositToLending
+    /// Requires that the collateral tokens are still held by the SolLock account, undelegated
+    /// Requires that the reserve was refreshed earlier in the same transaction
+    ///
+    /// Redeems all collateral tokens of the account and unwraps the SOL received. The locked
+    /// lamports become the redeemed lamports, including the interest earned while lent out.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[WRITE]` Reserve account
+    ///   3. `[WRITE]` Reserve liquidity supply account
+    ///   4. `[WRITE]` Reserve collateral mint account
+    ///   5. `[]` Lending market account
+    ///   6. `[]` Lending market authority account
+    ///   7. `[WRITE]` Associated token account of the SolLock account for wrapped SOL
+    ///   8. `[WRITE]` Associated token account of the SolLock account for the collateral mint
+    ///   9. `[]` Wrapped SOL mint account
+    ///   10. `[]` Clock sysvar account
+    ///   11. `[]` Lending program account
+    ///   12. `[]` SPL Token program account
+    ///   13. `[]` SPL Associated Token Account program account
+    ///   14. `[]` System program account
+    WithdrawFromLending(WithdrawFromLending),
+
+    /// Compare the balance of a SolLock account with its locked lamports and rent reserve
+    /// Can be used in any initialized state.
+    ///
+    /// Logs any discrepancy, such as lamports transferred directly to the account, which
+    /// are not tracked by it. Depending on the action, the untracked lamports are then left
+    /// as they are, swept to the owner, or absorbed into the account as a deposit.
+    /// Absorbing requires that the locked lamports are not deposited into a yield backend,
+    /// and follows the transitions and deposit cap of AddSol.
+    /// Nothing is swept or absorbed if the account holds fewer lamports than it tracks.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER, WRITE]` Owner account
+    ///   1. `[WRITE]` SolLock account
+    ///   2. `[]` Config account
+    ///   3. `[WRITE]` Stats account
+    Reconcile(Reconcile),
+}
+
+/// Create a CreateAccount instruction
+pub fn create_account(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    memo: &str,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    #[allow(unused_mut)]
+    let mut accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(sol_lock_account, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    #[cfg(feature = "allowlist")]
+    accounts.push(AccountMeta::new_readonly(
+        get_allowlist_entry(program_id, owner).0,
+        false,
+    ));
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::CreateAccount(CreateAccount {
+            acc_index,
+            has_payer: false,
+            memo: memo.to_string(),
+        }),
+        accounts,
+    )
+}
+
+/// Create an AddSol instruction debiting the owner
+pub fn add_sol(program_id: &Pubkey, owner: &Pubkey, acc_index: u64, lamports: u64) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::AddSol(AddSol {
+            acc_index,
+            lamports,
+            has_payer: false,
+        }),
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(get_config(program_id).0, false),
+            AccountMeta::new(get_stats(program_id).0, false),
+        ],
+    )
+}
+
+/// Create an AddSol instruction debiting a payer other than the owner
+pub fn add_sol_from_payer(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    lamports: u64,
+    payer: &Pubkey,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::AddSol(AddSol {
+            acc_index,
+            lamports,
+            has_payer: true,
+        }),
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(get_config(program_id).0, false),
+            AccountMeta::new(get_stats(program_id).0, false),
+            AccountMeta::new(*payer, true),
+        ],
+    )
+}
+
+/// Create a SetDeadline instruction
+pub fn set_deadline(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    deadline: UnixTimestamp,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::SetDeadline(SetDeadline {
+            acc_index,
+            deadline,
+        }),
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new_readonly(get_config(program_id).0, false),
+        ],
+    )
+}
+
+/// Create a Lock instruction
+pub fn lock(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    commitment: Option<[u8; 32]>,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::Lock(Lock {
+            acc_index,
+            commitment,
+        }),
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new_readonly(get_config(program_id).0, false),
+            AccountMeta::new(get_stats(program_id).0, false),
+        ],
+    )
+}
+
+/// Create an Unlock instruction crediting the owner, or the receiver if one is given
+///
+/// If an unlock authority is given, it signs instead of the owner with the current nonce of the account.
+/// If a memo is given, it is logged with the SPL Memo program.
+pub fn unlock(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    receiver: Option<&Pubkey>,
+    unlock_authority: Option<(&Pubkey, u64)>,
+    memo: Option<&str>,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    let mut accounts = vec![
+        AccountMeta::new(*owner, unlock_authority.is_none()),
+        AccountMeta::new(sol_lock_account, false),
+        AccountMeta::new(get_treasury(program_id).0, false),
+        AccountMeta::new(get_stats(program_id).0, false),
+    ];
+    if let Some(receiver) = receiver {
+        accounts.push(AccountMeta::new(*receiver, false));
+    }
+    if memo.is_some() {
+        accounts.push(AccountMeta::new_readonly(MEMO_PROGRAM_ID, false));
+    }
+    if let Some((unlock_authority, _)) = unlock_authority {
+        accounts.push(AccountMeta::new_readonly(*unlock_authority, true));
+    }
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::Unlock(Unlock {
+            acc_index,
+            has_receiver: receiver.is_some(),
+            nonce: unlock_authority.map(|(_, nonce)| nonce),
+            memo: memo.map(str::to_string),
+        }),
+        accounts,
+    )
+}
+
+/// Create a CreateAndLock instruction
+///
+/// If a payer is given, it pays for the account and the locked lamports instead of the owner.
+pub fn create_and_lock(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    lamports: u64,
+    deadline: UnixTimestamp,
+    payer: Option<&Pubkey>,
+    memo: &str,
+    commitment: Option<[u8; 32]>,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    let mut accounts = vec![
+        AccountMeta::new(*owner, payer.is_none()),
+        AccountMeta::new(sol_lock_account, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(get_config(program_id).0, false),
+        AccountMeta::new(get_stats(program_id).0, false),
+    ];
+    #[cfg(feature = "allowlist")]
+    accounts.push(AccountMeta::new_readonly(
+        get_allowlist_entry(program_id, owner).0,
+        false,
+    ));
+    if let Some(payer) = payer {
+        accounts.push(AccountMeta::new(*payer, true));
+    }
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::CreateAndLock(CreateAndLock {
+            acc_index,
+            lamports,
+            deadline,
+            has_payer: payer.is_some(),
+            memo: memo.to_string(),
+            commitment,
+        }),
+        accounts,
+    )
+}
+
+/// Create a CrankUnlock instruction
+///
+/// The receiver must be the receiver stored in the SolLock account, if it has one.
+pub fn crank_unlock(
+    program_id: &Pubkey,
+    cranker: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    receiver: Option<&Pubkey>,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    let mut accounts = vec![
+        AccountMeta::new(*cranker, true),
+        AccountMeta::new(*owner, false),
+        AccountMeta::new(sol_lock_account, false),
+        AccountMeta::new(get_treasury(program_id).0, false),
+        AccountMeta::new(get_stats(program_id).0, false),
+    ];
+    if let Some(receiver) = receiver {
+        accounts.push(AccountMeta::new(*receiver, false));
+    }
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::CrankUnlock(CrankUnlock { acc_index }),
+        accounts,
+    )
+}
+
+/// Create a Relock instruction
+pub fn relock(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    lamports: u64,
+    deadline: UnixTimestamp,
+    commitment: Option<[u8; 32]>,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::Relock(Relock {
+            acc_index,
+            lamports,
+            deadline,
+            commitment,
+        }),
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(get_config(program_id).0, false),
+            AccountMeta::new(get_stats(program_id).0, false),
+        ],
+    )
+}
+
+/// Create a ClaimEscrow instruction
+pub fn claim_escrow(
+    program_id: &Pubkey,
+    counterparty: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::ClaimEscrow(ClaimEscrow { acc_index }),
+        vec![
+            AccountMeta::new(*counterparty, true),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new(get_treasury(program_id).0, false),
+            AccountMeta::new(get_stats(program_id).0, false),
+        ],
+    )
+}
+
+/// Create a RefundEscrow instruction
+pub fn refund_escrow(program_id: &Pubkey, owner: &Pubkey, acc_index: u64) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::RefundEscrow(RefundEscrow { acc_index }),
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new(get_treasury(program_id).0, false),
+            AccountMeta::new(get_stats(program_id).0, false),
+        ],
+    )
+}
+
+/// Create a Contribute instruction
+pub fn contribute(
+    program_id: &Pubkey,
+    contributor: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    lamports: u64,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+    let contribution = get_contribution(program_id, &sol_lock_account, contributor).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::Contribute(Contribute {
+            acc_index,
+            lamports,
+        }),
+        vec![
+            AccountMeta::new(*contributor, true),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new(contribution, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(get_config(program_id).0, false),
+            AccountMeta::new(get_stats(program_id).0, false),
+        ],
+    )
+}
+
+/// Create a RefundContribution instruction
+pub fn refund_contribution(
+    program_id: &Pubkey,
+    contributor: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+    let contribution = get_contribution(program_id, &sol_lock_account, contributor).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::RefundContribution(RefundContribution { acc_index }),
+        vec![
+            AccountMeta::new(*contributor, true),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new(contribution, false),
+            AccountMeta::new(get_stats(program_id).0, false),
+        ],
+    )
+}
+
+/// Create a Heartbeat instruction
+pub fn heartbeat(program_id: &Pubkey, owner: &Pubkey, acc_index: u64) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::Heartbeat(Heartbeat { acc_index }),
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(sol_lock_account, false),
+        ],
+    )
+}
+
+/// Create an InitiateRecovery instruction
+pub fn initiate_recovery(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    new_owner: &Pubkey,
+    guardians: &[Pubkey],
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*owner, false),
+        AccountMeta::new(sol_lock_account, false),
+    ];
+    accounts.extend(
+        guardians
+            .iter()
+            .map(|guardian| AccountMeta::new_readonly(*guardian, true)),
+    );
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::InitiateRecovery(InitiateRecovery {
+            acc_index,
+            new_owner: *new_owner,
+        }),
+        accounts,
+    )
+}
+
+/// Create a CheckPrice instruction
+pub fn check_price(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    price_feed: &Pubkey,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::CheckPrice(CheckPrice { acc_index }),
+        vec![
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new_readonly(*price_feed, false),
+        ],
+    )
+}
+
+/// Create a SetDeadlineRelative instruction
+pub fn set_deadline_relative(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    seconds: u64,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::SetDeadlineRelative(SetDeadlineRelative { acc_index, seconds }),
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new_readonly(get_config(program_id).0, false),
+        ],
+    )
+}
+
+/// Create an InitializeConfig instruction
+pub fn initialize_config(
+    program_id: &Pubkey,
+    upgrade_authority: &Pubkey,
+    config: Config,
+) -> Instruction {
+    let program_data =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id()).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::InitializeConfig(InitializeConfig { config }),
+        vec![
+            AccountMeta::new(*upgrade_authority, true),
+            AccountMeta::new(get_config(program_id).0, false),
+            AccountMeta::new(get_treasury(program_id).0, false),
+            AccountMeta::new(get_stats(program_id).0, false),
+            AccountMeta::new_readonly(program_data, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Create an UpdateConfig instruction
+pub fn update_config(program_id: &Pubkey, admin: &Pubkey, config: Config) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::UpdateConfig(UpdateConfig { config }),
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(get_config(program_id).0, false),
+        ],
+    )
+}
+
+/// Create a WithdrawTreasury instruction
+pub fn withdraw_treasury(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    destination: &Pubkey,
+    lamports: u64,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::WithdrawTreasury(WithdrawTreasury { lamports }),
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(get_config(program_id).0, false),
+            AccountMeta::new(get_treasury(program_id).0, false),
+            AccountMeta::new(*destination, false),
+        ],
+    )
+}
+
+/// Create an AddToAllowlist instruction
+pub fn add_to_allowlist(program_id: &Pubkey, admin: &Pubkey, owner: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::AddToAllowlist(AddToAllowlist { owner: *owner }),
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(get_config(program_id).0, false),
+            AccountMeta::new(get_allowlist_entry(program_id, owner).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Create a RemoveFromAllowlist instruction
+pub fn remove_from_allowlist(program_id: &Pubkey, admin: &Pubkey, owner: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::RemoveFromAllowlist(RemoveFromAllowlist { owner: *owner }),
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(get_config(program_id).0, false),
+            AccountMeta::new(get_allowlist_entry(program_id, owner).0, false),
+        ],
+    )
+}
+
+/// Create a CreateNextAccount instruction
+///
+/// `next_index` is the next_index of the IndexCounter account of the owner, or 0 if it does
+/// not exist yet.
+pub fn create_next_account(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    next_index: u64,
+    memo: &str,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, next_index).0;
+
+    #[allow(unused_mut)]
+    let mut accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(get_index_counter(program_id, owner).0, false),
+        AccountMeta::new(sol_lock_account, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    #[cfg(feature = "allowlist")]
+    accounts.push(AccountMeta::new_readonly(
+        get_allowlist_entry(program_id, owner).0,
+        false,
+    ));
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::CreateNextAccount(CreateNextAccount {
+            has_payer: false,
+            memo: memo.to_string(),
+        }),
+        accounts,
+    )
+}
+
+/// Create a CreateLabeledAccount instruction
+pub fn create_labeled_account(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    label: &str,
+    memo: &str,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, get_label_acc_index(label)).0;
+
+    #[allow(unused_mut)]
+    let mut accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(sol_lock_account, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    #[cfg(feature = "allowlist")]
+    accounts.push(AccountMeta::new_readonly(
+        get_allowlist_entry(program_id, owner).0,
+        false,
+    ));
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::CreateLabeledAccount(CreateLabeledAccount {
+            label: label.to_string(),
+            has_payer: false,
+            memo: memo.to_string(),
+        }),
+        accounts,
+    )
+}
+
+/// Create a Migrate instruction
+pub fn migrate(program_id: &Pubkey, payer: &Pubkey, owner: &Pubkey, acc_index: u64) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::Migrate(Migrate { acc_index }),
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Create a Realloc instruction
+pub fn realloc(program_id: &Pubkey, owner: &Pubkey, acc_index: u64) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::Realloc(Realloc { acc_index }),
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Create a GetVersion instruction
+pub fn get_version(program_id: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::GetVersion(GetVersion {}),
+        vec![],
+    )
+}
+
+/// Create an InitializeReceiptMint instruction
+pub fn initialize_receipt_mint(program_id: &Pubkey, admin: &Pubkey) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::InitializeReceiptMint(InitializeReceiptMint {}),
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(get_config(program_id).0, false),
+            AccountMeta::new(get_receipt_mint(program_id).0, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Create a MintReceipt instruction minting to the destination token account
+pub fn mint_receipt(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    destination: &Pubkey,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::MintReceipt(MintReceipt { acc_index }),
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new(get_receipt_mint(program_id).0, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        ],
+    )
+}
+
+/// Create a BurnReceipt instruction burning from the source token account of the owner
+pub fn burn_receipt(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    source: &Pubkey,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::BurnReceipt(BurnReceipt { acc_index }),
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new(get_receipt_mint(program_id).0, false),
+            AccountMeta::new(*source, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        ],
+    )
+}
+
+/// Create a MintPosition instruction minting the position NFT to the owner
+///
+/// Multisig signers of the account are appended by the caller.
+pub fn mint_position(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    unlock_authority: Option<&Pubkey>,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+    let position_mint = get_position_mint(program_id, &sol_lock_account).0;
+
+    let mut accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(sol_lock_account, false),
+        AccountMeta::new(position_mint, false),
+        AccountMeta::new(get_associated_token_address(owner, &position_mint), false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    if let Some(unlock_authority) = unlock_authority {
+        accounts.push(AccountMeta::new_readonly(*unlock_authority, true));
+    }
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::MintPosition(MintPosition { acc_index }),
+        accounts,
+    )
+}
+
+/// Create an UnlockPosition instruction for the holder of the position NFT, holding it in
+/// their associated token account
+pub fn unlock_position(
+    program_id: &Pubkey,
+    holder: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+    let position_mint = get_position_mint(program_id, &sol_lock_account).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::UnlockPosition(UnlockPosition { acc_index }),
+        vec![
+            AccountMeta::new(*holder, true),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new(position_mint, false),
+            AccountMeta::new(get_associated_token_address(holder, &position_mint), false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(get_treasury(program_id).0, false),
+            AccountMeta::new(get_stats(program_id).0, false),
+        ],
+    )
+}
+
+/// Create a GetVotingPower instruction
+pub fn get_voting_power(program_id: &Pubkey, owner: &Pubkey, acc_index: u64) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::GetVotingPower(GetVotingPower { acc_index }),
+        vec![
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new(sol_lock_account, false),
+        ],
+    )
+}
+
+/// Create a CreateVoterWeightRecord instruction
+pub fn create_voter_weight_record(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::CreateVoterWeightRecord(CreateVoterWeightRecord {
+            realm: *realm,
+            governing_token_mint: *governing_token_mint,
+        }),
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(
+                get_voter_weight_record(program_id, realm, governing_token_mint, owner).0,
+                false,
+            ),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Create an UpdateVoterWeightRecord instruction
+pub fn update_voter_weight_record(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::UpdateVoterWeightRecord(UpdateVoterWeightRecord {
+            acc_index,
+            realm: *realm,
+            governing_token_mint: *governing_token_mint,
+        }),
+        vec![
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(get_sol_lock_account(program_id, owner, acc_index).0, false),
+            AccountMeta::new(
+                get_voter_weight_record(program_id, realm, governing_token_mint, owner).0,
+                false,
+            ),
+        ],
+    )
+}
+
+/// Create a WithdrawRewards instruction
+pub fn withdraw_rewards(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    stake_account: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::WithdrawRewards(WithdrawRewards { acc_index }),
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(get_sol_lock_account(program_id, owner, acc_index).0, false),
+            AccountMeta::new(*stake_account, false),
+            AccountMeta::new_readonly(clock::id(), false),
+            AccountMeta::new_readonly(stake_history::id(), false),
+            AccountMeta::new_readonly(stake::program::id(), false),
+        ],
+    )
+}
+
+/// Create a Compound instruction
+pub fn compound(
+    program_id: &Pubkey,
+    cranker: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    stake_account: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::Compound(Compound { acc_index }),
+        vec![
+            AccountMeta::new(*cranker, true),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new(get_sol_lock_account(program_id, owner, acc_index).0, false),
+            AccountMeta::new(*stake_account, false),
+            AccountMeta::new_readonly(clock::id(), false),
+            AccountMeta::new_readonly(stake_history::id(), false),
+            AccountMeta::new_readonly(stake::program::id(), false),
+            AccountMeta::new(get_stats(program_id).0, false),
+        ],
+    )
+}
+
+/// Create a Stake instruction delegating the locked lamports to a vote account
+pub fn stake(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    vote_account: &Pubkey,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::Stake(Stake { acc_index }),
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new(get_stake_account(program_id, &sol_lock_account).0, false),
+            AccountMeta::new_readonly(*vote_account, false),
+            AccountMeta::new_readonly(clock::id(), false),
+            AccountMeta::new_readonly(rent::id(), false),
+            AccountMeta::new_readonly(stake_history::id(), false),
+            AccountMeta::new_readonly(stake::config::id(), false),
+            AccountMeta::new_readonly(stake::program::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Create a SetValidatorAllowlist instruction
+pub fn set_validator_allowlist(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    validators: Vec<Pubkey>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::SetValidatorAllowlist(SetValidatorAllowlist {
+            acc_index,
+            validators,
+        }),
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(get_sol_lock_account(program_id, owner, acc_index).0, false),
+        ],
+    )
+}
+
+/// Create a SplitStake instruction
+pub fn split_stake(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    lamports: u64,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::SplitStake(SplitStake {
+            acc_index,
+            lamports,
+        }),
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new(get_stake_account(program_id, &sol_lock_account).0, false),
+            AccountMeta::new(
+                get_split_stake_account(program_id, &sol_lock_account).0,
+                false,
+            ),
+            AccountMeta::new_readonly(stake::program::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Create a MergeStake instruction
+pub fn merge_stake(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    source_stake_account: &Pubkey,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::MergeStake(MergeStake { acc_index }),
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new(get_stake_account(program_id, &sol_lock_account).0, false),
+            AccountMeta::new(*source_stake_account, false),
+            AccountMeta::new_readonly(clock::id(), false),
+            AccountMeta::new_readonly(stake_history::id(), false),
+            AccountMeta::new_readonly(stake::program::id(), false),
+        ],
+    )
+}
+
+/// Create a ConvertToLst instruction depositing the locked lamports into a stake pool
+pub fn convert_to_lst(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    pool: &StakePoolAccounts,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::ConvertToLst(ConvertToLst { acc_index }),
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new(
+                get_lst_funding_account(program_id, &sol_lock_account).0,
+                false,
+            ),
+            AccountMeta::new(*pool.stake_pool, false),
+            AccountMeta::new_readonly(*pool.withdraw_authority, false),
+            AccountMeta::new(*pool.reserve_stake, false),
+            AccountMeta::new(*pool.manager_fee_account, false),
+            AccountMeta::new(*pool.pool_mint, false),
+            AccountMeta::new(
+                get_associated_token_address(&sol_lock_account, pool.pool_mint),
+                false,
+            ),
+            AccountMeta::new_readonly(STAKE_POOL_PROGRAM_ID, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Create a RedeemLst instruction withdrawing the locked lamports from a stake pool
+pub fn redeem_lst(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    pool: &StakePoolAccounts,
+) -> Instruction {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::RedeemLst(RedeemLst { acc_index }),
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(sol_lock_account, false),
+            AccountMeta::new(*pool.stake_pool, false),
+            AccountMeta::new_readonly(*pool.withdraw_authority, false),
+            AccountMeta::new(*pool.reserve_stake, false),
+            AccountMeta::new(*pool.manager_fee_account, false),
+            AccountMeta::new(*pool.pool_mint, false),
+            AccountMeta::new(
+                get_associated_token_address(&sol_lock_account, pool.pool_mint),
+                false,
+            ),
+            AccountMeta::new_readonly(clock::id(), false),
+            AccountMeta::new_readonly(stake_history::id(), false),
+            AccountMeta::new_readonly(stake::program::id(), false),
+            AccountMeta::new_readonly(STAKE_POOL_PROGRAM_ID, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        ],
+    )
+}
+
+/// The accounts shared by DepositToLending and WithdrawFromLending
+fn lending_account_metas(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    reserve: &LendingReserveAccounts,
+) -> Vec<AccountMeta> {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+
+    vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(sol_lock_account, false),
+        AccountMeta::new(*reserve.reserve, false),
+        AccountMeta::new(*reserve.reserve_liquidity_supply, false),
+        AccountMeta::new(*reserve.reserve_collateral_mint, false),
+        AccountMeta::new_readonly(*reserve.lending_market, false),
+        AccountMeta::new_readonly(*reserve.lending_market_authority, false),
+        AccountMeta::new(
+            get_associated_token_address(&sol_lock_account, &NATIVE_MINT),
+            false,
+        ),
+        AccountMeta::new(
+            get_associated_token_address(&sol_lock_account, reserve.reserve_collateral_mint),
+            false,
+        ),
+        AccountMeta::new_readonly(NATIVE_MINT, false),
+        AccountMeta::new_readonly(clock::id(), false),
+        AccountMeta::new_readonly(LENDING_PROGRAM_ID, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]
+}
+
+/// Create a DepositToLending instruction lending the locked lamports out from a reserve
+pub fn deposit_to_lending(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    reserve: &LendingReserveAccounts,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::DepositToLending(DepositToLending { acc_index }),
+        lending_account_metas(program_id, owner, acc_index, reserve),
+    )
+}
+
+/// Create a WithdrawFromLending instruction withdrawing the locked lamports from a reserve
+pub fn withdraw_from_lending(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    reserve: &LendingReserveAccounts,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::WithdrawFromLending(WithdrawFromLending { acc_index }),
+        lending_account_metas(program_id, owner, acc_index, reserve),
+    )
+}
+
+/// Create a Reconcile instruction
+pub fn reconcile(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    action: SurplusAction,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &SolLockInstruction::Reconcile(Reconcile { acc_index, action }),
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(get_sol_lock_account(program_id, owner, acc_index).0, false),
+            AccountMeta::new_readonly(get_config(program_id).0, false),
+            AccountMeta::new(get_stats(program_id).0, false),
+        ],
+    )
 }
@@ -0,0 +1,42 @@
+//! Checked arithmetic on lamports, failing with ArithmeticOverflow instead of wrapping
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+};
+
+use crate::error::SolLockError;
+
+/// Add two amounts of lamports
+pub fn checked_add(a: u64, b: u64) -> Result<u64, ProgramError> {
+    a.checked_add(b)
+        .ok_or_else(|| SolLockError::ArithmeticOverflow.into())
+}
+
+/// Subtract an amount of lamports from another
+pub fn checked_sub(a: u64, b: u64) -> Result<u64, ProgramError> {
+    a.checked_sub(b)
+        .ok_or_else(|| SolLockError::ArithmeticOverflow.into())
+}
+
+/// Add `lamports` to the balance of an account
+pub fn credit_lamports(account_info: &AccountInfo, lamports: u64) -> ProgramResult {
+    let mut balance = account_info.try_borrow_mut_lamports()?;
+    **balance = checked_add(**balance, lamports)?;
+    Ok(())
+}
+
+/// Remove `lamports` from the balance of an account owned by the program
+pub fn debit_lamports(account_info: &AccountInfo, lamports: u64) -> ProgramResult {
+    let mut balance = account_info.try_borrow_mut_lamports()?;
+    **balance = checked_sub(**balance, lamports)?;
+    Ok(())
+}
+
+/// Move `lamports` from an account owned by the program to another account
+pub fn transfer_lamports(
+    from_info: &AccountInfo,
+    to_info: &AccountInfo,
+    lamports: u64,
+) -> ProgramResult {
+    debit_lamports(from_info, lamports)?;
+    credit_lamports(to_info, lamports)
+}
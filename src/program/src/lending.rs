@@ -0,0 +1,132 @@
+//! The SPL Token Lending instructions and reserve fields needed to lend out locked SOL,
+//! as implemented by Solend
+use arrayref::array_ref;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey,
+    pubkey::Pubkey,
+    sysvar::clock,
+};
+
+use crate::{error::SolLockError, token::TOKEN_PROGRAM_ID};
+
+/// The Solend lending program
+pub const LENDING_PROGRAM_ID: Pubkey = pubkey!("So1endDq2YkqhipRh3WViPa8hdiSpxWy6z3Z6tMCpAo");
+
+/// The instruction tags of the lending program
+const DEPOSIT_RESERVE_LIQUIDITY: u8 = 4;
+const REDEEM_RESERVE_COLLATERAL: u8 = 5;
+
+/// The offsets of the reserve fields that the program reads
+const LENDING_MARKET_OFFSET: usize = 10;
+const LIQUIDITY_MINT_OFFSET: usize = 42;
+const LIQUIDITY_SUPPLY_OFFSET: usize = 75;
+const COLLATERAL_MINT_OFFSET: usize = 227;
+
+/// The smallest reserve account holding all the fields that the program reads
+const RESERVE_MIN_LEN: usize = COLLATERAL_MINT_OFFSET + 32;
+
+/// The accounts of a lending reserve that depositing and redeeming liquidity go through
+pub struct LendingReserveAccounts<'a> {
+    /// The reserve
+    pub reserve: &'a Pubkey,
+    /// The token account holding the liquidity of the reserve
+    pub reserve_liquidity_supply: &'a Pubkey,
+    /// The collateral token mint of the reserve
+    pub reserve_collateral_mint: &'a Pubkey,
+    /// The lending market of the reserve
+    pub lending_market: &'a Pubkey,
+    /// The authority of the lending market
+    pub lending_market_authority: &'a Pubkey,
+}
+
+/// The fields of a lending reserve that the program reads
+pub struct Reserve {
+    /// The lending market of the reserve
+    pub lending_market: Pubkey,
+    /// The mint of the tokens lent out by the reserve
+    pub liquidity_mint: Pubkey,
+    /// The token account holding the liquidity of the reserve
+    pub liquidity_supply: Pubkey,
+    /// The collateral token mint of the reserve
+    pub collateral_mint: Pubkey,
+}
+
+/// Read the lending market, liquidity mint and supply, and collateral mint of a reserve
+pub fn read_reserve(data: &[u8]) -> Result<Reserve, ProgramError> {
+    if data.len() < RESERVE_MIN_LEN {
+        return Err(SolLockError::InvalidLendingReserve.into());
+    }
+
+    Ok(Reserve {
+        lending_market: Pubkey::new_from_array(*array_ref![data, LENDING_MARKET_OFFSET, 32]),
+        liquidity_mint: Pubkey::new_from_array(*array_ref![data, LIQUIDITY_MINT_OFFSET, 32]),
+        liquidity_supply: Pubkey::new_from_array(*array_ref![data, LIQUIDITY_SUPPLY_OFFSET, 32]),
+        collateral_mint: Pubkey::new_from_array(*array_ref![data, COLLATERAL_MINT_OFFSET, 32]),
+    })
+}
+
+/// Deposit `liquidity_amount` tokens from the source liquidity account into a reserve,
+/// minting collateral tokens to the destination collateral account
+///
+/// The reserve must be refreshed earlier in the same transaction.
+pub fn deposit_reserve_liquidity(
+    reserve: &LendingReserveAccounts,
+    source_liquidity: &Pubkey,
+    destination_collateral: &Pubkey,
+    user_transfer_authority: &Pubkey,
+    liquidity_amount: u64,
+) -> Instruction {
+    let mut data = vec![DEPOSIT_RESERVE_LIQUIDITY];
+    data.extend_from_slice(&liquidity_amount.to_le_bytes());
+
+    Instruction {
+        program_id: LENDING_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*source_liquidity, false),
+            AccountMeta::new(*destination_collateral, false),
+            AccountMeta::new(*reserve.reserve, false),
+            AccountMeta::new(*reserve.reserve_liquidity_supply, false),
+            AccountMeta::new(*reserve.reserve_collateral_mint, false),
+            AccountMeta::new_readonly(*reserve.lending_market, false),
+            AccountMeta::new_readonly(*reserve.lending_market_authority, false),
+            AccountMeta::new_readonly(*user_transfer_authority, true),
+            AccountMeta::new_readonly(clock::id(), false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+/// Burn `collateral_amount` collateral tokens from the source collateral account to
+/// withdraw their value in liquidity from a reserve to the destination liquidity account
+///
+/// The reserve must be refreshed earlier in the same transaction.
+pub fn redeem_reserve_collateral(
+    reserve: &LendingReserveAccounts,
+    source_collateral: &Pubkey,
+    destination_liquidity: &Pubkey,
+    user_transfer_authority: &Pubkey,
+    collateral_amount: u64,
+) -> Instruction {
+    let mut data = vec![REDEEM_RESERVE_COLLATERAL];
+    data.extend_from_slice(&collateral_amount.to_le_bytes());
+
+    Instruction {
+        program_id: LENDING_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*source_collateral, false),
+            AccountMeta::new(*destination_liquidity, false),
+            AccountMeta::new(*reserve.reserve, false),
+            AccountMeta::new(*reserve.reserve_collateral_mint, false),
+            AccountMeta::new(*reserve.reserve_liquidity_supply, false),
+            AccountMeta::new_readonly(*reserve.lending_market, false),
+            AccountMeta::new_readonly(*reserve.lending_market_authority, false),
+            AccountMeta::new_readonly(*user_transfer_authority, true),
+            AccountMeta::new_readonly(clock::id(), false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
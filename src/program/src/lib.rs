@@ -1,11 +1,37 @@
 //! A program that locks SOL in an account until a unix timestamp
 #![deny(missing_docs)]
-#![forbid(unsafe_code)]
+#![cfg_attr(not(test), forbid(unsafe_code))]
+#![cfg_attr(test, deny(unsafe_code))]
 
+/// Log a message with msg! unless built with the minimal-logs feature
+///
+/// Used for the messages that only narrate a successful instruction, which cost compute units
+/// formatting pubkeys without helping to debug failures.
+macro_rules! verbose_msg {
+    ($($arg:tt)*) => {
+        if cfg!(not(feature = "minimal-logs")) {
+            solana_program::msg!($($arg)*);
+        }
+    };
+}
+
+#[cfg(feature = "cpi")]
+pub mod cpi;
 mod entrypoint;
 mod error;
+pub mod governance;
 pub mod instruction;
+mod lamport_utils;
+pub mod lending;
+mod memo;
+mod oracle;
 mod pack_utils;
 pub mod processor;
-mod state;
+pub mod receipt;
+pub mod stake_pool;
+pub mod state;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+mod token;
 mod validation_utils;
+pub mod yield_adapter;
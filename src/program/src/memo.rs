@@ -0,0 +1,14 @@
+//! The SPL Memo instruction, so that transfers out of SolLock accounts can carry a memo
+use solana_program::{instruction::Instruction, pubkey, pubkey::Pubkey};
+
+/// The SPL Memo program
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Log a memo, without requiring any signer
+pub fn build_memo(memo: &str) -> Instruction {
+    Instruction {
+        program_id: MEMO_PROGRAM_ID,
+        accounts: vec![],
+        data: memo.as_bytes().to_vec(),
+    }
+}
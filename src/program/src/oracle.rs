@@ -0,0 +1,64 @@
+//! Reading prices from Pyth price accounts
+use arrayref::array_ref;
+use solana_program::{clock::UnixTimestamp, program_error::ProgramError, pubkey, pubkey::Pubkey};
+
+use crate::error::SolLockError;
+
+/// The Pyth oracle program, which owns the price accounts
+pub const PYTH_PROGRAM_ID: Pubkey = pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+
+/// The magic number at the start of every Pyth account
+const PYTH_MAGIC: u32 = 0xa1b2_c3d4;
+/// The version of the Pyth account layout that is supported
+const PYTH_VERSION: u32 = 2;
+/// The account type of Pyth price accounts
+const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+/// The status of an aggregate price that is being traded
+const PYTH_STATUS_TRADING: u32 = 1;
+
+/// The size of the header of a Pyth price account, up to and including the aggregate price
+const PYTH_PRICE_HEADER_LEN: usize = 240;
+
+/// The maximum age of a price before it is considered stale
+pub const MAX_PRICE_AGE: UnixTimestamp = 60;
+
+/// The aggregate price of a Pyth price account
+pub struct PythPrice {
+    /// The price, in units of 10^expo
+    pub price: i64,
+    /// The exponent of the price
+    pub expo: i32,
+    /// The time the price was published at
+    pub publish_time: UnixTimestamp,
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(*array_ref![data, offset, 4])
+}
+
+fn read_i64(data: &[u8], offset: usize) -> i64 {
+    i64::from_le_bytes(*array_ref![data, offset, 8])
+}
+
+/// Read the aggregate price of a Pyth price account
+///
+/// Requires that the account is a Pyth price account and that the price is being traded.
+pub fn read_pyth_price(data: &[u8]) -> Result<PythPrice, ProgramError> {
+    if data.len() < PYTH_PRICE_HEADER_LEN
+        || read_u32(data, 0) != PYTH_MAGIC
+        || read_u32(data, 4) != PYTH_VERSION
+        || read_u32(data, 8) != PYTH_PRICE_ACCOUNT_TYPE
+    {
+        return Err(SolLockError::InvalidPriceFeed.into());
+    }
+
+    if read_u32(data, 224) != PYTH_STATUS_TRADING {
+        return Err(SolLockError::StalePrice.into());
+    }
+
+    Ok(PythPrice {
+        price: read_i64(data, 208),
+        expo: read_u32(data, 20) as i32,
+        publish_time: read_i64(data, 96),
+    })
+}
@@ -6,7 +6,7 @@ use solana_program::{
     program_pack::Pack,
 };
 
-use crate::{error::SolLockError, state::Account};
+use crate::{error::SolLockError, state::Account, validation_utils::assert_initialized};
 
 pub fn unpack_option<T>(
     src: &[u8],
@@ -38,6 +38,8 @@ pub fn pack_option<'a, 'b, T>(
     }
 }
 
+/// Access account data through a single unpack, which also checks that the account is initialized,
+/// so instructions need no separate assert_initialized
 pub trait WithData<T> {
     fn with_immut_data(&self, f: impl FnOnce(T) -> ProgramResult) -> ProgramResult;
     fn with_mut_data(&self, f: impl FnOnce(T) -> Result<T, ProgramError>) -> ProgramResult;
@@ -45,7 +47,7 @@ pub trait WithData<T> {
 
 impl WithData<Account> for AccountInfo<'_> {
     fn with_immut_data(&self, f: impl FnOnce(Account) -> ProgramResult) -> ProgramResult {
-        let sol_lock_account_data = Account::unpack(&self.data.borrow())?;
+        let sol_lock_account_data = assert_initialized::<Account>(self)?;
         f(sol_lock_account_data)?;
         Ok(())
     }
@@ -54,7 +56,7 @@ impl WithData<Account> for AccountInfo<'_> {
         &self,
         f: impl FnOnce(Account) -> Result<Account, ProgramError>,
     ) -> ProgramResult {
-        let sol_lock_account_data = Account::unpack(&self.data.borrow())?;
+        let sol_lock_account_data = assert_initialized::<Account>(self)?;
         let sol_lock_account_data = f(sol_lock_account_data)?;
         sol_lock_account_data.pack_into_slice(&mut self.data.borrow_mut());
         Ok(())
@@ -1,20 +1,60 @@
 //! Program instruction processor
+#![deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable
+)]
+
 use crate::{
     error::SolLockError,
+    governance::{
+        VoterWeightRecord, VOTER_WEIGHT_RECORD_DISCRIMINATOR, VOTER_WEIGHT_RECORD_SEED,
+        VOTER_WEIGHT_RECORD_SIZE,
+    },
     instruction::*,
+    lamport_utils::{checked_add, checked_sub, credit_lamports, debit_lamports, transfer_lamports},
+    lending::{read_reserve, LENDING_PROGRAM_ID},
+    memo::{self, MEMO_PROGRAM_ID},
+    oracle::{read_pyth_price, PYTH_PROGRAM_ID},
     pack_utils::WithData,
-    state::{Account, State, SOL_LOCK_ACCOUNT_SIZE},
+    receipt::{read_layout_version, DISCRIMINATOR_OFFSET, MIN_ACCOUNT_LEN, VERSION_OFFSET},
+    stake_pool::STAKE_POOL_PROGRAM_ID,
+    state::{
+        Account, AllowlistEntry, ClaimTicket, Config, Contribution, IndexCounter, NameRecord,
+        State, Stats, YieldBackend, ACCOUNT_VERSION, ALLOWLIST_ENTRY_SIZE, ALLOWLIST_SEED,
+        BUMP_SEED_OFFSET, CLAIM_TICKET_SEED, CLAIM_TICKET_SIZE, CONFIG_SEED, CONFIG_SIZE,
+        CONTRIBUTION_SEED, CONTRIBUTION_SIZE, DISCRIMINATOR_LEN, INDEX_COUNTER_SEED,
+        INDEX_COUNTER_SIZE, LABEL_SEED, LST_FUNDING_SEED, NAME_RECORD_SEED, NAME_RECORD_SIZE,
+        POSITION_MINT_SEED, RECEIPT_MINT_DECIMALS, RECEIPT_MINT_SEED,
+        SOL_LOCK_ACCOUNT_DISCRIMINATOR, SOL_LOCK_ACCOUNT_SIZE, SPLIT_STAKE_ACCOUNT_SEED,
+        STAKE_ACCOUNT_SEED, STATS_SEED, STATS_SIZE, TOTAL_SHARE_BPS, TREASURY_SEED, VERSION_LEN,
+    },
+    token::{
+        self, get_associated_token_address, unpack_token_account, ASSOCIATED_TOKEN_PROGRAM_ID,
+        MINT_LEN, NATIVE_MINT, TOKEN_PROGRAM_ID,
+    },
     validation_utils::*,
+    yield_adapter::{
+        self, Lending, NativeStake, NativeStakeDelegation, SolLockSigner, StakePoolDeposit,
+        StakePoolWithdrawal, YieldAdapter,
+    },
 };
-use borsh::BorshDeserialize;
+use arrayref::array_ref;
+use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    borsh::try_from_slice_unchecked,
+    clock::UnixTimestamp,
     entrypoint::ProgramResult,
+    hash::hashv,
     msg,
-    program::{invoke, invoke_signed},
+    program::{invoke, invoke_signed, set_return_data},
     program_error::ProgramError,
+    program_memory::sol_memset,
     program_pack::Pack,
     pubkey::Pubkey,
+    stake::{instruction as stake_instruction, state::StakeState},
     system_instruction,
     sysvar::{clock::Clock, rent::Rent, Sysvar},
 };
@@ -35,8 +75,112 @@ pub fn process_instruction(
         SolLockInstruction::SetDeadline(ctx) => set_deadline(program_id, accounts, ctx)?,
         SolLockInstruction::Lock(ctx) => lock(program_id, accounts, ctx)?,
         SolLockInstruction::Unlock(ctx) => unlock(program_id, accounts, ctx)?,
-        SolLockInstruction::Stake(_) => unimplemented!(),
-        SolLockInstruction::Unstake(_) => unimplemented!(),
+        SolLockInstruction::Stake(ctx) => stake(program_id, accounts, ctx)?,
+        SolLockInstruction::Unstake(ctx) => unstake(program_id, accounts, ctx)?,
+        SolLockInstruction::TopUpRent(ctx) => top_up_rent(program_id, accounts, ctx)?,
+        SolLockInstruction::CreateAndLock(ctx) => create_and_lock(program_id, accounts, ctx)?,
+        SolLockInstruction::SetDeadlineMany(ctx) => set_deadline_many(program_id, accounts, ctx)?,
+        SolLockInstruction::AddSolAndLock(ctx) => add_sol_and_lock(program_id, accounts, ctx)?,
+        SolLockInstruction::SetName(ctx) => set_name(program_id, accounts, ctx)?,
+        SolLockInstruction::UnlockAndClose(ctx) => unlock_and_close(program_id, accounts, ctx)?,
+        SolLockInstruction::ProposeOwner(ctx) => propose_owner(program_id, accounts, ctx)?,
+        SolLockInstruction::AcceptOwner(ctx) => accept_owner(program_id, accounts, ctx)?,
+        SolLockInstruction::SetUnlockAuthority(ctx) => {
+            set_unlock_authority(program_id, accounts, ctx)?
+        }
+        SolLockInstruction::SetReceiver(ctx) => set_receiver(program_id, accounts, ctx)?,
+        SolLockInstruction::SetRecoveryPointer(ctx) => {
+            set_recovery_pointer(program_id, accounts, ctx)?
+        }
+        SolLockInstruction::SetCrankTip(ctx) => set_crank_tip(program_id, accounts, ctx)?,
+        SolLockInstruction::CrankUnlock(ctx) => crank_unlock(program_id, accounts, ctx)?,
+        SolLockInstruction::Relock(ctx) => relock(program_id, accounts, ctx)?,
+        SolLockInstruction::SetAutoExtend(ctx) => set_auto_extend(program_id, accounts, ctx)?,
+        SolLockInstruction::SetVesting(ctx) => set_vesting(program_id, accounts, ctx)?,
+        SolLockInstruction::SetDepositCap(ctx) => set_deposit_cap(program_id, accounts, ctx)?,
+        SolLockInstruction::UnlockToClaim(ctx) => unlock_to_claim(program_id, accounts, ctx)?,
+        SolLockInstruction::ClaimUnlocked(ctx) => claim_unlocked(program_id, accounts, ctx)?,
+        SolLockInstruction::SetStream(ctx) => set_stream(program_id, accounts, ctx)?,
+        SolLockInstruction::Claim(ctx) => claim(program_id, accounts, ctx)?,
+        SolLockInstruction::SetHashLock(ctx) => set_hash_lock(program_id, accounts, ctx)?,
+        SolLockInstruction::ClaimWithPreimage(ctx) => {
+            claim_with_preimage(program_id, accounts, ctx)?
+        }
+        SolLockInstruction::SetEscrow(ctx) => set_escrow(program_id, accounts, ctx)?,
+        SolLockInstruction::ClaimEscrow(ctx) => claim_escrow(program_id, accounts, ctx)?,
+        SolLockInstruction::RefundEscrow(ctx) => refund_escrow(program_id, accounts, ctx)?,
+        SolLockInstruction::RaiseDispute(ctx) => raise_dispute(program_id, accounts, ctx)?,
+        SolLockInstruction::Resolve(ctx) => resolve(program_id, accounts, ctx)?,
+        SolLockInstruction::SetOpenDeposits(ctx) => set_open_deposits(program_id, accounts, ctx)?,
+        SolLockInstruction::Contribute(ctx) => contribute(program_id, accounts, ctx)?,
+        SolLockInstruction::SetRaiseTarget(ctx) => set_raise_target(program_id, accounts, ctx)?,
+        SolLockInstruction::RefundContribution(ctx) => {
+            refund_contribution(program_id, accounts, ctx)?
+        }
+        SolLockInstruction::SetBeneficiary(ctx) => set_beneficiary(program_id, accounts, ctx)?,
+        SolLockInstruction::Heartbeat(ctx) => heartbeat(program_id, accounts, ctx)?,
+        SolLockInstruction::ClaimAsBeneficiary(ctx) => {
+            claim_as_beneficiary(program_id, accounts, ctx)?
+        }
+        SolLockInstruction::SetBeneficiaryShares(ctx) => {
+            set_beneficiary_shares(program_id, accounts, ctx)?
+        }
+        SolLockInstruction::SetMultisig(ctx) => set_multisig(program_id, accounts, ctx)?,
+        SolLockInstruction::SetGuardians(ctx) => set_guardians(program_id, accounts, ctx)?,
+        SolLockInstruction::InitiateRecovery(ctx) => initiate_recovery(program_id, accounts, ctx)?,
+        SolLockInstruction::VetoRecovery(ctx) => veto_recovery(program_id, accounts, ctx)?,
+        SolLockInstruction::SetPriceCondition(ctx) => {
+            set_price_condition(program_id, accounts, ctx)?
+        }
+        SolLockInstruction::CheckPrice(ctx) => check_price(program_id, accounts, ctx)?,
+        SolLockInstruction::SetEpochDeadline(ctx) => set_epoch_deadline(program_id, accounts, ctx)?,
+        SolLockInstruction::SetDeadlineRelative(ctx) => {
+            set_deadline_relative(program_id, accounts, ctx)?
+        }
+        SolLockInstruction::InitializeConfig(ctx) => initialize_config(program_id, accounts, ctx)?,
+        SolLockInstruction::UpdateConfig(ctx) => update_config(program_id, accounts, ctx)?,
+        SolLockInstruction::WithdrawTreasury(ctx) => withdraw_treasury(program_id, accounts, ctx)?,
+        SolLockInstruction::AddToAllowlist(ctx) => add_to_allowlist(program_id, accounts, ctx)?,
+        SolLockInstruction::RemoveFromAllowlist(ctx) => {
+            remove_from_allowlist(program_id, accounts, ctx)?
+        }
+        SolLockInstruction::CreateNextAccount(ctx) => {
+            create_next_account(program_id, accounts, ctx)?
+        }
+        SolLockInstruction::CreateLabeledAccount(ctx) => {
+            create_labeled_account(program_id, accounts, ctx)?
+        }
+        SolLockInstruction::Migrate(ctx) => migrate(program_id, accounts, ctx)?,
+        SolLockInstruction::Realloc(ctx) => realloc(program_id, accounts, ctx)?,
+        SolLockInstruction::GetVersion(ctx) => get_version(ctx)?,
+        SolLockInstruction::InitializeReceiptMint(ctx) => {
+            initialize_receipt_mint(program_id, accounts, ctx)?
+        }
+        SolLockInstruction::MintReceipt(ctx) => mint_receipt(program_id, accounts, ctx)?,
+        SolLockInstruction::BurnReceipt(ctx) => burn_receipt(program_id, accounts, ctx)?,
+        SolLockInstruction::MintPosition(ctx) => mint_position(program_id, accounts, ctx)?,
+        SolLockInstruction::UnlockPosition(ctx) => unlock_position(program_id, accounts, ctx)?,
+        SolLockInstruction::GetVotingPower(ctx) => get_voting_power(program_id, accounts, ctx)?,
+        SolLockInstruction::CreateVoterWeightRecord(ctx) => {
+            create_voter_weight_record(program_id, accounts, ctx)?
+        }
+        SolLockInstruction::UpdateVoterWeightRecord(ctx) => {
+            update_voter_weight_record(program_id, accounts, ctx)?
+        }
+        SolLockInstruction::WithdrawRewards(ctx) => withdraw_rewards(program_id, accounts, ctx)?,
+        SolLockInstruction::Compound(ctx) => compound(program_id, accounts, ctx)?,
+        SolLockInstruction::SetValidatorAllowlist(ctx) => {
+            set_validator_allowlist(program_id, accounts, ctx)?
+        }
+        SolLockInstruction::SplitStake(ctx) => split_stake(program_id, accounts, ctx)?,
+        SolLockInstruction::MergeStake(ctx) => merge_stake(program_id, accounts, ctx)?,
+        SolLockInstruction::ConvertToLst(ctx) => convert_to_lst(program_id, accounts, ctx)?,
+        SolLockInstruction::RedeemLst(ctx) => redeem_lst(program_id, accounts, ctx)?,
+        SolLockInstruction::DepositToLending(ctx) => deposit_to_lending(program_id, accounts, ctx)?,
+        SolLockInstruction::WithdrawFromLending(ctx) => {
+            withdraw_from_lending(program_id, accounts, ctx)?
+        }
+        SolLockInstruction::Reconcile(ctx) => reconcile(program_id, accounts, ctx)?,
     }
 
     Ok(())
@@ -50,20 +194,53 @@ pub fn create_account(
 ) -> ProgramResult {
     msg!("SolLock::CreateAccount");
 
-    let CreateAccount { acc_index } = ctx;
+    let CreateAccount {
+        acc_index,
+        has_payer,
+        memo,
+    } = ctx;
 
     let account_info_iter = &mut accounts.iter();
     let owner_info = next_account_info(account_info_iter)?;
     let sol_lock_account_info = next_account_info(account_info_iter)?;
     let system_account_info = next_account_info(account_info_iter)?;
+    #[cfg(feature = "allowlist")]
+    let allowlist_entry_info = next_account_info(account_info_iter)?;
+    let sol_payer_account_res = next_account_info(account_info_iter);
+
+    let payer_account_info = assert_payer_validity(owner_info, sol_payer_account_res, has_payer)?;
+
+    assert_system_program(system_account_info)?;
+    assert_is_signer(payer_account_info)?;
+    assert_valid_memo(&memo)?;
+    #[cfg(feature = "allowlist")]
+    assert_owner_allowlisted(program_id, owner_info, allowlist_entry_info)?;
 
-    let (sol_lock_account_key, sol_lock_account_bump_seed) = Pubkey::find_program_address(
-        &[owner_info.key.as_ref(), &acc_index.to_le_bytes()],
+    init_sol_lock_account(
         program_id,
-    );
+        owner_info,
+        sol_lock_account_info,
+        system_account_info,
+        payer_account_info,
+        acc_index,
+        memo,
+    )
+}
 
-    assert_is_signer(owner_info)?;
-    assert_keys_equal(sol_lock_account_key.clone(), *sol_lock_account_info.key)?;
+/// Create the SolLock account of an owner at an index, paid for by the payer
+fn init_sol_lock_account<'a>(
+    program_id: &Pubkey,
+    owner_info: &AccountInfo<'a>,
+    sol_lock_account_info: &AccountInfo<'a>,
+    system_account_info: &AccountInfo<'a>,
+    payer_account_info: &AccountInfo<'a>,
+    acc_index: u64,
+    memo: String,
+) -> ProgramResult {
+    let (sol_lock_account_key, sol_lock_account_bump_seed) =
+        get_sol_lock_account(program_id, owner_info.key, acc_index);
+
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
     assert_owned_by(sol_lock_account_info, system_account_info.key)?;
 
     let rent = Rent::get()?;
@@ -71,14 +248,14 @@ pub fn create_account(
 
     invoke_signed(
         &system_instruction::create_account(
-            &owner_info.key,
+            payer_account_info.key,
             &sol_lock_account_key,
             lamports,
-            SOL_LOCK_ACCOUNT_SIZE.try_into().unwrap(),
+            SOL_LOCK_ACCOUNT_SIZE as u64,
             program_id,
         ),
         &[
-            owner_info.clone(),
+            payer_account_info.clone(),
             sol_lock_account_info.clone(),
             system_account_info.clone(),
         ],
@@ -91,15 +268,17 @@ pub fn create_account(
 
     let sol_lock_account_data = Account {
         owner: owner_info.key.clone(),
-        lamports: None,
-        deadline: None,
         state: State::Initialized,
-        stake_account: None,
+        memo,
+        created_at: Clock::get()?.unix_timestamp,
+        rent_reserve: lamports,
+        bump_seed: sol_lock_account_bump_seed,
+        ..Account::default()
     };
 
     sol_lock_account_data.pack_into_slice(&mut sol_lock_account_info.data.borrow_mut());
 
-    msg!(
+    verbose_msg!(
         "Account {:?} created successfully.",
         sol_lock_account_info.key
     );
@@ -107,10 +286,202 @@ pub fn create_account(
     Ok(())
 }
 
-fn get_sol_lock_account(program_id: &Pubkey, owner: &Pubkey, acc_index: u64) -> (Pubkey, u8) {
+/// Check that an owner may create SolLock accounts, given its AllowlistEntry account
+#[cfg(feature = "allowlist")]
+fn assert_owner_allowlisted(
+    program_id: &Pubkey,
+    owner_info: &AccountInfo,
+    allowlist_entry_info: &AccountInfo,
+) -> ProgramResult {
+    assert_keys_equal(
+        get_allowlist_entry(program_id, owner_info.key).0,
+        *allowlist_entry_info.key,
+    )?;
+    assert_allowlisted(allowlist_entry_info, program_id)
+}
+
+/// Get the next account if it was passed according to the instruction data
+fn next_optional_account_info<'a, 'b, I: Iterator<Item = &'a AccountInfo<'b>>>(
+    iter: &mut I,
+    is_passed: bool,
+) -> Result<I::Item, ProgramError> {
+    if is_passed {
+        next_account_info(iter)
+    } else {
+        Err(ProgramError::NotEnoughAccountKeys)
+    }
+}
+
+/// Derive the address and bump seed of the SolLock account of an owner at an index
+pub fn get_sol_lock_account(program_id: &Pubkey, owner: &Pubkey, acc_index: u64) -> (Pubkey, u8) {
     Pubkey::find_program_address(&[owner.as_ref(), &acc_index.to_le_bytes()], program_id)
 }
 
+/// Derive the address and bump seed of an existing SolLock account from the bump seed stored in it,
+/// which costs far less than searching for the bump seed with get_sol_lock_account
+///
+/// Falls back to searching for the bump seed if the account is not a SolLock account of the
+/// current size, e.g. because it does not exist yet or still needs a Realloc.
+pub fn get_stored_sol_lock_account(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    sol_lock_account_info: &AccountInfo,
+) -> Result<(Pubkey, u8), ProgramError> {
+    let data = sol_lock_account_info.try_borrow_data()?;
+    if sol_lock_account_info.owner != program_id || data.len() < SOL_LOCK_ACCOUNT_SIZE {
+        return Ok(get_sol_lock_account(program_id, owner, acc_index));
+    }
+
+    let bump_seed = data[BUMP_SEED_OFFSET];
+    let sol_lock_account_key = Pubkey::create_program_address(
+        &[owner.as_ref(), &acc_index.to_le_bytes(), &[bump_seed]],
+        program_id,
+    )
+    .map_err(|_| SolLockError::PublicKeyMismatch)?;
+
+    Ok((sol_lock_account_key, bump_seed))
+}
+
+fn get_claim_ticket(program_id: &Pubkey, receiver: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CLAIM_TICKET_SEED, receiver.as_ref()], program_id)
+}
+
+/// Get the Contribution account of a contributor to a SolLock account
+pub fn get_contribution(
+    program_id: &Pubkey,
+    sol_lock_account: &Pubkey,
+    contributor: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CONTRIBUTION_SEED,
+            sol_lock_account.as_ref(),
+            contributor.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Get the Config account of the program
+pub fn get_config(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], program_id)
+}
+
+/// Get the AllowlistEntry account of an owner
+pub fn get_allowlist_entry(program_id: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ALLOWLIST_SEED, owner.as_ref()], program_id)
+}
+
+/// Get the account index of a labeled SolLock account
+///
+/// Labeled accounts live at an index derived from their label, so every instruction taking
+/// an acc_index works with them.
+pub fn get_label_acc_index(label: &str) -> u64 {
+    let label_hash = hashv(&[LABEL_SEED, label.as_bytes()]).to_bytes();
+    u64::from_le_bytes(*array_ref![label_hash, 0, 8])
+}
+
+/// Get the IndexCounter account of an owner
+pub fn get_index_counter(program_id: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[INDEX_COUNTER_SEED, owner.as_ref()], program_id)
+}
+
+/// Get the Stats account of the program
+pub fn get_stats(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STATS_SEED], program_id)
+}
+
+/// Get the treasury account of the program, which collects the protocol fees
+pub fn get_treasury(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_SEED], program_id)
+}
+
+/// Get the address of the receipt token mint
+pub fn get_receipt_mint(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECEIPT_MINT_SEED], program_id)
+}
+
+/// Get the SPL Governance voter weight record of an owner for a realm
+pub fn get_voter_weight_record(
+    program_id: &Pubkey,
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+    owner: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VOTER_WEIGHT_RECORD_SEED,
+            realm.as_ref(),
+            governing_token_mint.as_ref(),
+            owner.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Get the stake account of a SolLock account
+pub fn get_stake_account(program_id: &Pubkey, sol_lock_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STAKE_ACCOUNT_SEED, sol_lock_account.as_ref()], program_id)
+}
+
+/// Get the stake account a SolLock account splits part of its stake into
+pub fn get_split_stake_account(program_id: &Pubkey, sol_lock_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SPLIT_STAKE_ACCOUNT_SEED, sol_lock_account.as_ref()],
+        program_id,
+    )
+}
+
+/// Get the system account a SolLock account deposits its locked lamports into a stake pool from
+pub fn get_lst_funding_account(program_id: &Pubkey, sol_lock_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[LST_FUNDING_SEED, sol_lock_account.as_ref()], program_id)
+}
+
+/// Get the address of the position NFT mint of a SolLock account
+pub fn get_position_mint(program_id: &Pubkey, sol_lock_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POSITION_MINT_SEED, sol_lock_account.as_ref()], program_id)
+}
+
+/// Read the Config account of the program, or the default config if it is not initialized yet
+fn load_config(program_id: &Pubkey, config_info: &AccountInfo) -> Result<Config, ProgramError> {
+    assert_keys_equal(get_config(program_id).0, *config_info.key)?;
+
+    if config_info.data_is_empty() {
+        return Ok(Config::default());
+    }
+
+    assert_owned_by(config_info, program_id)?;
+    Config::unpack(&config_info.data.borrow())
+}
+
+/// Apply a change to the Stats account of the program, unless it is not initialized yet
+fn update_stats(
+    program_id: &Pubkey,
+    stats_info: &AccountInfo,
+    update: impl FnOnce(&mut Stats),
+) -> ProgramResult {
+    assert_keys_equal(get_stats(program_id).0, *stats_info.key)?;
+
+    if stats_info.data_is_empty() {
+        return Ok(());
+    }
+
+    assert_owned_by(stats_info, program_id)?;
+    let mut stats = Stats::unpack_unchecked(&stats_info.data.borrow())?;
+    update(&mut stats);
+    stats.pack_into_slice(&mut stats_info.data.borrow_mut());
+
+    Ok(())
+}
+
+fn get_name_record(program_id: &Pubkey, owner: &Pubkey, label_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[NAME_RECORD_SEED, owner.as_ref(), label_hash.as_ref()],
+        program_id,
+    )
+}
+
 /// Add Sol to a SolLock account to prepare for locking
 pub fn add_sol(program_id: &Pubkey, accounts: &[AccountInfo], ctx: AddSol) -> ProgramResult {
     msg!("SolLock::AddSol");
@@ -125,37 +496,164 @@ pub fn add_sol(program_id: &Pubkey, accounts: &[AccountInfo], ctx: AddSol) -> Pr
     let owner_info = next_account_info(account_info_iter)?;
     let sol_lock_account_info = next_account_info(account_info_iter)?;
     let system_account_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
     let sol_payer_account_res = next_account_info(account_info_iter);
 
-    let payer_account_info = if sol_payer_account_res.is_ok() {
-        if !has_payer {
-            return Err(SolLockError::ConflictingPayerInfo.into());
-        }
-        sol_payer_account_res.unwrap()
-    } else {
-        if has_payer {
-            return Err(SolLockError::ConflictingPayerInfo.into());
-        }
-        owner_info
-    };
+    let payer_account_info = assert_payer_validity(owner_info, sol_payer_account_res, has_payer)?;
 
-    let sol_lock_account_key = get_sol_lock_account(program_id, owner_info.key, acc_index).0;
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
 
+    assert_system_program(system_account_info)?;
     assert_is_signer(owner_info)?;
     assert_is_signer(payer_account_info)?;
     assert_keys_equal(sol_lock_account_key.clone(), *sol_lock_account_info.key)?;
+    assert_keys_unequal(*sol_lock_account_info.key, *payer_account_info.key)?;
     assert_owned_by(sol_lock_account_info, program_id)?;
-    assert_initialized::<Account>(&sol_lock_account_info)?;
+    let config = load_config(program_id, config_info)?;
+    assert_not_paused(&config)?;
 
-    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
-        msg!(
+    sol_lock_account_info.with_mut_data(|sol_lock_account_data| {
+        let lamports = capped_deposit(&sol_lock_account_data, lamports)?;
+
+        verbose_msg!(
             "Adding {} lamports to SolLock account {:#?}",
             lamports,
-            owner_info.key,
+            sol_lock_account_key,
+        );
+
+        invoke(
+            &system_instruction::transfer(payer_account_info.key, &sol_lock_account_key, lamports),
+            &[
+                payer_account_info.clone(),
+                sol_lock_account_info.clone(),
+                system_account_info.clone(),
+            ],
+        )?;
+
+        let mut sol_lock_account_data = apply_deposit(sol_lock_account_data, lamports, &config)?;
+        if sol_lock_account_data.state == State::Locked {
+            sol_lock_account_data.record_deposit(lamports);
+            update_stats(program_id, stats_info, |stats| {
+                stats.record_deposit(lamports)
+            })?;
+        }
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Record a deposit in a SolLock account and transition it to its next state
+fn apply_deposit(
+    mut sol_lock_account_data: Account,
+    lamports: u64,
+    config: &Config,
+) -> Result<Account, ProgramError> {
+    let new_state = match sol_lock_account_data.state {
+        State::Uninitialized => return Err(SolLockError::UnexpectedState.into()),
+        State::Initialized => State::HasFunds,
+        State::HasFunds => State::HasFunds,
+        State::HasDeadline => State::ReadyUnlocked,
+        State::ReadyUnlocked => State::ReadyUnlocked,
+        State::Locked => State::Locked,
+        State::Staked => State::Staked,
+        State::Disputed => return Err(SolLockError::FundsLocked.into()),
+    };
+
+    match sol_lock_account_data.state {
+        State::Initialized | State::HasDeadline => {
+            sol_lock_account_data.lamports = Some(lamports);
+        }
+        State::HasFunds | State::ReadyUnlocked | State::Locked | State::Staked => {
+            sol_lock_account_data.lamports = Some(checked_add(
+                sol_lock_account_data
+                    .lamports
+                    .ok_or(SolLockError::NoFunds)?,
+                lamports,
+            )?);
+        }
+        State::Uninitialized | State::Disputed => return Err(SolLockError::UnexpectedState.into()),
+    };
+    assert_min_lock_amount(&sol_lock_account_data, config)?;
+
+    sol_lock_account_data.state = new_state;
+
+    Ok(sol_lock_account_data)
+}
+
+/// Limit a deposit to the deposit cap of a SolLock account
+///
+/// Returns the lamports to deposit: all of them if the cap is not exceeded, or the part up to
+/// the cap if the account refunds the excess. Fails if the cap is exceeded otherwise.
+fn capped_deposit(sol_lock_account_data: &Account, lamports: u64) -> Result<u64, ProgramError> {
+    let room = match sol_lock_account_data.deposit_cap {
+        Some(cap) => cap.saturating_sub(sol_lock_account_data.lamports.unwrap_or(0)),
+        None => return Ok(lamports),
+    };
+
+    if lamports <= room {
+        return Ok(lamports);
+    }
+    if !sol_lock_account_data.refund_excess {
+        return Err(SolLockError::DepositCapExceeded.into());
+    }
+
+    verbose_msg!(
+        "Deposit cap reached, refunding {} lamports",
+        lamports - room
+    );
+
+    Ok(room)
+}
+
+/// Add Sol to a SolLock account and lock it
+pub fn add_sol_and_lock(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: AddSolAndLock,
+) -> ProgramResult {
+    msg!("SolLock::AddSolAndLock");
+
+    let AddSolAndLock {
+        acc_index,
+        lamports,
+        commitment,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_system_program(system_account_info)?;
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    let config = load_config(program_id, config_info)?;
+    assert_not_paused(&config)?;
+
+    sol_lock_account_info.with_mut_data(|sol_lock_account_data| {
+        assert_can_add_sol_and_lock(&sol_lock_account_data)?;
+        let lamports = capped_deposit(&sol_lock_account_data, lamports)?;
+
+        verbose_msg!(
+            "Adding {} lamports to SolLock account {:#?} and locking it",
+            lamports,
+            sol_lock_account_key,
         );
 
         invoke(
-            &system_instruction::transfer(&owner_info.key, &sol_lock_account_key, lamports),
+            &system_instruction::transfer(owner_info.key, &sol_lock_account_key, lamports),
             &[
                 owner_info.clone(),
                 sol_lock_account_info.clone(),
@@ -163,28 +661,33 @@ pub fn add_sol(program_id: &Pubkey, accounts: &[AccountInfo], ctx: AddSol) -> Pr
             ],
         )?;
 
-        let new_state = match sol_lock_account_data.state {
-            State::Uninitialized => unreachable!(),
-            State::Initialized => State::HasFunds,
-            State::HasFunds => State::HasFunds,
-            State::HasDeadline => State::ReadyUnlocked,
-            State::ReadyUnlocked => State::ReadyUnlocked,
-            State::Locked => State::Locked,
-            State::Staked => State::Staked,
-        };
+        let mut sol_lock_account_data = apply_deposit(sol_lock_account_data, lamports, &config)?;
+        assert_has_funds(&sol_lock_account_data)?;
+        assert_commitment_mutable(&sol_lock_account_data)?;
 
-        match sol_lock_account_data.state {
-            State::Initialized | State::HasDeadline => {
-                sol_lock_account_data.lamports = Some(lamports);
-            }
-            State::HasFunds | State::ReadyUnlocked | State::Locked | State::Staked => {
-                sol_lock_account_data.lamports =
-                    Some(sol_lock_account_data.lamports.unwrap() + lamports);
-            }
-            State::Uninitialized => unreachable!(),
+        sol_lock_account_data.state = match sol_lock_account_data.state {
+            State::ReadyUnlocked => State::Locked,
+            State::Uninitialized
+            | State::Initialized
+            | State::HasFunds
+            | State::HasDeadline
+            | State::Locked
+            | State::Staked
+            | State::Disputed => return Err(SolLockError::UnexpectedState.into()),
         };
+        sol_lock_account_data.price_target_reached = false;
+        sol_lock_account_data.lock_fee_bps = config.fee_bps;
+        sol_lock_account_data.commitment = commitment;
+        sol_lock_account_data.locked_at = Some(Clock::get()?.unix_timestamp);
+        start_stream(&mut sol_lock_account_data)?;
 
-        sol_lock_account_data.state = new_state;
+        let locked_lamports = sol_lock_account_data
+            .lamports
+            .ok_or(SolLockError::NoFunds)?;
+        sol_lock_account_data.record_lock(locked_lamports);
+        update_stats(program_id, stats_info, |stats| {
+            stats.record_lock(locked_lamports)
+        })?;
 
         Ok(sol_lock_account_data)
     })?;
@@ -200,34 +703,41 @@ pub fn remove_sol(program_id: &Pubkey, accounts: &[AccountInfo], ctx: RemoveSol)
         acc_index,
         lamports,
         has_receiver,
+        memo,
+        close,
     } = ctx;
 
     let account_info_iter = &mut accounts.iter();
     let owner_info = next_account_info(account_info_iter)?;
     let sol_lock_account_info = next_account_info(account_info_iter)?;
-    let sol_receiver_account_res = next_account_info(account_info_iter);
+    let treasury_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+    let sol_receiver_account_res = next_optional_account_info(account_info_iter, has_receiver);
+    let memo_program_res = next_optional_account_info(account_info_iter, memo.is_some());
+    let cosigner_infos = account_info_iter.as_slice();
 
     let receiver_account_info =
         assert_receiver_validity(owner_info, sol_receiver_account_res, has_receiver)?;
 
-    let sol_lock_key = Pubkey::find_program_address(
-        &[owner_info.key.as_ref(), &acc_index.to_le_bytes()],
-        program_id,
-    )
-    .0;
+    let sol_lock_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
 
     assert_is_signer(owner_info)?;
     assert_keys_equal(sol_lock_key, *sol_lock_account_info.key)?;
+    assert_keys_unequal(*sol_lock_account_info.key, *receiver_account_info.key)?;
     assert_owned_by(sol_lock_account_info, program_id)?;
-    assert_initialized::<Account>(&sol_lock_account_info)?;
 
     sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
         assert_sufficient_funds(&sol_lock_account_data, lamports)?;
         assert_has_funds(&sol_lock_account_data)?;
+        if close && sol_lock_account_data.lamports != Some(lamports) {
+            return Err(SolLockError::CloseRequiresFullWithdrawal.into());
+        }
 
         macro_rules! has_lamports_remaining {
             ($account:ident, $lamports:ident) => {
-                $account.lamports.unwrap() > $lamports
+                $account.lamports.unwrap_or(0) > $lamports
             };
         }
 
@@ -237,33 +747,115 @@ pub fn remove_sol(program_id: &Pubkey, accounts: &[AccountInfo], ctx: RemoveSol)
             }
             State::HasFunds => State::Initialized,
             State::ReadyUnlocked => State::ReadyUnlocked,
+            State::Locked if sol_lock_account_data.vesting_start.is_some() => {
+                assert_no_unlock_authority(&sol_lock_account_data)?;
+                assert_multisig_approved(&sol_lock_account_data, cosigner_infos)?;
+                let now = Clock::get()?.unix_timestamp;
+                roll_deadline(&mut sol_lock_account_data, now)?;
+                assert_can_withdraw_vested(&sol_lock_account_data, lamports, now)?;
+                assert_no_outstanding_tokens(&sol_lock_account_data)?;
+                assert_stored_receiver(&sol_lock_account_data, receiver_account_info)?;
+
+                if has_lamports_remaining!(sol_lock_account_data, lamports) {
+                    State::Locked
+                } else {
+                    State::Initialized
+                }
+            }
 
             State::Initialized | State::HasDeadline => return Err(SolLockError::NoFunds.into()),
-            State::Locked | State::Staked => return Err(SolLockError::FundsLocked.into()),
+            State::Locked | State::Staked | State::Disputed => {
+                return Err(SolLockError::FundsLocked.into())
+            }
 
-            State::Uninitialized => unreachable!(),
+            State::Uninitialized => return Err(SolLockError::UnexpectedState.into()),
         };
 
+        // Vested lamports are unlocked, so they pay the protocol fee like Unlock
+        let is_unlock = sol_lock_account_data.state == State::Locked;
         match sol_lock_account_data.state {
             State::HasFunds | State::ReadyUnlocked => {
-                sol_lock_account_data.lamports =
-                    Some(sol_lock_account_data.lamports.unwrap() - lamports);
+                sol_lock_account_data.lamports = Some(checked_sub(
+                    sol_lock_account_data
+                        .lamports
+                        .ok_or(SolLockError::NoFunds)?,
+                    lamports,
+                )?);
             }
+            State::Locked => withdraw_vested(&mut sol_lock_account_data, lamports)?,
             State::Uninitialized
             | State::Initialized
             | State::HasDeadline
-            | State::Locked
-            | State::Staked => unreachable!(),
+            | State::Staked
+            | State::Disputed => return Err(SolLockError::UnexpectedState.into()),
         };
 
         sol_lock_account_data.state = new_state;
 
-        **sol_lock_account_info.try_borrow_mut_lamports()? -= lamports;
-        **receiver_account_info.try_borrow_mut_lamports()? += lamports;
+        if close {
+            assert_no_outstanding_tokens(&sol_lock_account_data)?;
+            assert_no_yield_backend(&sol_lock_account_data)?;
+        } else {
+            assert_rent_reserve_kept(
+                &sol_lock_account_data,
+                sol_lock_account_info.lamports(),
+                lamports,
+            )?;
+            assert_remains_rent_exempt(&Rent::get()?, sol_lock_account_info, lamports)?;
+        }
+
+        if is_unlock {
+            let unlocked = sol_lock_account_data.state == State::Initialized;
+            update_stats(program_id, stats_info, |stats| {
+                stats.record_unlock(lamports, unlocked)
+            })?;
+        }
+
+        let lamports_to_transfer = if is_unlock {
+            pay_unlock_fee(
+                program_id,
+                &sol_lock_account_data,
+                sol_lock_account_info,
+                treasury_info,
+                lamports,
+            )?
+        } else {
+            lamports
+        };
+
+        // Closing transfers the whole remaining balance below
+        if !close {
+            transfer_lamports(
+                sol_lock_account_info,
+                receiver_account_info,
+                lamports_to_transfer,
+            )?;
+        }
 
         Ok(sol_lock_account_data)
     })?;
 
+    if close {
+        close_sol_lock_account(sol_lock_account_info, receiver_account_info)?;
+    }
+
+    forward_memo(memo.as_deref(), memo_program_res)?;
+
+    Ok(())
+}
+
+/// Log the memo of an Unlock or RemoveSol instruction with the SPL Memo program, if one is passed
+fn forward_memo(
+    memo: Option<&str>,
+    memo_program_res: Result<&AccountInfo, ProgramError>,
+) -> ProgramResult {
+    if let Some(memo) = memo {
+        let memo_program_info = memo_program_res?;
+        assert_keys_equal(MEMO_PROGRAM_ID, *memo_program_info.key)?;
+
+        invoke(&memo::build_memo(memo), &[memo_program_info.clone()])?;
+    }
+
     Ok(())
 }
 
@@ -283,162 +875,10772 @@ pub fn set_deadline(
     let account_info_iter = &mut accounts.iter();
     let owner_info = next_account_info(account_info_iter)?;
     let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
 
-    let sol_lock_account_key = get_sol_lock_account(program_id, owner_info.key, acc_index).0;
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
 
     assert_is_signer(owner_info)?;
     assert_keys_equal(sol_lock_account_key.clone(), *sol_lock_account_info.key)?;
     assert_owned_by(sol_lock_account_info, program_id)?;
-    assert_initialized::<Account>(&sol_lock_account_info)?;
+    let config = load_config(program_id, config_info)?;
 
-    msg!(
+    verbose_msg!(
         "Setting deadline for SolLock account {:#?} to {:#?}",
-        owner_info.key,
+        sol_lock_account_key,
         deadline
     );
 
-    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
-        assert_valid_new_deadline(&sol_lock_account_data, deadline)?;
-
-        let new_state = match sol_lock_account_data.state {
-            State::Initialized => State::HasDeadline,
-            State::HasFunds => State::ReadyUnlocked,
-            State::HasDeadline => State::HasDeadline,
-            State::ReadyUnlocked => State::ReadyUnlocked,
-            State::Locked => State::Locked,
-            State::Staked => State::Locked,
-            State::Uninitialized => unreachable!(),
-        };
-
-        match sol_lock_account_data.state {
-            State::Initialized
-            | State::HasFunds
-            | State::HasDeadline
-            | State::ReadyUnlocked
-            | State::Locked
-            | State::Staked => {
-                sol_lock_account_data.deadline = Some(deadline);
-            }
-            State::Uninitialized => unreachable!(),
-        };
-
-        sol_lock_account_data.state = new_state;
-
-        Ok(sol_lock_account_data)
+    sol_lock_account_info.with_mut_data(|sol_lock_account_data| {
+        apply_deadline(sol_lock_account_data, deadline, &config)
     })?;
 
     Ok(())
 }
 
-/// Lock a SolLock account
-pub fn lock(program_id: &Pubkey, accounts: &[AccountInfo], ctx: Lock) -> ProgramResult {
-    msg!("SolLock::Lock");
+/// Set the deadline of a SolLock account relative to the current on-chain time
+pub fn set_deadline_relative(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetDeadlineRelative,
+) -> ProgramResult {
+    msg!("SolLock::SetDeadlineRelative");
 
-    let Lock { acc_index } = ctx;
+    let SetDeadlineRelative { acc_index, seconds } = ctx;
 
     let account_info_iter = &mut accounts.iter();
     let owner_info = next_account_info(account_info_iter)?;
     let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
 
-    let sol_lock_account_key = get_sol_lock_account(program_id, owner_info.key, acc_index).0;
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
 
     assert_is_signer(owner_info)?;
-    assert_keys_equal(sol_lock_account_key.clone(), *sol_lock_account_info.key)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
     assert_owned_by(sol_lock_account_info, program_id)?;
-    assert_initialized::<Account>(&sol_lock_account_info)?;
-
-    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
-        assert_can_lock(&sol_lock_account_data)?;
+    let config = load_config(program_id, config_info)?;
 
-        msg!("Locking SolLock account {:#?}", owner_info.key);
+    let now = Clock::get()?.unix_timestamp;
+    let deadline = TryInto::<UnixTimestamp>::try_into(seconds)
+        .ok()
+        .and_then(|seconds| now.checked_add(seconds))
+        .ok_or(ProgramError::InvalidInstructionData)?;
 
-        sol_lock_account_data.state = match sol_lock_account_data.state {
-            State::ReadyUnlocked => State::Locked,
-            State::Uninitialized
-            | State::Initialized
-            | State::HasFunds
-            | State::HasDeadline
-            | State::Locked
-            | State::Staked => unreachable!(),
-        };
+    verbose_msg!(
+        "Setting deadline for SolLock account {:#?} to {:#?} ({} seconds from now)",
+        sol_lock_account_key,
+        deadline,
+        seconds
+    );
 
-        Ok(sol_lock_account_data)
+    sol_lock_account_info.with_mut_data(|sol_lock_account_data| {
+        apply_deadline(sol_lock_account_data, deadline, &config)
     })?;
 
     Ok(())
 }
 
-/// Unlock a SolLock account
+/// Set the deadline of a SolLock account and transition it to its next state
+fn apply_deadline(
+    mut sol_lock_account_data: Account,
+    deadline: UnixTimestamp,
+    config: &Config,
+) -> Result<Account, ProgramError> {
+    assert_valid_new_deadline(&sol_lock_account_data, deadline)?;
+    assert_deadline_not_too_far(deadline, Clock::get()?.unix_timestamp, config)?;
+
+    let new_state = match sol_lock_account_data.state {
+        State::Initialized => State::HasDeadline,
+        State::HasFunds => State::ReadyUnlocked,
+        State::HasDeadline => State::HasDeadline,
+        State::ReadyUnlocked => State::ReadyUnlocked,
+        State::Locked => State::Locked,
+        State::Staked => State::Locked,
+        State::Disputed => return Err(SolLockError::FundsLocked.into()),
+        State::Uninitialized => return Err(SolLockError::UnexpectedState.into()),
+    };
+
+    match sol_lock_account_data.state {
+        State::Initialized
+        | State::HasFunds
+        | State::HasDeadline
+        | State::ReadyUnlocked
+        | State::Locked
+        | State::Staked => {
+            sol_lock_account_data.deadline = Some(deadline);
+        }
+        State::Uninitialized | State::Disputed => return Err(SolLockError::UnexpectedState.into()),
+    };
+
+    sol_lock_account_data.state = new_state;
+
+    Ok(sol_lock_account_data)
+}
+
+/// Set the same deadline on several SolLock accounts of an owner
+pub fn set_deadline_many(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetDeadlineMany,
+) -> ProgramResult {
+    msg!("SolLock::SetDeadlineMany");
+
+    let SetDeadlineMany {
+        acc_indices,
+        deadline,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    assert_is_signer(owner_info)?;
+    let config = load_config(program_id, config_info)?;
+
+    let sol_lock_account_infos = account_info_iter.as_slice();
+    if sol_lock_account_infos.len() != acc_indices.len() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    for (acc_index, sol_lock_account_info) in acc_indices.into_iter().zip(sol_lock_account_infos) {
+        let sol_lock_account_key = get_stored_sol_lock_account(
+            program_id,
+            owner_info.key,
+            acc_index,
+            sol_lock_account_info,
+        )?
+        .0;
+
+        assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+        assert_owned_by(sol_lock_account_info, program_id)?;
+
+        verbose_msg!(
+            "Setting deadline for SolLock account {:#?} to {:#?}",
+            sol_lock_account_info.key,
+            deadline
+        );
+
+        sol_lock_account_info.with_mut_data(|sol_lock_account_data| {
+            apply_deadline(sol_lock_account_data, deadline, &config)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Lock a SolLock account
+pub fn lock(program_id: &Pubkey, accounts: &[AccountInfo], ctx: Lock) -> ProgramResult {
+    msg!("SolLock::Lock");
+
+    let Lock {
+        acc_index,
+        commitment,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key.clone(), *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    let config = load_config(program_id, config_info)?;
+    assert_not_paused(&config)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_can_lock(&sol_lock_account_data, &config)?;
+        assert_commitment_mutable(&sol_lock_account_data)?;
+
+        verbose_msg!("Locking SolLock account {:#?}", sol_lock_account_key);
+
+        sol_lock_account_data.state = match sol_lock_account_data.state {
+            State::ReadyUnlocked => State::Locked,
+            State::Uninitialized
+            | State::Initialized
+            | State::HasFunds
+            | State::HasDeadline
+            | State::Locked
+            | State::Staked
+            | State::Disputed => return Err(SolLockError::UnexpectedState.into()),
+        };
+        sol_lock_account_data.price_target_reached = false;
+        sol_lock_account_data.lock_fee_bps = config.fee_bps;
+        sol_lock_account_data.commitment = commitment;
+        sol_lock_account_data.locked_at = Some(Clock::get()?.unix_timestamp);
+        start_stream(&mut sol_lock_account_data)?;
+
+        let locked_lamports = sol_lock_account_data
+            .lamports
+            .ok_or(SolLockError::NoFunds)?;
+        sol_lock_account_data.record_lock(locked_lamports);
+        update_stats(program_id, stats_info, |stats| {
+            stats.record_lock(locked_lamports)
+        })?;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Unlock a SolLock account
 pub fn unlock(program_id: &Pubkey, accounts: &[AccountInfo], ctx: Unlock) -> ProgramResult {
     msg!("SolLock::Unlock");
 
     let Unlock {
         acc_index,
         has_receiver,
+        nonce,
+        memo,
     } = ctx;
 
     let account_info_iter = &mut accounts.iter();
     let owner_info = next_account_info(account_info_iter)?;
     let sol_lock_account_info = next_account_info(account_info_iter)?;
-    let sol_receiver_account_res = next_account_info(account_info_iter);
+    let treasury_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+    let sol_receiver_account_res = next_optional_account_info(account_info_iter, has_receiver);
+    let memo_program_res = next_optional_account_info(account_info_iter, memo.is_some());
+    let unlock_signer_infos = account_info_iter.as_slice();
 
     let receiver_account_info =
         assert_receiver_validity(owner_info, sol_receiver_account_res, has_receiver)?;
 
-    let sol_lock_account_key = get_sol_lock_account(program_id, owner_info.key, acc_index).0;
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
 
-    assert_is_signer(owner_info)?;
     assert_keys_equal(sol_lock_account_key.clone(), *sol_lock_account_info.key)?;
+    assert_keys_unequal(*sol_lock_account_info.key, *receiver_account_info.key)?;
     assert_owned_by(sol_lock_account_info, program_id)?;
-    assert_initialized::<Account>(&sol_lock_account_info)?;
 
     sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
-        let now = Clock::get()?.unix_timestamp;
+        assert_unlock_approved(&sol_lock_account_data, owner_info, unlock_signer_infos)?;
+        consume_delegated_nonce(&mut sol_lock_account_data, nonce)?;
+        assert_stored_receiver(&sol_lock_account_data, receiver_account_info)?;
 
-        if let Err(err) = assert_can_unlock(&sol_lock_account_data, now) {
-            let premature_unlock: ProgramError = SolLockError::PrematureUnlock.into();
-            if err == premature_unlock {
-                msg!(
-                    "Deadline: {}, Now: {}",
-                    sol_lock_account_data.deadline.unwrap(),
-                    now
-                );
+        let lamports = release_unlocked_lamports(&mut sol_lock_account_data)?;
+        assert_rent_reserve_kept(
+            &sol_lock_account_data,
+            sol_lock_account_info.lamports(),
+            lamports,
+        )?;
+        let unlocked = sol_lock_account_data.state == State::Initialized;
+        sol_lock_account_data.record_withdrawal(lamports);
+        update_stats(program_id, stats_info, |stats| {
+            stats.record_unlock(lamports, unlocked)
+        })?;
+        let lamports_to_transfer = pay_unlock_fee(
+            program_id,
+            &sol_lock_account_data,
+            sol_lock_account_info,
+            treasury_info,
+            lamports,
+        )?;
+
+        transfer_lamports(
+            sol_lock_account_info,
+            receiver_account_info,
+            lamports_to_transfer,
+        )?;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    forward_memo(memo.as_deref(), memo_program_res)?;
+
+    Ok(())
+}
+
+/// Release the lamports of a locked SolLock account that can be unlocked now
+///
+/// Releases all lamports and unlocks the account once its deadline has passed, or only
+/// the vested lamports if the account is vesting and its deadline has not passed yet.
+/// Returns the lamports released, which the caller must transfer out of the account.
+fn release_unlocked_lamports(sol_lock_account_data: &mut Account) -> Result<u64, ProgramError> {
+    let now = Clock::get()?.unix_timestamp;
+    roll_deadline(sol_lock_account_data, now)?;
+
+    if is_vesting_before_deadline(sol_lock_account_data, now) {
+        let lamports = sol_lock_account_data
+            .withdrawable_vested_lamports(now)
+            .ok_or(SolLockError::AccountFieldNotSet)?;
+        assert_can_withdraw_vested(sol_lock_account_data, lamports, now)?;
+        assert_no_outstanding_tokens(sol_lock_account_data)?;
+
+        verbose_msg!("Withdrawing {} vested lamports", lamports);
+
+        withdraw_vested(sol_lock_account_data, lamports)?;
+
+        return Ok(lamports);
+    }
+
+    check_unlock(sol_lock_account_data, now)?;
+
+    let new_state = match sol_lock_account_data.state {
+        State::Locked => State::Initialized,
+        State::Uninitialized
+        | State::Initialized
+        | State::HasFunds
+        | State::HasDeadline
+        | State::ReadyUnlocked
+        | State::Staked
+        | State::Disputed => return Err(SolLockError::UnexpectedState.into()),
+    };
+
+    let lamports_to_transfer = match sol_lock_account_data.state {
+        State::Locked => {
+            let lamports = sol_lock_account_data
+                .lamports
+                .take()
+                .ok_or(SolLockError::NoFunds)?;
+            sol_lock_account_data.deadline = None;
+            sol_lock_account_data.stake_account = None;
+            sol_lock_account_data.vested_withdrawn = 0;
+            sol_lock_account_data.stream_start = None;
+            sol_lock_account_data.stream_claimed = 0;
+            lamports
+        }
+        State::Initialized
+        | State::HasFunds
+        | State::HasDeadline
+        | State::ReadyUnlocked
+        | State::Staked
+        | State::Disputed
+        | State::Uninitialized => return Err(SolLockError::UnexpectedState.into()),
+    };
+
+    sol_lock_account_data.state = new_state;
+
+    Ok(lamports_to_transfer)
+}
+
+/// Transfer the protocol fee on `lamports` unlocked from a SolLock account to the treasury
+///
+/// The fee is the one frozen into the account when it was locked. Returns the lamports
+/// left for the receiver, which the caller must transfer out of the account.
+fn pay_unlock_fee(
+    program_id: &Pubkey,
+    sol_lock_account_data: &Account,
+    sol_lock_account_info: &AccountInfo,
+    treasury_info: &AccountInfo,
+    lamports: u64,
+) -> Result<u64, ProgramError> {
+    assert_keys_equal(get_treasury(program_id).0, *treasury_info.key)?;
+
+    let fee = (lamports as u128 * sol_lock_account_data.lock_fee_bps as u128
+        / TOTAL_SHARE_BPS as u128) as u64;
+
+    if fee > 0 {
+        verbose_msg!("Paying a protocol fee of {} lamports", fee);
+
+        transfer_lamports(sol_lock_account_info, treasury_info, fee)?;
+    }
+
+    checked_sub(lamports, fee)
+}
+
+/// Check the nonce of an instruction signed by a delegate of the owner and increment it
+///
+/// Only the unlock authority acts on behalf of the owner, so instructions signed by the owner
+/// do not need a nonce.
+fn consume_delegated_nonce(
+    sol_lock_account_data: &mut Account,
+    nonce: Option<u64>,
+) -> ProgramResult {
+    if sol_lock_account_data.unlock_authority.is_none() {
+        return Ok(());
+    }
+
+    assert_valid_nonce(sol_lock_account_data, nonce)?;
+    sol_lock_account_data.nonce += 1;
+
+    Ok(())
+}
+
+/// Whether a SolLock account is vesting and its deadline has not passed yet,
+/// in which case only its vested lamports can be withdrawn
+fn is_vesting_before_deadline(sol_lock_account_data: &Account, now: UnixTimestamp) -> bool {
+    sol_lock_account_data.state == State::Locked
+        && sol_lock_account_data.vesting_start.is_some()
+        && matches!(sol_lock_account_data.deadline, Some(deadline) if now < deadline)
+}
+
+/// Withdraw vested lamports from a locked SolLock account, unlocking it if none are left
+///
+/// The caller must check that the lamports have vested with `assert_can_withdraw_vested`.
+fn withdraw_vested(sol_lock_account_data: &mut Account, lamports: u64) -> ProgramResult {
+    let remaining = checked_sub(
+        sol_lock_account_data
+            .lamports
+            .ok_or(SolLockError::NoFunds)?,
+        lamports,
+    )?;
+
+    if remaining > 0 {
+        sol_lock_account_data.lamports = Some(remaining);
+        sol_lock_account_data.vested_withdrawn =
+            checked_add(sol_lock_account_data.vested_withdrawn, lamports)?;
+    } else {
+        sol_lock_account_data.lamports = None;
+        sol_lock_account_data.deadline = None;
+        sol_lock_account_data.vested_withdrawn = 0;
+        sol_lock_account_data.state = State::Initialized;
+    }
+
+    Ok(())
+}
+
+/// Claim streamed lamports from a locked SolLock account, unlocking it if none are left
+fn withdraw_streamed(sol_lock_account_data: &mut Account, lamports: u64) -> ProgramResult {
+    let remaining = checked_sub(
+        sol_lock_account_data
+            .lamports
+            .ok_or(SolLockError::NoFunds)?,
+        lamports,
+    )?;
+
+    if remaining > 0 {
+        sol_lock_account_data.lamports = Some(remaining);
+        sol_lock_account_data.stream_claimed =
+            checked_add(sol_lock_account_data.stream_claimed, lamports)?;
+    } else {
+        sol_lock_account_data.lamports = None;
+        sol_lock_account_data.deadline = None;
+        sol_lock_account_data.stream_start = None;
+        sol_lock_account_data.stream_claimed = 0;
+        sol_lock_account_data.state = State::Initialized;
+    }
+
+    Ok(())
+}
+
+/// Start streaming a SolLock account that was just locked, if it streams
+fn start_stream(sol_lock_account_data: &mut Account) -> ProgramResult {
+    if sol_lock_account_data.stream_rate.is_some() {
+        sol_lock_account_data.stream_start = Some(Clock::get()?.unix_timestamp);
+        sol_lock_account_data.stream_claimed = 0;
+    }
+
+    Ok(())
+}
+
+/// Roll the deadline of an auto-extending SolLock account forward if its grace period has passed
+///
+/// The deadline moves by whole auto-extension periods until `now` is before the end of
+/// the grace period following the new deadline.
+fn roll_deadline(sol_lock_account_data: &mut Account, now: UnixTimestamp) -> ProgramResult {
+    if let Some(new_deadline) = sol_lock_account_data.rolled_deadline(now)? {
+        verbose_msg!(
+            "Grace period ended, rolling deadline forward to {}",
+            new_deadline
+        );
+
+        sol_lock_account_data.deadline = Some(new_deadline);
+    }
+
+    Ok(())
+}
+
+/// Check that a SolLock account can be unlocked, logging the deadlines if it is too early
+fn check_unlock(sol_lock_account_data: &Account, now: UnixTimestamp) -> ProgramResult {
+    assert_no_outstanding_tokens(sol_lock_account_data)?;
+
+    let epoch = Clock::get()?.epoch;
+    if let Err(err) = assert_can_unlock(sol_lock_account_data, now, epoch) {
+        let premature_unlock: ProgramError = SolLockError::PrematureUnlock.into();
+        if err == premature_unlock {
+            msg!(
+                "Deadline: {}, Now: {}",
+                sol_lock_account_data
+                    .deadline
+                    .ok_or(SolLockError::AccountFieldNotSet)?,
+                now
+            );
+            if let Some(epoch_deadline) = sol_lock_account_data.epoch_deadline {
+                msg!("Epoch deadline: {}, Epoch: {}", epoch_deadline, epoch);
             }
-            return Err(err);
         }
+        return Err(err);
+    }
 
-        let new_state = match sol_lock_account_data.state {
-            State::Locked => State::Initialized,
+    Ok(())
+}
+
+/// Unlock a SolLock account and close it
+pub fn unlock_and_close(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: UnlockAndClose,
+) -> ProgramResult {
+    msg!("SolLock::UnlockAndClose");
+
+    let UnlockAndClose {
+        acc_index,
+        has_receiver,
+        nonce,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+    let sol_receiver_account_res = next_optional_account_info(account_info_iter, has_receiver);
+    let unlock_signer_infos = account_info_iter.as_slice();
+
+    let receiver_account_info =
+        assert_receiver_validity(owner_info, sol_receiver_account_res, has_receiver)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_keys_unequal(*sol_lock_account_info.key, *receiver_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    let mut sol_lock_account_data = assert_initialized::<Account>(sol_lock_account_info)?;
+    assert_unlock_approved(&sol_lock_account_data, owner_info, unlock_signer_infos)?;
+    consume_delegated_nonce(&mut sol_lock_account_data, nonce)?;
+    assert_stored_receiver(&sol_lock_account_data, receiver_account_info)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    roll_deadline(&mut sol_lock_account_data, now)?;
+    check_unlock(&sol_lock_account_data, now)?;
+
+    let lamports = sol_lock_account_data
+        .lamports
+        .ok_or(SolLockError::NoFunds)?;
+    pay_unlock_fee(
+        program_id,
+        &sol_lock_account_data,
+        sol_lock_account_info,
+        treasury_info,
+        lamports,
+    )?;
+    update_stats(program_id, stats_info, |stats| {
+        stats.record_unlock(lamports, true)
+    })?;
+
+    close_sol_lock_account(sol_lock_account_info, receiver_account_info)
+}
+
+/// Close a SolLock account, transferring all of its lamports, including its rent reserve,
+/// to the receiver and clearing its data
+fn close_sol_lock_account(
+    sol_lock_account_info: &AccountInfo,
+    receiver_account_info: &AccountInfo,
+) -> ProgramResult {
+    let lamports_to_transfer = sol_lock_account_info.lamports();
+
+    verbose_msg!(
+        "Closing SolLock account {:#?} and transferring {} lamports",
+        sol_lock_account_info.key,
+        lamports_to_transfer
+    );
+
+    **sol_lock_account_info.try_borrow_mut_lamports()? = 0;
+    credit_lamports(receiver_account_info, lamports_to_transfer)?;
+
+    let mut sol_lock_account_data = sol_lock_account_info.data.borrow_mut();
+    let data_len = sol_lock_account_data.len();
+    sol_memset(&mut sol_lock_account_data, 0, data_len);
+
+    Ok(())
+}
+
+/// Stake the funds in a SolLock account
+pub fn stake(program_id: &Pubkey, accounts: &[AccountInfo], ctx: Stake) -> ProgramResult {
+    msg!("SolLock::Stake");
+
+    let Stake { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let stake_account_info = next_account_info(account_info_iter)?;
+    let vote_account_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let stake_history_info = next_account_info(account_info_iter)?;
+    let stake_config_info = next_account_info(account_info_iter)?;
+    let stake_program_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+
+    let (sol_lock_account_key, sol_lock_account_bump_seed) =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?;
+    let (stake_account_key, stake_account_bump_seed) =
+        get_stake_account(program_id, &sol_lock_account_key);
+
+    assert_clock_sysvar(clock_info)?;
+    assert_rent_sysvar(rent_info)?;
+    assert_stake_history_sysvar(stake_history_info)?;
+    assert_stake_config(stake_config_info)?;
+    assert_stake_program(stake_program_info)?;
+    assert_system_program(system_account_info)?;
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_keys_equal(stake_account_key, *stake_account_info.key)?;
+
+    let acc_index_bytes = acc_index.to_le_bytes();
+    let sol_lock_account_seeds: &[&[u8]] = &[
+        owner_info.key.as_ref(),
+        &acc_index_bytes,
+        &[sol_lock_account_bump_seed],
+    ];
+    let stake_account_seeds: &[&[u8]] = &[
+        STAKE_ACCOUNT_SEED,
+        sol_lock_account_key.as_ref(),
+        &[stake_account_bump_seed],
+    ];
+
+    let adapter = NativeStake {
+        sol_lock: SolLockSigner {
+            info: sol_lock_account_info,
+            seeds: sol_lock_account_seeds,
+        },
+        stake_account_info,
+        clock_info,
+        stake_history_info,
+        stake_program_info,
+        delegation: Some(NativeStakeDelegation {
+            stake_account_seeds,
+            vote_account_info,
+            rent_info,
+            stake_config_info,
+            system_account_info,
+        }),
+    };
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_can_stake(&sol_lock_account_data)?;
+        assert_validator_allowed(&sol_lock_account_data, vote_account_info.key)?;
+
+        adapter.deposit(sol_lock_account_data.lamports.unwrap_or(0))?;
+
+        sol_lock_account_data.state = State::Staked;
+        sol_lock_account_data.stake_account = Some(stake_account_key);
+        sol_lock_account_data.yield_backend = adapter.backend();
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Unstake the funds in a SolLock account
+pub fn unstake(program_id: &Pubkey, accounts: &[AccountInfo], ctx: Unstake) -> ProgramResult {
+    msg!("SolLock::Unstake");
+
+    let Unstake { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let stake_account_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let stake_history_info = next_account_info(account_info_iter)?;
+    let stake_program_info = next_account_info(account_info_iter)?;
+
+    let (sol_lock_account_key, sol_lock_account_bump_seed) =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?;
+
+    assert_clock_sysvar(clock_info)?;
+    assert_stake_history_sysvar(stake_history_info)?;
+    assert_stake_program(stake_program_info)?;
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    let acc_index_bytes = acc_index.to_le_bytes();
+    let sol_lock_account_seeds: &[&[u8]] = &[
+        owner_info.key.as_ref(),
+        &acc_index_bytes,
+        &[sol_lock_account_bump_seed],
+    ];
+
+    let adapter = NativeStake {
+        sol_lock: SolLockSigner {
+            info: sol_lock_account_info,
+            seeds: sol_lock_account_seeds,
+        },
+        stake_account_info,
+        clock_info,
+        stake_history_info,
+        stake_program_info,
+        delegation: None,
+    };
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_can_unstake(&sol_lock_account_data)?;
+
+        let stake_account_key = sol_lock_account_data
+            .stake_account
+            .ok_or(SolLockError::InvalidStakeAccount)?;
+        let is_split_stake_account =
+            sol_lock_account_data.split_stake_account == Some(*stake_account_info.key);
+        if !is_split_stake_account {
+            assert_keys_equal(stake_account_key, *stake_account_info.key)?;
+        }
+
+        if !adapter.start_withdrawal()? {
+            return Ok(sol_lock_account_data);
+        }
+
+        // The split stake account holds part of the locked lamports, so it must come back first
+        if !is_split_stake_account && sol_lock_account_data.split_stake_account.is_some() {
+            return Err(SolLockError::SplitStakeOutstanding.into());
+        }
+
+        let lamports = adapter.withdraw()?;
+
+        if is_split_stake_account {
+            sol_lock_account_data.split_stake_account = None;
+            sol_lock_account_data.unstaked_lamports =
+                checked_add(sol_lock_account_data.unstaked_lamports, lamports)?;
+
+            return Ok(sol_lock_account_data);
+        }
+
+        sol_lock_account_data.state = match sol_lock_account_data.state {
+            State::Staked => State::Locked,
             State::Uninitialized
             | State::Initialized
             | State::HasFunds
             | State::HasDeadline
             | State::ReadyUnlocked
-            | State::Staked => unreachable!(),
-        };
-
-        let lamports_to_transfer = match sol_lock_account_data.state {
-            State::Locked => {
-                let lamports = sol_lock_account_data.lamports.take().unwrap();
-                sol_lock_account_data.deadline = None;
-                sol_lock_account_data.stake_account = None;
-                lamports
-            }
-            State::Initialized
-            | State::HasFunds
-            | State::HasDeadline
-            | State::ReadyUnlocked
-            | State::Staked
-            | State::Uninitialized => unreachable!(),
+            | State::Locked
+            | State::Disputed => return Err(SolLockError::UnexpectedState.into()),
         };
-
-        sol_lock_account_data.state = new_state;
-
-        **sol_lock_account_info.try_borrow_mut_lamports()? -= lamports_to_transfer;
-        **receiver_account_info.try_borrow_mut_lamports()? += lamports_to_transfer;
+        sol_lock_account_data.lamports = Some(checked_add(
+            lamports,
+            sol_lock_account_data.unstaked_lamports,
+        )?);
+        sol_lock_account_data.stake_account = None;
+        sol_lock_account_data.unstaked_lamports = 0;
+        sol_lock_account_data.yield_backend = YieldBackend::None;
 
         Ok(sol_lock_account_data)
     })?;
 
     Ok(())
 }
+
+/// Top up the rent-exempt reserve of a SolLock account
+pub fn top_up_rent(program_id: &Pubkey, accounts: &[AccountInfo], ctx: TopUpRent) -> ProgramResult {
+    msg!("SolLock::TopUpRent");
+
+    let TopUpRent { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let payer_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+
+    assert_system_program(system_account_info)?;
+    assert_is_signer(payer_info)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    let mut sol_lock_account_data = assert_initialized::<Account>(sol_lock_account_info)?;
+
+    let sol_lock_account_key = get_stored_sol_lock_account(
+        program_id,
+        &sol_lock_account_data.owner,
+        acc_index,
+        sol_lock_account_info,
+    )?
+    .0;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+
+    let rent = Rent::get()?;
+    let required_reserve = rent.minimum_balance(sol_lock_account_info.data_len());
+    let current_reserve = sol_lock_account_info
+        .lamports()
+        .saturating_sub(sol_lock_account_data.held_lamports());
+    let top_up = required_reserve.saturating_sub(current_reserve);
+
+    sol_lock_account_data.rent_reserve = required_reserve;
+    sol_lock_account_data.pack_into_slice(&mut sol_lock_account_info.data.borrow_mut());
+
+    if top_up == 0 {
+        verbose_msg!(
+            "SolLock account {:#?} is already rent-exempt.",
+            sol_lock_account_info.key
+        );
+        return Ok(());
+    }
+
+    invoke(
+        &system_instruction::transfer(payer_info.key, &sol_lock_account_key, top_up),
+        &[
+            payer_info.clone(),
+            sol_lock_account_info.clone(),
+            system_account_info.clone(),
+        ],
+    )?;
+
+    verbose_msg!(
+        "Topped up rent reserve of SolLock account {:#?} by {} lamports",
+        sol_lock_account_info.key,
+        top_up
+    );
+
+    Ok(())
+}
+
+/// Create a SolLock account and lock lamports in it
+pub fn create_and_lock(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: CreateAndLock,
+) -> ProgramResult {
+    msg!("SolLock::CreateAndLock");
+
+    let CreateAndLock {
+        acc_index,
+        lamports,
+        deadline,
+        has_payer,
+        memo,
+        commitment,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+    #[cfg(feature = "allowlist")]
+    let allowlist_entry_info = next_account_info(account_info_iter)?;
+    let sol_payer_account_res = next_account_info(account_info_iter);
+
+    let payer_account_info = assert_payer_validity(owner_info, sol_payer_account_res, has_payer)?;
+
+    let (sol_lock_account_key, sol_lock_account_bump_seed) =
+        get_sol_lock_account(program_id, owner_info.key, acc_index);
+
+    assert_system_program(system_account_info)?;
+    assert_is_signer(payer_account_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, system_account_info.key)?;
+    let config = load_config(program_id, config_info)?;
+    assert_not_paused(&config)?;
+    let now = Clock::get()?.unix_timestamp;
+    assert_deadline_not_too_far(deadline, now, &config)?;
+    assert_valid_memo(&memo)?;
+    #[cfg(feature = "allowlist")]
+    assert_owner_allowlisted(program_id, owner_info, allowlist_entry_info)?;
+
+    let mut sol_lock_account_data = Account {
+        owner: *owner_info.key,
+        lamports: Some(lamports),
+        deadline: Some(deadline),
+        state: State::Locked,
+        lock_fee_bps: config.fee_bps,
+        memo,
+        commitment,
+        created_at: now,
+        locked_at: Some(now),
+        lock_count: 1,
+        lifetime_locked: lamports,
+        bump_seed: sol_lock_account_bump_seed,
+        ..Account::default()
+    };
+    assert_has_funds(&sol_lock_account_data)?;
+    assert_min_lock_amount(&sol_lock_account_data, &config)?;
+
+    let rent = Rent::get()?;
+    let rent_lamports = rent.minimum_balance(SOL_LOCK_ACCOUNT_SIZE);
+    sol_lock_account_data.rent_reserve = rent_lamports;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            payer_account_info.key,
+            &sol_lock_account_key,
+            checked_add(rent_lamports, lamports)?,
+            SOL_LOCK_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[
+            payer_account_info.clone(),
+            sol_lock_account_info.clone(),
+            system_account_info.clone(),
+        ],
+        &[&[
+            owner_info.key.as_ref(),
+            &acc_index.to_le_bytes(),
+            &[sol_lock_account_bump_seed],
+        ]],
+    )?;
+
+    sol_lock_account_data.pack_into_slice(&mut sol_lock_account_info.data.borrow_mut());
+    update_stats(program_id, stats_info, |stats| stats.record_lock(lamports))?;
+
+    verbose_msg!(
+        "Account {:?} created and locked with {} lamports until {}.",
+        sol_lock_account_info.key,
+        lamports,
+        deadline
+    );
+
+    Ok(())
+}
+
+/// Point a human-readable name of the owner at a SolLock account
+pub fn set_name(program_id: &Pubkey, accounts: &[AccountInfo], ctx: SetName) -> ProgramResult {
+    msg!("SolLock::SetName");
+
+    let SetName {
+        acc_index,
+        label_hash,
+        has_payer,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let name_record_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let sol_payer_account_res = next_optional_account_info(account_info_iter, has_payer);
+
+    let payer_account_info = assert_payer_validity(owner_info, sol_payer_account_res, has_payer)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+    let (name_record_key, name_record_bump_seed) =
+        get_name_record(program_id, owner_info.key, &label_hash);
+
+    assert_system_program(system_account_info)?;
+    assert_is_signer(owner_info)?;
+    assert_is_signer(payer_account_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_initialized::<Account>(sol_lock_account_info)?;
+    assert_keys_equal(name_record_key, *name_record_info.key)?;
+
+    let previous_sol_lock_account = if name_record_info.owner == system_account_info.key {
+        let rent = Rent::get()?;
+
+        invoke_signed(
+            &system_instruction::create_account(
+                payer_account_info.key,
+                &name_record_key,
+                rent.minimum_balance(NAME_RECORD_SIZE),
+                NAME_RECORD_SIZE as u64,
+                program_id,
+            ),
+            &[
+                payer_account_info.clone(),
+                name_record_info.clone(),
+                system_account_info.clone(),
+            ],
+            &[&[
+                NAME_RECORD_SEED,
+                owner_info.key.as_ref(),
+                label_hash.as_ref(),
+                &[name_record_bump_seed],
+            ]],
+        )?;
+
+        None
+    } else {
+        assert_owned_by(name_record_info, program_id)?;
+        Some(NameRecord::unpack_from_slice(&name_record_info.data.borrow())?.sol_lock_account)
+    };
+
+    if previous_sol_lock_account != Some(sol_lock_account_key) {
+        if let Some(previous_sol_lock_account) = previous_sol_lock_account {
+            let previous_sol_lock_account_info = next_account_info(account_info_iter)?;
+            assert_keys_equal(
+                previous_sol_lock_account,
+                *previous_sol_lock_account_info.key,
+            )?;
+
+            // A closed account has no count left to release
+            if previous_sol_lock_account_info.owner == program_id
+                && !previous_sol_lock_account_info.data_is_empty()
+            {
+                previous_sol_lock_account_info.with_mut_data(|mut previous_data| {
+                    previous_data.name_records = previous_data.name_records.saturating_sub(1);
+                    Ok(previous_data)
+                })?;
+            }
+        }
+
+        sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+            sol_lock_account_data.name_records = sol_lock_account_data
+                .name_records
+                .checked_add(1)
+                .ok_or(SolLockError::ArithmeticOverflow)?;
+            Ok(sol_lock_account_data)
+        })?;
+    }
+
+    let name_record = NameRecord {
+        owner: *owner_info.key,
+        label_hash,
+        sol_lock_account: sol_lock_account_key,
+    };
+
+    name_record.pack_into_slice(&mut name_record_info.data.borrow_mut());
+
+    verbose_msg!(
+        "Name record {:?} now points to SolLock account {:?}.",
+        name_record_info.key,
+        sol_lock_account_info.key
+    );
+
+    Ok(())
+}
+
+/// Propose a new owner for a SolLock account
+pub fn propose_owner(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: ProposeOwner,
+) -> ProgramResult {
+    msg!("SolLock::ProposeOwner");
+
+    let ProposeOwner {
+        acc_index,
+        new_owner,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    if let Some(new_owner) = new_owner {
+        assert_keys_unequal(new_owner, *owner_info.key)?;
+    }
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        verbose_msg!(
+            "Proposing owner {:?} for SolLock account {:#?}",
+            new_owner,
+            sol_lock_account_info.key
+        );
+
+        sol_lock_account_data.pending_owner = new_owner;
+        sol_lock_account_data.recovery_available_at = None;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Accept the ownership of a SolLock account, moving it to a SolLock account of the new owner
+pub fn accept_owner(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: AcceptOwner,
+) -> ProgramResult {
+    msg!("SolLock::AcceptOwner");
+
+    let AcceptOwner {
+        acc_index,
+        new_acc_index,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let new_owner_info = next_account_info(account_info_iter)?;
+    let new_sol_lock_account_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+    let (new_sol_lock_account_key, new_sol_lock_account_bump_seed) =
+        get_sol_lock_account(program_id, new_owner_info.key, new_acc_index);
+
+    assert_system_program(system_account_info)?;
+    assert_is_signer(new_owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    let mut sol_lock_account_data = assert_initialized::<Account>(sol_lock_account_info)?;
+    assert_keys_equal(new_sol_lock_account_key, *new_sol_lock_account_info.key)?;
+    assert_owned_by(new_sol_lock_account_info, system_account_info.key)?;
+    assert_can_accept_owner(
+        &sol_lock_account_data,
+        new_owner_info.key,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            new_owner_info.key,
+            &new_sol_lock_account_key,
+            rent.minimum_balance(SOL_LOCK_ACCOUNT_SIZE),
+            SOL_LOCK_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[
+            new_owner_info.clone(),
+            new_sol_lock_account_info.clone(),
+            system_account_info.clone(),
+        ],
+        &[&[
+            new_owner_info.key.as_ref(),
+            &new_acc_index.to_le_bytes(),
+            &[new_sol_lock_account_bump_seed],
+        ]],
+    )?;
+
+    let held_lamports = sol_lock_account_data.held_lamports();
+    let rent_reserve = checked_sub(sol_lock_account_info.lamports(), held_lamports)?;
+
+    **sol_lock_account_info.try_borrow_mut_lamports()? = 0;
+    credit_lamports(new_sol_lock_account_info, held_lamports)?;
+    credit_lamports(owner_info, rent_reserve)?;
+
+    sol_lock_account_data.owner = *new_owner_info.key;
+    sol_lock_account_data.pending_owner = None;
+    sol_lock_account_data.recovery_available_at = None;
+    sol_lock_account_data.rent_reserve = rent.minimum_balance(SOL_LOCK_ACCOUNT_SIZE);
+    sol_lock_account_data.bump_seed = new_sol_lock_account_bump_seed;
+    sol_lock_account_data.pack_into_slice(&mut new_sol_lock_account_info.data.borrow_mut());
+
+    let mut old_data = sol_lock_account_info.data.borrow_mut();
+    let old_data_len = old_data.len();
+    sol_memset(&mut old_data, 0, old_data_len);
+
+    verbose_msg!(
+        "SolLock account {:?} moved to {:?} owned by {:?}.",
+        sol_lock_account_info.key,
+        new_sol_lock_account_info.key,
+        new_owner_info.key
+    );
+
+    Ok(())
+}
+
+/// Set or clear the unlock authority of a SolLock account
+pub fn set_unlock_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetUnlockAuthority,
+) -> ProgramResult {
+    msg!("SolLock::SetUnlockAuthority");
+
+    let SetUnlockAuthority {
+        acc_index,
+        unlock_authority,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        verbose_msg!(
+            "Setting unlock authority of SolLock account {:#?} to {:?}",
+            sol_lock_account_info.key,
+            unlock_authority
+        );
+
+        sol_lock_account_data.unlock_authority = unlock_authority;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Set or clear the receiver of a SolLock account
+pub fn set_receiver(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetReceiver,
+) -> ProgramResult {
+    msg!("SolLock::SetReceiver");
+
+    let SetReceiver {
+        acc_index,
+        receiver,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_not_locked(&sol_lock_account_data)?;
+
+        verbose_msg!(
+            "Setting receiver of SolLock account {:#?} to {:?}",
+            sol_lock_account_info.key,
+            receiver
+        );
+
+        sol_lock_account_data.receiver = receiver;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Set or clear the recovery pointer of a SolLock account
+pub fn set_recovery_pointer(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetRecoveryPointer,
+) -> ProgramResult {
+    msg!("SolLock::SetRecoveryPointer");
+
+    let SetRecoveryPointer {
+        acc_index,
+        recovery_pointer,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_not_locked(&sol_lock_account_data)?;
+
+        verbose_msg!(
+            "Setting recovery pointer of SolLock account {:#?} to {:?}",
+            sol_lock_account_info.key,
+            recovery_pointer
+        );
+
+        sol_lock_account_data.recovery_pointer = recovery_pointer;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Set or clear the crank tip of a SolLock account
+pub fn set_crank_tip(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetCrankTip,
+) -> ProgramResult {
+    msg!("SolLock::SetCrankTip");
+
+    let SetCrankTip {
+        acc_index,
+        crank_tip,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_not_locked(&sol_lock_account_data)?;
+
+        verbose_msg!(
+            "Setting crank tip of SolLock account {:#?} to {:?}",
+            sol_lock_account_info.key,
+            crank_tip
+        );
+
+        sol_lock_account_data.crank_tip = crank_tip;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Unlock a SolLock account on behalf of its owner, paying the crank tip to the cranker
+pub fn crank_unlock(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: CrankUnlock,
+) -> ProgramResult {
+    msg!("SolLock::CrankUnlock");
+
+    let CrankUnlock { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let cranker_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+    let sol_receiver_account_res = next_account_info(account_info_iter);
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(cranker_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        let crank_tip = assert_can_crank(&sol_lock_account_data)?;
+
+        let receiver_account_info = match sol_lock_account_data.receiver {
+            Some(_) => sol_receiver_account_res?,
+            None => owner_info,
+        };
+        assert_stored_receiver(&sol_lock_account_data, receiver_account_info)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        roll_deadline(&mut sol_lock_account_data, now)?;
+        check_unlock(&sol_lock_account_data, now)?;
+        assert_beneficiary_claim_closed(&sol_lock_account_data, now)?;
+
+        let lamports = sol_lock_account_data
+            .lamports
+            .take()
+            .ok_or(SolLockError::NoFunds)?;
+        sol_lock_account_data.deadline = None;
+        sol_lock_account_data.stake_account = None;
+        sol_lock_account_data.vested_withdrawn = 0;
+        sol_lock_account_data.stream_start = None;
+        sol_lock_account_data.stream_claimed = 0;
+        sol_lock_account_data.state = State::Initialized;
+        sol_lock_account_data.record_withdrawal(lamports);
+        update_stats(program_id, stats_info, |stats| {
+            stats.record_unlock(lamports, true)
+        })?;
+
+        let lamports = pay_unlock_fee(
+            program_id,
+            &sol_lock_account_data,
+            sol_lock_account_info,
+            treasury_info,
+            lamports,
+        )?;
+        let tip = crank_tip.min(lamports);
+
+        verbose_msg!(
+            "Unlocking SolLock account {:#?}, paying a tip of {} lamports to {:#?}",
+            sol_lock_account_info.key,
+            tip,
+            cranker_info.key
+        );
+
+        debit_lamports(sol_lock_account_info, lamports)?;
+        credit_lamports(receiver_account_info, checked_sub(lamports, tip)?)?;
+        credit_lamports(cranker_info, tip)?;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Add Sol to a SolLock account, set its deadline and lock it again
+pub fn relock(program_id: &Pubkey, accounts: &[AccountInfo], ctx: Relock) -> ProgramResult {
+    msg!("SolLock::Relock");
+
+    let Relock {
+        acc_index,
+        lamports,
+        deadline,
+        commitment,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_system_program(system_account_info)?;
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    let config = load_config(program_id, config_info)?;
+    assert_not_paused(&config)?;
+
+    sol_lock_account_info.with_mut_data(|sol_lock_account_data| {
+        assert_not_locked(&sol_lock_account_data)?;
+        let lamports = capped_deposit(&sol_lock_account_data, lamports)?;
+
+        verbose_msg!(
+            "Adding {} lamports to SolLock account {:#?} and locking it until {}",
+            lamports,
+            sol_lock_account_info.key,
+            deadline
+        );
+
+        invoke(
+            &system_instruction::transfer(owner_info.key, &sol_lock_account_key, lamports),
+            &[
+                owner_info.clone(),
+                sol_lock_account_info.clone(),
+                system_account_info.clone(),
+            ],
+        )?;
+
+        let sol_lock_account_data = apply_deposit(sol_lock_account_data, lamports, &config)?;
+        let mut sol_lock_account_data = apply_deadline(sol_lock_account_data, deadline, &config)?;
+        assert_has_funds(&sol_lock_account_data)?;
+        assert_can_lock(&sol_lock_account_data, &config)?;
+        assert_commitment_mutable(&sol_lock_account_data)?;
+
+        sol_lock_account_data.state = match sol_lock_account_data.state {
+            State::ReadyUnlocked => State::Locked,
+            State::Uninitialized
+            | State::Initialized
+            | State::HasFunds
+            | State::HasDeadline
+            | State::Locked
+            | State::Staked
+            | State::Disputed => return Err(SolLockError::UnexpectedState.into()),
+        };
+        sol_lock_account_data.price_target_reached = false;
+        sol_lock_account_data.lock_fee_bps = config.fee_bps;
+        sol_lock_account_data.commitment = commitment;
+        sol_lock_account_data.locked_at = Some(Clock::get()?.unix_timestamp);
+        start_stream(&mut sol_lock_account_data)?;
+
+        let locked_lamports = sol_lock_account_data
+            .lamports
+            .ok_or(SolLockError::NoFunds)?;
+        sol_lock_account_data.record_lock(locked_lamports);
+        update_stats(program_id, stats_info, |stats| {
+            stats.record_lock(locked_lamports)
+        })?;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Set or clear the auto-extension period of a SolLock account
+pub fn set_auto_extend(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetAutoExtend,
+) -> ProgramResult {
+    msg!("SolLock::SetAutoExtend");
+
+    let SetAutoExtend {
+        acc_index,
+        auto_extend,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_valid_auto_extend(auto_extend)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_not_locked(&sol_lock_account_data)?;
+
+        verbose_msg!(
+            "Setting auto-extension period of SolLock account {:#?} to {:?}",
+            sol_lock_account_info.key,
+            auto_extend
+        );
+
+        sol_lock_account_data.auto_extend = auto_extend;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Set or clear the vesting start of a SolLock account
+pub fn set_vesting(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetVesting,
+) -> ProgramResult {
+    msg!("SolLock::SetVesting");
+
+    let SetVesting {
+        acc_index,
+        vesting_start,
+        vesting_cliff,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_not_locked(&sol_lock_account_data)?;
+        assert_valid_vesting(&sol_lock_account_data, vesting_start, vesting_cliff)?;
+        assert_single_schedule(
+            vesting_start.is_some(),
+            sol_lock_account_data.stream_rate.is_some(),
+        )?;
+
+        verbose_msg!(
+            "Setting vesting of SolLock account {:#?} to start at {:?} with cliff at {:?}",
+            sol_lock_account_info.key,
+            vesting_start,
+            vesting_cliff
+        );
+
+        sol_lock_account_data.vesting_start = vesting_start;
+        sol_lock_account_data.vesting_cliff = vesting_cliff;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Set or clear the deposit cap of a SolLock account
+pub fn set_deposit_cap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetDepositCap,
+) -> ProgramResult {
+    msg!("SolLock::SetDepositCap");
+
+    let SetDepositCap {
+        acc_index,
+        deposit_cap,
+        refund_excess,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_not_locked(&sol_lock_account_data)?;
+
+        verbose_msg!(
+            "Setting deposit cap of SolLock account {:#?} to {:?}, refunding excess: {}",
+            sol_lock_account_info.key,
+            deposit_cap,
+            refund_excess
+        );
+
+        sol_lock_account_data.deposit_cap = deposit_cap;
+        sol_lock_account_data.refund_excess = refund_excess;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Unlock a SolLock account into the claim ticket of its receiver
+pub fn unlock_to_claim(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: UnlockToClaim,
+) -> ProgramResult {
+    msg!("SolLock::UnlockToClaim");
+
+    let UnlockToClaim { acc_index, nonce } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let receiver_account_info = next_account_info(account_info_iter)?;
+    let claim_ticket_info = next_account_info(account_info_iter)?;
+    let payer_account_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+    let unlock_signer_infos = account_info_iter.as_slice();
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+    let (claim_ticket_key, claim_ticket_bump_seed) =
+        get_claim_ticket(program_id, receiver_account_info.key);
+
+    assert_system_program(system_account_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_keys_equal(claim_ticket_key, *claim_ticket_info.key)?;
+
+    if claim_ticket_info.owner == system_account_info.key {
+        assert_is_signer(payer_account_info)?;
+
+        let rent = Rent::get()?;
+
+        invoke_signed(
+            &system_instruction::create_account(
+                payer_account_info.key,
+                &claim_ticket_key,
+                rent.minimum_balance(CLAIM_TICKET_SIZE),
+                CLAIM_TICKET_SIZE as u64,
+                program_id,
+            ),
+            &[
+                payer_account_info.clone(),
+                claim_ticket_info.clone(),
+                system_account_info.clone(),
+            ],
+            &[&[
+                CLAIM_TICKET_SEED,
+                receiver_account_info.key.as_ref(),
+                &[claim_ticket_bump_seed],
+            ]],
+        )?;
+
+        ClaimTicket {
+            receiver: *receiver_account_info.key,
+            lamports: 0,
+        }
+        .pack_into_slice(&mut claim_ticket_info.data.borrow_mut());
+    } else {
+        assert_owned_by(claim_ticket_info, program_id)?;
+    }
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_unlock_approved(&sol_lock_account_data, owner_info, unlock_signer_infos)?;
+        consume_delegated_nonce(&mut sol_lock_account_data, nonce)?;
+        assert_stored_receiver(&sol_lock_account_data, receiver_account_info)?;
+
+        let lamports = release_unlocked_lamports(&mut sol_lock_account_data)?;
+        let unlocked = sol_lock_account_data.state == State::Initialized;
+        sol_lock_account_data.record_withdrawal(lamports);
+        update_stats(program_id, stats_info, |stats| {
+            stats.record_unlock(lamports, unlocked)
+        })?;
+        let lamports_to_transfer = pay_unlock_fee(
+            program_id,
+            &sol_lock_account_data,
+            sol_lock_account_info,
+            treasury_info,
+            lamports,
+        )?;
+
+        let mut claim_ticket = ClaimTicket::unpack(&claim_ticket_info.data.borrow())?;
+        claim_ticket.lamports = claim_ticket
+            .lamports
+            .checked_add(lamports_to_transfer)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        claim_ticket.pack_into_slice(&mut claim_ticket_info.data.borrow_mut());
+
+        verbose_msg!(
+            "Unlocking {} lamports into claim ticket {:#?}",
+            lamports_to_transfer,
+            claim_ticket_info.key
+        );
+
+        transfer_lamports(
+            sol_lock_account_info,
+            claim_ticket_info,
+            lamports_to_transfer,
+        )?;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Claim the lamports unlocked into a claim ticket
+pub fn claim_unlocked(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: ClaimUnlocked,
+) -> ProgramResult {
+    msg!("SolLock::ClaimUnlocked");
+
+    let ClaimUnlocked {} = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let receiver_account_info = next_account_info(account_info_iter)?;
+    let claim_ticket_info = next_account_info(account_info_iter)?;
+
+    let claim_ticket_key = get_claim_ticket(program_id, receiver_account_info.key).0;
+
+    assert_is_signer(receiver_account_info)?;
+    assert_keys_equal(claim_ticket_key, *claim_ticket_info.key)?;
+    assert_owned_by(claim_ticket_info, program_id)?;
+    let mut claim_ticket = assert_initialized::<ClaimTicket>(claim_ticket_info)?;
+
+    let lamports = claim_ticket.lamports;
+    if lamports == 0 {
+        return Err(SolLockError::NoFunds.into());
+    }
+
+    verbose_msg!(
+        "Claiming {} lamports from claim ticket {:#?}",
+        lamports,
+        claim_ticket_info.key
+    );
+
+    claim_ticket.lamports = 0;
+    claim_ticket.pack_into_slice(&mut claim_ticket_info.data.borrow_mut());
+
+    transfer_lamports(claim_ticket_info, receiver_account_info, lamports)?;
+
+    Ok(())
+}
+
+/// Set or clear the stream rate of a SolLock account
+pub fn set_stream(program_id: &Pubkey, accounts: &[AccountInfo], ctx: SetStream) -> ProgramResult {
+    msg!("SolLock::SetStream");
+
+    let SetStream {
+        acc_index,
+        stream_rate,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_not_locked(&sol_lock_account_data)?;
+        assert_single_schedule(
+            sol_lock_account_data.vesting_start.is_some(),
+            stream_rate.is_some(),
+        )?;
+
+        verbose_msg!(
+            "Setting stream rate of SolLock account {:#?} to {:?} lamports per second",
+            sol_lock_account_info.key,
+            stream_rate
+        );
+
+        sol_lock_account_data.stream_rate = stream_rate;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Claim the streamed lamports of a SolLock account
+pub fn claim(program_id: &Pubkey, accounts: &[AccountInfo], ctx: Claim) -> ProgramResult {
+    msg!("SolLock::Claim");
+
+    let Claim { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let receiver_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(receiver_account_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_can_claim_streamed(&sol_lock_account_data, receiver_account_info)?;
+        assert_no_outstanding_tokens(&sol_lock_account_data)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let lamports = sol_lock_account_data
+            .claimable_streamed_lamports(now)
+            .ok_or(SolLockError::AccountFieldNotSet)?;
+        if lamports == 0 {
+            return Err(SolLockError::NoFunds.into());
+        }
+
+        verbose_msg!(
+            "Claiming {} streamed lamports from SolLock account {:#?}",
+            lamports,
+            sol_lock_account_info.key
+        );
+
+        withdraw_streamed(&mut sol_lock_account_data, lamports)?;
+        let unlocked = sol_lock_account_data.state == State::Initialized;
+        update_stats(program_id, stats_info, |stats| {
+            stats.record_unlock(lamports, unlocked)
+        })?;
+        let lamports_to_transfer = pay_unlock_fee(
+            program_id,
+            &sol_lock_account_data,
+            sol_lock_account_info,
+            treasury_info,
+            lamports,
+        )?;
+
+        transfer_lamports(
+            sol_lock_account_info,
+            receiver_account_info,
+            lamports_to_transfer,
+        )?;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Set or clear the hash lock of a SolLock account
+pub fn set_hash_lock(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetHashLock,
+) -> ProgramResult {
+    msg!("SolLock::SetHashLock");
+
+    let SetHashLock {
+        acc_index,
+        hash_lock,
+        counterparty,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_valid_hash_lock(hash_lock, counterparty)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_not_locked(&sol_lock_account_data)?;
+
+        verbose_msg!(
+            "Setting hash lock of SolLock account {:#?} to {:?} for counterparty {:?}",
+            sol_lock_account_info.key,
+            hash_lock,
+            counterparty
+        );
+
+        sol_lock_account_data.hash_lock = hash_lock;
+        sol_lock_account_data.counterparty = counterparty;
+        sol_lock_account_data.arbiter = None;
+        sol_lock_account_data.dispute_period = None;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Claim the lamports of a hash-locked SolLock account with the preimage of its hash lock
+pub fn claim_with_preimage(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: ClaimWithPreimage,
+) -> ProgramResult {
+    msg!("SolLock::ClaimWithPreimage");
+
+    let ClaimWithPreimage {
+        acc_index,
+        preimage,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let counterparty_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(counterparty_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        let now = Clock::get()?.unix_timestamp;
+        assert_can_claim_hash_lock(&sol_lock_account_data, counterparty_info, &preimage, now)?;
+        assert_no_outstanding_tokens(&sol_lock_account_data)?;
+
+        let lamports = sol_lock_account_data
+            .lamports
+            .take()
+            .ok_or(SolLockError::NoFunds)?;
+        sol_lock_account_data.deadline = None;
+        sol_lock_account_data.vested_withdrawn = 0;
+        sol_lock_account_data.stream_start = None;
+        sol_lock_account_data.stream_claimed = 0;
+        sol_lock_account_data.hash_lock = None;
+        sol_lock_account_data.counterparty = None;
+        sol_lock_account_data.state = State::Initialized;
+
+        verbose_msg!(
+            "Counterparty {:#?} claimed {} lamports from SolLock account {:#?}",
+            counterparty_info.key,
+            lamports,
+            sol_lock_account_info.key
+        );
+
+        update_stats(program_id, stats_info, |stats| {
+            stats.record_unlock(lamports, true)
+        })?;
+        let lamports_to_transfer = pay_unlock_fee(
+            program_id,
+            &sol_lock_account_data,
+            sol_lock_account_info,
+            treasury_info,
+            lamports,
+        )?;
+
+        transfer_lamports(
+            sol_lock_account_info,
+            counterparty_info,
+            lamports_to_transfer,
+        )?;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Turn a SolLock account into a two-party escrow
+pub fn set_escrow(program_id: &Pubkey, accounts: &[AccountInfo], ctx: SetEscrow) -> ProgramResult {
+    msg!("SolLock::SetEscrow");
+
+    let SetEscrow {
+        acc_index,
+        counterparty,
+        arbiter,
+        dispute_period,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_valid_arbiter(counterparty, arbiter, dispute_period)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_not_locked(&sol_lock_account_data)?;
+
+        verbose_msg!(
+            "Setting escrow counterparty of SolLock account {:#?} to {:?} with arbiter {:?}",
+            sol_lock_account_info.key,
+            counterparty,
+            arbiter
+        );
+
+        sol_lock_account_data.hash_lock = None;
+        sol_lock_account_data.counterparty = counterparty;
+        sol_lock_account_data.arbiter = arbiter;
+        sol_lock_account_data.dispute_period = dispute_period;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Claim the lamports of an escrow SolLock account as its counterparty
+pub fn claim_escrow(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: ClaimEscrow,
+) -> ProgramResult {
+    msg!("SolLock::ClaimEscrow");
+
+    let ClaimEscrow { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let counterparty_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(counterparty_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        let now = Clock::get()?.unix_timestamp;
+        assert_can_claim_escrow(&sol_lock_account_data, counterparty_info, now)?;
+        assert_no_outstanding_tokens(&sol_lock_account_data)?;
+
+        let lamports = release_escrow(&mut sol_lock_account_data)?;
+
+        verbose_msg!(
+            "Counterparty {:#?} claimed {} lamports from SolLock account {:#?}",
+            counterparty_info.key,
+            lamports,
+            sol_lock_account_info.key
+        );
+
+        update_stats(program_id, stats_info, |stats| {
+            stats.record_unlock(lamports, true)
+        })?;
+        let lamports_to_transfer = pay_unlock_fee(
+            program_id,
+            &sol_lock_account_data,
+            sol_lock_account_info,
+            treasury_info,
+            lamports,
+        )?;
+
+        transfer_lamports(
+            sol_lock_account_info,
+            counterparty_info,
+            lamports_to_transfer,
+        )?;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Take back the lamports of an escrow SolLock account its counterparty has not claimed
+pub fn refund_escrow(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: RefundEscrow,
+) -> ProgramResult {
+    msg!("SolLock::RefundEscrow");
+
+    let RefundEscrow { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        let now = Clock::get()?.unix_timestamp;
+        assert_can_refund_escrow(&sol_lock_account_data, now)?;
+        assert_no_outstanding_tokens(&sol_lock_account_data)?;
+
+        let lamports = release_escrow(&mut sol_lock_account_data)?;
+
+        verbose_msg!(
+            "Refunded {} lamports of SolLock account {:#?} to owner {:#?}",
+            lamports,
+            sol_lock_account_info.key,
+            owner_info.key
+        );
+
+        update_stats(program_id, stats_info, |stats| {
+            stats.record_unlock(lamports, true)
+        })?;
+        let lamports_to_transfer = pay_unlock_fee(
+            program_id,
+            &sol_lock_account_data,
+            sol_lock_account_info,
+            treasury_info,
+            lamports,
+        )?;
+
+        transfer_lamports(sol_lock_account_info, owner_info, lamports_to_transfer)?;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Take all lamports out of an escrow account and remove the escrow
+fn release_escrow(sol_lock_account_data: &mut Account) -> Result<u64, ProgramError> {
+    let lamports = sol_lock_account_data
+        .lamports
+        .take()
+        .ok_or(SolLockError::NoFunds)?;
+    sol_lock_account_data.deadline = None;
+    sol_lock_account_data.vested_withdrawn = 0;
+    sol_lock_account_data.stream_start = None;
+    sol_lock_account_data.stream_claimed = 0;
+    sol_lock_account_data.counterparty = None;
+    sol_lock_account_data.arbiter = None;
+    sol_lock_account_data.dispute_period = None;
+    sol_lock_account_data.state = State::Initialized;
+
+    Ok(lamports)
+}
+
+/// Dispute an escrow SolLock account as its owner or counterparty
+pub fn raise_dispute(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: RaiseDispute,
+) -> ProgramResult {
+    msg!("SolLock::RaiseDispute");
+
+    let RaiseDispute { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let party_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(party_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        let now = Clock::get()?.unix_timestamp;
+        assert_can_raise_dispute(&sol_lock_account_data, party_info, now)?;
+
+        let deadline = now
+            .checked_add(
+                sol_lock_account_data
+                    .dispute_period
+                    .ok_or(SolLockError::AccountFieldNotSet)?,
+            )
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        verbose_msg!(
+            "{:#?} disputed SolLock account {:#?}, arbiter can resolve until {}",
+            party_info.key,
+            sol_lock_account_info.key,
+            deadline
+        );
+
+        sol_lock_account_data.deadline = Some(deadline);
+        sol_lock_account_data.state = State::Disputed;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Direct the lamports of a disputed escrow SolLock account to either party as its arbiter
+pub fn resolve(program_id: &Pubkey, accounts: &[AccountInfo], ctx: Resolve) -> ProgramResult {
+    msg!("SolLock::Resolve");
+
+    let Resolve {
+        acc_index,
+        to_counterparty,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let arbiter_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let counterparty_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(arbiter_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        let now = Clock::get()?.unix_timestamp;
+        assert_can_resolve_dispute(&sol_lock_account_data, arbiter_info, counterparty_info, now)?;
+        assert_no_outstanding_tokens(&sol_lock_account_data)?;
+
+        let lamports = release_escrow(&mut sol_lock_account_data)?;
+        let receiver_info = if to_counterparty {
+            counterparty_info
+        } else {
+            owner_info
+        };
+
+        verbose_msg!(
+            "Arbiter {:#?} sent {} lamports of SolLock account {:#?} to {:#?}",
+            arbiter_info.key,
+            lamports,
+            sol_lock_account_info.key,
+            receiver_info.key
+        );
+
+        update_stats(program_id, stats_info, |stats| {
+            stats.record_unlock(lamports, true)
+        })?;
+        let lamports_to_transfer = pay_unlock_fee(
+            program_id,
+            &sol_lock_account_data,
+            sol_lock_account_info,
+            treasury_info,
+            lamports,
+        )?;
+
+        transfer_lamports(sol_lock_account_info, receiver_info, lamports_to_transfer)?;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Allow or forbid anyone to contribute lamports to a SolLock account
+pub fn set_open_deposits(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetOpenDeposits,
+) -> ProgramResult {
+    msg!("SolLock::SetOpenDeposits");
+
+    let SetOpenDeposits {
+        acc_index,
+        open_deposits,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        verbose_msg!(
+            "Setting open deposits of SolLock account {:#?} to {}",
+            sol_lock_account_info.key,
+            open_deposits
+        );
+
+        sol_lock_account_data.open_deposits = open_deposits;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Add Sol to an open-deposit SolLock account as any account
+pub fn contribute(program_id: &Pubkey, accounts: &[AccountInfo], ctx: Contribute) -> ProgramResult {
+    msg!("SolLock::Contribute");
+
+    let Contribute {
+        acc_index,
+        lamports,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let contributor_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let contribution_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+    let (contribution_key, contribution_bump_seed) =
+        get_contribution(program_id, &sol_lock_account_key, contributor_info.key);
+
+    assert_system_program(system_account_info)?;
+    assert_is_signer(contributor_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_keys_equal(contribution_key, *contribution_info.key)?;
+    let config = load_config(program_id, config_info)?;
+    assert_not_paused(&config)?;
+
+    if contribution_info.owner == system_account_info.key {
+        let rent = Rent::get()?;
+
+        invoke_signed(
+            &system_instruction::create_account(
+                contributor_info.key,
+                &contribution_key,
+                rent.minimum_balance(CONTRIBUTION_SIZE),
+                CONTRIBUTION_SIZE as u64,
+                program_id,
+            ),
+            &[
+                contributor_info.clone(),
+                contribution_info.clone(),
+                system_account_info.clone(),
+            ],
+            &[&[
+                CONTRIBUTION_SEED,
+                sol_lock_account_key.as_ref(),
+                contributor_info.key.as_ref(),
+                &[contribution_bump_seed],
+            ]],
+        )?;
+
+        Contribution {
+            sol_lock_account: sol_lock_account_key,
+            contributor: *contributor_info.key,
+            lamports: 0,
+        }
+        .pack_into_slice(&mut contribution_info.data.borrow_mut());
+    } else {
+        assert_owned_by(contribution_info, program_id)?;
+    }
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_open_deposits(&sol_lock_account_data)?;
+        assert_crowdfund_open(&sol_lock_account_data, Clock::get()?.unix_timestamp)?;
+        let lamports = capped_deposit(&sol_lock_account_data, lamports)?;
+
+        let mut contribution = Contribution::unpack(&contribution_info.data.borrow())?;
+        contribution.lamports = contribution
+            .lamports
+            .checked_add(lamports)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        contribution.pack_into_slice(&mut contribution_info.data.borrow_mut());
+        sol_lock_account_data.raised = sol_lock_account_data.raised.saturating_add(lamports);
+
+        verbose_msg!(
+            "{:#?} contributed {} lamports to SolLock account {:#?}",
+            contributor_info.key,
+            lamports,
+            sol_lock_account_info.key
+        );
+
+        invoke(
+            &system_instruction::transfer(contributor_info.key, &sol_lock_account_key, lamports),
+            &[
+                contributor_info.clone(),
+                sol_lock_account_info.clone(),
+                system_account_info.clone(),
+            ],
+        )?;
+
+        let mut sol_lock_account_data = apply_deposit(sol_lock_account_data, lamports, &config)?;
+        if sol_lock_account_data.state == State::Locked {
+            sol_lock_account_data.record_deposit(lamports);
+            update_stats(program_id, stats_info, |stats| {
+                stats.record_deposit(lamports)
+            })?;
+        }
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Set the raise target and refund period of a crowdfunding SolLock account
+pub fn set_raise_target(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetRaiseTarget,
+) -> ProgramResult {
+    msg!("SolLock::SetRaiseTarget");
+
+    let SetRaiseTarget {
+        acc_index,
+        raise_target,
+        refund_period,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_valid_raise_target(raise_target, refund_period)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_not_locked(&sol_lock_account_data)?;
+
+        verbose_msg!(
+            "Setting raise target of SolLock account {:#?} to {:?} with refund period {:?}",
+            sol_lock_account_info.key,
+            raise_target,
+            refund_period
+        );
+
+        sol_lock_account_data.raise_target = raise_target;
+        sol_lock_account_data.refund_period = refund_period;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Get back the lamports contributed to a crowdfund that missed its raise target
+pub fn refund_contribution(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: RefundContribution,
+) -> ProgramResult {
+    msg!("SolLock::RefundContribution");
+
+    let RefundContribution { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let contributor_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let contribution_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+    let contribution_key =
+        get_contribution(program_id, &sol_lock_account_key, contributor_info.key).0;
+
+    assert_is_signer(contributor_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_keys_equal(contribution_key, *contribution_info.key)?;
+    assert_owned_by(contribution_info, program_id)?;
+    let mut contribution = assert_initialized::<Contribution>(contribution_info)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        let now = Clock::get()?.unix_timestamp;
+        assert_can_refund_contribution(&sol_lock_account_data, &contribution, now)?;
+        assert_no_outstanding_tokens(&sol_lock_account_data)?;
+
+        let held = sol_lock_account_data
+            .lamports
+            .ok_or(SolLockError::NoFunds)?;
+        let lamports = contribution.lamports.min(held);
+
+        if held > lamports {
+            sol_lock_account_data.lamports = Some(held - lamports);
+        } else {
+            sol_lock_account_data.lamports = None;
+            sol_lock_account_data.deadline = None;
+            sol_lock_account_data.vested_withdrawn = 0;
+            sol_lock_account_data.stream_start = None;
+            sol_lock_account_data.stream_claimed = 0;
+            sol_lock_account_data.state = State::Initialized;
+        }
+        sol_lock_account_data.raised = sol_lock_account_data.raised.saturating_sub(lamports);
+        let unlocked = sol_lock_account_data.state == State::Initialized;
+        update_stats(program_id, stats_info, |stats| {
+            stats.record_unlock(lamports, unlocked)
+        })?;
+
+        contribution.lamports = 0;
+        contribution.pack_into_slice(&mut contribution_info.data.borrow_mut());
+
+        verbose_msg!(
+            "Refunding {} lamports of SolLock account {:#?} to contributor {:#?}",
+            lamports,
+            sol_lock_account_info.key,
+            contributor_info.key
+        );
+
+        transfer_lamports(sol_lock_account_info, contributor_info, lamports)?;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Set up or remove the dead man's switch of a SolLock account
+pub fn set_beneficiary(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetBeneficiary,
+) -> ProgramResult {
+    msg!("SolLock::SetBeneficiary");
+
+    let SetBeneficiary {
+        acc_index,
+        beneficiary,
+        heartbeat_interval,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_valid_beneficiary(beneficiary, heartbeat_interval)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_not_locked(&sol_lock_account_data)?;
+
+        verbose_msg!(
+            "Setting beneficiary of SolLock account {:#?} to {:?} with heartbeat interval {:?}",
+            sol_lock_account_info.key,
+            beneficiary,
+            heartbeat_interval
+        );
+
+        sol_lock_account_data.beneficiary = beneficiary;
+        sol_lock_account_data.heartbeat_interval = heartbeat_interval;
+        sol_lock_account_data.last_heartbeat = None;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Prove that the owner of a SolLock account with a dead man's switch is still active
+pub fn heartbeat(program_id: &Pubkey, accounts: &[AccountInfo], ctx: Heartbeat) -> ProgramResult {
+    msg!("SolLock::Heartbeat");
+
+    let Heartbeat { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        if sol_lock_account_data.beneficiary.is_none() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+
+        verbose_msg!(
+            "Heartbeat of SolLock account {:#?} at {}",
+            sol_lock_account_info.key,
+            now
+        );
+
+        sol_lock_account_data.last_heartbeat = Some(now);
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Claim the lamports of a SolLock account whose owner stopped sending heartbeats
+pub fn claim_as_beneficiary(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: ClaimAsBeneficiary,
+) -> ProgramResult {
+    msg!("SolLock::ClaimAsBeneficiary");
+
+    let ClaimAsBeneficiary { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let beneficiary_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(beneficiary_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        let share_count = sol_lock_account_data.beneficiary_shares.len();
+        let share_infos = account_info_iter
+            .as_slice()
+            .get(..share_count)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let cosigner_infos = account_info_iter
+            .as_slice()
+            .get(share_count..)
+            .unwrap_or_default();
+
+        let now = Clock::get()?.unix_timestamp;
+        assert_can_claim_as_beneficiary(&sol_lock_account_data, beneficiary_info, now)?;
+        assert_no_outstanding_tokens(&sol_lock_account_data)?;
+        assert_multisig_approved(&sol_lock_account_data, cosigner_infos)?;
+
+        let lamports = sol_lock_account_data
+            .lamports
+            .take()
+            .ok_or(SolLockError::NoFunds)?;
+        sol_lock_account_data.deadline = None;
+        sol_lock_account_data.vested_withdrawn = 0;
+        sol_lock_account_data.stream_start = None;
+        sol_lock_account_data.stream_claimed = 0;
+        sol_lock_account_data.last_heartbeat = None;
+        sol_lock_account_data.state = State::Initialized;
+
+        verbose_msg!(
+            "Beneficiary {:#?} claimed {} lamports from SolLock account {:#?}",
+            beneficiary_info.key,
+            lamports,
+            sol_lock_account_info.key
+        );
+
+        update_stats(program_id, stats_info, |stats| {
+            stats.record_unlock(lamports, true)
+        })?;
+        let lamports_to_transfer = pay_unlock_fee(
+            program_id,
+            &sol_lock_account_data,
+            sol_lock_account_info,
+            treasury_info,
+            lamports,
+        )?;
+        debit_lamports(sol_lock_account_info, lamports_to_transfer)?;
+
+        if sol_lock_account_data.beneficiary_shares.is_empty() {
+            credit_lamports(beneficiary_info, lamports_to_transfer)?;
+            return Ok(sol_lock_account_data);
+        }
+
+        let mut remaining = lamports_to_transfer;
+        let shares = sol_lock_account_data.beneficiary_shares.iter();
+        for (index, (share, share_info)) in shares.zip(share_infos).enumerate() {
+            assert_keys_equal(share.beneficiary, *share_info.key)?;
+
+            let share_lamports = if index + 1 == share_count {
+                remaining
+            } else {
+                (lamports_to_transfer as u128 * share.bps as u128 / TOTAL_SHARE_BPS as u128) as u64
+            };
+            remaining = checked_sub(remaining, share_lamports)?;
+
+            verbose_msg!(
+                "Sending {} lamports to share account {:#?}",
+                share_lamports,
+                share_info.key
+            );
+
+            credit_lamports(share_info, share_lamports)?;
+        }
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Set how the lamports claimed by the beneficiary of a SolLock account are split
+pub fn set_beneficiary_shares(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetBeneficiaryShares,
+) -> ProgramResult {
+    msg!("SolLock::SetBeneficiaryShares");
+
+    let SetBeneficiaryShares { acc_index, shares } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_valid_beneficiary_shares(&shares)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_not_locked(&sol_lock_account_data)?;
+
+        verbose_msg!(
+            "Setting beneficiary shares of SolLock account {:#?} to {:?}",
+            sol_lock_account_info.key,
+            shares
+        );
+
+        sol_lock_account_data.beneficiary_shares = shares;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Set the multisig signers that must co-sign unlocks of a SolLock account
+pub fn set_multisig(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetMultisig,
+) -> ProgramResult {
+    msg!("SolLock::SetMultisig");
+
+    let SetMultisig {
+        acc_index,
+        signers,
+        threshold,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_valid_multisig(&signers, threshold)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_not_locked(&sol_lock_account_data)?;
+
+        verbose_msg!(
+            "Setting multisig of SolLock account {:#?} to {} of {:?}",
+            sol_lock_account_info.key,
+            threshold,
+            signers
+        );
+
+        sol_lock_account_data.multisig_signers = signers;
+        sol_lock_account_data.multisig_threshold = threshold;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Set the guardians that can recover a SolLock account
+pub fn set_guardians(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetGuardians,
+) -> ProgramResult {
+    msg!("SolLock::SetGuardians");
+
+    let SetGuardians {
+        acc_index,
+        guardians,
+        threshold,
+        recovery_delay,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_valid_guardians(&guardians, threshold, recovery_delay)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_no_pending_recovery(&sol_lock_account_data)?;
+
+        verbose_msg!(
+            "Setting guardians of SolLock account {:#?} to {} of {:?} with recovery delay {:?}",
+            sol_lock_account_info.key,
+            threshold,
+            guardians,
+            recovery_delay
+        );
+
+        sol_lock_account_data.guardians = guardians;
+        sol_lock_account_data.guardian_threshold = threshold;
+        sol_lock_account_data.recovery_delay = recovery_delay;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Propose a new owner for a SolLock account on behalf of its guardians
+pub fn initiate_recovery(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: InitiateRecovery,
+) -> ProgramResult {
+    msg!("SolLock::InitiateRecovery");
+
+    let InitiateRecovery {
+        acc_index,
+        new_owner,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let guardian_infos = account_info_iter.as_slice();
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_keys_unequal(new_owner, *owner_info.key)?;
+
+    let now = Clock::get()?.unix_timestamp;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_guardians_approved(&sol_lock_account_data, guardian_infos)?;
+
+        let recovery_available_at = now
+            .checked_add(sol_lock_account_data.recovery_delay.unwrap_or(0))
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        verbose_msg!(
+            "Guardians proposing owner {:?} for SolLock account {:#?}, acceptable from {:?}",
+            new_owner,
+            sol_lock_account_info.key,
+            recovery_available_at
+        );
+
+        sol_lock_account_data.pending_owner = Some(new_owner);
+        sol_lock_account_data.recovery_available_at = Some(recovery_available_at);
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Cancel a recovery started by the guardians of a SolLock account
+pub fn veto_recovery(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: VetoRecovery,
+) -> ProgramResult {
+    msg!("SolLock::VetoRecovery");
+
+    let VetoRecovery { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        if sol_lock_account_data.recovery_available_at.is_none() {
+            return Err(SolLockError::NoPendingRecovery.into());
+        }
+
+        verbose_msg!(
+            "Vetoing recovery of SolLock account {:#?} to {:?}",
+            sol_lock_account_info.key,
+            sol_lock_account_data.pending_owner
+        );
+
+        sol_lock_account_data.pending_owner = None;
+        sol_lock_account_data.recovery_available_at = None;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Set the Pyth price condition of a SolLock account
+pub fn set_price_condition(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetPriceCondition,
+) -> ProgramResult {
+    msg!("SolLock::SetPriceCondition");
+
+    let SetPriceCondition {
+        acc_index,
+        price_feed,
+        price_target,
+        requires_deadline,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_valid_price_condition(price_feed, price_target, requires_deadline)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_not_locked(&sol_lock_account_data)?;
+
+        verbose_msg!(
+            "Setting price condition of SolLock account {:#?} to {:?} >= {:?}, requires deadline: {}",
+            sol_lock_account_info.key,
+            price_feed,
+            price_target,
+            requires_deadline
+        );
+
+        sol_lock_account_data.price_feed = price_feed;
+        sol_lock_account_data.price_target = price_target;
+        sol_lock_account_data.price_requires_deadline = requires_deadline;
+        sol_lock_account_data.price_target_reached = false;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Record that the Pyth price of a SolLock account reached its price target
+pub fn check_price(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: CheckPrice,
+) -> ProgramResult {
+    msg!("SolLock::CheckPrice");
+
+    let CheckPrice { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let price_feed_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    // Anyone can create an account with the layout of a price account, only Pyth's are trusted
+    assert_owned_by(price_feed_info, &PYTH_PROGRAM_ID)?;
+
+    let price = read_pyth_price(&price_feed_info.data.borrow())?;
+    let now = Clock::get()?.unix_timestamp;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        let price_feed = sol_lock_account_data
+            .price_feed
+            .ok_or(SolLockError::InvalidPriceFeed)?;
+        assert_keys_equal(price_feed, *price_feed_info.key)?;
+        assert_price_target_reached(&sol_lock_account_data, &price, now)?;
+
+        verbose_msg!(
+            "Price {} (expo {}) reached target {:?} of SolLock account {:#?}",
+            price.price,
+            price.expo,
+            sol_lock_account_data.price_target,
+            sol_lock_account_info.key
+        );
+
+        sol_lock_account_data.price_target_reached = true;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Set the epoch a SolLock account is locked until
+pub fn set_epoch_deadline(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetEpochDeadline,
+) -> ProgramResult {
+    msg!("SolLock::SetEpochDeadline");
+
+    let SetEpochDeadline {
+        acc_index,
+        epoch_deadline,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_not_locked(&sol_lock_account_data)?;
+
+        verbose_msg!(
+            "Setting epoch deadline of SolLock account {:#?} to {:?}",
+            sol_lock_account_info.key,
+            epoch_deadline
+        );
+
+        sol_lock_account_data.epoch_deadline = epoch_deadline;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Create the Config account of the program
+pub fn initialize_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: InitializeConfig,
+) -> ProgramResult {
+    msg!("SolLock::InitializeConfig");
+
+    let InitializeConfig { config } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let authority_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+    let program_data_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+
+    let (config_key, config_bump_seed) = get_config(program_id);
+    let (treasury_key, treasury_bump_seed) = get_treasury(program_id);
+    let (stats_key, stats_bump_seed) = get_stats(program_id);
+
+    assert_system_program(system_account_info)?;
+    assert_upgrade_authority(program_id, program_data_info, authority_info)?;
+    assert_keys_equal(config_key, *config_info.key)?;
+    assert_owned_by(config_info, system_account_info.key)?;
+    assert_keys_equal(treasury_key, *treasury_info.key)?;
+    assert_owned_by(treasury_info, system_account_info.key)?;
+    assert_keys_equal(stats_key, *stats_info.key)?;
+    assert_owned_by(stats_info, system_account_info.key)?;
+    assert_valid_config(&config)?;
+
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority_info.key,
+            &config_key,
+            rent.minimum_balance(CONFIG_SIZE),
+            CONFIG_SIZE as u64,
+            program_id,
+        ),
+        &[
+            authority_info.clone(),
+            config_info.clone(),
+            system_account_info.clone(),
+        ],
+        &[&[CONFIG_SEED, &[config_bump_seed]]],
+    )?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority_info.key,
+            &treasury_key,
+            rent.minimum_balance(0),
+            0,
+            program_id,
+        ),
+        &[
+            authority_info.clone(),
+            treasury_info.clone(),
+            system_account_info.clone(),
+        ],
+        &[&[TREASURY_SEED, &[treasury_bump_seed]]],
+    )?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority_info.key,
+            &stats_key,
+            rent.minimum_balance(STATS_SIZE),
+            STATS_SIZE as u64,
+            program_id,
+        ),
+        &[
+            authority_info.clone(),
+            stats_info.clone(),
+            system_account_info.clone(),
+        ],
+        &[&[STATS_SEED, &[stats_bump_seed]]],
+    )?;
+
+    verbose_msg!("Initializing config {:?}", config);
+
+    config.pack_into_slice(&mut config_info.data.borrow_mut());
+    Stats::default().pack_into_slice(&mut stats_info.data.borrow_mut());
+
+    Ok(())
+}
+
+/// Update the Config account of the program
+pub fn update_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: UpdateConfig,
+) -> ProgramResult {
+    msg!("SolLock::UpdateConfig");
+
+    let UpdateConfig { config } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let admin_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    assert_is_signer(admin_info)?;
+    assert_keys_equal(get_config(program_id).0, *config_info.key)?;
+    assert_owned_by(config_info, program_id)?;
+    let old_config = assert_initialized::<Config>(config_info)?;
+    assert_keys_equal(old_config.admin, *admin_info.key)?;
+    assert_valid_config(&config)?;
+
+    verbose_msg!("Updating config from {:?} to {:?}", old_config, config);
+
+    config.pack_into_slice(&mut config_info.data.borrow_mut());
+
+    Ok(())
+}
+
+/// Withdraw protocol fees collected by the treasury
+pub fn withdraw_treasury(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: WithdrawTreasury,
+) -> ProgramResult {
+    msg!("SolLock::WithdrawTreasury");
+
+    let WithdrawTreasury { lamports } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let admin_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+
+    assert_is_signer(admin_info)?;
+    assert_keys_equal(get_config(program_id).0, *config_info.key)?;
+    assert_owned_by(config_info, program_id)?;
+    let config = assert_initialized::<Config>(config_info)?;
+    assert_keys_equal(config.admin, *admin_info.key)?;
+    assert_keys_equal(get_treasury(program_id).0, *treasury_info.key)?;
+    assert_owned_by(treasury_info, program_id)?;
+    assert_not_owned_by(destination_info, program_id)?;
+
+    let rent = Rent::get()?;
+    let withdrawable = treasury_info
+        .lamports()
+        .saturating_sub(rent.minimum_balance(treasury_info.data_len()));
+    assert_can_withdraw_treasury(lamports, withdrawable)?;
+
+    transfer_lamports(treasury_info, destination_info, lamports)?;
+
+    // Always logged, so treasury withdrawals can be audited from transaction logs
+    msg!(
+        "Treasury withdrawal: {}, Destination: {}, Remaining: {}",
+        lamports,
+        destination_info.key,
+        withdrawable - lamports
+    );
+
+    Ok(())
+}
+
+/// Allow an owner to create SolLock accounts when the program is built with the allowlist feature
+pub fn add_to_allowlist(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: AddToAllowlist,
+) -> ProgramResult {
+    msg!("SolLock::AddToAllowlist");
+
+    let AddToAllowlist { owner } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let admin_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let allowlist_entry_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+
+    let (allowlist_entry_key, allowlist_entry_bump_seed) = get_allowlist_entry(program_id, &owner);
+
+    assert_system_program(system_account_info)?;
+    assert_is_signer(admin_info)?;
+    assert_keys_equal(get_config(program_id).0, *config_info.key)?;
+    assert_owned_by(config_info, program_id)?;
+    let config = assert_initialized::<Config>(config_info)?;
+    assert_keys_equal(config.admin, *admin_info.key)?;
+    assert_keys_equal(allowlist_entry_key, *allowlist_entry_info.key)?;
+    assert_owned_by(allowlist_entry_info, system_account_info.key)?;
+
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            admin_info.key,
+            &allowlist_entry_key,
+            rent.minimum_balance(ALLOWLIST_ENTRY_SIZE),
+            ALLOWLIST_ENTRY_SIZE as u64,
+            program_id,
+        ),
+        &[
+            admin_info.clone(),
+            allowlist_entry_info.clone(),
+            system_account_info.clone(),
+        ],
+        &[&[ALLOWLIST_SEED, owner.as_ref(), &[allowlist_entry_bump_seed]]],
+    )?;
+
+    verbose_msg!("Allowing owner {:#?}", owner);
+
+    AllowlistEntry { owner }.pack_into_slice(&mut allowlist_entry_info.data.borrow_mut());
+
+    Ok(())
+}
+
+/// Disallow an owner from creating SolLock accounts when the program is built with the
+/// allowlist feature
+pub fn remove_from_allowlist(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: RemoveFromAllowlist,
+) -> ProgramResult {
+    msg!("SolLock::RemoveFromAllowlist");
+
+    let RemoveFromAllowlist { owner } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let admin_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let allowlist_entry_info = next_account_info(account_info_iter)?;
+
+    assert_is_signer(admin_info)?;
+    assert_keys_equal(get_config(program_id).0, *config_info.key)?;
+    assert_owned_by(config_info, program_id)?;
+    let config = assert_initialized::<Config>(config_info)?;
+    assert_keys_equal(config.admin, *admin_info.key)?;
+    assert_keys_equal(
+        get_allowlist_entry(program_id, &owner).0,
+        *allowlist_entry_info.key,
+    )?;
+    assert_allowlisted(allowlist_entry_info, program_id)?;
+
+    verbose_msg!("Disallowing owner {:#?}", owner);
+
+    let lamports = allowlist_entry_info.lamports();
+    **allowlist_entry_info.try_borrow_mut_lamports()? = 0;
+    credit_lamports(admin_info, lamports)?;
+
+    let mut allowlist_entry_data = allowlist_entry_info.data.borrow_mut();
+    let data_len = allowlist_entry_data.len();
+    sol_memset(&mut allowlist_entry_data, 0, data_len);
+
+    Ok(())
+}
+
+/// Create a SolLock account at the next index of the owner, returning the index
+pub fn create_next_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: CreateNextAccount,
+) -> ProgramResult {
+    msg!("SolLock::CreateNextAccount");
+
+    let CreateNextAccount { has_payer, memo } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let index_counter_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    #[cfg(feature = "allowlist")]
+    let allowlist_entry_info = next_account_info(account_info_iter)?;
+    let sol_payer_account_res = next_account_info(account_info_iter);
+
+    let payer_account_info = assert_payer_validity(owner_info, sol_payer_account_res, has_payer)?;
+
+    let (index_counter_key, index_counter_bump_seed) =
+        get_index_counter(program_id, owner_info.key);
+
+    assert_system_program(system_account_info)?;
+    assert_is_signer(payer_account_info)?;
+    assert_keys_equal(index_counter_key, *index_counter_info.key)?;
+    assert_valid_memo(&memo)?;
+    #[cfg(feature = "allowlist")]
+    assert_owner_allowlisted(program_id, owner_info, allowlist_entry_info)?;
+
+    let acc_index = if index_counter_info.owner == system_account_info.key {
+        let rent = Rent::get()?;
+
+        invoke_signed(
+            &system_instruction::create_account(
+                payer_account_info.key,
+                &index_counter_key,
+                rent.minimum_balance(INDEX_COUNTER_SIZE),
+                INDEX_COUNTER_SIZE as u64,
+                program_id,
+            ),
+            &[
+                payer_account_info.clone(),
+                index_counter_info.clone(),
+                system_account_info.clone(),
+            ],
+            &[&[
+                INDEX_COUNTER_SEED,
+                owner_info.key.as_ref(),
+                &[index_counter_bump_seed],
+            ]],
+        )?;
+
+        0
+    } else {
+        assert_owned_by(index_counter_info, program_id)?;
+        assert_initialized::<IndexCounter>(index_counter_info)?.next_index
+    };
+
+    verbose_msg!("Assigning account index {}", acc_index);
+
+    init_sol_lock_account(
+        program_id,
+        owner_info,
+        sol_lock_account_info,
+        system_account_info,
+        payer_account_info,
+        acc_index,
+        memo,
+    )?;
+
+    IndexCounter {
+        owner: *owner_info.key,
+        next_index: acc_index
+            .checked_add(1)
+            .ok_or(ProgramError::InvalidAccountData)?,
+    }
+    .pack_into_slice(&mut index_counter_info.data.borrow_mut());
+
+    set_return_data(&acc_index.to_le_bytes());
+
+    Ok(())
+}
+
+/// Create a SolLock account at the index derived from a label, and store the label in it
+pub fn create_labeled_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: CreateLabeledAccount,
+) -> ProgramResult {
+    msg!("SolLock::CreateLabeledAccount");
+
+    let CreateLabeledAccount {
+        label,
+        has_payer,
+        memo,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    #[cfg(feature = "allowlist")]
+    let allowlist_entry_info = next_account_info(account_info_iter)?;
+    let sol_payer_account_res = next_account_info(account_info_iter);
+
+    let payer_account_info = assert_payer_validity(owner_info, sol_payer_account_res, has_payer)?;
+
+    assert_system_program(system_account_info)?;
+    assert_is_signer(payer_account_info)?;
+    assert_valid_label(&label)?;
+    assert_valid_memo(&memo)?;
+    #[cfg(feature = "allowlist")]
+    assert_owner_allowlisted(program_id, owner_info, allowlist_entry_info)?;
+
+    let acc_index = get_label_acc_index(&label);
+
+    verbose_msg!(
+        "Labeling SolLock account {:?} at index {}",
+        label,
+        acc_index
+    );
+
+    init_sol_lock_account(
+        program_id,
+        owner_info,
+        sol_lock_account_info,
+        system_account_info,
+        payer_account_info,
+        acc_index,
+        memo,
+    )?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        sol_lock_account_data.label = label;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Migrate a SolLock account to the current layout
+pub fn migrate(program_id: &Pubkey, accounts: &[AccountInfo], ctx: Migrate) -> ProgramResult {
+    msg!("SolLock::Migrate");
+
+    let Migrate { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let payer_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+
+    let (sol_lock_account_key, sol_lock_account_bump_seed) =
+        get_sol_lock_account(program_id, owner_info.key, acc_index);
+
+    assert_system_program(system_account_info)?;
+    assert_is_signer(payer_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    let old_len = sol_lock_account_info.data_len();
+    let version = read_layout_version(&sol_lock_account_info.data.borrow());
+    if version == ACCOUNT_VERSION && old_len == SOL_LOCK_ACCOUNT_SIZE {
+        return Err(SolLockError::AccountAlreadyMigrated.into());
+    }
+    if version > ACCOUNT_VERSION
+        || old_len < MIN_ACCOUNT_LEN
+        || (version == 0 && old_len + DISCRIMINATOR_LEN + VERSION_LEN > SOL_LOCK_ACCOUNT_SIZE)
+    {
+        return Err(SolLockError::UnpackError.into());
+    }
+
+    verbose_msg!(
+        "Migrating SolLock account {:#?} from layout version {} to {}",
+        sol_lock_account_key,
+        version,
+        ACCOUNT_VERSION,
+    );
+
+    grow_sol_lock_account(sol_lock_account_info, payer_info, system_account_info)?;
+
+    let mut data = sol_lock_account_info.data.borrow_mut();
+    if version == 0 {
+        // The discriminator and version were inserted right after the state field
+        data.copy_within(DISCRIMINATOR_OFFSET..old_len, VERSION_OFFSET + VERSION_LEN);
+    }
+    data[DISCRIMINATOR_OFFSET..VERSION_OFFSET].copy_from_slice(&SOL_LOCK_ACCOUNT_DISCRIMINATOR);
+    data[VERSION_OFFSET] = ACCOUNT_VERSION;
+
+    // Fail rather than leave data that doesn't unpack in the current layout
+    let mut sol_lock_account_data = Account::unpack(&data)?;
+    sol_lock_account_data.rent_reserve = Rent::get()?.minimum_balance(SOL_LOCK_ACCOUNT_SIZE);
+    sol_lock_account_data.bump_seed = sol_lock_account_bump_seed;
+    if old_len < SOL_LOCK_ACCOUNT_SIZE {
+        // The name record count is new to the grown layout
+        sol_lock_account_data.name_records = 0;
+    }
+    sol_lock_account_data.pack_into_slice(&mut data);
+
+    Ok(())
+}
+
+/// Grow a SolLock account to the size of the current layout
+pub fn realloc(program_id: &Pubkey, accounts: &[AccountInfo], ctx: Realloc) -> ProgramResult {
+    msg!("SolLock::Realloc");
+
+    let Realloc { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+
+    let (sol_lock_account_key, sol_lock_account_bump_seed) =
+        get_sol_lock_account(program_id, owner_info.key, acc_index);
+
+    assert_system_program(system_account_info)?;
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    if sol_lock_account_info.data_len() >= SOL_LOCK_ACCOUNT_SIZE {
+        return Err(SolLockError::NoReallocNeeded.into());
+    }
+
+    verbose_msg!(
+        "Growing SolLock account {:#?} from {} to {} bytes",
+        sol_lock_account_key,
+        sol_lock_account_info.data_len(),
+        SOL_LOCK_ACCOUNT_SIZE,
+    );
+
+    grow_sol_lock_account(sol_lock_account_info, owner_info, system_account_info)?;
+
+    let rent_reserve = Rent::get()?.minimum_balance(SOL_LOCK_ACCOUNT_SIZE);
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        sol_lock_account_data.rent_reserve = rent_reserve;
+        sol_lock_account_data.name_records = 0;
+        sol_lock_account_data.bump_seed = sol_lock_account_bump_seed;
+        Ok(sol_lock_account_data)
+    })
+}
+
+/// Grow a SolLock account to SOL_LOCK_ACCOUNT_SIZE, zeroing the new bytes, with the payer
+/// topping up its rent reserve so the locked lamports stay untouched
+fn grow_sol_lock_account<'a>(
+    sol_lock_account_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    system_account_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    let old_len = sol_lock_account_info.data_len();
+    if old_len >= SOL_LOCK_ACCOUNT_SIZE {
+        return Ok(());
+    }
+
+    let rent = Rent::get()?;
+    let rent_top_up = rent
+        .minimum_balance(SOL_LOCK_ACCOUNT_SIZE)
+        .saturating_sub(rent.minimum_balance(old_len));
+    if rent_top_up > 0 {
+        invoke(
+            &system_instruction::transfer(payer_info.key, sol_lock_account_info.key, rent_top_up),
+            &[
+                payer_info.clone(),
+                sol_lock_account_info.clone(),
+                system_account_info.clone(),
+            ],
+        )?;
+    }
+
+    sol_lock_account_info.realloc(SOL_LOCK_ACCOUNT_SIZE, true)
+}
+
+/// Set the version of the program as return data
+pub fn get_version(_ctx: GetVersion) -> ProgramResult {
+    msg!("SolLock::GetVersion");
+
+    let version_info = VersionInfo {
+        program_version: env!("CARGO_PKG_VERSION").to_string(),
+        account_version: ACCOUNT_VERSION,
+    };
+    set_return_data(&version_info.try_to_vec()?);
+
+    Ok(())
+}
+
+/// Create the receipt token mint
+pub fn initialize_receipt_mint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _ctx: InitializeReceiptMint,
+) -> ProgramResult {
+    msg!("SolLock::InitializeReceiptMint");
+
+    let account_info_iter = &mut accounts.iter();
+    let admin_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let receipt_mint_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+
+    let (receipt_mint_key, receipt_mint_bump_seed) = get_receipt_mint(program_id);
+
+    assert_system_program(system_account_info)?;
+    assert_is_signer(admin_info)?;
+    assert_keys_equal(get_config(program_id).0, *config_info.key)?;
+    assert_owned_by(config_info, program_id)?;
+    let config = assert_initialized::<Config>(config_info)?;
+    assert_keys_equal(config.admin, *admin_info.key)?;
+    assert_keys_equal(receipt_mint_key, *receipt_mint_info.key)?;
+    assert_owned_by(receipt_mint_info, system_account_info.key)?;
+    assert_keys_equal(TOKEN_PROGRAM_ID, *token_program_info.key)?;
+
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            admin_info.key,
+            &receipt_mint_key,
+            rent.minimum_balance(MINT_LEN),
+            MINT_LEN as u64,
+            &TOKEN_PROGRAM_ID,
+        ),
+        &[
+            admin_info.clone(),
+            receipt_mint_info.clone(),
+            system_account_info.clone(),
+        ],
+        &[&[RECEIPT_MINT_SEED, &[receipt_mint_bump_seed]]],
+    )?;
+
+    verbose_msg!("Initializing receipt mint {:#?}", receipt_mint_key);
+
+    invoke(
+        &token::initialize_mint2(&receipt_mint_key, &receipt_mint_key, RECEIPT_MINT_DECIMALS),
+        &[receipt_mint_info.clone(), token_program_info.clone()],
+    )?;
+
+    Ok(())
+}
+
+/// Mint receipt tokens against the locked lamports of a SolLock account
+pub fn mint_receipt(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: MintReceipt,
+) -> ProgramResult {
+    msg!("SolLock::MintReceipt");
+
+    let MintReceipt { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let receipt_mint_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+    let (receipt_mint_key, receipt_mint_bump_seed) = get_receipt_mint(program_id);
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_keys_equal(receipt_mint_key, *receipt_mint_info.key)?;
+    assert_keys_equal(TOKEN_PROGRAM_ID, *token_program_info.key)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_can_mint_receipt(&sol_lock_account_data)?;
+
+        let amount = sol_lock_account_data
+            .lamports
+            .ok_or(SolLockError::NoFunds)?;
+
+        verbose_msg!(
+            "Minting {} receipt tokens against SolLock account {:#?}",
+            amount,
+            sol_lock_account_key
+        );
+
+        invoke_signed(
+            &token::mint_to(
+                &receipt_mint_key,
+                destination_info.key,
+                &receipt_mint_key,
+                amount,
+            ),
+            &[
+                receipt_mint_info.clone(),
+                destination_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[&[RECEIPT_MINT_SEED, &[receipt_mint_bump_seed]]],
+        )?;
+
+        sol_lock_account_data.receipt_minted = amount;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Burn the receipt tokens minted for a SolLock account
+pub fn burn_receipt(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: BurnReceipt,
+) -> ProgramResult {
+    msg!("SolLock::BurnReceipt");
+
+    let BurnReceipt { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let receipt_mint_info = next_account_info(account_info_iter)?;
+    let source_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_keys_equal(get_receipt_mint(program_id).0, *receipt_mint_info.key)?;
+    assert_keys_equal(TOKEN_PROGRAM_ID, *token_program_info.key)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        let amount = sol_lock_account_data.receipt_minted;
+        if amount == 0 {
+            return Err(SolLockError::NoReceiptMinted.into());
+        }
+
+        verbose_msg!(
+            "Burning {} receipt tokens of SolLock account {:#?}",
+            amount,
+            sol_lock_account_key
+        );
+
+        invoke(
+            &token::burn(
+                source_info.key,
+                receipt_mint_info.key,
+                owner_info.key,
+                amount,
+            ),
+            &[
+                source_info.clone(),
+                receipt_mint_info.clone(),
+                owner_info.clone(),
+                token_program_info.clone(),
+            ],
+        )?;
+
+        sol_lock_account_data.receipt_minted = 0;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Mint the position NFT of a SolLock account to its owner
+pub fn mint_position(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: MintPosition,
+) -> ProgramResult {
+    msg!("SolLock::MintPosition");
+
+    let MintPosition { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let position_mint_info = next_account_info(account_info_iter)?;
+    let owner_token_account_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let associated_token_program_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let unlock_signer_infos = account_info_iter.as_slice();
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+    let (position_mint_key, position_mint_bump_seed) =
+        get_position_mint(program_id, &sol_lock_account_key);
+
+    assert_system_program(system_account_info)?;
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_keys_equal(position_mint_key, *position_mint_info.key)?;
+    assert_keys_equal(
+        get_associated_token_address(owner_info.key, &position_mint_key),
+        *owner_token_account_info.key,
+    )?;
+    assert_keys_equal(TOKEN_PROGRAM_ID, *token_program_info.key)?;
+    assert_keys_equal(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        *associated_token_program_info.key,
+    )?;
+
+    let position_mint_seeds: &[&[u8]] = &[
+        POSITION_MINT_SEED,
+        sol_lock_account_key.as_ref(),
+        &[position_mint_bump_seed],
+    ];
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_can_mint_position(&sol_lock_account_data)?;
+        // The holder unlocks without the unlock authority or multisig, so they approve the mint
+        assert_unlock_approved(&sol_lock_account_data, owner_info, unlock_signer_infos)?;
+
+        // The mint outlives the NFT, so a relocked account reuses it
+        if *position_mint_info.owner != TOKEN_PROGRAM_ID {
+            let rent = Rent::get()?;
+
+            invoke_signed(
+                &system_instruction::create_account(
+                    owner_info.key,
+                    &position_mint_key,
+                    rent.minimum_balance(MINT_LEN),
+                    MINT_LEN as u64,
+                    &TOKEN_PROGRAM_ID,
+                ),
+                &[
+                    owner_info.clone(),
+                    position_mint_info.clone(),
+                    system_account_info.clone(),
+                ],
+                &[position_mint_seeds],
+            )?;
+
+            invoke(
+                &token::initialize_mint2(&position_mint_key, &position_mint_key, 0),
+                &[position_mint_info.clone(), token_program_info.clone()],
+            )?;
+        }
+
+        if *owner_token_account_info.owner != TOKEN_PROGRAM_ID {
+            invoke(
+                &token::create_associated_token_account(
+                    owner_info.key,
+                    owner_info.key,
+                    &position_mint_key,
+                ),
+                &[
+                    owner_info.clone(),
+                    owner_token_account_info.clone(),
+                    position_mint_info.clone(),
+                    system_account_info.clone(),
+                    token_program_info.clone(),
+                    associated_token_program_info.clone(),
+                ],
+            )?;
+        }
+
+        verbose_msg!(
+            "Minting position NFT {:#?} of SolLock account {:#?}",
+            position_mint_key,
+            sol_lock_account_key
+        );
+
+        invoke_signed(
+            &token::mint_to(
+                &position_mint_key,
+                owner_token_account_info.key,
+                &position_mint_key,
+                1,
+            ),
+            &[
+                position_mint_info.clone(),
+                owner_token_account_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[position_mint_seeds],
+        )?;
+
+        sol_lock_account_data.position_mint = Some(position_mint_key);
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Unlock a SolLock account as the holder of its position NFT
+pub fn unlock_position(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: UnlockPosition,
+) -> ProgramResult {
+    msg!("SolLock::UnlockPosition");
+
+    let UnlockPosition { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let holder_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let position_mint_info = next_account_info(account_info_iter)?;
+    let holder_token_account_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(holder_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_keys_equal(
+        get_position_mint(program_id, &sol_lock_account_key).0,
+        *position_mint_info.key,
+    )?;
+    assert_keys_equal(TOKEN_PROGRAM_ID, *token_program_info.key)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_position_holder(
+            &sol_lock_account_data,
+            holder_info,
+            holder_token_account_info,
+        )?;
+
+        // The position itself is what authorizes this unlock
+        let position_mint = sol_lock_account_data.position_mint.take();
+        let lamports = release_unlocked_lamports(&mut sol_lock_account_data)?;
+        let unlocked = sol_lock_account_data.state == State::Initialized;
+        if unlocked {
+            verbose_msg!("Burning position NFT {:#?}", position_mint_info.key);
+
+            invoke(
+                &token::burn(
+                    holder_token_account_info.key,
+                    position_mint_info.key,
+                    holder_info.key,
+                    1,
+                ),
+                &[
+                    holder_token_account_info.clone(),
+                    position_mint_info.clone(),
+                    holder_info.clone(),
+                    token_program_info.clone(),
+                ],
+            )?;
+        } else {
+            sol_lock_account_data.position_mint = position_mint;
+        }
+
+        sol_lock_account_data.record_withdrawal(lamports);
+        update_stats(program_id, stats_info, |stats| {
+            stats.record_unlock(lamports, unlocked)
+        })?;
+        let lamports_to_transfer = pay_unlock_fee(
+            program_id,
+            &sol_lock_account_data,
+            sol_lock_account_info,
+            treasury_info,
+            lamports,
+        )?;
+
+        transfer_lamports(sol_lock_account_info, holder_info, lamports_to_transfer)?;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Get and snapshot the voting power of a SolLock account
+pub fn get_voting_power(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: GetVotingPower,
+) -> ProgramResult {
+    msg!("SolLock::GetVotingPower");
+
+    let GetVotingPower { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    let now = Clock::get()?.unix_timestamp;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        let voting_power = sol_lock_account_data.voting_power(now);
+
+        verbose_msg!(
+            "SolLock account {:#?} has voting power {}",
+            sol_lock_account_key,
+            voting_power
+        );
+
+        sol_lock_account_data.voting_power = voting_power;
+        sol_lock_account_data.voting_power_at = now;
+        set_return_data(&voting_power.try_to_vec()?);
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Create the voter weight record of an owner for a realm
+pub fn create_voter_weight_record(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: CreateVoterWeightRecord,
+) -> ProgramResult {
+    msg!("SolLock::CreateVoterWeightRecord");
+
+    let CreateVoterWeightRecord {
+        realm,
+        governing_token_mint,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let voter_weight_record_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+
+    let (voter_weight_record_key, voter_weight_record_bump_seed) =
+        get_voter_weight_record(program_id, &realm, &governing_token_mint, owner_info.key);
+
+    assert_system_program(system_account_info)?;
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(voter_weight_record_key, *voter_weight_record_info.key)?;
+
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            owner_info.key,
+            &voter_weight_record_key,
+            rent.minimum_balance(VOTER_WEIGHT_RECORD_SIZE),
+            VOTER_WEIGHT_RECORD_SIZE as u64,
+            program_id,
+        ),
+        &[
+            owner_info.clone(),
+            voter_weight_record_info.clone(),
+            system_account_info.clone(),
+        ],
+        &[&[
+            VOTER_WEIGHT_RECORD_SEED,
+            realm.as_ref(),
+            governing_token_mint.as_ref(),
+            owner_info.key.as_ref(),
+            &[voter_weight_record_bump_seed],
+        ]],
+    )?;
+
+    let voter_weight_record = VoterWeightRecord {
+        account_discriminator: VOTER_WEIGHT_RECORD_DISCRIMINATOR,
+        realm,
+        governing_token_mint,
+        governing_token_owner: *owner_info.key,
+        voter_weight: 0,
+        voter_weight_expiry: Some(0),
+        weight_action: None,
+        weight_action_target: None,
+        reserved: [0; 8],
+    };
+    voter_weight_record.serialize(&mut &mut voter_weight_record_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Update the voter weight record of an owner to the voting power of a SolLock account
+pub fn update_voter_weight_record(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: UpdateVoterWeightRecord,
+) -> ProgramResult {
+    msg!("SolLock::UpdateVoterWeightRecord");
+
+    let UpdateVoterWeightRecord {
+        acc_index,
+        realm,
+        governing_token_mint,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let voter_weight_record_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    let sol_lock_account_data = assert_initialized::<Account>(sol_lock_account_info)?;
+    assert_keys_equal(
+        get_voter_weight_record(program_id, &realm, &governing_token_mint, owner_info.key).0,
+        *voter_weight_record_info.key,
+    )?;
+    assert_owned_by(voter_weight_record_info, program_id)?;
+
+    let clock = Clock::get()?;
+    let voting_power = sol_lock_account_data.voting_power(clock.unix_timestamp);
+
+    let mut voter_weight_record =
+        VoterWeightRecord::deserialize(&mut &voter_weight_record_info.data.borrow()[..])?;
+
+    verbose_msg!(
+        "Setting voter weight of {:#?} in realm {:#?} to {}",
+        owner_info.key,
+        realm,
+        voting_power
+    );
+
+    voter_weight_record.voter_weight = voting_power;
+    voter_weight_record.voter_weight_expiry = Some(clock.slot);
+    voter_weight_record.serialize(&mut &mut voter_weight_record_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Read the rewards of the stake account of a staked SolLock account, i.e. its lamports above
+/// the locked principal, and the part of them that can be withdrawn
+fn read_stake_rewards(
+    sol_lock_account_data: &Account,
+    stake_account_info: &AccountInfo,
+) -> Result<(u64, u64), ProgramError> {
+    let stake_account_key = sol_lock_account_data
+        .stake_account
+        .ok_or(SolLockError::InvalidStakeAccount)?;
+    assert_keys_equal(stake_account_key, *stake_account_info.key)?;
+
+    let stake_state = try_from_slice_unchecked::<StakeState>(&stake_account_info.data.borrow())
+        .map_err(|_| SolLockError::InvalidStakeAccount)?;
+    let rent_exempt_reserve = stake_state
+        .meta()
+        .ok_or(SolLockError::InvalidStakeAccount)?
+        .rent_exempt_reserve;
+    let delegated = stake_state
+        .delegation()
+        .map_or(0, |delegation| delegation.stake);
+
+    let principal = sol_lock_account_data.lamports.unwrap_or(0);
+    let lamports = stake_account_info.lamports();
+    let rewards = lamports.saturating_sub(principal);
+    // The stake program only releases lamports that are neither delegated nor reserved for rent
+    let withdrawable_rewards =
+        lamports.saturating_sub(principal.max(delegated.saturating_add(rent_exempt_reserve)));
+
+    Ok((rewards, withdrawable_rewards))
+}
+
+/// Withdraw the stake rewards of a staked SolLock account to the owner
+pub fn withdraw_rewards(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: WithdrawRewards,
+) -> ProgramResult {
+    msg!("SolLock::WithdrawRewards");
+
+    let WithdrawRewards { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let stake_account_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let stake_history_info = next_account_info(account_info_iter)?;
+    let stake_program_info = next_account_info(account_info_iter)?;
+
+    let (sol_lock_account_key, sol_lock_account_bump_seed) =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?;
+
+    assert_clock_sysvar(clock_info)?;
+    assert_stake_history_sysvar(stake_history_info)?;
+    assert_stake_program(stake_program_info)?;
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    let sol_lock_account_data = assert_initialized::<Account>(sol_lock_account_info)?;
+    assert_can_unstake(&sol_lock_account_data)?;
+
+    let rewards = read_stake_rewards(&sol_lock_account_data, stake_account_info)?.1;
+    if rewards == 0 {
+        return Err(SolLockError::NoRewardsToWithdraw.into());
+    }
+
+    verbose_msg!(
+        "Withdrawing {} lamports of rewards from stake account {:#?}",
+        rewards,
+        stake_account_info.key
+    );
+
+    let acc_index_bytes = acc_index.to_le_bytes();
+    invoke_signed(
+        &stake_instruction::withdraw(
+            stake_account_info.key,
+            &sol_lock_account_key,
+            owner_info.key,
+            rewards,
+            None,
+        ),
+        &[
+            stake_account_info.clone(),
+            owner_info.clone(),
+            clock_info.clone(),
+            stake_history_info.clone(),
+            sol_lock_account_info.clone(),
+            stake_program_info.clone(),
+        ],
+        &[&[
+            owner_info.key.as_ref(),
+            &acc_index_bytes,
+            &[sol_lock_account_bump_seed],
+        ]],
+    )?;
+
+    Ok(())
+}
+
+/// Compound the stake rewards of a staked SolLock account into its locked principal
+pub fn compound(program_id: &Pubkey, accounts: &[AccountInfo], ctx: Compound) -> ProgramResult {
+    msg!("SolLock::Compound");
+
+    let Compound { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let cranker_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let stake_account_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let stake_history_info = next_account_info(account_info_iter)?;
+    let stake_program_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+
+    let (sol_lock_account_key, sol_lock_account_bump_seed) =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?;
+
+    assert_clock_sysvar(clock_info)?;
+    assert_stake_history_sysvar(stake_history_info)?;
+    assert_stake_program(stake_program_info)?;
+    assert_is_signer(cranker_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    let acc_index_bytes = acc_index.to_le_bytes();
+    let sol_lock_account_seeds: &[&[u8]] = &[
+        owner_info.key.as_ref(),
+        &acc_index_bytes,
+        &[sol_lock_account_bump_seed],
+    ];
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_can_unstake(&sol_lock_account_data)?;
+        let crank_tip = assert_can_crank(&sol_lock_account_data)?;
+
+        let (rewards, withdrawable_rewards) =
+            read_stake_rewards(&sol_lock_account_data, stake_account_info)?;
+        if rewards == 0 {
+            return Err(SolLockError::NoRewardsToCompound.into());
+        }
+
+        // The tip can only come out of the rewards the stake program releases
+        let tip = crank_tip.min(withdrawable_rewards);
+        let compounded = checked_sub(rewards, tip)?;
+
+        verbose_msg!(
+            "Compounding {} lamports of rewards into SolLock account {:#?}, paying a tip of {} lamports to {:#?}",
+            compounded,
+            sol_lock_account_key,
+            tip,
+            cranker_info.key
+        );
+
+        if tip > 0 {
+            invoke_signed(
+                &stake_instruction::withdraw(
+                    stake_account_info.key,
+                    &sol_lock_account_key,
+                    cranker_info.key,
+                    tip,
+                    None,
+                ),
+                &[
+                    stake_account_info.clone(),
+                    cranker_info.clone(),
+                    clock_info.clone(),
+                    stake_history_info.clone(),
+                    sol_lock_account_info.clone(),
+                    stake_program_info.clone(),
+                ],
+                &[sol_lock_account_seeds],
+            )?;
+        }
+
+        sol_lock_account_data.lamports = sol_lock_account_data
+            .lamports
+            .map(|lamports| checked_add(lamports, compounded))
+            .transpose()?;
+        sol_lock_account_data.record_deposit(compounded);
+        update_stats(program_id, stats_info, |stats| {
+            stats.record_deposit(compounded)
+        })?;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Set the vote accounts the locked lamports of a SolLock account can be staked with
+pub fn set_validator_allowlist(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SetValidatorAllowlist,
+) -> ProgramResult {
+    msg!("SolLock::SetValidatorAllowlist");
+
+    let SetValidatorAllowlist {
+        acc_index,
+        validators,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_valid_validator_allowlist(&validators)?;
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_not_locked(&sol_lock_account_data)?;
+
+        verbose_msg!(
+            "Setting validator allowlist of SolLock account {:#?} to {:?}",
+            sol_lock_account_info.key,
+            validators
+        );
+
+        sol_lock_account_data.allowed_validators = validators;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Split part of the stake of a staked SolLock account into its split stake account
+pub fn split_stake(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: SplitStake,
+) -> ProgramResult {
+    msg!("SolLock::SplitStake");
+
+    let SplitStake {
+        acc_index,
+        lamports,
+    } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let stake_account_info = next_account_info(account_info_iter)?;
+    let split_stake_account_info = next_account_info(account_info_iter)?;
+    let stake_program_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+
+    let (sol_lock_account_key, sol_lock_account_bump_seed) =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?;
+    let (split_stake_account_key, split_stake_account_bump_seed) =
+        get_split_stake_account(program_id, &sol_lock_account_key);
+
+    assert_stake_program(stake_program_info)?;
+    assert_system_program(system_account_info)?;
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_keys_equal(split_stake_account_key, *split_stake_account_info.key)?;
+
+    let acc_index_bytes = acc_index.to_le_bytes();
+    let sol_lock_account_seeds: &[&[u8]] = &[
+        owner_info.key.as_ref(),
+        &acc_index_bytes,
+        &[sol_lock_account_bump_seed],
+    ];
+    let split_stake_account_seeds: &[&[u8]] = &[
+        SPLIT_STAKE_ACCOUNT_SEED,
+        sol_lock_account_key.as_ref(),
+        &[split_stake_account_bump_seed],
+    ];
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_can_unstake(&sol_lock_account_data)?;
+        if sol_lock_account_data.split_stake_account.is_some() {
+            return Err(SolLockError::SplitStakeOutstanding.into());
+        }
+
+        let stake_account_key = sol_lock_account_data
+            .stake_account
+            .ok_or(SolLockError::InvalidStakeAccount)?;
+        assert_keys_equal(stake_account_key, *stake_account_info.key)?;
+        if lamports == 0 || lamports >= stake_account_info.lamports() {
+            return Err(SolLockError::InvalidSplitAmount.into());
+        }
+
+        verbose_msg!(
+            "Splitting {} lamports off stake account {:#?} into {:#?}",
+            lamports,
+            stake_account_key,
+            split_stake_account_key
+        );
+
+        // Allocates and assigns the split stake account before splitting into it
+        for instruction in stake_instruction::split(
+            &stake_account_key,
+            &sol_lock_account_key,
+            lamports,
+            &split_stake_account_key,
+        ) {
+            invoke_signed(
+                &instruction,
+                &[
+                    stake_account_info.clone(),
+                    split_stake_account_info.clone(),
+                    sol_lock_account_info.clone(),
+                    system_account_info.clone(),
+                    stake_program_info.clone(),
+                ],
+                &[sol_lock_account_seeds, split_stake_account_seeds],
+            )?;
+        }
+
+        sol_lock_account_data.split_stake_account = Some(split_stake_account_key);
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Merge a secondary stake account into the stake account of a staked SolLock account
+pub fn merge_stake(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: MergeStake,
+) -> ProgramResult {
+    msg!("SolLock::MergeStake");
+
+    let MergeStake { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let stake_account_info = next_account_info(account_info_iter)?;
+    let source_stake_account_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let stake_history_info = next_account_info(account_info_iter)?;
+    let stake_program_info = next_account_info(account_info_iter)?;
+
+    let (sol_lock_account_key, sol_lock_account_bump_seed) =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?;
+
+    assert_clock_sysvar(clock_info)?;
+    assert_stake_history_sysvar(stake_history_info)?;
+    assert_stake_program(stake_program_info)?;
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+
+    let acc_index_bytes = acc_index.to_le_bytes();
+    let sol_lock_account_seeds: &[&[u8]] = &[
+        owner_info.key.as_ref(),
+        &acc_index_bytes,
+        &[sol_lock_account_bump_seed],
+    ];
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_can_unstake(&sol_lock_account_data)?;
+
+        let stake_account_key = sol_lock_account_data
+            .stake_account
+            .ok_or(SolLockError::InvalidStakeAccount)?;
+        assert_keys_equal(stake_account_key, *stake_account_info.key)?;
+        if stake_account_key == *source_stake_account_info.key {
+            return Err(SolLockError::InvalidStakeAccount.into());
+        }
+
+        // Only stake accounts the SolLock account can withdraw from count as locked
+        let source_stake_state =
+            try_from_slice_unchecked::<StakeState>(&source_stake_account_info.data.borrow())
+                .map_err(|_| SolLockError::InvalidStakeAccount)?;
+        let source_authorized = source_stake_state
+            .authorized()
+            .ok_or(SolLockError::InvalidStakeAccount)?;
+        assert_keys_equal(sol_lock_account_key, source_authorized.withdrawer)?;
+
+        let is_split_stake_account =
+            sol_lock_account_data.split_stake_account == Some(*source_stake_account_info.key);
+        let source_lamports = source_stake_account_info.lamports();
+
+        verbose_msg!(
+            "Merging stake account {:#?} with {} lamports into {:#?}",
+            source_stake_account_info.key,
+            source_lamports,
+            stake_account_key
+        );
+
+        for instruction in stake_instruction::merge(
+            &stake_account_key,
+            source_stake_account_info.key,
+            &sol_lock_account_key,
+        ) {
+            invoke_signed(
+                &instruction,
+                &[
+                    stake_account_info.clone(),
+                    source_stake_account_info.clone(),
+                    clock_info.clone(),
+                    stake_history_info.clone(),
+                    sol_lock_account_info.clone(),
+                    stake_program_info.clone(),
+                ],
+                &[sol_lock_account_seeds],
+            )?;
+        }
+
+        if is_split_stake_account {
+            sol_lock_account_data.split_stake_account = None;
+        } else {
+            // The lamports of any other stake account are newly locked
+            sol_lock_account_data.lamports = sol_lock_account_data
+                .lamports
+                .map(|lamports| checked_add(lamports, source_lamports))
+                .transpose()?;
+            sol_lock_account_data.record_deposit(source_lamports);
+        }
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Convert the locked lamports of a SolLock account into the pool token of a stake pool
+pub fn convert_to_lst(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: ConvertToLst,
+) -> ProgramResult {
+    msg!("SolLock::ConvertToLst");
+
+    let ConvertToLst { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let funding_account_info = next_account_info(account_info_iter)?;
+    let stake_pool_info = next_account_info(account_info_iter)?;
+    let withdraw_authority_info = next_account_info(account_info_iter)?;
+    let reserve_stake_info = next_account_info(account_info_iter)?;
+    let manager_fee_account_info = next_account_info(account_info_iter)?;
+    let pool_mint_info = next_account_info(account_info_iter)?;
+    let pool_token_account_info = next_account_info(account_info_iter)?;
+    let stake_pool_program_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let associated_token_program_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+
+    let (sol_lock_account_key, sol_lock_account_bump_seed) =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?;
+    let (funding_account_key, funding_account_bump_seed) =
+        get_lst_funding_account(program_id, &sol_lock_account_key);
+
+    assert_system_program(system_account_info)?;
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_keys_equal(funding_account_key, *funding_account_info.key)?;
+    assert_keys_equal(STAKE_POOL_PROGRAM_ID, *stake_pool_program_info.key)?;
+    assert_owned_by(stake_pool_info, &STAKE_POOL_PROGRAM_ID)?;
+    assert_keys_equal(
+        get_associated_token_address(&sol_lock_account_key, pool_mint_info.key),
+        *pool_token_account_info.key,
+    )?;
+    assert_keys_equal(TOKEN_PROGRAM_ID, *token_program_info.key)?;
+    assert_keys_equal(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        *associated_token_program_info.key,
+    )?;
+
+    let acc_index_bytes = acc_index.to_le_bytes();
+    let sol_lock_account_seeds: &[&[u8]] = &[
+        owner_info.key.as_ref(),
+        &acc_index_bytes,
+        &[sol_lock_account_bump_seed],
+    ];
+    let funding_account_seeds: &[&[u8]] = &[
+        LST_FUNDING_SEED,
+        sol_lock_account_key.as_ref(),
+        &[funding_account_bump_seed],
+    ];
+
+    let adapter = yield_adapter::StakePool {
+        sol_lock: SolLockSigner {
+            info: sol_lock_account_info,
+            seeds: sol_lock_account_seeds,
+        },
+        stake_pool_info,
+        withdraw_authority_info,
+        reserve_stake_info,
+        manager_fee_account_info,
+        pool_mint_info,
+        pool_token_account_info,
+        stake_pool_program_info,
+        token_program_info,
+        deposit: Some(StakePoolDeposit {
+            payer_info: owner_info,
+            funding_account: SolLockSigner {
+                info: funding_account_info,
+                seeds: funding_account_seeds,
+            },
+            associated_token_program_info,
+            system_account_info,
+        }),
+        withdrawal: None,
+    };
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_can_stake(&sol_lock_account_data)?;
+
+        let lamports = sol_lock_account_data.lamports.unwrap_or(0);
+        adapter.deposit(lamports)?;
+
+        sol_lock_account_data.lst_pool = Some(*stake_pool_info.key);
+        sol_lock_account_data.lst_deposited = lamports;
+        sol_lock_account_data.yield_backend = adapter.backend();
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Redeem the pool tokens of a SolLock account for SOL, locking it again
+pub fn redeem_lst(program_id: &Pubkey, accounts: &[AccountInfo], ctx: RedeemLst) -> ProgramResult {
+    msg!("SolLock::RedeemLst");
+
+    let RedeemLst { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let stake_pool_info = next_account_info(account_info_iter)?;
+    let withdraw_authority_info = next_account_info(account_info_iter)?;
+    let reserve_stake_info = next_account_info(account_info_iter)?;
+    let manager_fee_account_info = next_account_info(account_info_iter)?;
+    let pool_mint_info = next_account_info(account_info_iter)?;
+    let pool_token_account_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let stake_history_info = next_account_info(account_info_iter)?;
+    let stake_program_info = next_account_info(account_info_iter)?;
+    let stake_pool_program_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    let (sol_lock_account_key, sol_lock_account_bump_seed) =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?;
+
+    assert_clock_sysvar(clock_info)?;
+    assert_stake_history_sysvar(stake_history_info)?;
+    assert_stake_program(stake_program_info)?;
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_keys_equal(STAKE_POOL_PROGRAM_ID, *stake_pool_program_info.key)?;
+    assert_keys_equal(
+        get_associated_token_address(&sol_lock_account_key, pool_mint_info.key),
+        *pool_token_account_info.key,
+    )?;
+    assert_keys_equal(TOKEN_PROGRAM_ID, *token_program_info.key)?;
+
+    let acc_index_bytes = acc_index.to_le_bytes();
+    let sol_lock_account_seeds: &[&[u8]] = &[
+        owner_info.key.as_ref(),
+        &acc_index_bytes,
+        &[sol_lock_account_bump_seed],
+    ];
+
+    let adapter = yield_adapter::StakePool {
+        sol_lock: SolLockSigner {
+            info: sol_lock_account_info,
+            seeds: sol_lock_account_seeds,
+        },
+        stake_pool_info,
+        withdraw_authority_info,
+        reserve_stake_info,
+        manager_fee_account_info,
+        pool_mint_info,
+        pool_token_account_info,
+        stake_pool_program_info,
+        token_program_info,
+        deposit: None,
+        withdrawal: Some(StakePoolWithdrawal {
+            clock_info,
+            stake_history_info,
+            stake_program_info,
+        }),
+    };
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        let stake_pool = sol_lock_account_data
+            .lst_pool
+            .ok_or(SolLockError::NotConvertedToLst)?;
+        assert_keys_equal(stake_pool, *stake_pool_info.key)?;
+
+        let redeemed = adapter.withdraw()?;
+
+        verbose_msg!("Redeemed {} lamports", redeemed);
+
+        // Lamports added while converted stayed in the account
+        sol_lock_account_data.lamports = sol_lock_account_data
+            .lamports
+            .map(|lamports| {
+                checked_add(
+                    checked_sub(lamports, sol_lock_account_data.lst_deposited)?,
+                    redeemed,
+                )
+            })
+            .transpose()?;
+        sol_lock_account_data.lst_pool = None;
+        sol_lock_account_data.lst_deposited = 0;
+        sol_lock_account_data.yield_backend = YieldBackend::None;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Lend the locked lamports of a SolLock account out from a lending reserve
+pub fn deposit_to_lending(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: DepositToLending,
+) -> ProgramResult {
+    msg!("SolLock::DepositToLending");
+
+    let DepositToLending { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let reserve_info = next_account_info(account_info_iter)?;
+    let reserve_liquidity_supply_info = next_account_info(account_info_iter)?;
+    let reserve_collateral_mint_info = next_account_info(account_info_iter)?;
+    let lending_market_info = next_account_info(account_info_iter)?;
+    let lending_market_authority_info = next_account_info(account_info_iter)?;
+    let liquidity_account_info = next_account_info(account_info_iter)?;
+    let collateral_account_info = next_account_info(account_info_iter)?;
+    let native_mint_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let lending_program_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let associated_token_program_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+
+    let (sol_lock_account_key, sol_lock_account_bump_seed) =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?;
+
+    assert_clock_sysvar(clock_info)?;
+    assert_system_program(system_account_info)?;
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_keys_equal(LENDING_PROGRAM_ID, *lending_program_info.key)?;
+    assert_owned_by(reserve_info, &LENDING_PROGRAM_ID)?;
+    assert_lending_reserve(
+        &read_reserve(&reserve_info.data.borrow())?,
+        reserve_liquidity_supply_info.key,
+        reserve_collateral_mint_info.key,
+        lending_market_info.key,
+    )?;
+    assert_keys_equal(NATIVE_MINT, *native_mint_info.key)?;
+    assert_keys_equal(
+        get_associated_token_address(&sol_lock_account_key, &NATIVE_MINT),
+        *liquidity_account_info.key,
+    )?;
+    assert_keys_equal(
+        get_associated_token_address(&sol_lock_account_key, reserve_collateral_mint_info.key),
+        *collateral_account_info.key,
+    )?;
+    assert_keys_equal(TOKEN_PROGRAM_ID, *token_program_info.key)?;
+    assert_keys_equal(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        *associated_token_program_info.key,
+    )?;
+
+    let acc_index_bytes = acc_index.to_le_bytes();
+    let sol_lock_account_seeds: &[&[u8]] = &[
+        owner_info.key.as_ref(),
+        &acc_index_bytes,
+        &[sol_lock_account_bump_seed],
+    ];
+
+    let adapter = Lending {
+        sol_lock: SolLockSigner {
+            info: sol_lock_account_info,
+            seeds: sol_lock_account_seeds,
+        },
+        payer_info: owner_info,
+        reserve_info,
+        reserve_liquidity_supply_info,
+        reserve_collateral_mint_info,
+        lending_market_info,
+        lending_market_authority_info,
+        liquidity_account_info,
+        collateral_account_info,
+        native_mint_info,
+        clock_info,
+        lending_program_info,
+        token_program_info,
+        associated_token_program_info,
+        system_account_info,
+    };
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        assert_can_stake(&sol_lock_account_data)?;
+
+        let lamports = sol_lock_account_data.lamports.unwrap_or(0);
+        adapter.deposit(lamports)?;
+
+        sol_lock_account_data.lending_reserve = Some(*reserve_info.key);
+        sol_lock_account_data.lending_deposited = lamports;
+        sol_lock_account_data.lending_collateral =
+            unpack_token_account(&collateral_account_info.data.borrow())?.amount;
+        sol_lock_account_data.yield_backend = adapter.backend();
+
+        assert_lending_collateral_held(
+            &sol_lock_account_data,
+            collateral_account_info,
+            &sol_lock_account_key,
+        )?;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Withdraw the locked lamports of a SolLock account from a lending reserve, locking them again
+pub fn withdraw_from_lending(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ctx: WithdrawFromLending,
+) -> ProgramResult {
+    msg!("SolLock::WithdrawFromLending");
+
+    let WithdrawFromLending { acc_index } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let reserve_info = next_account_info(account_info_iter)?;
+    let reserve_liquidity_supply_info = next_account_info(account_info_iter)?;
+    let reserve_collateral_mint_info = next_account_info(account_info_iter)?;
+    let lending_market_info = next_account_info(account_info_iter)?;
+    let lending_market_authority_info = next_account_info(account_info_iter)?;
+    let liquidity_account_info = next_account_info(account_info_iter)?;
+    let collateral_account_info = next_account_info(account_info_iter)?;
+    let native_mint_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let lending_program_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let associated_token_program_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+
+    let (sol_lock_account_key, sol_lock_account_bump_seed) =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?;
+
+    assert_clock_sysvar(clock_info)?;
+    assert_system_program(system_account_info)?;
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    assert_keys_equal(LENDING_PROGRAM_ID, *lending_program_info.key)?;
+    assert_owned_by(reserve_info, &LENDING_PROGRAM_ID)?;
+    assert_lending_reserve(
+        &read_reserve(&reserve_info.data.borrow())?,
+        reserve_liquidity_supply_info.key,
+        reserve_collateral_mint_info.key,
+        lending_market_info.key,
+    )?;
+    assert_keys_equal(NATIVE_MINT, *native_mint_info.key)?;
+    assert_keys_equal(
+        get_associated_token_address(&sol_lock_account_key, &NATIVE_MINT),
+        *liquidity_account_info.key,
+    )?;
+    assert_keys_equal(
+        get_associated_token_address(&sol_lock_account_key, reserve_collateral_mint_info.key),
+        *collateral_account_info.key,
+    )?;
+    assert_keys_equal(TOKEN_PROGRAM_ID, *token_program_info.key)?;
+    assert_keys_equal(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        *associated_token_program_info.key,
+    )?;
+
+    let acc_index_bytes = acc_index.to_le_bytes();
+    let sol_lock_account_seeds: &[&[u8]] = &[
+        owner_info.key.as_ref(),
+        &acc_index_bytes,
+        &[sol_lock_account_bump_seed],
+    ];
+
+    let adapter = Lending {
+        sol_lock: SolLockSigner {
+            info: sol_lock_account_info,
+            seeds: sol_lock_account_seeds,
+        },
+        payer_info: owner_info,
+        reserve_info,
+        reserve_liquidity_supply_info,
+        reserve_collateral_mint_info,
+        lending_market_info,
+        lending_market_authority_info,
+        liquidity_account_info,
+        collateral_account_info,
+        native_mint_info,
+        clock_info,
+        lending_program_info,
+        token_program_info,
+        associated_token_program_info,
+        system_account_info,
+    };
+
+    sol_lock_account_info.with_mut_data(|mut sol_lock_account_data| {
+        let reserve = sol_lock_account_data
+            .lending_reserve
+            .ok_or(SolLockError::NotDepositedInLending)?;
+        assert_keys_equal(reserve, *reserve_info.key)?;
+        assert_lending_collateral_held(
+            &sol_lock_account_data,
+            collateral_account_info,
+            &sol_lock_account_key,
+        )?;
+
+        let redeemed = adapter.withdraw()?;
+
+        verbose_msg!("Redeemed {} lamports", redeemed);
+
+        // Lamports added while lent out stayed in the account
+        let lending_deposited = sol_lock_account_data.lending_deposited;
+        sol_lock_account_data.lamports = sol_lock_account_data
+            .lamports
+            .map(|lamports| checked_add(checked_sub(lamports, lending_deposited)?, redeemed))
+            .transpose()?;
+        sol_lock_account_data.lending_reserve = None;
+        sol_lock_account_data.lending_deposited = 0;
+        sol_lock_account_data.lending_collateral = 0;
+        sol_lock_account_data.yield_backend = YieldBackend::None;
+
+        Ok(sol_lock_account_data)
+    })?;
+
+    Ok(())
+}
+
+/// Log the lamports of a SolLock account that it does not track, and sweep or absorb them
+pub fn reconcile(program_id: &Pubkey, accounts: &[AccountInfo], ctx: Reconcile) -> ProgramResult {
+    msg!("SolLock::Reconcile");
+
+    let Reconcile { acc_index, action } = ctx;
+
+    let account_info_iter = &mut accounts.iter();
+    let owner_info = next_account_info(account_info_iter)?;
+    let sol_lock_account_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let stats_info = next_account_info(account_info_iter)?;
+
+    let sol_lock_account_key =
+        get_stored_sol_lock_account(program_id, owner_info.key, acc_index, sol_lock_account_info)?
+            .0;
+
+    assert_is_signer(owner_info)?;
+    assert_keys_equal(sol_lock_account_key, *sol_lock_account_info.key)?;
+    assert_owned_by(sol_lock_account_info, program_id)?;
+    let config = load_config(program_id, config_info)?;
+
+    sol_lock_account_info.with_mut_data(|sol_lock_account_data| {
+        let balance = sol_lock_account_info.lamports();
+        let tracked = checked_add(
+            sol_lock_account_data.held_lamports(),
+            sol_lock_account_data.rent_reserve,
+        )?;
+
+        if balance < tracked {
+            msg!(
+                "SolLock account {:#?} holds {} lamports, {} fewer than it tracks",
+                sol_lock_account_key,
+                balance,
+                tracked - balance
+            );
+            return Ok(sol_lock_account_data);
+        }
+
+        let surplus = balance - tracked;
+
+        msg!(
+            "SolLock account {:#?} holds {} lamports, {} more than it tracks",
+            sol_lock_account_key,
+            balance,
+            surplus
+        );
+
+        if surplus == 0 {
+            return Ok(sol_lock_account_data);
+        }
+
+        match action {
+            SurplusAction::Log => Ok(sol_lock_account_data),
+            SurplusAction::Sweep => {
+                verbose_msg!("Sweeping {} lamports to the owner", surplus);
+
+                transfer_lamports(sol_lock_account_info, owner_info, surplus)?;
+
+                Ok(sol_lock_account_data)
+            }
+            SurplusAction::Absorb => {
+                assert_no_yield_backend(&sol_lock_account_data)?;
+                let lamports = capped_deposit(&sol_lock_account_data, surplus)?;
+
+                verbose_msg!("Absorbing {} lamports into the locked lamports", lamports);
+
+                let mut sol_lock_account_data =
+                    apply_deposit(sol_lock_account_data, lamports, &config)?;
+                if sol_lock_account_data.state == State::Locked {
+                    sol_lock_account_data.record_deposit(lamports);
+                    update_stats(program_id, stats_info, |stats| {
+                        stats.record_deposit(lamports)
+                    })?;
+                }
+
+                Ok(sol_lock_account_data)
+            }
+        }
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::BeneficiaryShare;
+    use solana_program::{
+        entrypoint::SUCCESS, instruction::Instruction, program_stubs,
+        program_utils::limited_deserialize, system_instruction::SystemInstruction,
+    };
+    use std::{
+        cell::{Cell, RefCell},
+        sync::Once,
+    };
+
+    const ACC_INDEX: u64 = 7;
+
+    thread_local! {
+        static NOW: Cell<UnixTimestamp> = const { Cell::new(0) };
+        static INVOKED: RefCell<Vec<Instruction>> = const { RefCell::new(Vec::new()) };
+        static INVOKE_EFFECT: Cell<Option<InvokeEffect>> = const { Cell::new(None) };
+    }
+
+    /// Stands in for what another program does when invoked
+    type InvokeEffect = fn(&Instruction, &[AccountInfo]) -> ProgramResult;
+
+    /// Serves the Clock and Rent sysvars, system transfers and account creations to the processor in tests
+    ///
+    /// The clock reads the time set with `set_now` on the calling test thread. Instructions for
+    /// other programs are recorded for `take_invoked`, and succeed without effect unless the
+    /// test set one with `set_invoke_effect`.
+    struct TestSyscallStubs;
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        #[allow(unsafe_code)]
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            let clock = Clock {
+                unix_timestamp: NOW.with(Cell::get),
+                ..Clock::default()
+            };
+            // The processor passes a pointer to a Clock
+            unsafe { *(var_addr as *mut Clock) = clock };
+            SUCCESS
+        }
+
+        #[allow(unsafe_code)]
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            // The processor passes a pointer to a Rent
+            unsafe { *(var_addr as *mut Rent) = Rent::default() };
+            SUCCESS
+        }
+
+        fn sol_invoke_signed(
+            &self,
+            instruction: &Instruction,
+            account_infos: &[AccountInfo],
+            _signers_seeds: &[&[&[u8]]],
+        ) -> ProgramResult {
+            if instruction.program_id != solana_program::system_program::id() {
+                INVOKED.with(|invoked| invoked.borrow_mut().push(instruction.clone()));
+                return match INVOKE_EFFECT.with(Cell::get) {
+                    Some(effect) => effect(instruction, account_infos),
+                    None => Ok(()),
+                };
+            }
+            let lamports = match limited_deserialize(&instruction.data, 1024) {
+                Ok(SystemInstruction::Transfer { lamports }) => lamports,
+                // Tests pass new accounts with their data already allocated
+                Ok(SystemInstruction::CreateAccount { lamports, .. }) => lamports,
+                _ => return Err(ProgramError::IncorrectProgramId),
+            };
+            let info = |index: usize| {
+                instruction
+                    .accounts
+                    .get(index)
+                    .and_then(|meta| account_infos.iter().find(|info| *info.key == meta.pubkey))
+                    .ok_or(ProgramError::NotEnoughAccountKeys)
+            };
+
+            transfer_lamports(info(0)?, info(1)?, lamports)
+        }
+    }
+
+    /// Set the time of the Clock sysvar for the current test
+    fn set_now(now: UnixTimestamp) {
+        static INSTALL_STUBS: Once = Once::new();
+        INSTALL_STUBS.call_once(|| {
+            program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs));
+        });
+
+        NOW.with(|cell| cell.set(now));
+    }
+
+    /// Apply `effect` to the instructions invoked for programs other than the system program
+    fn set_invoke_effect(effect: InvokeEffect) {
+        INVOKE_EFFECT.with(|cell| cell.set(Some(effect)));
+    }
+
+    /// Take the instructions invoked for programs other than the system program so far
+    fn take_invoked() -> Vec<Instruction> {
+        INVOKED.with(|invoked| invoked.take())
+    }
+
+    /// The keys, lamports and data backing the AccountInfos of a test
+    struct TestAccount {
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+    }
+
+    impl TestAccount {
+        fn new(key: Pubkey) -> Self {
+            Self {
+                key,
+                owner: solana_program::system_program::id(),
+                lamports: 0,
+                data: vec![],
+            }
+        }
+
+        /// A SolLock account holding its locked lamports on top of the rent-exempt minimum
+        fn sol_lock_account(program_id: &Pubkey, mut account: Account) -> Self {
+            let (key, bump_seed) = get_sol_lock_account(program_id, &account.owner, ACC_INDEX);
+            account.bump_seed = bump_seed;
+            let mut data = vec![0; Account::LEN];
+            account.pack_into_slice(&mut data);
+
+            Self {
+                key,
+                owner: *program_id,
+                lamports: Rent::default().minimum_balance(Account::LEN) + account.held_lamports(),
+                data,
+            }
+        }
+
+        fn account(&self) -> Result<Account, ProgramError> {
+            Account::unpack_from_slice(&self.data)
+        }
+
+        fn info(&mut self, is_signer: bool) -> AccountInfo<'_> {
+            AccountInfo::new(
+                &self.key,
+                is_signer,
+                true,
+                &mut self.lamports,
+                &mut self.data,
+                &self.owner,
+                false,
+                0,
+            )
+        }
+    }
+
+    #[test]
+    fn test_malformed_instruction_data_is_rejected() {
+        let program_id = Pubkey::new_unique();
+
+        assert!(process_instruction(&program_id, &[], &[]).is_err());
+        assert!(process_instruction(&program_id, &[], &[u8::MAX, 1, 2, 3]).is_err());
+    }
+
+    /// The number of dummy accounts passed to every instruction, more than any instruction reads
+    const MAX_TEST_ACCOUNTS: usize = 24;
+
+    #[test]
+    fn test_every_instruction_rejects_missing_accounts_and_malformed_data() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut accounts: Vec<_> = (0..MAX_TEST_ACCOUNTS)
+            .map(|_| TestAccount::new(Pubkey::new_unique()))
+            .collect();
+        let infos: Vec<_> = accounts
+            .iter_mut()
+            .map(|account| account.info(true))
+            .collect();
+
+        // The shortest data each instruction decodes from, with every field zeroed
+        let instructions: Vec<(u8, Vec<u8>)> = (0..=u8::MAX)
+            .filter_map(|tag| {
+                (0..1024)
+                    .map(|len| [&[tag][..], &vec![0; len]].concat())
+                    .find(|data| SolLockInstruction::try_from_slice(data).is_ok())
+                    .map(|data| (tag, data))
+            })
+            .collect();
+        // Every variant up to the last one was found
+        assert!(instructions
+            .iter()
+            .enumerate()
+            .all(|(index, (tag, _))| index == usize::from(*tag)));
+
+        for (tag, data) in instructions {
+            let truncated = &data[..data.len() - 1];
+            let trailing = [&data[..], &[0]].concat();
+
+            if !truncated.is_empty() {
+                assert!(process_instruction(&program_id, &infos, truncated).is_err());
+            }
+            assert!(
+                process_instruction(&program_id, &infos, &trailing).is_err(),
+                "tag {}",
+                tag
+            );
+
+            // GetVersion reads no accounts and succeeds with any
+            if matches!(
+                SolLockInstruction::try_from_slice(&data),
+                Ok(SolLockInstruction::GetVersion(_))
+            ) {
+                continue;
+            }
+            for len in 0..=MAX_TEST_ACCOUNTS {
+                assert!(
+                    process_instruction(&program_id, &infos[..len], &data).is_err(),
+                    "tag {} with {} accounts",
+                    tag,
+                    len
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_remove_sol_without_lamports_is_rejected() {
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut treasury = TestAccount::new(get_treasury(&program_id).0);
+        let mut stats = TestAccount::new(get_stats(&program_id).0);
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                owner: owner.key,
+                state: State::HasFunds,
+                lamports: None,
+                ..Account::default()
+            },
+        );
+        let data = sol_lock_account.data.clone();
+
+        let result = remove_sol(
+            &program_id,
+            &[
+                owner.info(true),
+                sol_lock_account.info(false),
+                treasury.info(false),
+                stats.info(false),
+            ],
+            RemoveSol {
+                acc_index: ACC_INDEX,
+                lamports: 1,
+                has_receiver: false,
+                memo: None,
+                close: false,
+            },
+        );
+
+        assert_eq!(result, Err(SolLockError::AccountFieldNotSet.into()));
+        assert_eq!(sol_lock_account.data, data);
+    }
+
+    #[test]
+    fn test_remove_sol_with_missing_receiver_is_rejected() {
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut treasury = TestAccount::new(get_treasury(&program_id).0);
+        let mut stats = TestAccount::new(get_stats(&program_id).0);
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                owner: owner.key,
+                state: State::HasFunds,
+                lamports: Some(1_000_000),
+                ..Account::default()
+            },
+        );
+
+        let result = remove_sol(
+            &program_id,
+            &[
+                owner.info(true),
+                sol_lock_account.info(false),
+                treasury.info(false),
+                stats.info(false),
+            ],
+            RemoveSol {
+                acc_index: ACC_INDEX,
+                lamports: 1,
+                has_receiver: true,
+                memo: None,
+                close: false,
+            },
+        );
+
+        assert_eq!(result, Err(SolLockError::ConflictingPayerInfo.into()));
+    }
+
+    #[test]
+    fn test_malformed_account_data_is_rejected() {
+        let program_id = Pubkey::new_unique();
+        let owner_key = Pubkey::new_unique();
+        let account = || Account {
+            owner: owner_key,
+            state: State::HasFunds,
+            lamports: Some(1_000_000),
+            ..Account::default()
+        };
+        let remove_sol_ctx = || RemoveSol {
+            acc_index: ACC_INDEX,
+            lamports: 1,
+            has_receiver: false,
+            memo: None,
+            close: false,
+        };
+
+        // Unknown state
+        let mut owner = TestAccount::new(owner_key);
+        let mut treasury = TestAccount::new(get_treasury(&program_id).0);
+        let mut stats = TestAccount::new(get_stats(&program_id).0);
+        let mut sol_lock_account = TestAccount::sol_lock_account(&program_id, account());
+        sol_lock_account.data[crate::receipt::STATE_OFFSET] = u8::MAX;
+        assert_eq!(
+            remove_sol(
+                &program_id,
+                &[
+                    owner.info(true),
+                    sol_lock_account.info(false),
+                    treasury.info(false),
+                    stats.info(false)
+                ],
+                remove_sol_ctx(),
+            ),
+            Err(SolLockError::UnpackError.into())
+        );
+
+        // Invalid option tag of the lamports
+        let mut sol_lock_account = TestAccount::sol_lock_account(&program_id, account());
+        sol_lock_account.data[crate::receipt::LAMPORTS_OFFSET] = 2;
+        assert_eq!(
+            remove_sol(
+                &program_id,
+                &[
+                    owner.info(true),
+                    sol_lock_account.info(false),
+                    treasury.info(false),
+                    stats.info(false)
+                ],
+                remove_sol_ctx(),
+            ),
+            Err(SolLockError::UnpackError.into())
+        );
+
+        // Truncated data
+        let mut sol_lock_account = TestAccount::sol_lock_account(&program_id, account());
+        sol_lock_account.data.truncate(MIN_ACCOUNT_LEN);
+        assert!(remove_sol(
+            &program_id,
+            &[
+                owner.info(true),
+                sol_lock_account.info(false),
+                treasury.info(false),
+                stats.info(false)
+            ],
+            remove_sol_ctx(),
+        )
+        .is_err());
+    }
+
+    /// A locked account of `owner` requiring 2 of the 3 `cosigners`
+    fn multisig_account(owner: &Pubkey, cosigners: &[TestAccount]) -> Account {
+        Account {
+            owner: *owner,
+            state: State::Locked,
+            lamports: Some(1_000_000),
+            deadline: Some(1_000),
+            multisig_signers: cosigners.iter().map(|cosigner| cosigner.key).collect(),
+            multisig_threshold: 2,
+            ..Account::default()
+        }
+    }
+
+    fn cosigners() -> Vec<TestAccount> {
+        (0..3)
+            .map(|_| TestAccount::new(Pubkey::new_unique()))
+            .collect()
+    }
+
+    #[test]
+    fn test_remove_sol_of_vested_lamports_requires_multisig() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut treasury = TestAccount::new(get_treasury(&program_id).0);
+        let mut stats = TestAccount::new(get_stats(&program_id).0);
+        let mut cosigners = cosigners();
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                vesting_start: Some(0),
+                ..multisig_account(&owner.key, &cosigners)
+            },
+        );
+        let remove_sol_ctx = || RemoveSol {
+            acc_index: ACC_INDEX,
+            lamports: 100_000,
+            has_receiver: false,
+            memo: None,
+            close: false,
+        };
+
+        for (signed, is_signer) in [(0, true), (1, true), (3, false)] {
+            let mut accounts = vec![
+                owner.info(true),
+                sol_lock_account.info(false),
+                treasury.info(false),
+                stats.info(false),
+            ];
+            accounts.extend(
+                cosigners
+                    .iter_mut()
+                    .take(signed)
+                    .map(|cosigner| cosigner.info(is_signer)),
+            );
+            assert_eq!(
+                remove_sol(&program_id, &accounts, remove_sol_ctx()),
+                Err(SolLockError::MultisigThresholdNotMet.into())
+            );
+        }
+
+        let balance = sol_lock_account.lamports;
+        let mut accounts = vec![
+            owner.info(true),
+            sol_lock_account.info(false),
+            treasury.info(false),
+            stats.info(false),
+        ];
+        accounts.extend(
+            cosigners
+                .iter_mut()
+                .skip(1)
+                .map(|cosigner| cosigner.info(true)),
+        );
+        assert_eq!(remove_sol(&program_id, &accounts, remove_sol_ctx()), Ok(()));
+        drop(accounts);
+        assert_eq!(sol_lock_account.lamports, balance - 100_000);
+        assert_eq!(owner.lamports, 100_000);
+    }
+
+    #[test]
+    fn test_mint_position_requires_multisig() {
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut cosigners = cosigners();
+        let mut sol_lock_account =
+            TestAccount::sol_lock_account(&program_id, multisig_account(&owner.key, &cosigners));
+        let position_mint_key = get_position_mint(&program_id, &sol_lock_account.key).0;
+        let mut position_mint = TestAccount::new(position_mint_key);
+        let mut owner_token_account =
+            TestAccount::new(get_associated_token_address(&owner.key, &position_mint_key));
+        let mut token_program = TestAccount::new(TOKEN_PROGRAM_ID);
+        let mut associated_token_program = TestAccount::new(ASSOCIATED_TOKEN_PROGRAM_ID);
+        let mut system_program = TestAccount::new(solana_program::system_program::id());
+        let data = sol_lock_account.data.clone();
+
+        let mut accounts = vec![
+            owner.info(true),
+            sol_lock_account.info(false),
+            position_mint.info(false),
+            owner_token_account.info(false),
+            token_program.info(false),
+            associated_token_program.info(false),
+            system_program.info(false),
+        ];
+        accounts.extend(
+            cosigners
+                .iter_mut()
+                .take(1)
+                .map(|cosigner| cosigner.info(true)),
+        );
+
+        assert_eq!(
+            mint_position(
+                &program_id,
+                &accounts,
+                MintPosition {
+                    acc_index: ACC_INDEX
+                }
+            ),
+            Err(SolLockError::MultisigThresholdNotMet.into())
+        );
+        drop(accounts);
+        assert_eq!(sol_lock_account.data, data);
+    }
+
+    #[test]
+    fn test_claim_as_beneficiary_requires_multisig() {
+        set_now(2_000);
+        let program_id = Pubkey::new_unique();
+        let mut beneficiary = TestAccount::new(Pubkey::new_unique());
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut treasury = TestAccount::new(get_treasury(&program_id).0);
+        let mut stats = TestAccount::new(get_stats(&program_id).0);
+        let mut cosigners = cosigners();
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                beneficiary: Some(beneficiary.key),
+                heartbeat_interval: Some(500),
+                ..multisig_account(&owner.key, &cosigners)
+            },
+        );
+        let ctx = || ClaimAsBeneficiary {
+            acc_index: ACC_INDEX,
+        };
+
+        let mut accounts = vec![
+            beneficiary.info(true),
+            owner.info(false),
+            sol_lock_account.info(false),
+            treasury.info(false),
+            stats.info(false),
+        ];
+        accounts.extend(
+            cosigners
+                .iter_mut()
+                .take(1)
+                .map(|cosigner| cosigner.info(true)),
+        );
+        assert_eq!(
+            claim_as_beneficiary(&program_id, &accounts, ctx()),
+            Err(SolLockError::MultisigThresholdNotMet.into())
+        );
+        drop(accounts);
+
+        let mut accounts = vec![
+            beneficiary.info(true),
+            owner.info(false),
+            sol_lock_account.info(false),
+            treasury.info(false),
+            stats.info(false),
+        ];
+        accounts.extend(
+            cosigners
+                .iter_mut()
+                .take(2)
+                .map(|cosigner| cosigner.info(true)),
+        );
+        assert_eq!(claim_as_beneficiary(&program_id, &accounts, ctx()), Ok(()));
+        drop(accounts);
+        assert_eq!(beneficiary.lamports, 1_000_000);
+        assert_eq!(
+            sol_lock_account.account().map(|account| account.state),
+            Ok(State::Initialized)
+        );
+    }
+
+    #[cfg(feature = "allowlist")]
+    #[test]
+    fn test_create_and_lock_requires_allowlisted_owner() {
+        set_now(0);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        owner.lamports = 10_000_000;
+        let mut sol_lock_account =
+            TestAccount::new(get_sol_lock_account(&program_id, &owner.key, ACC_INDEX).0);
+        let mut system_program = TestAccount::new(solana_program::system_program::id());
+        let mut config = TestAccount::new(get_config(&program_id).0);
+        let mut stats = TestAccount::new(get_stats(&program_id).0);
+        let mut allowlist_entry = TestAccount::new(get_allowlist_entry(&program_id, &owner.key).0);
+
+        let result = create_and_lock(
+            &program_id,
+            &[
+                owner.info(true),
+                sol_lock_account.info(false),
+                system_program.info(false),
+                config.info(false),
+                stats.info(false),
+                allowlist_entry.info(false),
+            ],
+            CreateAndLock {
+                acc_index: ACC_INDEX,
+                lamports: 1_000_000,
+                deadline: 1_000,
+                has_payer: false,
+                memo: String::new(),
+                commitment: None,
+            },
+        );
+
+        assert_eq!(result, Err(SolLockError::NotAllowlisted.into()));
+        assert_eq!(owner.lamports, 10_000_000);
+    }
+
+    /// A Pyth price account with an aggregate price of `price` published at `publish_time`
+    fn pyth_price_account(price: i64, publish_time: UnixTimestamp, status: u32) -> TestAccount {
+        let mut data = vec![0; 240];
+        data[0..4].copy_from_slice(&0xa1b2_c3d4_u32.to_le_bytes());
+        data[4..8].copy_from_slice(&2_u32.to_le_bytes());
+        data[8..12].copy_from_slice(&3_u32.to_le_bytes());
+        data[96..104].copy_from_slice(&publish_time.to_le_bytes());
+        data[208..216].copy_from_slice(&price.to_le_bytes());
+        data[224..228].copy_from_slice(&status.to_le_bytes());
+
+        TestAccount {
+            owner: PYTH_PROGRAM_ID,
+            data,
+            ..TestAccount::new(Pubkey::new_unique())
+        }
+    }
+
+    fn check_price_of(
+        program_id: &Pubkey,
+        price_feed: &mut TestAccount,
+    ) -> (ProgramResult, Result<Account, ProgramError>) {
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            program_id,
+            Account {
+                owner: owner.key,
+                state: State::Locked,
+                lamports: Some(1_000_000),
+                deadline: Some(10_000),
+                price_feed: Some(price_feed.key),
+                price_target: Some(100),
+                ..Account::default()
+            },
+        );
+
+        let result = check_price(
+            program_id,
+            &[
+                owner.info(false),
+                sol_lock_account.info(false),
+                price_feed.info(false),
+            ],
+            CheckPrice {
+                acc_index: ACC_INDEX,
+            },
+        );
+
+        (result, sol_lock_account.account())
+    }
+
+    #[test]
+    fn test_check_price_records_reached_target() {
+        set_now(1_000);
+        let program_id = Pubkey::new_unique();
+
+        let (result, account) = check_price_of(&program_id, &mut pyth_price_account(100, 990, 1));
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            account.map(|account| account.price_target_reached),
+            Ok(true)
+        );
+    }
+
+    #[test]
+    fn test_check_price_rejects_untrusted_prices() {
+        set_now(1_000);
+        let program_id = Pubkey::new_unique();
+        let max_age = crate::oracle::MAX_PRICE_AGE;
+
+        // Not owned by the Pyth program
+        let mut price_feed = TestAccount {
+            owner: Pubkey::new_unique(),
+            ..pyth_price_account(100, 990, 1)
+        };
+        assert_eq!(
+            check_price_of(&program_id, &mut price_feed).0,
+            Err(SolLockError::IncorrectOwner.into())
+        );
+
+        // Stale
+        let mut price_feed = pyth_price_account(100, 1_000 - max_age - 1, 1);
+        assert_eq!(
+            check_price_of(&program_id, &mut price_feed).0,
+            Err(SolLockError::StalePrice.into())
+        );
+
+        // Not trading
+        let mut price_feed = pyth_price_account(100, 990, 2);
+        assert_eq!(
+            check_price_of(&program_id, &mut price_feed).0,
+            Err(SolLockError::StalePrice.into())
+        );
+
+        // Below the price target
+        let mut price_feed = pyth_price_account(99, 990, 1);
+        let (result, account) = check_price_of(&program_id, &mut price_feed);
+        assert_eq!(result, Err(SolLockError::PriceTargetNotReached.into()));
+        assert_eq!(
+            account.map(|account| account.price_target_reached),
+            Ok(false)
+        );
+    }
+
+    /// A locked account of `owner` with a protocol fee of 1% frozen into it
+    fn fee_account(owner: &Pubkey) -> Account {
+        Account {
+            owner: *owner,
+            state: State::Locked,
+            lamports: Some(1_000_000),
+            deadline: Some(1_000),
+            lock_fee_bps: 100,
+            ..Account::default()
+        }
+    }
+
+    #[test]
+    fn test_remove_sol_pays_unlock_fee_on_vested_lamports_only() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut treasury = TestAccount::new(get_treasury(&program_id).0);
+        let mut stats = TestAccount::new(get_stats(&program_id).0);
+        let remove_sol_ctx = || RemoveSol {
+            acc_index: ACC_INDEX,
+            lamports: 100_000,
+            has_receiver: false,
+            memo: None,
+            close: false,
+        };
+
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                vesting_start: Some(0),
+                ..fee_account(&owner.key)
+            },
+        );
+        assert_eq!(
+            remove_sol(
+                &program_id,
+                &[
+                    owner.info(true),
+                    sol_lock_account.info(false),
+                    treasury.info(false),
+                    stats.info(false),
+                ],
+                remove_sol_ctx(),
+            ),
+            Ok(())
+        );
+        assert_eq!(treasury.lamports, 1_000);
+        assert_eq!(owner.lamports, 99_000);
+
+        // Lamports that were never locked leave without a fee
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                state: State::HasFunds,
+                deadline: None,
+                ..fee_account(&owner.key)
+            },
+        );
+        assert_eq!(
+            remove_sol(
+                &program_id,
+                &[
+                    owner.info(true),
+                    sol_lock_account.info(false),
+                    treasury.info(false),
+                    stats.info(false),
+                ],
+                remove_sol_ctx(),
+            ),
+            Ok(())
+        );
+        assert_eq!(treasury.lamports, 1_000);
+        assert_eq!(owner.lamports, 199_000);
+    }
+
+    #[test]
+    fn test_claims_pay_unlock_fee() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut counterparty = TestAccount::new(Pubkey::new_unique());
+        let mut arbiter = TestAccount::new(Pubkey::new_unique());
+        let mut treasury = TestAccount::new(get_treasury(&program_id).0);
+        let mut stats = TestAccount::new(get_stats(&program_id).0);
+
+        // Claim of 500 seconds streamed at 1000 lamports per second
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                receiver: Some(counterparty.key),
+                stream_rate: Some(1_000),
+                stream_start: Some(0),
+                ..fee_account(&owner.key)
+            },
+        );
+        assert_eq!(
+            claim(
+                &program_id,
+                &[
+                    counterparty.info(true),
+                    owner.info(false),
+                    sol_lock_account.info(false),
+                    treasury.info(false),
+                    stats.info(false),
+                ],
+                Claim {
+                    acc_index: ACC_INDEX,
+                },
+            ),
+            Ok(())
+        );
+        assert_eq!((treasury.lamports, counterparty.lamports), (5_000, 495_000));
+
+        // ClaimWithPreimage
+        let preimage = b"preimage".to_vec();
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                hash_lock: Some(solana_program::hash::hash(&preimage).to_bytes()),
+                counterparty: Some(counterparty.key),
+                ..fee_account(&owner.key)
+            },
+        );
+        assert_eq!(
+            claim_with_preimage(
+                &program_id,
+                &[
+                    counterparty.info(true),
+                    owner.info(false),
+                    sol_lock_account.info(false),
+                    treasury.info(false),
+                    stats.info(false),
+                ],
+                ClaimWithPreimage {
+                    acc_index: ACC_INDEX,
+                    preimage,
+                },
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            (treasury.lamports, counterparty.lamports),
+            (15_000, 1_485_000)
+        );
+
+        // ClaimEscrow
+        let (owner_key, counterparty_key, arbiter_key) = (owner.key, counterparty.key, arbiter.key);
+        let escrow = || Account {
+            counterparty: Some(counterparty_key),
+            arbiter: Some(arbiter_key),
+            dispute_period: Some(100),
+            ..fee_account(&owner_key)
+        };
+        let mut sol_lock_account = TestAccount::sol_lock_account(&program_id, escrow());
+        assert_eq!(
+            claim_escrow(
+                &program_id,
+                &[
+                    counterparty.info(true),
+                    owner.info(false),
+                    sol_lock_account.info(false),
+                    treasury.info(false),
+                    stats.info(false),
+                ],
+                ClaimEscrow {
+                    acc_index: ACC_INDEX,
+                },
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            (treasury.lamports, counterparty.lamports),
+            (25_000, 2_475_000)
+        );
+
+        // RefundEscrow after the deadline
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                deadline: Some(500),
+                ..escrow()
+            },
+        );
+        assert_eq!(
+            refund_escrow(
+                &program_id,
+                &[
+                    owner.info(true),
+                    sol_lock_account.info(false),
+                    treasury.info(false),
+                    stats.info(false),
+                ],
+                RefundEscrow {
+                    acc_index: ACC_INDEX,
+                },
+            ),
+            Ok(())
+        );
+        assert_eq!((treasury.lamports, owner.lamports), (35_000, 990_000));
+
+        // Resolve to the counterparty
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                state: State::Disputed,
+                ..escrow()
+            },
+        );
+        assert_eq!(
+            resolve(
+                &program_id,
+                &[
+                    arbiter.info(true),
+                    owner.info(false),
+                    sol_lock_account.info(false),
+                    counterparty.info(false),
+                    treasury.info(false),
+                    stats.info(false),
+                ],
+                Resolve {
+                    acc_index: ACC_INDEX,
+                    to_counterparty: true,
+                },
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            (treasury.lamports, counterparty.lamports),
+            (45_000, 3_465_000)
+        );
+    }
+
+    #[test]
+    fn test_claim_as_beneficiary_splits_lamports_after_fee() {
+        set_now(2_000);
+        let program_id = Pubkey::new_unique();
+        let mut beneficiary = TestAccount::new(Pubkey::new_unique());
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut treasury = TestAccount::new(get_treasury(&program_id).0);
+        let mut stats = TestAccount::new(get_stats(&program_id).0);
+        let mut share_accounts: Vec<TestAccount> = (0..3)
+            .map(|_| TestAccount::new(Pubkey::new_unique()))
+            .collect();
+        let beneficiary_shares = share_accounts
+            .iter()
+            .zip([3_333, 3_333, 3_334])
+            .map(|(share_account, bps)| BeneficiaryShare {
+                beneficiary: share_account.key,
+                bps,
+            })
+            .collect();
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                beneficiary: Some(beneficiary.key),
+                heartbeat_interval: Some(500),
+                beneficiary_shares,
+                ..fee_account(&owner.key)
+            },
+        );
+
+        let mut accounts = vec![
+            beneficiary.info(true),
+            owner.info(false),
+            sol_lock_account.info(false),
+            treasury.info(false),
+            stats.info(false),
+        ];
+        accounts.extend(share_accounts.iter_mut().map(|share| share.info(false)));
+        assert_eq!(
+            claim_as_beneficiary(
+                &program_id,
+                &accounts,
+                ClaimAsBeneficiary {
+                    acc_index: ACC_INDEX,
+                },
+            ),
+            Ok(())
+        );
+        drop(accounts);
+
+        // 990_000 lamports are left after the fee, and the last share gets the remainder
+        assert_eq!(treasury.lamports, 10_000);
+        assert_eq!(
+            share_accounts
+                .iter()
+                .map(|share| share.lamports)
+                .collect::<Vec<_>>(),
+            vec![329_967, 329_967, 330_066]
+        );
+        assert_eq!(beneficiary.lamports, 0);
+    }
+
+    fn stats_account(program_id: &Pubkey, stats: Stats) -> TestAccount {
+        let mut data = vec![0; Stats::LEN];
+        stats.pack_into_slice(&mut data);
+
+        TestAccount {
+            owner: *program_id,
+            data,
+            ..TestAccount::new(get_stats(program_id).0)
+        }
+    }
+
+    fn stats_of(stats: &TestAccount) -> Result<Stats, ProgramError> {
+        Stats::unpack_from_slice(&stats.data)
+    }
+
+    #[test]
+    fn test_unlocks_outside_unlock_are_recorded_in_stats() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut counterparty = TestAccount::new(Pubkey::new_unique());
+        let mut treasury = TestAccount::new(get_treasury(&program_id).0);
+        let mut stats = stats_account(
+            &program_id,
+            Stats {
+                total_locked: 2_000_000,
+                active_locks: 2,
+                total_volume: 2_000_000,
+            },
+        );
+
+        // A partial vested withdrawal keeps the account locked
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                vesting_start: Some(0),
+                ..fee_account(&owner.key)
+            },
+        );
+        assert_eq!(
+            remove_sol(
+                &program_id,
+                &[
+                    owner.info(true),
+                    sol_lock_account.info(false),
+                    treasury.info(false),
+                    stats.info(false),
+                ],
+                RemoveSol {
+                    acc_index: ACC_INDEX,
+                    lamports: 100_000,
+                    has_receiver: false,
+                    memo: None,
+                    close: false,
+                },
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            stats_of(&stats),
+            Ok(Stats {
+                total_locked: 1_900_000,
+                active_locks: 2,
+                total_volume: 2_000_000,
+            })
+        );
+
+        // Claiming an escrow unlocks the account
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                counterparty: Some(counterparty.key),
+                ..fee_account(&owner.key)
+            },
+        );
+        assert_eq!(
+            claim_escrow(
+                &program_id,
+                &[
+                    counterparty.info(true),
+                    owner.info(false),
+                    sol_lock_account.info(false),
+                    treasury.info(false),
+                    stats.info(false),
+                ],
+                ClaimEscrow {
+                    acc_index: ACC_INDEX,
+                },
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            stats_of(&stats),
+            Ok(Stats {
+                total_locked: 900_000,
+                active_locks: 1,
+                total_volume: 2_000_000,
+            })
+        );
+    }
+
+    #[test]
+    fn test_contribute_to_locked_account_is_recorded_in_stats() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut contributor = TestAccount::new(Pubkey::new_unique());
+        contributor.lamports = 1_000_000;
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                open_deposits: true,
+                ..fee_account(&owner.key)
+            },
+        );
+        let contribution_key =
+            get_contribution(&program_id, &sol_lock_account.key, &contributor.key).0;
+        let mut contribution = TestAccount {
+            owner: program_id,
+            data: vec![0; CONTRIBUTION_SIZE],
+            ..TestAccount::new(contribution_key)
+        };
+        Contribution {
+            sol_lock_account: sol_lock_account.key,
+            contributor: contributor.key,
+            lamports: 0,
+        }
+        .pack_into_slice(&mut contribution.data);
+        let mut system_program = TestAccount::new(solana_program::system_program::id());
+        let mut config = TestAccount::new(get_config(&program_id).0);
+        let mut stats = stats_account(
+            &program_id,
+            Stats {
+                total_locked: 1_000_000,
+                active_locks: 1,
+                total_volume: 1_000_000,
+            },
+        );
+
+        assert_eq!(
+            contribute(
+                &program_id,
+                &[
+                    contributor.info(true),
+                    owner.info(false),
+                    sol_lock_account.info(false),
+                    contribution.info(false),
+                    system_program.info(false),
+                    config.info(false),
+                    stats.info(false),
+                ],
+                Contribute {
+                    acc_index: ACC_INDEX,
+                    lamports: 250_000,
+                },
+            ),
+            Ok(())
+        );
+
+        assert_eq!(
+            stats_of(&stats),
+            Ok(Stats {
+                total_locked: 1_250_000,
+                active_locks: 1,
+                total_volume: 1_250_000,
+            })
+        );
+        assert_eq!(
+            sol_lock_account.account().map(|account| account.lamports),
+            Ok(Some(1_250_000))
+        );
+        assert_eq!(contributor.lamports, 750_000);
+    }
+
+    /// A locked account of `owner` with `new_owner` proposed as its owner
+    fn transferable_account(owner: &Pubkey, new_owner: &Pubkey) -> Account {
+        Account {
+            owner: *owner,
+            state: State::Locked,
+            lamports: Some(1_000_000),
+            deadline: Some(1_000),
+            pending_owner: Some(*new_owner),
+            rent_reserve: Rent::default().minimum_balance(Account::LEN),
+            ..Account::default()
+        }
+    }
+
+    /// Accept the ownership of `account` as its pending owner, returning the result with
+    /// the old owner, the old SolLock account and the new SolLock account
+    fn run_accept_owner(
+        program_id: &Pubkey,
+        account: Account,
+    ) -> (ProgramResult, TestAccount, TestAccount, TestAccount) {
+        let new_acc_index = ACC_INDEX + 1;
+        let new_owner_key = account.pending_owner.unwrap_or_default();
+        let mut owner = TestAccount::new(account.owner);
+        let mut sol_lock_account = TestAccount::sol_lock_account(program_id, account);
+        let mut new_owner = TestAccount::new(new_owner_key);
+        new_owner.lamports = 100_000_000;
+        let mut new_sol_lock_account = TestAccount {
+            data: vec![0; Account::LEN],
+            ..TestAccount::new(get_sol_lock_account(program_id, &new_owner_key, new_acc_index).0)
+        };
+        let mut system_program = TestAccount::new(solana_program::system_program::id());
+
+        let result = accept_owner(
+            program_id,
+            &[
+                owner.info(false),
+                sol_lock_account.info(false),
+                new_owner.info(true),
+                new_sol_lock_account.info(false),
+                system_program.info(false),
+            ],
+            AcceptOwner {
+                acc_index: ACC_INDEX,
+                new_acc_index,
+            },
+        );
+
+        (result, owner, sol_lock_account, new_sol_lock_account)
+    }
+
+    #[test]
+    fn test_accept_owner_moves_locked_lamports() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let new_owner = Pubkey::new_unique();
+        let rent_reserve = Rent::default().minimum_balance(Account::LEN);
+
+        let (result, owner, sol_lock_account, new_sol_lock_account) = run_accept_owner(
+            &program_id,
+            transferable_account(&Pubkey::new_unique(), &new_owner),
+        );
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(owner.lamports, rent_reserve);
+        assert_eq!(sol_lock_account.lamports, 0);
+        assert!(sol_lock_account.data.iter().all(|byte| *byte == 0));
+        assert_eq!(new_sol_lock_account.lamports, rent_reserve + 1_000_000);
+        let account = new_sol_lock_account.account();
+        assert_eq!(
+            account
+                .as_ref()
+                .map(|account| (account.owner, account.pending_owner)),
+            Ok((new_owner, None))
+        );
+        assert_eq!(
+            account.map(|account| (account.state, account.lamports, account.rent_reserve)),
+            Ok((State::Locked, Some(1_000_000), rent_reserve))
+        );
+    }
+
+    #[test]
+    fn test_accept_owner_rejects_orphaning_derived_accounts() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let new_owner = Pubkey::new_unique();
+        let account = || transferable_account(&owner, &new_owner);
+
+        let cases = [
+            (
+                Account {
+                    position_mint: Some(Pubkey::new_unique()),
+                    ..account()
+                },
+                SolLockError::PositionOutstanding,
+            ),
+            (
+                Account {
+                    yield_backend: YieldBackend::Lending,
+                    lending_reserve: Some(Pubkey::new_unique()),
+                    lending_deposited: 1_000_000,
+                    ..account()
+                },
+                SolLockError::YieldBackendActive,
+            ),
+            (
+                Account {
+                    split_stake_account: Some(Pubkey::new_unique()),
+                    ..account()
+                },
+                SolLockError::SplitStakeOutstanding,
+            ),
+            (
+                Account {
+                    name_records: 1,
+                    ..account()
+                },
+                SolLockError::NameRecordsOutstanding,
+            ),
+            (
+                Account {
+                    raise_target: Some(2_000_000),
+                    refund_period: Some(1_000),
+                    raised: 500_000,
+                    ..account()
+                },
+                SolLockError::RefundsPending,
+            ),
+        ];
+
+        for (account, error) in cases {
+            let lamports = Rent::default().minimum_balance(Account::LEN) + account.held_lamports();
+            let (result, owner, sol_lock_account, new_sol_lock_account) =
+                run_accept_owner(&program_id, account);
+
+            assert_eq!(result, Err(error.into()));
+            assert_eq!(owner.lamports, 0);
+            assert_eq!(sol_lock_account.lamports, lamports);
+            assert_eq!(
+                sol_lock_account.account().map(|account| account.owner),
+                Ok(owner.key)
+            );
+            assert_eq!(new_sol_lock_account.lamports, 0);
+        }
+
+        // Contributions can no longer be refunded once the raise target is reached
+        let (result, ..) = run_accept_owner(
+            &program_id,
+            Account {
+                raise_target: Some(2_000_000),
+                refund_period: Some(1_000),
+                raised: 2_000_000,
+                ..account()
+            },
+        );
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn test_set_name_counts_name_records() {
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        owner.lamports = 10_000_000;
+        let label_hash = hashv(&[b"savings"]).to_bytes();
+        let mut name_record = TestAccount {
+            data: vec![0; NAME_RECORD_SIZE],
+            ..TestAccount::new(get_name_record(&program_id, &owner.key, &label_hash).0)
+        };
+        let mut system_program = TestAccount::new(solana_program::system_program::id());
+        let mut previous_sol_lock_account = TestAccount {
+            key: get_sol_lock_account(&program_id, &owner.key, ACC_INDEX + 1).0,
+            ..TestAccount::sol_lock_account(
+                &program_id,
+                Account {
+                    owner: owner.key,
+                    state: State::Initialized,
+                    name_records: 1,
+                    ..Account::default()
+                },
+            )
+        };
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                owner: owner.key,
+                state: State::Initialized,
+                ..Account::default()
+            },
+        );
+        let ctx = || SetName {
+            acc_index: ACC_INDEX,
+            label_hash,
+            has_payer: false,
+        };
+        let name_records = |account: &TestAccount| account.account().map(|a| a.name_records);
+
+        // Creating the name record counts it
+        assert_eq!(
+            set_name(
+                &program_id,
+                &[
+                    owner.info(true),
+                    sol_lock_account.info(false),
+                    name_record.info(false),
+                    system_program.info(false),
+                ],
+                ctx(),
+            ),
+            Ok(())
+        );
+        assert_eq!(name_records(&sol_lock_account), Ok(1));
+
+        // Repointing it moves the count, and needs the account it pointed at
+        name_record.owner = program_id;
+        NameRecord {
+            owner: owner.key,
+            label_hash,
+            sol_lock_account: previous_sol_lock_account.key,
+        }
+        .pack_into_slice(&mut name_record.data);
+        sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                owner: owner.key,
+                state: State::Initialized,
+                ..Account::default()
+            },
+        );
+
+        assert_eq!(
+            set_name(
+                &program_id,
+                &[
+                    owner.info(true),
+                    sol_lock_account.info(false),
+                    name_record.info(false),
+                    system_program.info(false),
+                ],
+                ctx(),
+            ),
+            Err(ProgramError::NotEnoughAccountKeys)
+        );
+        assert_eq!(
+            set_name(
+                &program_id,
+                &[
+                    owner.info(true),
+                    sol_lock_account.info(false),
+                    name_record.info(false),
+                    system_program.info(false),
+                    previous_sol_lock_account.info(false),
+                ],
+                ctx(),
+            ),
+            Ok(())
+        );
+        assert_eq!(name_records(&sol_lock_account), Ok(1));
+        assert_eq!(name_records(&previous_sol_lock_account), Ok(0));
+        assert_eq!(
+            NameRecord::unpack_from_slice(&name_record.data).map(|record| record.sol_lock_account),
+            Ok(sol_lock_account.key)
+        );
+    }
+
+    #[test]
+    fn test_set_name_can_be_paid_for_by_a_payer() {
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut payer = TestAccount::new(Pubkey::new_unique());
+        payer.lamports = 10_000_000;
+        let label_hash = hashv(&[b"savings"]).to_bytes();
+        let mut name_record = TestAccount {
+            data: vec![0; NAME_RECORD_SIZE],
+            ..TestAccount::new(get_name_record(&program_id, &owner.key, &label_hash).0)
+        };
+        let mut system_program = TestAccount::new(solana_program::system_program::id());
+        let account = || Account {
+            owner: owner.key,
+            state: State::Initialized,
+            ..Account::default()
+        };
+        let mut previous_sol_lock_account = TestAccount {
+            key: get_sol_lock_account(&program_id, &owner.key, ACC_INDEX + 1).0,
+            ..TestAccount::sol_lock_account(
+                &program_id,
+                Account {
+                    name_records: 1,
+                    ..account()
+                },
+            )
+        };
+        let mut sol_lock_account = TestAccount::sol_lock_account(&program_id, account());
+        let ctx = || SetName {
+            acc_index: ACC_INDEX,
+            label_hash,
+            has_payer: true,
+        };
+
+        assert_eq!(
+            set_name(
+                &program_id,
+                &[
+                    owner.info(true),
+                    sol_lock_account.info(false),
+                    name_record.info(false),
+                    system_program.info(false),
+                ],
+                ctx(),
+            ),
+            Err(SolLockError::ConflictingPayerInfo.into())
+        );
+        assert_eq!(
+            set_name(
+                &program_id,
+                &[
+                    owner.info(true),
+                    sol_lock_account.info(false),
+                    name_record.info(false),
+                    system_program.info(false),
+                    payer.info(false),
+                ],
+                ctx(),
+            ),
+            Err(ProgramError::MissingRequiredSignature)
+        );
+
+        assert_eq!(
+            set_name(
+                &program_id,
+                &[
+                    owner.info(true),
+                    sol_lock_account.info(false),
+                    name_record.info(false),
+                    system_program.info(false),
+                    payer.info(true),
+                ],
+                ctx(),
+            ),
+            Ok(())
+        );
+        assert_eq!(owner.lamports, 0);
+        assert_eq!(
+            payer.lamports,
+            10_000_000 - Rent::default().minimum_balance(NAME_RECORD_SIZE)
+        );
+
+        // The SolLock account the name pointed at comes after the payer
+        name_record.owner = program_id;
+        NameRecord {
+            owner: owner.key,
+            label_hash,
+            sol_lock_account: previous_sol_lock_account.key,
+        }
+        .pack_into_slice(&mut name_record.data);
+
+        assert_eq!(
+            set_name(
+                &program_id,
+                &[
+                    owner.info(true),
+                    sol_lock_account.info(false),
+                    name_record.info(false),
+                    system_program.info(false),
+                    payer.info(true),
+                    previous_sol_lock_account.info(false),
+                ],
+                ctx(),
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            previous_sol_lock_account
+                .account()
+                .map(|account| account.name_records),
+            Ok(0)
+        );
+        assert_eq!(
+            NameRecord::unpack_from_slice(&name_record.data).map(|record| record.sol_lock_account),
+            Ok(sol_lock_account.key)
+        );
+    }
+
+    #[test]
+    fn test_top_up_rent_ignores_lamports_held_by_a_yield_backend() {
+        let program_id = Pubkey::new_unique();
+        let rent_reserve = Rent::default().minimum_balance(Account::LEN);
+        let mut payer = TestAccount::new(Pubkey::new_unique());
+        payer.lamports = 1_000_000;
+        let mut system_program = TestAccount::new(solana_program::system_program::id());
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                owner: Pubkey::new_unique(),
+                state: State::Locked,
+                lamports: Some(1_000_000),
+                deadline: Some(1_000),
+                yield_backend: YieldBackend::Lending,
+                lending_reserve: Some(Pubkey::new_unique()),
+                lending_deposited: 1_000_000,
+                ..Account::default()
+            },
+        );
+        let mut top_up = |payer: &mut TestAccount, sol_lock_account: &mut TestAccount| {
+            top_up_rent(
+                &program_id,
+                &[
+                    payer.info(true),
+                    sol_lock_account.info(false),
+                    system_program.info(false),
+                ],
+                TopUpRent {
+                    acc_index: ACC_INDEX,
+                },
+            )
+        };
+
+        // The balance is the whole reserve, as the locked lamports are lent out
+        assert_eq!(top_up(&mut payer, &mut sol_lock_account), Ok(()));
+        assert_eq!(payer.lamports, 1_000_000);
+        assert_eq!(sol_lock_account.lamports, rent_reserve);
+        assert_eq!(
+            sol_lock_account
+                .account()
+                .map(|account| account.rent_reserve),
+            Ok(rent_reserve)
+        );
+
+        sol_lock_account.lamports -= 1_000;
+        assert_eq!(top_up(&mut payer, &mut sol_lock_account), Ok(()));
+        assert_eq!(payer.lamports, 999_000);
+        assert_eq!(sol_lock_account.lamports, rent_reserve);
+    }
+
+    fn config_account(program_id: &Pubkey, config: Config) -> TestAccount {
+        let mut data = vec![0; Config::LEN];
+        config.pack_into_slice(&mut data);
+
+        TestAccount {
+            owner: *program_id,
+            data,
+            ..TestAccount::new(get_config(program_id).0)
+        }
+    }
+
+    /// Run CreateAndLock for `owner`, paid by `payer` if passed, returning the result
+    /// with the SolLock account
+    fn run_create_and_lock(
+        program_id: &Pubkey,
+        owner: &mut TestAccount,
+        payer: Option<&mut TestAccount>,
+        config: &mut TestAccount,
+        stats: &mut TestAccount,
+        lamports: u64,
+        deadline: UnixTimestamp,
+    ) -> (ProgramResult, TestAccount) {
+        let mut sol_lock_account = TestAccount {
+            data: vec![0; Account::LEN],
+            ..TestAccount::new(get_sol_lock_account(program_id, &owner.key, ACC_INDEX).0)
+        };
+        let mut system_program = TestAccount::new(solana_program::system_program::id());
+        #[cfg(feature = "allowlist")]
+        let mut allowlist_entry = {
+            let mut data = vec![0; ALLOWLIST_ENTRY_SIZE];
+            AllowlistEntry { owner: owner.key }.pack_into_slice(&mut data);
+            TestAccount {
+                owner: *program_id,
+                data,
+                ..TestAccount::new(get_allowlist_entry(program_id, &owner.key).0)
+            }
+        };
+
+        let has_payer = payer.is_some();
+        let mut accounts = vec![
+            owner.info(!has_payer),
+            sol_lock_account.info(false),
+            system_program.info(false),
+            config.info(false),
+            stats.info(false),
+        ];
+        #[cfg(feature = "allowlist")]
+        accounts.push(allowlist_entry.info(false));
+        if let Some(payer) = payer {
+            accounts.push(payer.info(true));
+        }
+
+        let result = create_and_lock(
+            program_id,
+            &accounts,
+            CreateAndLock {
+                acc_index: ACC_INDEX,
+                lamports,
+                deadline,
+                has_payer,
+                memo: String::new(),
+                commitment: None,
+            },
+        );
+        drop(accounts);
+
+        (result, sol_lock_account)
+    }
+
+    #[test]
+    fn test_create_and_lock_locks_lamports_and_freezes_the_fee() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let rent_reserve = Rent::default().minimum_balance(Account::LEN);
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        owner.lamports = 100_000_000;
+        let mut config = config_account(
+            &program_id,
+            Config {
+                admin: Pubkey::new_unique(),
+                fee_bps: 250,
+                ..Config::default()
+            },
+        );
+        let mut stats = stats_account(&program_id, Stats::default());
+
+        let (result, sol_lock_account) = run_create_and_lock(
+            &program_id,
+            &mut owner,
+            None,
+            &mut config,
+            &mut stats,
+            2_000_000,
+            1_500,
+        );
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(owner.lamports, 100_000_000 - rent_reserve - 2_000_000);
+        assert_eq!(sol_lock_account.lamports, rent_reserve + 2_000_000);
+        let account = sol_lock_account.account();
+        assert_eq!(
+            account.as_ref().map(|account| (
+                account.owner,
+                account.state,
+                account.lamports,
+                account.deadline
+            )),
+            Ok((owner.key, State::Locked, Some(2_000_000), Some(1_500)))
+        );
+        assert_eq!(
+            account.map(|account| (
+                account.lock_fee_bps,
+                account.rent_reserve,
+                account.locked_at
+            )),
+            Ok((250, rent_reserve, Some(500)))
+        );
+        assert_eq!(
+            stats_of(&stats),
+            Ok(Stats {
+                total_locked: 2_000_000,
+                active_locks: 1,
+                total_volume: 2_000_000,
+            })
+        );
+    }
+
+    #[test]
+    fn test_create_and_lock_with_payer_debits_the_payer() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let rent_reserve = Rent::default().minimum_balance(Account::LEN);
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut payer = TestAccount::new(Pubkey::new_unique());
+        payer.lamports = 100_000_000;
+        let mut config = TestAccount::new(get_config(&program_id).0);
+        let mut stats = TestAccount::new(get_stats(&program_id).0);
+
+        let (result, sol_lock_account) = run_create_and_lock(
+            &program_id,
+            &mut owner,
+            Some(&mut payer),
+            &mut config,
+            &mut stats,
+            1_000_000,
+            1_500,
+        );
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(owner.lamports, 0);
+        assert_eq!(payer.lamports, 100_000_000 - rent_reserve - 1_000_000);
+        assert_eq!(
+            sol_lock_account.account().map(|account| account.owner),
+            Ok(owner.key)
+        );
+    }
+
+    #[test]
+    fn test_create_and_lock_enforces_the_config() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let config = || Config {
+            admin: Pubkey::new_unique(),
+            max_lock_duration: 1_000,
+            min_lock_lamports: 1_000_000,
+            ..Config::default()
+        };
+
+        let cases = [
+            (config(), 999_999, 1_500, SolLockError::AmountTooSmall),
+            (config(), 1_000_000, 1_501, SolLockError::DeadlineTooFar),
+            (
+                Config {
+                    paused: true,
+                    ..config()
+                },
+                1_000_000,
+                1_500,
+                SolLockError::ProgramPaused,
+            ),
+        ];
+
+        for (config, lamports, deadline, error) in cases {
+            let mut owner = TestAccount::new(Pubkey::new_unique());
+            owner.lamports = 100_000_000;
+            let mut config = config_account(&program_id, config);
+            let mut stats = TestAccount::new(get_stats(&program_id).0);
+
+            let (result, sol_lock_account) = run_create_and_lock(
+                &program_id,
+                &mut owner,
+                None,
+                &mut config,
+                &mut stats,
+                lamports,
+                deadline,
+            );
+
+            assert_eq!(result, Err(error.into()));
+            assert_eq!(owner.lamports, 100_000_000);
+            assert_eq!(sol_lock_account.lamports, 0);
+        }
+    }
+
+    /// Remove `lamports` from the SolLock account of `owner` to the owner, with no receiver,
+    /// memo or cosigners
+    fn remove_sol_of(
+        program_id: &Pubkey,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+        lamports: u64,
+        close: bool,
+    ) -> ProgramResult {
+        let mut treasury = TestAccount::new(get_treasury(program_id).0);
+        let mut stats = TestAccount::new(get_stats(program_id).0);
+
+        remove_sol(
+            program_id,
+            &[
+                owner.info(true),
+                sol_lock_account.info(false),
+                treasury.info(false),
+                stats.info(false),
+            ],
+            RemoveSol {
+                acc_index: ACC_INDEX,
+                lamports,
+                has_receiver: false,
+                memo: None,
+                close,
+            },
+        )
+    }
+
+    /// An account of `owner` locking 1_000_000 lamports that vest from 0 until 1_000
+    fn vesting_account(owner: &Pubkey) -> Account {
+        Account {
+            owner: *owner,
+            state: State::Locked,
+            lamports: Some(1_000_000),
+            deadline: Some(1_000),
+            vesting_start: Some(0),
+            ..Account::default()
+        }
+    }
+
+    #[test]
+    fn test_remove_sol_withdraws_linearly_vested_lamports() {
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account =
+            TestAccount::sol_lock_account(&program_id, vesting_account(&owner.key));
+        let vesting = |account: &TestAccount| {
+            account
+                .account()
+                .map(|account| (account.state, account.lamports, account.vested_withdrawn))
+        };
+
+        // A quarter of the lamports vested after a quarter of the vesting period
+        set_now(250);
+        assert_eq!(
+            remove_sol_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                250_001,
+                false
+            ),
+            Err(SolLockError::InsufficientVestedFunds.into())
+        );
+        assert_eq!(
+            remove_sol_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                250_000,
+                false
+            ),
+            Ok(())
+        );
+        assert_eq!(owner.lamports, 250_000);
+        assert_eq!(
+            vesting(&sol_lock_account),
+            Ok((State::Locked, Some(750_000), 250_000))
+        );
+        assert_eq!(
+            remove_sol_of(&program_id, &mut owner, &mut sol_lock_account, 1, false),
+            Err(SolLockError::InsufficientVestedFunds.into())
+        );
+
+        // Withdrawn lamports still count towards the vested ones
+        set_now(600);
+        assert_eq!(
+            remove_sol_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                350_000,
+                false
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            vesting(&sol_lock_account),
+            Ok((State::Locked, Some(400_000), 600_000))
+        );
+
+        // Everything vested at the deadline, and withdrawing it unlocks the account
+        set_now(1_000);
+        assert_eq!(
+            remove_sol_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                400_000,
+                false
+            ),
+            Ok(())
+        );
+        assert_eq!(owner.lamports, 1_000_000);
+        assert_eq!(
+            vesting(&sol_lock_account),
+            Ok((State::Initialized, None, 0))
+        );
+    }
+
+    #[test]
+    fn test_set_vesting_requires_an_unlocked_account_and_a_single_schedule() {
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let set_vesting_of = |owner: &mut TestAccount, sol_lock_account: &mut TestAccount| {
+            set_vesting(
+                &program_id,
+                &[owner.info(true), sol_lock_account.info(false)],
+                SetVesting {
+                    acc_index: ACC_INDEX,
+                    vesting_start: Some(0),
+                    vesting_cliff: None,
+                },
+            )
+        };
+
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                state: State::HasDeadline,
+                ..vesting_account(&owner.key)
+            },
+        );
+        assert_eq!(set_vesting_of(&mut owner, &mut sol_lock_account), Ok(()));
+        assert_eq!(
+            sol_lock_account
+                .account()
+                .map(|account| account.vesting_start),
+            Ok(Some(0))
+        );
+
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                vesting_start: None,
+                ..vesting_account(&owner.key)
+            },
+        );
+        assert_eq!(
+            set_vesting_of(&mut owner, &mut sol_lock_account),
+            Err(SolLockError::FundsLocked.into())
+        );
+
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                state: State::HasDeadline,
+                vesting_start: None,
+                stream_rate: Some(1_000),
+                stream_start: Some(0),
+                ..vesting_account(&owner.key)
+            },
+        );
+        assert_eq!(
+            set_vesting_of(&mut owner, &mut sol_lock_account),
+            Err(SolLockError::ConflictingSchedules.into())
+        );
+    }
+
+    #[test]
+    fn test_remove_sol_releases_nothing_before_the_cliff() {
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                vesting_cliff: Some(400),
+                ..vesting_account(&owner.key)
+            },
+        );
+
+        set_now(399);
+        assert_eq!(
+            remove_sol_of(&program_id, &mut owner, &mut sol_lock_account, 1, false),
+            Err(SolLockError::InsufficientVestedFunds.into())
+        );
+
+        // Everything that vested since the start is released at the cliff
+        set_now(400);
+        assert_eq!(
+            remove_sol_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                400_001,
+                false
+            ),
+            Err(SolLockError::InsufficientVestedFunds.into())
+        );
+        assert_eq!(
+            remove_sol_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                400_000,
+                false
+            ),
+            Ok(())
+        );
+        assert_eq!(owner.lamports, 400_000);
+    }
+
+    #[test]
+    fn test_set_vesting_rejects_cliffs_outside_the_vesting_period() {
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                state: State::HasDeadline,
+                vesting_start: None,
+                ..vesting_account(&owner.key)
+            },
+        );
+        let mut set_vesting_of = |vesting_start, vesting_cliff| {
+            set_vesting(
+                &program_id,
+                &[owner.info(true), sol_lock_account.info(false)],
+                SetVesting {
+                    acc_index: ACC_INDEX,
+                    vesting_start,
+                    vesting_cliff,
+                },
+            )
+        };
+
+        for (vesting_start, vesting_cliff) in [
+            (None, Some(400)),
+            (Some(500), Some(400)),
+            (Some(0), Some(1_001)),
+        ] {
+            assert_eq!(
+                set_vesting_of(vesting_start, vesting_cliff),
+                Err(SolLockError::InvalidVestingSchedule.into())
+            );
+        }
+        assert_eq!(set_vesting_of(Some(0), Some(1_000)), Ok(()));
+    }
+
+    /// Claim the streamed lamports of the SolLock account of `owner` as `receiver`
+    fn claim_of(
+        program_id: &Pubkey,
+        receiver: &mut TestAccount,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+    ) -> ProgramResult {
+        let mut treasury = TestAccount::new(get_treasury(program_id).0);
+        let mut stats = TestAccount::new(get_stats(program_id).0);
+
+        claim(
+            program_id,
+            &[
+                receiver.info(true),
+                owner.info(false),
+                sol_lock_account.info(false),
+                treasury.info(false),
+                stats.info(false),
+            ],
+            Claim {
+                acc_index: ACC_INDEX,
+            },
+        )
+    }
+
+    #[test]
+    fn test_claim_accrues_streamed_lamports_per_second() {
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut receiver = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                owner: owner.key,
+                state: State::Locked,
+                lamports: Some(1_000_000),
+                deadline: Some(10_000),
+                receiver: Some(receiver.key),
+                stream_rate: Some(1_000),
+                stream_start: Some(100),
+                ..Account::default()
+            },
+        );
+        let stream = |account: &TestAccount| {
+            account
+                .account()
+                .map(|account| (account.state, account.lamports, account.stream_claimed))
+        };
+
+        set_now(100);
+        assert_eq!(
+            claim_of(
+                &program_id,
+                &mut receiver,
+                &mut owner,
+                &mut sol_lock_account
+            ),
+            Err(SolLockError::NoFunds.into())
+        );
+
+        set_now(350);
+        assert_eq!(
+            claim_of(
+                &program_id,
+                &mut receiver,
+                &mut owner,
+                &mut sol_lock_account
+            ),
+            Ok(())
+        );
+        assert_eq!(receiver.lamports, 250_000);
+        assert_eq!(
+            stream(&sol_lock_account),
+            Ok((State::Locked, Some(750_000), 250_000))
+        );
+        assert_eq!(
+            claim_of(
+                &program_id,
+                &mut receiver,
+                &mut owner,
+                &mut sol_lock_account
+            ),
+            Err(SolLockError::NoFunds.into())
+        );
+
+        // The stream stops at the locked lamports, and claiming the last of them unlocks
+        set_now(5_000);
+        assert_eq!(
+            claim_of(
+                &program_id,
+                &mut receiver,
+                &mut owner,
+                &mut sol_lock_account
+            ),
+            Ok(())
+        );
+        assert_eq!(receiver.lamports, 1_000_000);
+        assert_eq!(stream(&sol_lock_account), Ok((State::Initialized, None, 0)));
+    }
+
+    #[test]
+    fn test_claim_requires_the_receiver_of_a_stream() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut receiver = TestAccount::new(Pubkey::new_unique());
+        let owner_key = owner.key;
+        let stream_account = |receiver: Option<Pubkey>| Account {
+            owner: owner_key,
+            state: State::Locked,
+            lamports: Some(1_000_000),
+            deadline: Some(10_000),
+            receiver,
+            stream_rate: Some(1_000),
+            stream_start: Some(0),
+            ..Account::default()
+        };
+
+        let mut sol_lock_account =
+            TestAccount::sol_lock_account(&program_id, stream_account(Some(receiver.key)));
+        let mut other = TestAccount::new(Pubkey::new_unique());
+        let mut owner_copy = TestAccount::new(owner.key);
+        assert_eq!(
+            claim_of(&program_id, &mut other, &mut owner, &mut sol_lock_account),
+            Err(SolLockError::PublicKeyMismatch.into())
+        );
+        assert_eq!(
+            claim_of(
+                &program_id,
+                &mut owner_copy,
+                &mut owner,
+                &mut sol_lock_account
+            ),
+            Err(SolLockError::PublicKeyMismatch.into())
+        );
+
+        // Without a stored receiver, the owner receives the stream
+        let mut sol_lock_account = TestAccount::sol_lock_account(&program_id, stream_account(None));
+        assert_eq!(
+            claim_of(
+                &program_id,
+                &mut owner_copy,
+                &mut owner,
+                &mut sol_lock_account
+            ),
+            Ok(())
+        );
+        assert_eq!(owner_copy.lamports, 500_000);
+
+        // Accounts that don't stream have nothing to claim
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                stream_rate: None,
+                stream_start: None,
+                ..stream_account(Some(receiver.key))
+            },
+        );
+        assert_eq!(
+            claim_of(
+                &program_id,
+                &mut receiver,
+                &mut owner,
+                &mut sol_lock_account
+            ),
+            Err(SolLockError::FundsLocked.into())
+        );
+    }
+
+    /// Unlock the SolLock account of `owner` to the owner, with no receiver, memo or signers
+    fn unlock_of(
+        program_id: &Pubkey,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+    ) -> ProgramResult {
+        let mut treasury = TestAccount::new(get_treasury(program_id).0);
+        let mut stats = TestAccount::new(get_stats(program_id).0);
+
+        unlock(
+            program_id,
+            &[
+                owner.info(true),
+                sol_lock_account.info(false),
+                treasury.info(false),
+                stats.info(false),
+            ],
+            Unlock {
+                acc_index: ACC_INDEX,
+                has_receiver: false,
+                nonce: None,
+                memo: None,
+            },
+        )
+    }
+
+    /// Claim the hash-locked lamports of the SolLock account of `owner` with `preimage`
+    fn claim_with_preimage_of(
+        program_id: &Pubkey,
+        counterparty: &mut TestAccount,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+        preimage: &[u8],
+    ) -> ProgramResult {
+        let mut treasury = TestAccount::new(get_treasury(program_id).0);
+        let mut stats = TestAccount::new(get_stats(program_id).0);
+
+        claim_with_preimage(
+            program_id,
+            &[
+                counterparty.info(true),
+                owner.info(false),
+                sol_lock_account.info(false),
+                treasury.info(false),
+                stats.info(false),
+            ],
+            ClaimWithPreimage {
+                acc_index: ACC_INDEX,
+                preimage: preimage.to_vec(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_claim_with_preimage_before_the_deadline() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut counterparty = TestAccount::new(Pubkey::new_unique());
+        let mut other = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                owner: owner.key,
+                state: State::Locked,
+                lamports: Some(1_000_000),
+                deadline: Some(1_000),
+                hash_lock: Some(hashv(&[b"preimage"]).to_bytes()),
+                counterparty: Some(counterparty.key),
+                ..Account::default()
+            },
+        );
+
+        assert_eq!(
+            claim_with_preimage_of(
+                &program_id,
+                &mut counterparty,
+                &mut owner,
+                &mut sol_lock_account,
+                b"guess"
+            ),
+            Err(SolLockError::InvalidPreimage.into())
+        );
+        assert_eq!(
+            claim_with_preimage_of(
+                &program_id,
+                &mut other,
+                &mut owner,
+                &mut sol_lock_account,
+                b"preimage"
+            ),
+            Err(SolLockError::PublicKeyMismatch.into())
+        );
+        // The owner can't unlock the lamports before the deadline either
+        assert_eq!(
+            unlock_of(&program_id, &mut owner, &mut sol_lock_account),
+            Err(SolLockError::PrematureUnlock.into())
+        );
+
+        assert_eq!(
+            claim_with_preimage_of(
+                &program_id,
+                &mut counterparty,
+                &mut owner,
+                &mut sol_lock_account,
+                b"preimage"
+            ),
+            Ok(())
+        );
+        assert_eq!(counterparty.lamports, 1_000_000);
+        assert_eq!(
+            sol_lock_account.account().map(|account| (
+                account.state,
+                account.lamports,
+                account.hash_lock
+            )),
+            Ok((State::Initialized, None, None))
+        );
+    }
+
+    #[test]
+    fn test_hash_lock_refunds_the_owner_after_the_deadline() {
+        set_now(1_000);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut counterparty = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                owner: owner.key,
+                state: State::Locked,
+                lamports: Some(1_000_000),
+                deadline: Some(1_000),
+                hash_lock: Some(hashv(&[b"preimage"]).to_bytes()),
+                counterparty: Some(counterparty.key),
+                ..Account::default()
+            },
+        );
+
+        assert_eq!(
+            claim_with_preimage_of(
+                &program_id,
+                &mut counterparty,
+                &mut owner,
+                &mut sol_lock_account,
+                b"preimage"
+            ),
+            Err(SolLockError::HashLockExpired.into())
+        );
+        assert_eq!(
+            unlock_of(&program_id, &mut owner, &mut sol_lock_account),
+            Ok(())
+        );
+        assert_eq!(owner.lamports, 1_000_000);
+        assert_eq!(counterparty.lamports, 0);
+    }
+
+    /// An escrow of `owner` locking 1_000_000 lamports for `counterparty` until 1_000
+    fn escrow_account(owner: &Pubkey, counterparty: &Pubkey) -> Account {
+        Account {
+            owner: *owner,
+            state: State::Locked,
+            lamports: Some(1_000_000),
+            deadline: Some(1_000),
+            counterparty: Some(*counterparty),
+            ..Account::default()
+        }
+    }
+
+    fn claim_escrow_of(
+        program_id: &Pubkey,
+        counterparty: &mut TestAccount,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+    ) -> ProgramResult {
+        let mut treasury = TestAccount::new(get_treasury(program_id).0);
+        let mut stats = TestAccount::new(get_stats(program_id).0);
+
+        claim_escrow(
+            program_id,
+            &[
+                counterparty.info(true),
+                owner.info(false),
+                sol_lock_account.info(false),
+                treasury.info(false),
+                stats.info(false),
+            ],
+            ClaimEscrow {
+                acc_index: ACC_INDEX,
+            },
+        )
+    }
+
+    fn refund_escrow_of(
+        program_id: &Pubkey,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+    ) -> ProgramResult {
+        let mut treasury = TestAccount::new(get_treasury(program_id).0);
+        let mut stats = TestAccount::new(get_stats(program_id).0);
+
+        refund_escrow(
+            program_id,
+            &[
+                owner.info(true),
+                sol_lock_account.info(false),
+                treasury.info(false),
+                stats.info(false),
+            ],
+            RefundEscrow {
+                acc_index: ACC_INDEX,
+            },
+        )
+    }
+
+    #[test]
+    fn test_claim_escrow_as_the_counterparty_before_the_deadline() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut counterparty = TestAccount::new(Pubkey::new_unique());
+        let mut other = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            escrow_account(&owner.key, &counterparty.key),
+        );
+
+        assert_eq!(
+            claim_escrow_of(&program_id, &mut other, &mut owner, &mut sol_lock_account),
+            Err(SolLockError::PublicKeyMismatch.into())
+        );
+        assert_eq!(
+            refund_escrow_of(&program_id, &mut owner, &mut sol_lock_account),
+            Err(SolLockError::PrematureUnlock.into())
+        );
+        assert_eq!(
+            claim_escrow_of(
+                &program_id,
+                &mut counterparty,
+                &mut owner,
+                &mut sol_lock_account
+            ),
+            Ok(())
+        );
+        assert_eq!(counterparty.lamports, 1_000_000);
+        assert_eq!(
+            sol_lock_account.account().map(|account| (
+                account.state,
+                account.lamports,
+                account.counterparty
+            )),
+            Ok((State::Initialized, None, None))
+        );
+    }
+
+    #[test]
+    fn test_refund_escrow_to_the_owner_after_the_deadline() {
+        set_now(1_000);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut counterparty = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            escrow_account(&owner.key, &counterparty.key),
+        );
+
+        assert_eq!(
+            claim_escrow_of(
+                &program_id,
+                &mut counterparty,
+                &mut owner,
+                &mut sol_lock_account
+            ),
+            Err(SolLockError::EscrowExpired.into())
+        );
+        assert_eq!(
+            refund_escrow_of(&program_id, &mut owner, &mut sol_lock_account),
+            Ok(())
+        );
+        assert_eq!(owner.lamports, 1_000_000);
+        assert_eq!(
+            sol_lock_account.account().map(|account| account.state),
+            Ok(State::Initialized)
+        );
+    }
+
+    /// An escrow of `owner` for `counterparty` that `arbiter` can resolve within 300 seconds
+    fn arbitrated_account(owner: &Pubkey, counterparty: &Pubkey, arbiter: &Pubkey) -> Account {
+        Account {
+            arbiter: Some(*arbiter),
+            dispute_period: Some(300),
+            ..escrow_account(owner, counterparty)
+        }
+    }
+
+    fn raise_dispute_of(
+        program_id: &Pubkey,
+        party: &mut TestAccount,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+    ) -> ProgramResult {
+        raise_dispute(
+            program_id,
+            &[
+                party.info(true),
+                owner.info(false),
+                sol_lock_account.info(false),
+            ],
+            RaiseDispute {
+                acc_index: ACC_INDEX,
+            },
+        )
+    }
+
+    fn resolve_of(
+        program_id: &Pubkey,
+        arbiter: &mut TestAccount,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+        counterparty: &mut TestAccount,
+        to_counterparty: bool,
+    ) -> ProgramResult {
+        let mut treasury = TestAccount::new(get_treasury(program_id).0);
+        let mut stats = TestAccount::new(get_stats(program_id).0);
+
+        resolve(
+            program_id,
+            &[
+                arbiter.info(true),
+                owner.info(false),
+                sol_lock_account.info(false),
+                counterparty.info(false),
+                treasury.info(false),
+                stats.info(false),
+            ],
+            Resolve {
+                acc_index: ACC_INDEX,
+                to_counterparty,
+            },
+        )
+    }
+
+    #[test]
+    fn test_raise_dispute_hands_the_escrow_to_the_arbiter() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut counterparty = TestAccount::new(Pubkey::new_unique());
+        let mut arbiter = TestAccount::new(Pubkey::new_unique());
+        let mut other = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            arbitrated_account(&owner.key, &counterparty.key, &arbiter.key),
+        );
+
+        assert_eq!(
+            resolve_of(
+                &program_id,
+                &mut arbiter,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut counterparty,
+                true
+            ),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            raise_dispute_of(&program_id, &mut other, &mut owner, &mut sol_lock_account),
+            Err(SolLockError::PublicKeyMismatch.into())
+        );
+        assert_eq!(
+            raise_dispute_of(
+                &program_id,
+                &mut counterparty,
+                &mut owner,
+                &mut sol_lock_account
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            sol_lock_account
+                .account()
+                .map(|account| (account.state, account.deadline)),
+            Ok((State::Disputed, Some(800)))
+        );
+
+        // A disputed escrow can only be resolved by its arbiter
+        assert_eq!(
+            claim_escrow_of(
+                &program_id,
+                &mut counterparty,
+                &mut owner,
+                &mut sol_lock_account
+            ),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            resolve_of(
+                &program_id,
+                &mut other,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut counterparty,
+                true
+            ),
+            Err(SolLockError::PublicKeyMismatch.into())
+        );
+    }
+
+    #[test]
+    fn test_resolve_pays_the_party_chosen_by_the_arbiter() {
+        for to_counterparty in [true, false] {
+            set_now(500);
+            let program_id = Pubkey::new_unique();
+            let mut owner = TestAccount::new(Pubkey::new_unique());
+            let mut party = TestAccount::new(owner.key);
+            let mut counterparty = TestAccount::new(Pubkey::new_unique());
+            let mut arbiter = TestAccount::new(Pubkey::new_unique());
+            let mut sol_lock_account = TestAccount::sol_lock_account(
+                &program_id,
+                arbitrated_account(&owner.key, &counterparty.key, &arbiter.key),
+            );
+
+            assert_eq!(
+                raise_dispute_of(&program_id, &mut party, &mut owner, &mut sol_lock_account),
+                Ok(())
+            );
+            assert_eq!(
+                resolve_of(
+                    &program_id,
+                    &mut arbiter,
+                    &mut owner,
+                    &mut sol_lock_account,
+                    &mut counterparty,
+                    to_counterparty
+                ),
+                Ok(())
+            );
+            let (paid, unpaid) = if to_counterparty {
+                (&counterparty, &owner)
+            } else {
+                (&owner, &counterparty)
+            };
+            assert_eq!(paid.lamports, 1_000_000);
+            assert_eq!(unpaid.lamports, 0);
+            assert_eq!(arbiter.lamports, 0);
+            assert_eq!(
+                sol_lock_account.account().map(|account| (
+                    account.state,
+                    account.lamports,
+                    account.arbiter,
+                    account.dispute_period
+                )),
+                Ok((State::Initialized, None, None, None))
+            );
+        }
+    }
+
+    #[test]
+    fn test_expired_dispute_is_refunded_to_the_owner() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut counterparty = TestAccount::new(Pubkey::new_unique());
+        let mut party = TestAccount::new(counterparty.key);
+        let mut arbiter = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            arbitrated_account(&owner.key, &counterparty.key, &arbiter.key),
+        );
+
+        assert_eq!(
+            raise_dispute_of(&program_id, &mut party, &mut owner, &mut sol_lock_account),
+            Ok(())
+        );
+
+        // The dispute replaces the deadline of 1_000 with the end of the dispute period
+        set_now(799);
+        assert_eq!(
+            refund_escrow_of(&program_id, &mut owner, &mut sol_lock_account),
+            Err(SolLockError::PrematureUnlock.into())
+        );
+
+        set_now(800);
+        assert_eq!(
+            resolve_of(
+                &program_id,
+                &mut arbiter,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut counterparty,
+                true
+            ),
+            Err(SolLockError::DisputeExpired.into())
+        );
+        assert_eq!(
+            refund_escrow_of(&program_id, &mut owner, &mut sol_lock_account),
+            Ok(())
+        );
+        assert_eq!(owner.lamports, 1_000_000);
+        assert_eq!(counterparty.lamports, 0);
+    }
+
+    /// An open-deposit account of `owner` holding 1_000_000 lamports locked until 1_000
+    fn crowdfund_account(owner: &Pubkey) -> Account {
+        Account {
+            owner: *owner,
+            state: State::Locked,
+            lamports: Some(1_000_000),
+            deadline: Some(1_000),
+            open_deposits: true,
+            ..Account::default()
+        }
+    }
+
+    /// The Contribution account of `contributor` to `sol_lock_account`
+    fn contribution_account(
+        program_id: &Pubkey,
+        sol_lock_account: &Pubkey,
+        contributor: &Pubkey,
+    ) -> TestAccount {
+        let mut contribution = TestAccount {
+            owner: *program_id,
+            data: vec![0; CONTRIBUTION_SIZE],
+            ..TestAccount::new(get_contribution(program_id, sol_lock_account, contributor).0)
+        };
+        Contribution {
+            sol_lock_account: *sol_lock_account,
+            contributor: *contributor,
+            lamports: 0,
+        }
+        .pack_into_slice(&mut contribution.data);
+
+        contribution
+    }
+
+    fn contribute_of(
+        program_id: &Pubkey,
+        contributor: &mut TestAccount,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+        contribution: &mut TestAccount,
+        lamports: u64,
+    ) -> ProgramResult {
+        let mut system_program = TestAccount::new(solana_program::system_program::id());
+        let mut config = TestAccount::new(get_config(program_id).0);
+        let mut stats = TestAccount::new(get_stats(program_id).0);
+
+        contribute(
+            program_id,
+            &[
+                contributor.info(true),
+                owner.info(false),
+                sol_lock_account.info(false),
+                contribution.info(false),
+                system_program.info(false),
+                config.info(false),
+                stats.info(false),
+            ],
+            Contribute {
+                acc_index: ACC_INDEX,
+                lamports,
+            },
+        )
+    }
+
+    fn contributed(contribution: &TestAccount) -> Result<u64, ProgramError> {
+        Contribution::unpack(&contribution.data).map(|contribution| contribution.lamports)
+    }
+
+    #[test]
+    fn test_contribute_tracks_each_contributor() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account =
+            TestAccount::sol_lock_account(&program_id, crowdfund_account(&owner.key));
+        let mut first = TestAccount::new(Pubkey::new_unique());
+        first.lamports = 1_000_000;
+        let mut first_contribution =
+            contribution_account(&program_id, &sol_lock_account.key, &first.key);
+        let mut second = TestAccount::new(Pubkey::new_unique());
+        second.lamports = 1_000_000;
+        let mut second_contribution =
+            contribution_account(&program_id, &sol_lock_account.key, &second.key);
+
+        // A contributor can only use their own Contribution account
+        assert_eq!(
+            contribute_of(
+                &program_id,
+                &mut first,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut second_contribution,
+                100_000
+            ),
+            Err(SolLockError::PublicKeyMismatch.into())
+        );
+
+        for lamports in [100_000, 150_000] {
+            assert_eq!(
+                contribute_of(
+                    &program_id,
+                    &mut first,
+                    &mut owner,
+                    &mut sol_lock_account,
+                    &mut first_contribution,
+                    lamports
+                ),
+                Ok(())
+            );
+        }
+        assert_eq!(
+            contribute_of(
+                &program_id,
+                &mut second,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut second_contribution,
+                400_000
+            ),
+            Ok(())
+        );
+
+        assert_eq!(contributed(&first_contribution), Ok(250_000));
+        assert_eq!(contributed(&second_contribution), Ok(400_000));
+        assert_eq!(first.lamports, 750_000);
+        assert_eq!(second.lamports, 600_000);
+        assert_eq!(
+            sol_lock_account
+                .account()
+                .map(|account| (account.lamports, account.raised)),
+            Ok((Some(1_650_000), 650_000))
+        );
+    }
+
+    #[test]
+    fn test_contribute_requires_open_deposits() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                open_deposits: false,
+                ..crowdfund_account(&owner.key)
+            },
+        );
+        let mut contributor = TestAccount::new(Pubkey::new_unique());
+        contributor.lamports = 1_000_000;
+        let mut contribution =
+            contribution_account(&program_id, &sol_lock_account.key, &contributor.key);
+
+        assert_eq!(
+            contribute_of(
+                &program_id,
+                &mut contributor,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut contribution,
+                100_000
+            ),
+            Err(SolLockError::DepositsClosed.into())
+        );
+        assert_eq!(contributor.lamports, 1_000_000);
+        assert_eq!(contributed(&contribution), Ok(0));
+    }
+
+    fn refund_contribution_of(
+        program_id: &Pubkey,
+        contributor: &mut TestAccount,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+        contribution: &mut TestAccount,
+    ) -> ProgramResult {
+        let mut stats = TestAccount::new(get_stats(program_id).0);
+
+        refund_contribution(
+            program_id,
+            &[
+                contributor.info(true),
+                owner.info(false),
+                sol_lock_account.info(false),
+                contribution.info(false),
+                stats.info(false),
+            ],
+            RefundContribution {
+                acc_index: ACC_INDEX,
+            },
+        )
+    }
+
+    #[test]
+    fn test_set_raise_target_requires_a_refund_period_on_an_unlocked_account() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut locked_account =
+            TestAccount::sol_lock_account(&program_id, crowdfund_account(&owner));
+        let mut unlocked_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                owner,
+                state: State::Initialized,
+                ..Account::default()
+            },
+        );
+        let set_raise_target_of =
+            |sol_lock_account: &mut TestAccount, raise_target, refund_period| {
+                set_raise_target(
+                    &program_id,
+                    &[
+                        TestAccount::new(owner).info(true),
+                        sol_lock_account.info(false),
+                    ],
+                    SetRaiseTarget {
+                        acc_index: ACC_INDEX,
+                        raise_target,
+                        refund_period,
+                    },
+                )
+            };
+
+        assert_eq!(
+            set_raise_target_of(&mut unlocked_account, Some(2_000_000), None),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            set_raise_target_of(&mut unlocked_account, Some(2_000_000), Some(0)),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            set_raise_target_of(&mut locked_account, Some(2_000_000), Some(500)),
+            Err(SolLockError::FundsLocked.into())
+        );
+        assert_eq!(
+            set_raise_target_of(&mut unlocked_account, Some(2_000_000), Some(500)),
+            Ok(())
+        );
+        assert_eq!(
+            unlocked_account
+                .account()
+                .map(|account| (account.raise_target, account.refund_period)),
+            Ok((Some(2_000_000), Some(500)))
+        );
+    }
+
+    #[test]
+    fn test_refund_contribution_within_the_refund_period_of_a_missed_target() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                raise_target: Some(2_000_000),
+                refund_period: Some(500),
+                ..crowdfund_account(&owner.key)
+            },
+        );
+        let mut contributor = TestAccount::new(Pubkey::new_unique());
+        contributor.lamports = 1_000_000;
+        let mut contribution =
+            contribution_account(&program_id, &sol_lock_account.key, &contributor.key);
+
+        assert_eq!(
+            contribute_of(
+                &program_id,
+                &mut contributor,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut contribution,
+                300_000
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            refund_contribution_of(
+                &program_id,
+                &mut contributor,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut contribution
+            ),
+            Err(SolLockError::RefundUnavailable.into())
+        );
+
+        set_now(1_000);
+        assert_eq!(
+            contribute_of(
+                &program_id,
+                &mut contributor,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut contribution,
+                300_000
+            ),
+            Err(SolLockError::CrowdfundEnded.into())
+        );
+        assert_eq!(
+            refund_contribution_of(
+                &program_id,
+                &mut contributor,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut contribution
+            ),
+            Ok(())
+        );
+        assert_eq!(contributor.lamports, 1_000_000);
+        assert_eq!(contributed(&contribution), Ok(0));
+        assert_eq!(
+            sol_lock_account.account().map(|account| (
+                account.state,
+                account.lamports,
+                account.raised
+            )),
+            Ok((State::Locked, Some(1_000_000), 0))
+        );
+        assert_eq!(
+            refund_contribution_of(
+                &program_id,
+                &mut contributor,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut contribution
+            ),
+            Err(SolLockError::NoFunds.into())
+        );
+    }
+
+    #[test]
+    fn test_refund_contribution_is_unavailable_once_the_target_is_reached() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                raise_target: Some(500_000),
+                refund_period: Some(500),
+                deposit_cap: Some(1_600_000),
+                ..crowdfund_account(&owner.key)
+            },
+        );
+        let mut contributor = TestAccount::new(Pubkey::new_unique());
+        contributor.lamports = 1_000_000;
+        let mut contribution =
+            contribution_account(&program_id, &sol_lock_account.key, &contributor.key);
+
+        // The deposit cap bounds the raise
+        assert_eq!(
+            contribute_of(
+                &program_id,
+                &mut contributor,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut contribution,
+                700_000
+            ),
+            Err(SolLockError::DepositCapExceeded.into())
+        );
+        assert_eq!(
+            contribute_of(
+                &program_id,
+                &mut contributor,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut contribution,
+                600_000
+            ),
+            Ok(())
+        );
+
+        set_now(1_000);
+        assert_eq!(
+            refund_contribution_of(
+                &program_id,
+                &mut contributor,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut contribution
+            ),
+            Err(SolLockError::RefundUnavailable.into())
+        );
+        assert_eq!(contributor.lamports, 400_000);
+    }
+
+    /// A locked account of `owner` that `beneficiary` can claim 500 seconds after the deadline
+    /// of 1_000 or the last heartbeat
+    fn dead_mans_switch_account(owner: &Pubkey, beneficiary: &Pubkey) -> Account {
+        Account {
+            owner: *owner,
+            state: State::Locked,
+            lamports: Some(1_000_000),
+            deadline: Some(1_000),
+            beneficiary: Some(*beneficiary),
+            heartbeat_interval: Some(500),
+            ..Account::default()
+        }
+    }
+
+    fn heartbeat_of(
+        program_id: &Pubkey,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+    ) -> ProgramResult {
+        heartbeat(
+            program_id,
+            &[owner.info(true), sol_lock_account.info(false)],
+            Heartbeat {
+                acc_index: ACC_INDEX,
+            },
+        )
+    }
+
+    fn claim_as_beneficiary_of(
+        program_id: &Pubkey,
+        beneficiary: &mut TestAccount,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+        share_accounts: &mut [TestAccount],
+    ) -> ProgramResult {
+        let mut treasury = TestAccount::new(get_treasury(program_id).0);
+        let mut stats = TestAccount::new(get_stats(program_id).0);
+
+        let mut accounts = vec![
+            beneficiary.info(true),
+            owner.info(false),
+            sol_lock_account.info(false),
+            treasury.info(false),
+            stats.info(false),
+        ];
+        accounts.extend(share_accounts.iter_mut().map(|share| share.info(false)));
+
+        claim_as_beneficiary(
+            program_id,
+            &accounts,
+            ClaimAsBeneficiary {
+                acc_index: ACC_INDEX,
+            },
+        )
+    }
+
+    #[test]
+    fn test_set_beneficiary_requires_a_heartbeat_interval_on_an_unlocked_account() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let beneficiary = Pubkey::new_unique();
+        let mut locked_account = TestAccount::sol_lock_account(
+            &program_id,
+            dead_mans_switch_account(&owner, &beneficiary),
+        );
+        let mut unlocked_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                owner,
+                state: State::Initialized,
+                ..Account::default()
+            },
+        );
+        let set_beneficiary_of =
+            |sol_lock_account: &mut TestAccount, beneficiary, heartbeat_interval| {
+                set_beneficiary(
+                    &program_id,
+                    &[
+                        TestAccount::new(owner).info(true),
+                        sol_lock_account.info(false),
+                    ],
+                    SetBeneficiary {
+                        acc_index: ACC_INDEX,
+                        beneficiary,
+                        heartbeat_interval,
+                    },
+                )
+            };
+
+        assert_eq!(
+            set_beneficiary_of(&mut unlocked_account, Some(beneficiary), None),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            set_beneficiary_of(&mut unlocked_account, None, Some(500)),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            set_beneficiary_of(&mut locked_account, None, None),
+            Err(SolLockError::FundsLocked.into())
+        );
+        assert_eq!(
+            set_beneficiary_of(&mut unlocked_account, Some(beneficiary), Some(500)),
+            Ok(())
+        );
+        assert_eq!(
+            unlocked_account
+                .account()
+                .map(|account| (account.beneficiary, account.heartbeat_interval)),
+            Ok((Some(beneficiary), Some(500)))
+        );
+    }
+
+    #[test]
+    fn test_heartbeat_delays_the_beneficiary_claim() {
+        set_now(1_200);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut beneficiary = TestAccount::new(Pubkey::new_unique());
+        let mut other = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            dead_mans_switch_account(&owner.key, &beneficiary.key),
+        );
+
+        assert_eq!(
+            heartbeat_of(&program_id, &mut owner, &mut sol_lock_account),
+            Ok(())
+        );
+        assert_eq!(
+            sol_lock_account
+                .account()
+                .map(|account| account.beneficiary_claim_time()),
+            Ok(Some(1_700))
+        );
+
+        // Without the heartbeat, the beneficiary could claim from 1_500
+        set_now(1_699);
+        assert_eq!(
+            claim_as_beneficiary_of(
+                &program_id,
+                &mut beneficiary,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut []
+            ),
+            Err(SolLockError::OwnerStillActive.into())
+        );
+
+        set_now(1_700);
+        assert_eq!(
+            claim_as_beneficiary_of(
+                &program_id,
+                &mut other,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut []
+            ),
+            Err(SolLockError::PublicKeyMismatch.into())
+        );
+        assert_eq!(
+            claim_as_beneficiary_of(
+                &program_id,
+                &mut beneficiary,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut []
+            ),
+            Ok(())
+        );
+        assert_eq!(beneficiary.lamports, 1_000_000);
+        assert_eq!(
+            sol_lock_account.account().map(|account| (
+                account.state,
+                account.lamports,
+                account.last_heartbeat
+            )),
+            Ok((State::Initialized, None, None))
+        );
+    }
+
+    #[test]
+    fn test_heartbeat_requires_a_beneficiary() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account =
+            TestAccount::sol_lock_account(&program_id, vesting_account(&owner.key));
+
+        assert_eq!(
+            heartbeat_of(&program_id, &mut owner, &mut sol_lock_account),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+
+    fn shares_of(share_accounts: &[TestAccount], bps: &[u16]) -> Vec<BeneficiaryShare> {
+        share_accounts
+            .iter()
+            .zip(bps)
+            .map(|(share_account, &bps)| BeneficiaryShare {
+                beneficiary: share_account.key,
+                bps,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_set_beneficiary_shares_must_sum_to_the_whole() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let share_accounts: Vec<TestAccount> = (0..5)
+            .map(|_| TestAccount::new(Pubkey::new_unique()))
+            .collect();
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                owner,
+                state: State::Initialized,
+                ..Account::default()
+            },
+        );
+        let mut set_beneficiary_shares_of = |shares| {
+            set_beneficiary_shares(
+                &program_id,
+                &[
+                    TestAccount::new(owner).info(true),
+                    sol_lock_account.info(false),
+                ],
+                SetBeneficiaryShares {
+                    acc_index: ACC_INDEX,
+                    shares,
+                },
+            )
+        };
+
+        let duplicated = vec![
+            BeneficiaryShare {
+                beneficiary: share_accounts[0].key,
+                bps: 5_000,
+            };
+            2
+        ];
+        for shares in [
+            shares_of(&share_accounts, &[5_000, 4_999]),
+            shares_of(&share_accounts, &[5_000, 5_000, 0]),
+            shares_of(&share_accounts, &[2_000; 5]),
+            duplicated,
+        ] {
+            assert_eq!(
+                set_beneficiary_shares_of(shares),
+                Err(SolLockError::InvalidBeneficiaryShares.into())
+            );
+        }
+        assert_eq!(
+            set_beneficiary_shares_of(shares_of(&share_accounts, &[2_500; 4])),
+            Ok(())
+        );
+        assert_eq!(
+            sol_lock_account
+                .account()
+                .map(|account| account.beneficiary_shares.len()),
+            Ok(4)
+        );
+    }
+
+    #[test]
+    fn test_claim_as_beneficiary_gives_the_rounding_remainder_to_the_last_share() {
+        set_now(1_500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut beneficiary = TestAccount::new(Pubkey::new_unique());
+        let mut share_accounts: Vec<TestAccount> = (0..3)
+            .map(|_| TestAccount::new(Pubkey::new_unique()))
+            .collect();
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                lamports: Some(1_000_001),
+                beneficiary_shares: shares_of(&share_accounts, &[3_333, 3_333, 3_334]),
+                ..dead_mans_switch_account(&owner.key, &beneficiary.key)
+            },
+        );
+
+        assert_eq!(
+            claim_as_beneficiary_of(
+                &program_id,
+                &mut beneficiary,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut share_accounts[..2]
+            ),
+            Err(ProgramError::NotEnoughAccountKeys)
+        );
+        assert_eq!(
+            claim_as_beneficiary_of(
+                &program_id,
+                &mut beneficiary,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut share_accounts
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            share_accounts
+                .iter()
+                .map(|share| share.lamports)
+                .collect::<Vec<_>>(),
+            vec![333_300, 333_300, 333_401]
+        );
+        assert_eq!(beneficiary.lamports, 0);
+    }
+
+    #[test]
+    fn test_claim_as_beneficiary_requires_the_share_accounts_in_order() {
+        set_now(1_500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut beneficiary = TestAccount::new(Pubkey::new_unique());
+        let mut share_accounts: Vec<TestAccount> = (0..2)
+            .map(|_| TestAccount::new(Pubkey::new_unique()))
+            .collect();
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                beneficiary_shares: shares_of(&share_accounts, &[4_000, 6_000]),
+                ..dead_mans_switch_account(&owner.key, &beneficiary.key)
+            },
+        );
+
+        share_accounts.reverse();
+        assert_eq!(
+            claim_as_beneficiary_of(
+                &program_id,
+                &mut beneficiary,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut share_accounts
+            ),
+            Err(SolLockError::PublicKeyMismatch.into())
+        );
+        assert_eq!(
+            sol_lock_account.account().map(|account| account.state),
+            Ok(State::Locked)
+        );
+    }
+
+    /// A locked account of `owner` recoverable by two of the `guardians` after 1_000 seconds
+    fn guarded_account(owner: &Pubkey, guardians: &[TestAccount]) -> Account {
+        Account {
+            guardians: guardians.iter().map(|guardian| guardian.key).collect(),
+            guardian_threshold: 2,
+            recovery_delay: Some(1_000),
+            pending_owner: None,
+            ..transferable_account(owner, &Pubkey::default())
+        }
+    }
+
+    fn initiate_recovery_of<'a>(
+        program_id: &Pubkey,
+        owner: &'a mut TestAccount,
+        sol_lock_account: &'a mut TestAccount,
+        guardian_infos: Vec<AccountInfo<'a>>,
+        new_owner: &Pubkey,
+    ) -> ProgramResult {
+        let mut accounts = vec![owner.info(false), sol_lock_account.info(false)];
+        accounts.extend(guardian_infos);
+
+        initiate_recovery(
+            program_id,
+            &accounts,
+            InitiateRecovery {
+                acc_index: ACC_INDEX,
+                new_owner: *new_owner,
+            },
+        )
+    }
+
+    fn veto_recovery_of(
+        program_id: &Pubkey,
+        owner: &Pubkey,
+        sol_lock_account: &mut TestAccount,
+    ) -> ProgramResult {
+        veto_recovery(
+            program_id,
+            &[
+                TestAccount::new(*owner).info(true),
+                sol_lock_account.info(false),
+            ],
+            VetoRecovery {
+                acc_index: ACC_INDEX,
+            },
+        )
+    }
+
+    #[test]
+    fn test_set_guardians_requires_a_reachable_quorum_and_a_delay() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let guardians: Vec<Pubkey> = (0..6).map(|_| Pubkey::new_unique()).collect();
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                owner,
+                state: State::Initialized,
+                ..Account::default()
+            },
+        );
+        let mut set_guardians_of = |guardians: &[Pubkey], threshold, recovery_delay| {
+            set_guardians(
+                &program_id,
+                &[
+                    TestAccount::new(owner).info(true),
+                    sol_lock_account.info(false),
+                ],
+                SetGuardians {
+                    acc_index: ACC_INDEX,
+                    guardians: guardians.to_vec(),
+                    threshold,
+                    recovery_delay,
+                },
+            )
+        };
+
+        for (guardians, threshold, recovery_delay) in [
+            (&guardians[..3], 0, Some(1_000)),
+            (&guardians[..3], 4, Some(1_000)),
+            (&guardians[..3], 2, None),
+            (&guardians[..3], 2, Some(0)),
+            (&guardians[..], 2, Some(1_000)),
+            (&[guardians[0], guardians[0]][..], 2, Some(1_000)),
+        ] {
+            assert_eq!(
+                set_guardians_of(guardians, threshold, recovery_delay),
+                Err(SolLockError::InvalidGuardians.into())
+            );
+        }
+        assert_eq!(set_guardians_of(&guardians[..3], 2, Some(1_000)), Ok(()));
+        assert_eq!(
+            sol_lock_account.account().map(|account| (
+                account.guardians.len(),
+                account.guardian_threshold,
+                account.recovery_delay
+            )),
+            Ok((3, 2, Some(1_000)))
+        );
+    }
+
+    #[test]
+    fn test_guardian_quorum_recovers_the_account_after_the_delay() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let new_owner = Pubkey::new_unique();
+        let mut guardians: Vec<TestAccount> = (0..3)
+            .map(|_| TestAccount::new(Pubkey::new_unique()))
+            .collect();
+        let mut sol_lock_account =
+            TestAccount::sol_lock_account(&program_id, guarded_account(&owner.key, &guardians));
+        let guardian_key = guardians[0].key;
+        let mut repeated_guardian = TestAccount::new(guardian_key);
+
+        // A single guardian doesn't count twice, and unsigned guardians don't count at all
+        let mut guardian_infos: Vec<AccountInfo> = guardians
+            .iter_mut()
+            .take(1)
+            .map(|guardian| guardian.info(true))
+            .collect();
+        guardian_infos.push(repeated_guardian.info(true));
+        assert_eq!(
+            initiate_recovery_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                guardian_infos,
+                &new_owner
+            ),
+            Err(SolLockError::GuardianThresholdNotMet.into())
+        );
+        let guardian_infos = guardians
+            .iter_mut()
+            .enumerate()
+            .map(|(index, guardian)| guardian.info(index == 0))
+            .collect();
+        assert_eq!(
+            initiate_recovery_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                guardian_infos,
+                &new_owner
+            ),
+            Err(SolLockError::GuardianThresholdNotMet.into())
+        );
+
+        let guardian_infos = guardians
+            .iter_mut()
+            .skip(1)
+            .map(|guardian| guardian.info(true))
+            .collect();
+        assert_eq!(
+            initiate_recovery_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                guardian_infos,
+                &new_owner
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            sol_lock_account
+                .account()
+                .map(|account| (account.pending_owner, account.recovery_available_at)),
+            Ok((Some(new_owner), Some(1_500)))
+        );
+
+        set_now(1_499);
+        let account = sol_lock_account.account().unwrap_or_default();
+        assert_eq!(
+            run_accept_owner(&program_id, account).0,
+            Err(SolLockError::RecoveryPending.into())
+        );
+
+        set_now(1_500);
+        let account = sol_lock_account.account().unwrap_or_default();
+        let (result, _, _, new_sol_lock_account) = run_accept_owner(&program_id, account);
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            new_sol_lock_account.account().map(|account| (
+                account.owner,
+                account.pending_owner,
+                account.recovery_available_at
+            )),
+            Ok((new_owner, None, None))
+        );
+    }
+
+    #[test]
+    fn test_owner_can_veto_a_pending_recovery() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let new_owner = Pubkey::new_unique();
+        let mut guardians: Vec<TestAccount> = (0..3)
+            .map(|_| TestAccount::new(Pubkey::new_unique()))
+            .collect();
+        let mut sol_lock_account =
+            TestAccount::sol_lock_account(&program_id, guarded_account(&owner.key, &guardians));
+
+        assert_eq!(
+            veto_recovery_of(&program_id, &owner.key, &mut sol_lock_account),
+            Err(SolLockError::NoPendingRecovery.into())
+        );
+        let guardian_infos = guardians
+            .iter_mut()
+            .map(|guardian| guardian.info(true))
+            .collect();
+        assert_eq!(
+            initiate_recovery_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                guardian_infos,
+                &new_owner
+            ),
+            Ok(())
+        );
+
+        // The guardians can't be replaced while their recovery is pending
+        assert_eq!(
+            set_guardians(
+                &program_id,
+                &[
+                    TestAccount::new(owner.key).info(true),
+                    sol_lock_account.info(false),
+                ],
+                SetGuardians {
+                    acc_index: ACC_INDEX,
+                    guardians: vec![],
+                    threshold: 0,
+                    recovery_delay: None,
+                },
+            ),
+            Err(SolLockError::RecoveryPending.into())
+        );
+
+        assert_eq!(
+            veto_recovery_of(&program_id, &owner.key, &mut sol_lock_account),
+            Ok(())
+        );
+        assert_eq!(
+            sol_lock_account
+                .account()
+                .map(|account| (account.pending_owner, account.recovery_available_at)),
+            Ok((None, None))
+        );
+
+        set_now(1_500);
+        let account = sol_lock_account.account().unwrap_or_default();
+        assert_eq!(
+            run_accept_owner(
+                &program_id,
+                Account {
+                    pending_owner: None,
+                    ..account
+                }
+            )
+            .0,
+            Err(SolLockError::PendingOwnerMismatch.into())
+        );
+    }
+
+    /// The ProgramData account of `program_id`, upgradeable by `authority`
+    fn program_data_account(program_id: &Pubkey, authority: &Pubkey) -> TestAccount {
+        let loader = solana_program::bpf_loader_upgradeable::id();
+        let mut data = vec![0; 45];
+        data[..4].copy_from_slice(&3u32.to_le_bytes());
+        data[12] = 1;
+        data[13..].copy_from_slice(authority.as_ref());
+
+        TestAccount {
+            owner: loader,
+            data,
+            ..TestAccount::new(Pubkey::find_program_address(&[program_id.as_ref()], &loader).0)
+        }
+    }
+
+    fn test_config(admin: &Pubkey) -> Config {
+        Config {
+            admin: *admin,
+            fee_bps: 100,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_initialize_config_requires_the_upgrade_authority() {
+        let program_id = Pubkey::new_unique();
+        let admin = Pubkey::new_unique();
+        let upgrade_authority = Pubkey::new_unique();
+        let mut program_data = program_data_account(&program_id, &upgrade_authority);
+        let mut config = TestAccount {
+            data: vec![0; CONFIG_SIZE],
+            ..TestAccount::new(get_config(&program_id).0)
+        };
+        let mut treasury = TestAccount::new(get_treasury(&program_id).0);
+        let mut stats = TestAccount {
+            data: vec![0xff; STATS_SIZE],
+            ..TestAccount::new(get_stats(&program_id).0)
+        };
+        let mut system_program = TestAccount::new(solana_program::system_program::id());
+        let mut initialize_config_as = |authority: &Pubkey, config_data| {
+            let mut authority = TestAccount::new(*authority);
+            authority.lamports = 100_000_000;
+
+            initialize_config(
+                &program_id,
+                &[
+                    authority.info(true),
+                    config.info(false),
+                    treasury.info(false),
+                    stats.info(false),
+                    program_data.info(false),
+                    system_program.info(false),
+                ],
+                InitializeConfig {
+                    config: config_data,
+                },
+            )
+        };
+
+        assert_eq!(
+            initialize_config_as(&admin, test_config(&admin)),
+            Err(SolLockError::NotUpgradeAuthority.into())
+        );
+        assert_eq!(
+            initialize_config_as(&upgrade_authority, test_config(&Pubkey::default())),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            initialize_config_as(&upgrade_authority, test_config(&admin)),
+            Ok(())
+        );
+        assert_eq!(Config::unpack(&config.data), Ok(test_config(&admin)));
+        assert_eq!(stats_of(&stats), Ok(Stats::default()));
+        assert!(treasury.lamports > 0);
+    }
+
+    #[test]
+    fn test_update_config_requires_the_admin() {
+        let program_id = Pubkey::new_unique();
+        let admin = Pubkey::new_unique();
+        let mut config = config_account(&program_id, test_config(&admin));
+        let mut update_config_as = |admin: &Pubkey, is_signer, config_data| {
+            update_config(
+                &program_id,
+                &[TestAccount::new(*admin).info(is_signer), config.info(false)],
+                UpdateConfig {
+                    config: config_data,
+                },
+            )
+        };
+        let paused = || Config {
+            paused: true,
+            ..test_config(&admin)
+        };
+
+        assert_eq!(
+            update_config_as(&Pubkey::new_unique(), true, paused()),
+            Err(SolLockError::PublicKeyMismatch.into())
+        );
+        assert_eq!(
+            update_config_as(&admin, false, paused()),
+            Err(ProgramError::MissingRequiredSignature)
+        );
+        assert_eq!(
+            update_config_as(
+                &admin,
+                true,
+                Config {
+                    fee_bps: TOTAL_SHARE_BPS + 1,
+                    ..test_config(&admin)
+                }
+            ),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(update_config_as(&admin, true, paused()), Ok(()));
+        assert_eq!(Config::unpack(&config.data), Ok(paused()));
+    }
+
+    /// Run CreateNextAccount for `owner`, creating the SolLock account at `acc_index`,
+    /// returning the result with the SolLock account
+    fn run_create_next_account(
+        program_id: &Pubkey,
+        owner: &mut TestAccount,
+        index_counter: &mut TestAccount,
+        acc_index: u64,
+    ) -> (ProgramResult, TestAccount) {
+        let mut sol_lock_account = TestAccount {
+            data: vec![0; Account::LEN],
+            ..TestAccount::new(get_sol_lock_account(program_id, &owner.key, acc_index).0)
+        };
+        let mut system_program = TestAccount::new(solana_program::system_program::id());
+        #[cfg(feature = "allowlist")]
+        let mut allowlist_entry = {
+            let mut data = vec![0; ALLOWLIST_ENTRY_SIZE];
+            AllowlistEntry { owner: owner.key }.pack_into_slice(&mut data);
+            TestAccount {
+                owner: *program_id,
+                data,
+                ..TestAccount::new(get_allowlist_entry(program_id, &owner.key).0)
+            }
+        };
+
+        let accounts = [
+            owner.info(true),
+            index_counter.info(false),
+            sol_lock_account.info(false),
+            system_program.info(false),
+            #[cfg(feature = "allowlist")]
+            allowlist_entry.info(false),
+        ];
+
+        let result = create_next_account(
+            program_id,
+            &accounts,
+            CreateNextAccount {
+                has_payer: false,
+                memo: String::new(),
+            },
+        );
+        drop(accounts);
+
+        (result, sol_lock_account)
+    }
+
+    #[test]
+    fn test_create_next_account_assigns_consecutive_indexes() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        owner.lamports = 100_000_000;
+        let mut index_counter = TestAccount {
+            data: vec![0; INDEX_COUNTER_SIZE],
+            ..TestAccount::new(get_index_counter(&program_id, &owner.key).0)
+        };
+        let index_counter_of = |index_counter: &TestAccount| {
+            IndexCounter::unpack(&index_counter.data).map(|counter| counter.next_index)
+        };
+
+        let (result, sol_lock_account) =
+            run_create_next_account(&program_id, &mut owner, &mut index_counter, 0);
+        assert_eq!(result, Ok(()));
+        assert_eq!(index_counter_of(&index_counter), Ok(1));
+        assert_eq!(
+            sol_lock_account
+                .account()
+                .map(|account| (account.owner, account.state)),
+            Ok((owner.key, State::Initialized))
+        );
+
+        // The system program hands the counter to the program when creating it
+        index_counter.owner = program_id;
+
+        let (result, _) = run_create_next_account(&program_id, &mut owner, &mut index_counter, 0);
+        assert_eq!(result, Err(SolLockError::PublicKeyMismatch.into()));
+        assert_eq!(index_counter_of(&index_counter), Ok(1));
+
+        let (result, sol_lock_account) =
+            run_create_next_account(&program_id, &mut owner, &mut index_counter, 1);
+        assert_eq!(result, Ok(()));
+        assert_eq!(index_counter_of(&index_counter), Ok(2));
+        assert_eq!(
+            sol_lock_account.account().map(|account| account.state),
+            Ok(State::Initialized)
+        );
+    }
+
+    #[test]
+    fn test_create_next_account_requires_the_counter_of_the_owner() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        owner.lamports = 100_000_000;
+        let mut index_counter = TestAccount {
+            data: vec![0; INDEX_COUNTER_SIZE],
+            ..TestAccount::new(get_index_counter(&program_id, &Pubkey::new_unique()).0)
+        };
+
+        let (result, sol_lock_account) =
+            run_create_next_account(&program_id, &mut owner, &mut index_counter, 0);
+        assert_eq!(result, Err(SolLockError::PublicKeyMismatch.into()));
+        assert!(sol_lock_account.data.iter().all(|byte| *byte == 0));
+        assert_eq!(owner.lamports, 100_000_000);
+    }
+
+    /// Run Migrate on the SolLock account of `owner` holding `data` and `lamports`, paid by
+    /// `payer`, returning the result with the data and lamports of the SolLock account
+    fn run_migrate(
+        program_id: &Pubkey,
+        owner: &Pubkey,
+        payer: &mut TestAccount,
+        data: &[u8],
+        lamports: u64,
+    ) -> (ProgramResult, Vec<u8>, u64) {
+        let key = get_sol_lock_account(program_id, owner, ACC_INDEX).0;
+        let mut lamports = lamports;
+        // AccountInfo::realloc stores the new length in the 8 bytes before the data, where the
+        // runtime serializes it, and grows the data in place
+        let mut buffer = vec![0; 8 + SOL_LOCK_ACCOUNT_SIZE];
+        buffer[8..8 + data.len()].copy_from_slice(data);
+        let sol_lock_account_info = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut buffer[8..8 + data.len()],
+            program_id,
+            false,
+            0,
+        );
+        let mut owner = TestAccount::new(*owner);
+        let mut system_program = TestAccount::new(solana_program::system_program::id());
+
+        let result = migrate(
+            program_id,
+            &[
+                payer.info(true),
+                owner.info(false),
+                sol_lock_account_info.clone(),
+                system_program.info(false),
+            ],
+            Migrate {
+                acc_index: ACC_INDEX,
+            },
+        );
+        let len = sol_lock_account_info.data_len();
+        drop(sol_lock_account_info);
+
+        (result, buffer[8..8 + len].to_vec(), lamports)
+    }
+
+    #[test]
+    fn test_migrate_upgrades_the_original_layout() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut payer = TestAccount::new(Pubkey::new_unique());
+        payer.lamports = 100_000_000;
+        let rent = Rent::default();
+        let mut data = vec![0; Account::LEN];
+        Account {
+            owner,
+            state: State::Locked,
+            lamports: Some(1_000_000),
+            deadline: Some(1_000),
+            ..Account::default()
+        }
+        .pack_into_slice(&mut data);
+        // Version 0 accounts end with the state, before the discriminator
+        let data = &data[..MIN_ACCOUNT_LEN];
+
+        let (result, data, lamports) = run_migrate(
+            &program_id,
+            &owner,
+            &mut payer,
+            data,
+            rent.minimum_balance(MIN_ACCOUNT_LEN) + 1_000_000,
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(data.len(), SOL_LOCK_ACCOUNT_SIZE);
+        assert_eq!(read_layout_version(&data), ACCOUNT_VERSION);
+        assert_eq!(
+            lamports,
+            rent.minimum_balance(SOL_LOCK_ACCOUNT_SIZE) + 1_000_000
+        );
+        assert_eq!(
+            payer.lamports,
+            100_000_000 - rent.minimum_balance(SOL_LOCK_ACCOUNT_SIZE)
+                + rent.minimum_balance(MIN_ACCOUNT_LEN)
+        );
+        assert_eq!(
+            Account::unpack(&data),
+            Ok(Account {
+                owner,
+                state: State::Locked,
+                lamports: Some(1_000_000),
+                deadline: Some(1_000),
+                rent_reserve: rent.minimum_balance(SOL_LOCK_ACCOUNT_SIZE),
+                bump_seed: get_sol_lock_account(&program_id, &owner, ACC_INDEX).1,
+                version: ACCOUNT_VERSION,
+                ..Account::default()
+            })
+        );
+
+        let (result, _, _) = run_migrate(&program_id, &owner, &mut payer, &data, lamports);
+        assert_eq!(result, Err(SolLockError::AccountAlreadyMigrated.into()));
+    }
+
+    #[test]
+    fn test_migrate_rejects_unknown_layouts() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut payer = TestAccount::new(Pubkey::new_unique());
+        payer.lamports = 100_000_000;
+        let mut data = vec![0; Account::LEN];
+        Account {
+            owner,
+            state: State::Locked,
+            ..Account::default()
+        }
+        .pack_into_slice(&mut data);
+
+        let (result, _, _) = run_migrate(
+            &program_id,
+            &owner,
+            &mut payer,
+            &data[..MIN_ACCOUNT_LEN - 1],
+            0,
+        );
+        assert_eq!(result, Err(SolLockError::UnpackError.into()));
+
+        data[VERSION_OFFSET] = ACCOUNT_VERSION + 1;
+        let (result, _, _) = run_migrate(&program_id, &owner, &mut payer, &data, 0);
+        assert_eq!(result, Err(SolLockError::UnpackError.into()));
+        assert_eq!(payer.lamports, 100_000_000);
+    }
+
+    /// An account of `owner` locking 1_000_000 lamports until 1_000
+    fn locked_account(owner: &Pubkey) -> Account {
+        Account {
+            owner: *owner,
+            state: State::Locked,
+            lamports: Some(1_000_000),
+            deadline: Some(1_000),
+            ..Account::default()
+        }
+    }
+
+    fn mint_receipt_of(
+        program_id: &Pubkey,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+        destination: &Pubkey,
+    ) -> ProgramResult {
+        let mut receipt_mint = TestAccount::new(get_receipt_mint(program_id).0);
+        let mut destination = TestAccount::new(*destination);
+        let mut token_program = TestAccount::new(TOKEN_PROGRAM_ID);
+
+        mint_receipt(
+            program_id,
+            &[
+                owner.info(true),
+                sol_lock_account.info(false),
+                receipt_mint.info(false),
+                destination.info(false),
+                token_program.info(false),
+            ],
+            MintReceipt {
+                acc_index: ACC_INDEX,
+            },
+        )
+    }
+
+    fn burn_receipt_of(
+        program_id: &Pubkey,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+        source: &Pubkey,
+    ) -> ProgramResult {
+        let mut receipt_mint = TestAccount::new(get_receipt_mint(program_id).0);
+        let mut source = TestAccount::new(*source);
+        let mut token_program = TestAccount::new(TOKEN_PROGRAM_ID);
+
+        burn_receipt(
+            program_id,
+            &[
+                owner.info(true),
+                sol_lock_account.info(false),
+                receipt_mint.info(false),
+                source.info(false),
+                token_program.info(false),
+            ],
+            BurnReceipt {
+                acc_index: ACC_INDEX,
+            },
+        )
+    }
+
+    #[test]
+    fn test_receipt_tokens_are_minted_and_burned_one_to_one() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let token_account = Pubkey::new_unique();
+        let receipt_mint = get_receipt_mint(&program_id).0;
+        let mut sol_lock_account =
+            TestAccount::sol_lock_account(&program_id, locked_account(&owner.key));
+
+        assert_eq!(
+            burn_receipt_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &token_account
+            ),
+            Err(SolLockError::NoReceiptMinted.into())
+        );
+        assert_eq!(
+            mint_receipt_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &token_account
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            take_invoked(),
+            vec![token::mint_to(
+                &receipt_mint,
+                &token_account,
+                &receipt_mint,
+                1_000_000
+            )]
+        );
+        assert_eq!(
+            sol_lock_account
+                .account()
+                .map(|account| account.receipt_minted),
+            Ok(1_000_000)
+        );
+        assert_eq!(
+            mint_receipt_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &token_account
+            ),
+            Err(SolLockError::ReceiptAlreadyMinted.into())
+        );
+
+        // The lamports can't leave the account while the receipt tokens are outstanding
+        set_now(1_000);
+        assert_eq!(
+            unlock_of(&program_id, &mut owner, &mut sol_lock_account),
+            Err(SolLockError::ReceiptNotBurned.into())
+        );
+
+        assert_eq!(
+            burn_receipt_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &token_account
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            take_invoked(),
+            vec![token::burn(
+                &token_account,
+                &receipt_mint,
+                &owner.key,
+                1_000_000
+            )]
+        );
+        assert_eq!(
+            sol_lock_account
+                .account()
+                .map(|account| account.receipt_minted),
+            Ok(0)
+        );
+        assert_eq!(
+            unlock_of(&program_id, &mut owner, &mut sol_lock_account),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_mint_receipt_requires_a_locked_account() {
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                state: State::HasFunds,
+                ..locked_account(&owner.key)
+            },
+        );
+
+        assert_eq!(
+            mint_receipt_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &Pubkey::new_unique()
+            ),
+            Err(SolLockError::AccountNotLocked.into())
+        );
+        assert_eq!(take_invoked(), vec![]);
+    }
+
+    /// The rent-exempt reserve of the stake accounts in tests
+    const STAKE_RENT_EXEMPT_RESERVE: u64 = 2_282_880;
+
+    /// A stake account holding `lamports`, `delegated` of which are delegated
+    fn delegated_stake_account(lamports: u64, delegated: u64) -> TestAccount {
+        let stake_state = StakeState::Stake(
+            solana_program::stake::state::Meta {
+                rent_exempt_reserve: STAKE_RENT_EXEMPT_RESERVE,
+                ..Default::default()
+            },
+            solana_program::stake::state::Stake {
+                delegation: solana_program::stake::state::Delegation {
+                    stake: delegated,
+                    ..Default::default()
+                },
+                credits_observed: 0,
+            },
+        );
+
+        TestAccount {
+            owner: solana_program::stake::program::id(),
+            lamports,
+            data: stake_state.try_to_vec().unwrap_or_default(),
+            ..TestAccount::new(Pubkey::new_unique())
+        }
+    }
+
+    /// An account of `owner` staking 1_000_000_000 lamports from `stake_account`, which tips
+    /// crankers 5_000 lamports
+    fn staked_account(owner: &Pubkey, stake_account: &Pubkey) -> Account {
+        Account {
+            state: State::Staked,
+            lamports: Some(1_000_000_000),
+            stake_account: Some(*stake_account),
+            crank_tip: Some(5_000),
+            ..locked_account(owner)
+        }
+    }
+
+    fn compound_of(
+        program_id: &Pubkey,
+        cranker: &mut TestAccount,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+        stake_account: &mut TestAccount,
+    ) -> ProgramResult {
+        let mut clock = TestAccount::new(solana_program::sysvar::clock::id());
+        let mut stake_history = TestAccount::new(solana_program::sysvar::stake_history::id());
+        let mut stake_program = TestAccount::new(solana_program::stake::program::id());
+        let mut stats = TestAccount::new(get_stats(program_id).0);
+
+        compound(
+            program_id,
+            &[
+                cranker.info(true),
+                owner.info(false),
+                sol_lock_account.info(false),
+                stake_account.info(false),
+                clock.info(false),
+                stake_history.info(false),
+                stake_program.info(false),
+                stats.info(false),
+            ],
+            Compound {
+                acc_index: ACC_INDEX,
+            },
+        )
+    }
+
+    #[test]
+    fn test_compound_adds_the_rewards_to_the_principal_and_tips_the_cranker() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut cranker = TestAccount::new(Pubkey::new_unique());
+        // 50_000 lamports of rewards were delegated, and 3_000 more were sent to the account
+        let mut stake_account =
+            delegated_stake_account(1_000_053_000, 1_000_050_000 - STAKE_RENT_EXEMPT_RESERVE);
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            staked_account(&owner.key, &stake_account.key),
+        );
+        let sol_lock_key = sol_lock_account.key;
+
+        assert_eq!(
+            compound_of(
+                &program_id,
+                &mut cranker,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut stake_account
+            ),
+            Ok(())
+        );
+
+        // The tip is capped at the rewards the stake program can release
+        assert_eq!(
+            take_invoked(),
+            vec![stake_instruction::withdraw(
+                &stake_account.key,
+                &sol_lock_key,
+                &cranker.key,
+                3_000,
+                None
+            )]
+        );
+        assert_eq!(
+            sol_lock_account
+                .account()
+                .map(|account| (account.lamports, account.lifetime_locked)),
+            Ok((Some(1_000_050_000), 50_000))
+        );
+
+        // Once the stake program paid the tip, there is nothing left to compound
+        stake_account.lamports -= 3_000;
+        assert_eq!(
+            compound_of(
+                &program_id,
+                &mut cranker,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut stake_account
+            ),
+            Err(SolLockError::NoRewardsToCompound.into())
+        );
+    }
+
+    #[test]
+    fn test_compound_requires_a_crank_tip_and_the_stored_stake_account() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut cranker = TestAccount::new(Pubkey::new_unique());
+        let mut stake_account =
+            delegated_stake_account(1_000_050_000, 1_000_050_000 - STAKE_RENT_EXEMPT_RESERVE);
+        let mut other_stake_account =
+            delegated_stake_account(1_000_050_000, 1_000_050_000 - STAKE_RENT_EXEMPT_RESERVE);
+
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                crank_tip: None,
+                ..staked_account(&owner.key, &stake_account.key)
+            },
+        );
+        assert_eq!(
+            compound_of(
+                &program_id,
+                &mut cranker,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut stake_account
+            ),
+            Err(SolLockError::CrankingDisabled.into())
+        );
+
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            staked_account(&owner.key, &stake_account.key),
+        );
+        assert_eq!(
+            compound_of(
+                &program_id,
+                &mut cranker,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut other_stake_account
+            ),
+            Err(SolLockError::PublicKeyMismatch.into())
+        );
+
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                state: State::Locked,
+                ..staked_account(&owner.key, &stake_account.key)
+            },
+        );
+        assert_eq!(
+            compound_of(
+                &program_id,
+                &mut cranker,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut stake_account
+            ),
+            Err(SolLockError::InvalidStateForUnstake.into())
+        );
+        assert_eq!(take_invoked(), vec![]);
+    }
+
+    /// A token account holding `amount` tokens of `mint` for `owner`
+    fn token_account(key: &Pubkey, mint: &Pubkey, owner: &Pubkey, amount: u64) -> TestAccount {
+        let mut data = vec![0; 165];
+        data[..32].copy_from_slice(mint.as_ref());
+        data[32..64].copy_from_slice(owner.as_ref());
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+
+        TestAccount {
+            owner: TOKEN_PROGRAM_ID,
+            data,
+            ..TestAccount::new(*key)
+        }
+    }
+
+    /// The stake pool accounts of the LST tests
+    struct TestStakePool {
+        stake_pool: TestAccount,
+        withdraw_authority: TestAccount,
+        reserve_stake: TestAccount,
+        manager_fee_account: TestAccount,
+        pool_mint: TestAccount,
+        stake_pool_program: TestAccount,
+        token_program: TestAccount,
+    }
+
+    impl TestStakePool {
+        fn new() -> Self {
+            Self {
+                stake_pool: TestAccount {
+                    owner: STAKE_POOL_PROGRAM_ID,
+                    ..TestAccount::new(Pubkey::new_unique())
+                },
+                withdraw_authority: TestAccount::new(Pubkey::new_unique()),
+                reserve_stake: TestAccount::new(Pubkey::new_unique()),
+                manager_fee_account: TestAccount::new(Pubkey::new_unique()),
+                pool_mint: TestAccount::new(Pubkey::new_unique()),
+                stake_pool_program: TestAccount::new(STAKE_POOL_PROGRAM_ID),
+                token_program: TestAccount::new(TOKEN_PROGRAM_ID),
+            }
+        }
+    }
+
+    fn convert_to_lst_of(
+        program_id: &Pubkey,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+        funding_account: &mut TestAccount,
+        pool: &mut TestStakePool,
+        pool_token_account: &mut TestAccount,
+    ) -> ProgramResult {
+        let mut associated_token_program = TestAccount::new(ASSOCIATED_TOKEN_PROGRAM_ID);
+        let mut system_program = TestAccount::new(solana_program::system_program::id());
+
+        convert_to_lst(
+            program_id,
+            &[
+                owner.info(true),
+                sol_lock_account.info(false),
+                funding_account.info(false),
+                pool.stake_pool.info(false),
+                pool.withdraw_authority.info(false),
+                pool.reserve_stake.info(false),
+                pool.manager_fee_account.info(false),
+                pool.pool_mint.info(false),
+                pool_token_account.info(false),
+                pool.stake_pool_program.info(false),
+                pool.token_program.info(false),
+                associated_token_program.info(false),
+                system_program.info(false),
+            ],
+            ConvertToLst {
+                acc_index: ACC_INDEX,
+            },
+        )
+    }
+
+    fn redeem_lst_of(
+        program_id: &Pubkey,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+        pool: &mut TestStakePool,
+        pool_token_account: &mut TestAccount,
+    ) -> ProgramResult {
+        let mut clock = TestAccount::new(solana_program::sysvar::clock::id());
+        let mut stake_history = TestAccount::new(solana_program::sysvar::stake_history::id());
+        let mut stake_program = TestAccount::new(solana_program::stake::program::id());
+
+        redeem_lst(
+            program_id,
+            &[
+                owner.info(true),
+                sol_lock_account.info(false),
+                pool.stake_pool.info(false),
+                pool.withdraw_authority.info(false),
+                pool.reserve_stake.info(false),
+                pool.manager_fee_account.info(false),
+                pool.pool_mint.info(false),
+                pool_token_account.info(false),
+                clock.info(false),
+                stake_history.info(false),
+                stake_program.info(false),
+                pool.stake_pool_program.info(false),
+                pool.token_program.info(false),
+            ],
+            RedeemLst {
+                acc_index: ACC_INDEX,
+            },
+        )
+    }
+
+    #[test]
+    fn test_convert_to_lst_deposits_the_locked_lamports() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account =
+            TestAccount::sol_lock_account(&program_id, locked_account(&owner.key));
+        let mut funding_account =
+            TestAccount::new(get_lst_funding_account(&program_id, &sol_lock_account.key).0);
+        let mut pool = TestStakePool::new();
+        let mut pool_token_account = TestAccount::new(get_associated_token_address(
+            &sol_lock_account.key,
+            &pool.pool_mint.key,
+        ));
+
+        assert_eq!(
+            convert_to_lst_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut funding_account,
+                &mut pool,
+                &mut pool_token_account
+            ),
+            Ok(())
+        );
+
+        // The pool token account is created, then the lamports are deposited from the
+        // funding account
+        assert_eq!(
+            take_invoked()
+                .iter()
+                .map(|instruction| instruction.program_id)
+                .collect::<Vec<_>>(),
+            vec![ASSOCIATED_TOKEN_PROGRAM_ID, STAKE_POOL_PROGRAM_ID]
+        );
+        assert_eq!(funding_account.lamports, 1_000_000);
+        assert_eq!(
+            sol_lock_account.lamports,
+            Rent::default().minimum_balance(Account::LEN)
+        );
+        let account = sol_lock_account.account();
+        assert_eq!(
+            account.as_ref().map(|account| (
+                account.lamports,
+                account.lst_pool,
+                account.lst_deposited,
+                account.yield_backend,
+                account.held_lamports()
+            )),
+            Ok((
+                Some(1_000_000),
+                Some(pool.stake_pool.key),
+                1_000_000,
+                YieldBackend::StakePool,
+                0
+            ))
+        );
+
+        assert_eq!(
+            convert_to_lst_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut funding_account,
+                &mut pool,
+                &mut pool_token_account
+            ),
+            Err(SolLockError::LstNotRedeemed.into())
+        );
+        set_now(1_000);
+        assert_eq!(
+            unlock_of(&program_id, &mut owner, &mut sol_lock_account),
+            Err(SolLockError::LstNotRedeemed.into())
+        );
+    }
+
+    #[test]
+    fn test_redeem_lst_locks_the_redeemed_lamports_and_later_deposits() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut pool = TestStakePool::new();
+        // 200_000 lamports were added to the account after it converted 1_000_000
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                lamports: Some(1_200_000),
+                lst_pool: Some(pool.stake_pool.key),
+                lst_deposited: 1_000_000,
+                yield_backend: YieldBackend::StakePool,
+                ..locked_account(&owner.key)
+            },
+        );
+        let mut pool_token_account = token_account(
+            &get_associated_token_address(&sol_lock_account.key, &pool.pool_mint.key),
+            &pool.pool_mint.key,
+            &sol_lock_account.key,
+            990_000,
+        );
+        let mut other_pool = TestStakePool::new();
+
+        assert_eq!(
+            redeem_lst_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut other_pool,
+                &mut pool_token_account
+            ),
+            Err(SolLockError::PublicKeyMismatch.into())
+        );
+
+        // The pool pays 1_050_000 lamports for the pool tokens into the SolLock account
+        set_invoke_effect(|instruction, account_infos| {
+            let destination = account_infos
+                .iter()
+                .find(|info| *info.key == instruction.accounts[2].pubkey)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            **destination.lamports.borrow_mut() += 1_050_000;
+            Ok(())
+        });
+        assert_eq!(
+            redeem_lst_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut pool,
+                &mut pool_token_account
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            take_invoked()
+                .iter()
+                .map(|instruction| instruction.program_id)
+                .collect::<Vec<_>>(),
+            vec![STAKE_POOL_PROGRAM_ID]
+        );
+        assert_eq!(
+            sol_lock_account.account().map(|account| (
+                account.lamports,
+                account.lst_pool,
+                account.lst_deposited,
+                account.yield_backend
+            )),
+            Ok((Some(1_250_000), None, 0, YieldBackend::None))
+        );
+
+        assert_eq!(
+            redeem_lst_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut pool,
+                &mut pool_token_account
+            ),
+            Err(SolLockError::NotConvertedToLst.into())
+        );
+    }
+
+    /// The lending reserve accounts of the lending tests
+    struct TestLendingReserve {
+        reserve: TestAccount,
+        reserve_liquidity_supply: TestAccount,
+        reserve_collateral_mint: TestAccount,
+        lending_market: TestAccount,
+        lending_market_authority: TestAccount,
+        native_mint: TestAccount,
+        lending_program: TestAccount,
+        token_program: TestAccount,
+    }
+
+    impl TestLendingReserve {
+        fn new() -> Self {
+            let reserve_liquidity_supply = TestAccount::new(Pubkey::new_unique());
+            let reserve_collateral_mint = TestAccount::new(Pubkey::new_unique());
+            let lending_market = TestAccount::new(Pubkey::new_unique());
+
+            // The fields of the reserve layout that the program reads
+            let mut data = vec![0; 619];
+            data[10..42].copy_from_slice(lending_market.key.as_ref());
+            data[42..74].copy_from_slice(NATIVE_MINT.as_ref());
+            data[75..107].copy_from_slice(reserve_liquidity_supply.key.as_ref());
+            data[227..259].copy_from_slice(reserve_collateral_mint.key.as_ref());
+
+            Self {
+                reserve: TestAccount {
+                    owner: LENDING_PROGRAM_ID,
+                    data,
+                    ..TestAccount::new(Pubkey::new_unique())
+                },
+                reserve_liquidity_supply,
+                reserve_collateral_mint,
+                lending_market,
+                lending_market_authority: TestAccount::new(Pubkey::new_unique()),
+                native_mint: TestAccount::new(NATIVE_MINT),
+                lending_program: TestAccount::new(LENDING_PROGRAM_ID),
+                token_program: TestAccount::new(TOKEN_PROGRAM_ID),
+            }
+        }
+    }
+
+    /// Deposit 9 collateral tokens for every 10 lamports, redeem 12 lamports for every 10
+    /// collateral tokens, and unwrap closed wrapped SOL accounts
+    fn lending_effect(instruction: &Instruction, account_infos: &[AccountInfo]) -> ProgramResult {
+        let info = |index: usize| {
+            account_infos
+                .iter()
+                .find(|info| *info.key == instruction.accounts[index].pubkey)
+                .ok_or(ProgramError::NotEnoughAccountKeys)
+        };
+        let set_amount = |info: &AccountInfo, amount: u64| {
+            info.data.borrow_mut()[64..72].copy_from_slice(&amount.to_le_bytes());
+        };
+        let amount = || u64::from_le_bytes(*array_ref![instruction.data, 1, 8]);
+
+        match (instruction.program_id, instruction.data.first()) {
+            // DepositReserveLiquidity
+            (LENDING_PROGRAM_ID, Some(4)) => {
+                **info(0)?.lamports.borrow_mut() -= amount();
+                set_amount(info(1)?, amount() / 10 * 9);
+            }
+            // RedeemReserveCollateral
+            (LENDING_PROGRAM_ID, Some(5)) => {
+                **info(1)?.lamports.borrow_mut() += amount() / 10 * 12;
+                set_amount(info(1)?, amount() / 10 * 12);
+            }
+            // CloseAccount
+            (TOKEN_PROGRAM_ID, Some(9)) => {
+                let lamports = info(0)?.lamports();
+                **info(0)?.lamports.borrow_mut() = 0;
+                **info(1)?.lamports.borrow_mut() += lamports;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn deposit_to_lending_of(
+        program_id: &Pubkey,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+        reserve: &mut TestLendingReserve,
+        liquidity_account: &mut TestAccount,
+        collateral_account: &mut TestAccount,
+    ) -> ProgramResult {
+        let mut clock = TestAccount::new(solana_program::sysvar::clock::id());
+        let mut associated_token_program = TestAccount::new(ASSOCIATED_TOKEN_PROGRAM_ID);
+        let mut system_program = TestAccount::new(solana_program::system_program::id());
+
+        deposit_to_lending(
+            program_id,
+            &[
+                owner.info(true),
+                sol_lock_account.info(false),
+                reserve.reserve.info(false),
+                reserve.reserve_liquidity_supply.info(false),
+                reserve.reserve_collateral_mint.info(false),
+                reserve.lending_market.info(false),
+                reserve.lending_market_authority.info(false),
+                liquidity_account.info(false),
+                collateral_account.info(false),
+                reserve.native_mint.info(false),
+                clock.info(false),
+                reserve.lending_program.info(false),
+                reserve.token_program.info(false),
+                associated_token_program.info(false),
+                system_program.info(false),
+            ],
+            DepositToLending {
+                acc_index: ACC_INDEX,
+            },
+        )
+    }
+
+    fn withdraw_from_lending_of(
+        program_id: &Pubkey,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+        reserve: &mut TestLendingReserve,
+        liquidity_account: &mut TestAccount,
+        collateral_account: &mut TestAccount,
+    ) -> ProgramResult {
+        let mut clock = TestAccount::new(solana_program::sysvar::clock::id());
+        let mut associated_token_program = TestAccount::new(ASSOCIATED_TOKEN_PROGRAM_ID);
+        let mut system_program = TestAccount::new(solana_program::system_program::id());
+
+        withdraw_from_lending(
+            program_id,
+            &[
+                owner.info(true),
+                sol_lock_account.info(false),
+                reserve.reserve.info(false),
+                reserve.reserve_liquidity_supply.info(false),
+                reserve.reserve_collateral_mint.info(false),
+                reserve.lending_market.info(false),
+                reserve.lending_market_authority.info(false),
+                liquidity_account.info(false),
+                collateral_account.info(false),
+                reserve.native_mint.info(false),
+                clock.info(false),
+                reserve.lending_program.info(false),
+                reserve.token_program.info(false),
+                associated_token_program.info(false),
+                system_program.info(false),
+            ],
+            WithdrawFromLending {
+                acc_index: ACC_INDEX,
+            },
+        )
+    }
+
+    #[test]
+    fn test_deposit_to_lending_lends_the_locked_lamports_out() {
+        set_now(500);
+        set_invoke_effect(lending_effect);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut sol_lock_account =
+            TestAccount::sol_lock_account(&program_id, locked_account(&owner.key));
+        let mut reserve = TestLendingReserve::new();
+        // The wrapped SOL account is created by the deposit, the collateral account exists
+        let mut liquidity_account = TestAccount::new(get_associated_token_address(
+            &sol_lock_account.key,
+            &NATIVE_MINT,
+        ));
+        let mut collateral_account = token_account(
+            &get_associated_token_address(
+                &sol_lock_account.key,
+                &reserve.reserve_collateral_mint.key,
+            ),
+            &reserve.reserve_collateral_mint.key,
+            &sol_lock_account.key,
+            0,
+        );
+
+        assert_eq!(
+            deposit_to_lending_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut reserve,
+                &mut liquidity_account,
+                &mut collateral_account
+            ),
+            Ok(())
+        );
+
+        // The lamports are wrapped, lent out, then the empty wrapped SOL account is closed
+        assert_eq!(
+            take_invoked()
+                .iter()
+                .map(|instruction| instruction.program_id)
+                .collect::<Vec<_>>(),
+            vec![
+                ASSOCIATED_TOKEN_PROGRAM_ID,
+                TOKEN_PROGRAM_ID,
+                LENDING_PROGRAM_ID,
+                TOKEN_PROGRAM_ID
+            ]
+        );
+        assert_eq!(
+            sol_lock_account.lamports,
+            Rent::default().minimum_balance(Account::LEN)
+        );
+        let account = sol_lock_account.account();
+        assert_eq!(
+            account.as_ref().map(|account| (
+                account.lamports,
+                account.lending_reserve,
+                account.lending_deposited,
+                account.lending_collateral,
+                account.yield_backend,
+                account.held_lamports()
+            )),
+            Ok((
+                Some(1_000_000),
+                Some(reserve.reserve.key),
+                1_000_000,
+                900_000,
+                YieldBackend::Lending,
+                0
+            ))
+        );
+
+        assert_eq!(
+            deposit_to_lending_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut reserve,
+                &mut liquidity_account,
+                &mut collateral_account
+            ),
+            Err(SolLockError::LendingNotWithdrawn.into())
+        );
+        set_now(1_000);
+        assert_eq!(
+            unlock_of(&program_id, &mut owner, &mut sol_lock_account),
+            Err(SolLockError::LendingNotWithdrawn.into())
+        );
+    }
+
+    #[test]
+    fn test_withdraw_from_lending_locks_the_redeemed_lamports() {
+        set_now(500);
+        set_invoke_effect(lending_effect);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut reserve = TestLendingReserve::new();
+        // 200_000 lamports were added to the account after it lent 1_000_000 out
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                lamports: Some(1_200_000),
+                lending_reserve: Some(reserve.reserve.key),
+                lending_deposited: 1_000_000,
+                lending_collateral: 900_000,
+                yield_backend: YieldBackend::Lending,
+                ..locked_account(&owner.key)
+            },
+        );
+        let liquidity_rent = Rent::default().minimum_balance(165);
+        let mut liquidity_account = TestAccount {
+            lamports: liquidity_rent,
+            ..token_account(
+                &get_associated_token_address(&sol_lock_account.key, &NATIVE_MINT),
+                &NATIVE_MINT,
+                &sol_lock_account.key,
+                0,
+            )
+        };
+        let collateral_key = get_associated_token_address(
+            &sol_lock_account.key,
+            &reserve.reserve_collateral_mint.key,
+        );
+        let collateral_mint = reserve.reserve_collateral_mint.key;
+        let mut collateral_account = token_account(
+            &collateral_key,
+            &collateral_mint,
+            &sol_lock_account.key,
+            900_000,
+        );
+        let mut other_reserve = TestLendingReserve::new();
+
+        assert_eq!(
+            withdraw_from_lending_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut other_reserve,
+                &mut liquidity_account,
+                &mut collateral_account
+            ),
+            Err(SolLockError::PublicKeyMismatch.into())
+        );
+
+        // Collateral that was moved away or delegated no longer backs the deposit
+        let mut spent_collateral_account = token_account(
+            &collateral_key,
+            &collateral_mint,
+            &sol_lock_account.key,
+            899_999,
+        );
+        assert_eq!(
+            withdraw_from_lending_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut reserve,
+                &mut liquidity_account,
+                &mut spent_collateral_account
+            ),
+            Err(SolLockError::LendingCollateralNotHeld.into())
+        );
+        let mut delegated_collateral_account = token_account(
+            &collateral_key,
+            &collateral_mint,
+            &sol_lock_account.key,
+            900_000,
+        );
+        delegated_collateral_account.data[72..76].copy_from_slice(&1u32.to_le_bytes());
+        assert_eq!(
+            withdraw_from_lending_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut reserve,
+                &mut liquidity_account,
+                &mut delegated_collateral_account
+            ),
+            Err(SolLockError::LendingCollateralNotHeld.into())
+        );
+        assert_eq!(take_invoked(), vec![]);
+
+        // The reserve pays 1_080_000 lamports for the collateral tokens
+        assert_eq!(
+            withdraw_from_lending_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut reserve,
+                &mut liquidity_account,
+                &mut collateral_account
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            take_invoked()
+                .iter()
+                .map(|instruction| instruction.program_id)
+                .collect::<Vec<_>>(),
+            vec![LENDING_PROGRAM_ID, TOKEN_PROGRAM_ID]
+        );
+        // The rent of the wrapped SOL account is refunded to the owner
+        assert_eq!(owner.lamports, liquidity_rent);
+        assert_eq!(
+            sol_lock_account.lamports,
+            Rent::default().minimum_balance(Account::LEN) + 1_280_000
+        );
+        assert_eq!(
+            sol_lock_account.account().map(|account| (
+                account.lamports,
+                account.lending_reserve,
+                account.lending_deposited,
+                account.lending_collateral,
+                account.yield_backend
+            )),
+            Ok((Some(1_280_000), None, 0, 0, YieldBackend::None))
+        );
+
+        assert_eq!(
+            withdraw_from_lending_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut reserve,
+                &mut liquidity_account,
+                &mut collateral_account
+            ),
+            Err(SolLockError::NotDepositedInLending.into())
+        );
+    }
+
+    #[test]
+    fn test_remove_sol_keeps_the_rent_reserve() {
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let rent_reserve = Rent::default().minimum_balance(Account::LEN);
+        // The account was reallocated, so its reserve grew by 1_000 lamports which the
+        // tracked lamports still count
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                owner: owner.key,
+                state: State::HasFunds,
+                lamports: Some(1_000_000),
+                rent_reserve: rent_reserve + 1_000,
+                ..Account::default()
+            },
+        );
+
+        assert_eq!(
+            remove_sol_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                1_000_000,
+                false
+            ),
+            Err(SolLockError::RentReserveNotKept.into())
+        );
+
+        assert_eq!(
+            remove_sol_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                999_000,
+                false
+            ),
+            Ok(())
+        );
+        assert_eq!(owner.lamports, 999_000);
+        assert_eq!(sol_lock_account.lamports, rent_reserve + 1_000);
+        assert_eq!(
+            sol_lock_account.account().map(|account| account.lamports),
+            Ok(Some(1_000))
+        );
+    }
+
+    #[test]
+    fn test_unlock_keeps_the_rent_reserve() {
+        set_now(1_000);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let rent_reserve = Rent::default().minimum_balance(Account::LEN);
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                rent_reserve: rent_reserve + 1,
+                ..locked_account(&owner.key)
+            },
+        );
+
+        assert_eq!(
+            unlock_of(&program_id, &mut owner, &mut sol_lock_account),
+            Err(SolLockError::RentReserveNotKept.into())
+        );
+        assert_eq!(sol_lock_account.lamports, rent_reserve + 1_000_000);
+
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                rent_reserve,
+                ..locked_account(&owner.key)
+            },
+        );
+        assert_eq!(
+            unlock_of(&program_id, &mut owner, &mut sol_lock_account),
+            Ok(())
+        );
+        assert_eq!(owner.lamports, 1_000_000);
+        assert_eq!(sol_lock_account.lamports, rent_reserve);
+    }
+
+    fn reconcile_of(
+        program_id: &Pubkey,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+        stats: &mut TestAccount,
+        action: SurplusAction,
+    ) -> ProgramResult {
+        let mut config = TestAccount::new(get_config(program_id).0);
+
+        reconcile(
+            program_id,
+            &[
+                owner.info(true),
+                sol_lock_account.info(false),
+                config.info(false),
+                stats.info(false),
+            ],
+            Reconcile {
+                acc_index: ACC_INDEX,
+                action,
+            },
+        )
+    }
+
+    #[test]
+    fn test_reconcile_sweeps_untracked_lamports_to_the_owner() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut stats = TestAccount::new(get_stats(&program_id).0);
+        let rent_reserve = Rent::default().minimum_balance(Account::LEN);
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                rent_reserve,
+                ..locked_account(&owner.key)
+            },
+        );
+        // Transferred to the account directly, without AddSol
+        sol_lock_account.lamports += 5_000;
+
+        assert_eq!(
+            reconcile_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut stats,
+                SurplusAction::Log
+            ),
+            Ok(())
+        );
+        assert_eq!(sol_lock_account.lamports, rent_reserve + 1_005_000);
+
+        assert_eq!(
+            reconcile_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut stats,
+                SurplusAction::Sweep
+            ),
+            Ok(())
+        );
+        assert_eq!(owner.lamports, 5_000);
+        assert_eq!(sol_lock_account.lamports, rent_reserve + 1_000_000);
+        assert_eq!(
+            sol_lock_account.account().map(|account| account.lamports),
+            Ok(Some(1_000_000))
+        );
+
+        // Nothing is left to sweep
+        assert_eq!(
+            reconcile_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut stats,
+                SurplusAction::Sweep
+            ),
+            Ok(())
+        );
+        assert_eq!(owner.lamports, 5_000);
+    }
+
+    #[test]
+    fn test_reconcile_absorbs_untracked_lamports_into_the_lock() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut stats = stats_account(
+            &program_id,
+            Stats {
+                total_locked: 1_000_000,
+                active_locks: 1,
+                total_volume: 1_000_000,
+            },
+        );
+        let rent_reserve = Rent::default().minimum_balance(Account::LEN);
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                rent_reserve,
+                ..locked_account(&owner.key)
+            },
+        );
+        sol_lock_account.lamports += 5_000;
+
+        assert_eq!(
+            reconcile_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut stats,
+                SurplusAction::Absorb
+            ),
+            Ok(())
+        );
+        assert_eq!(owner.lamports, 0);
+        assert_eq!(sol_lock_account.lamports, rent_reserve + 1_005_000);
+        assert_eq!(
+            sol_lock_account.account().map(|account| account.lamports),
+            Ok(Some(1_005_000))
+        );
+        assert_eq!(
+            stats_of(&stats),
+            Ok(Stats {
+                total_locked: 1_005_000,
+                active_locks: 1,
+                total_volume: 1_005_000,
+            })
+        );
+
+        // Lamports lent out are not in the balance, so a surplus cannot be told apart from
+        // yield
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                rent_reserve,
+                lending_reserve: Some(Pubkey::new_unique()),
+                lending_deposited: 1_000_000,
+                yield_backend: YieldBackend::Lending,
+                ..locked_account(&owner.key)
+            },
+        );
+        sol_lock_account.lamports += 5_000;
+        assert_eq!(
+            reconcile_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut stats,
+                SurplusAction::Absorb
+            ),
+            Err(SolLockError::YieldBackendActive.into())
+        );
+    }
+
+    #[test]
+    fn test_reconcile_leaves_a_short_account_unchanged() {
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut stats = TestAccount::new(get_stats(&program_id).0);
+        let rent_reserve = Rent::default().minimum_balance(Account::LEN);
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                rent_reserve,
+                ..locked_account(&owner.key)
+            },
+        );
+        sol_lock_account.lamports -= 5_000;
+
+        assert_eq!(
+            reconcile_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                &mut stats,
+                SurplusAction::Sweep
+            ),
+            Ok(())
+        );
+        assert_eq!(owner.lamports, 0);
+        assert_eq!(sol_lock_account.lamports, rent_reserve + 995_000);
+        assert_eq!(
+            sol_lock_account.account().map(|account| account.lamports),
+            Ok(Some(1_000_000))
+        );
+    }
+
+    #[test]
+    fn test_remove_sol_keeps_the_account_rent_exempt() {
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        // An account whose rent reserve is not recorded, and whose balance fell a lamport
+        // short of it
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                owner: owner.key,
+                state: State::HasFunds,
+                lamports: Some(1_000_000),
+                ..Account::default()
+            },
+        );
+        sol_lock_account.lamports -= 1;
+
+        assert_eq!(
+            remove_sol_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                1_000_000,
+                false
+            ),
+            Err(SolLockError::NotRentExempt.into())
+        );
+
+        assert_eq!(
+            remove_sol_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                999_999,
+                false
+            ),
+            Ok(())
+        );
+        assert_eq!(owner.lamports, 999_999);
+        assert_eq!(
+            sol_lock_account.lamports,
+            Rent::default().minimum_balance(Account::LEN)
+        );
+    }
+
+    #[test]
+    fn test_remove_sol_close_withdraws_everything_and_closes_the_account() {
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let owner_key = owner.key;
+        let rent_reserve = Rent::default().minimum_balance(Account::LEN);
+        let has_funds = || Account {
+            owner: owner_key,
+            state: State::HasFunds,
+            lamports: Some(1_000_000),
+            rent_reserve,
+            ..Account::default()
+        };
+        let mut sol_lock_account = TestAccount::sol_lock_account(&program_id, has_funds());
+
+        assert_eq!(
+            remove_sol_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                999_999,
+                true
+            ),
+            Err(SolLockError::CloseRequiresFullWithdrawal.into())
+        );
+
+        let mut receipt_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                receipt_minted: 1_000_000,
+                ..has_funds()
+            },
+        );
+        assert_eq!(
+            remove_sol_of(
+                &program_id,
+                &mut owner,
+                &mut receipt_account,
+                1_000_000,
+                true
+            ),
+            Err(SolLockError::ReceiptNotBurned.into())
+        );
+
+        // The rent reserve goes to the owner along with the lamports
+        assert_eq!(
+            remove_sol_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                1_000_000,
+                true
+            ),
+            Ok(())
+        );
+        assert_eq!(owner.lamports, rent_reserve + 1_000_000);
+        assert_eq!(sol_lock_account.lamports, 0);
+        assert!(sol_lock_account.data.iter().all(|byte| *byte == 0));
+    }
+
+    fn crank_unlock_of(
+        program_id: &Pubkey,
+        cranker: &mut TestAccount,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+    ) -> ProgramResult {
+        let mut treasury = TestAccount::new(get_treasury(program_id).0);
+        let mut stats = TestAccount::new(get_stats(program_id).0);
+
+        crank_unlock(
+            program_id,
+            &[
+                cranker.info(true),
+                owner.info(false),
+                sol_lock_account.info(false),
+                treasury.info(false),
+                stats.info(false),
+            ],
+            CrankUnlock {
+                acc_index: ACC_INDEX,
+            },
+        )
+    }
+
+    #[test]
+    fn test_crank_unlock_leaves_an_open_beneficiary_claim_alone() {
+        let program_id = Pubkey::new_unique();
+        let mut cranker = TestAccount::new(Pubkey::new_unique());
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let beneficiary = Pubkey::new_unique();
+        let switch = || Account {
+            crank_tip: Some(1_000),
+            ..dead_mans_switch_account(&owner.key, &beneficiary)
+        };
+        let mut sol_lock_account = TestAccount::sol_lock_account(&program_id, switch());
+        let mut claimable_account = TestAccount::sol_lock_account(&program_id, switch());
+
+        // The owner missed the heartbeat due at 1_500
+        set_now(1_500);
+        assert_eq!(
+            crank_unlock_of(
+                &program_id,
+                &mut cranker,
+                &mut owner,
+                &mut claimable_account
+            ),
+            Err(SolLockError::BeneficiaryClaimOpen.into())
+        );
+
+        set_now(1_499);
+        assert_eq!(
+            crank_unlock_of(&program_id, &mut cranker, &mut owner, &mut sol_lock_account),
+            Ok(())
+        );
+        assert_eq!(cranker.lamports, 1_000);
+        assert_eq!(owner.lamports, 999_000);
+    }
+
+    fn set_deposit_cap_of(
+        program_id: &Pubkey,
+        owner: &mut TestAccount,
+        sol_lock_account: &mut TestAccount,
+        deposit_cap: Option<u64>,
+    ) -> ProgramResult {
+        set_deposit_cap(
+            program_id,
+            &[owner.info(true), sol_lock_account.info(false)],
+            SetDepositCap {
+                acc_index: ACC_INDEX,
+                deposit_cap,
+                refund_excess: true,
+            },
+        )
+    }
+
+    #[test]
+    fn test_set_deposit_cap_requires_an_unlocked_account() {
+        set_now(500);
+        let program_id = Pubkey::new_unique();
+        let mut owner = TestAccount::new(Pubkey::new_unique());
+        let mut locked_sol_lock_account =
+            TestAccount::sol_lock_account(&program_id, locked_account(&owner.key));
+        let mut sol_lock_account = TestAccount::sol_lock_account(
+            &program_id,
+            Account {
+                owner: owner.key,
+                state: State::HasFunds,
+                lamports: Some(1_000_000),
+                ..Account::default()
+            },
+        );
+
+        assert_eq!(
+            set_deposit_cap_of(
+                &program_id,
+                &mut owner,
+                &mut locked_sol_lock_account,
+                Some(2_000_000)
+            ),
+            Err(SolLockError::FundsLocked.into())
+        );
+
+        assert_eq!(
+            set_deposit_cap_of(
+                &program_id,
+                &mut owner,
+                &mut sol_lock_account,
+                Some(2_000_000)
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            sol_lock_account
+                .account()
+                .map(|account| (account.deposit_cap, account.refund_excess)),
+            Ok((Some(2_000_000), true))
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! Stable read interface for SolLock accounts
+//!
+//! Other programs can use this module to check that an account is a valid SolLock account
+//! which is locked until at least a given time with at least a given number of lamports.
+//!
+//! The offsets below are part of the program's public interface. New account fields are only
+//! ever added after them, so integrations relying on them keep working across program upgrades.
+#![deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable
+)]
+
+use arrayref::array_ref;
+use solana_program::{
+    account_info::AccountInfo, clock::UnixTimestamp, program_error::ProgramError, pubkey::Pubkey,
+};
+
+use crate::{
+    error::SolLockError,
+    state::{
+        State, DEADLINE_LEN, DISCRIMINATOR_LEN, LAMPORTS_LEN, OWNER_LEN,
+        SOL_LOCK_ACCOUNT_DISCRIMINATOR, STAKE_ACC_LEN, STATE_LEN,
+    },
+};
+
+/// Offset of the owner pubkey
+pub const OWNER_OFFSET: usize = 0;
+/// Offset of the locked lamports: a 1 byte option tag followed by a little-endian u64
+pub const LAMPORTS_OFFSET: usize = 32;
+/// Offset of the deadline: a 1 byte option tag followed by a little-endian i64 unix timestamp
+pub const DEADLINE_OFFSET: usize = 41;
+/// Offset of the 1 byte account state
+pub const STATE_OFFSET: usize = 83;
+/// Offset of the 8 byte account discriminator, for `getProgramAccounts` memcmp filters
+///
+/// Holds `SOL_LOCK_ACCOUNT_DISCRIMINATOR`, or zeros in accounts not written since it was added.
+pub const DISCRIMINATOR_OFFSET: usize = 84;
+/// Offset of the 1 byte account layout version, see `ACCOUNT_VERSION`
+pub const VERSION_OFFSET: usize = 92;
+/// The minimum data length of a SolLock account
+pub const MIN_ACCOUNT_LEN: usize = STATE_OFFSET + 1;
+
+/// The state byte of a locked account
+pub const STATE_LOCKED: u8 = State::Locked as u8;
+/// The state byte of a locked account whose funds are staked
+pub const STATE_STAKED: u8 = State::Staked as u8;
+
+// The offsets above must never move. Fail the build if the account layout changes under them.
+const _: () = assert!(LAMPORTS_OFFSET == OWNER_OFFSET + OWNER_LEN);
+const _: () = assert!(DEADLINE_OFFSET == LAMPORTS_OFFSET + LAMPORTS_LEN);
+const _: () = assert!(STATE_OFFSET == DEADLINE_OFFSET + DEADLINE_LEN + STAKE_ACC_LEN);
+const _: () = assert!(DISCRIMINATOR_OFFSET == STATE_OFFSET + STATE_LEN);
+const _: () = assert!(VERSION_OFFSET == DISCRIMINATOR_OFFSET + DISCRIMINATOR_LEN);
+const _: () = assert!(STATE_LOCKED == 5 && STATE_STAKED == 6);
+
+/// The lock information of a locked SolLock account
+#[derive(Debug, PartialEq)]
+pub struct LockReceipt {
+    /// The owner of the account
+    pub owner: Pubkey,
+    /// The number of locked lamports
+    pub lamports: u64,
+    /// The time the lamports are locked until
+    pub deadline: UnixTimestamp,
+}
+
+/// Read the layout version of SolLock account data
+///
+/// Returns 0 for the layouts from before the discriminator, which have no version.
+pub fn read_layout_version(data: &[u8]) -> u8 {
+    if data.len() > VERSION_OFFSET
+        && data[DISCRIMINATOR_OFFSET..VERSION_OFFSET] == SOL_LOCK_ACCOUNT_DISCRIMINATOR
+    {
+        data[VERSION_OFFSET]
+    } else {
+        0
+    }
+}
+
+/// Read the lock information of a locked SolLock account
+///
+/// Fails if the account is not owned by the SolLock program, is not the SolLock account
+/// of its owner at `acc_index`, or is not locked.
+pub fn read_lock_receipt(
+    program_id: &Pubkey,
+    account_info: &AccountInfo,
+    acc_index: u64,
+) -> Result<LockReceipt, ProgramError> {
+    if account_info.owner != program_id {
+        return Err(SolLockError::IncorrectOwner.into());
+    }
+
+    let data = account_info.data.borrow();
+    if data.len() < MIN_ACCOUNT_LEN {
+        return Err(SolLockError::UnpackError.into());
+    }
+
+    let owner = Pubkey::new_from_array(*array_ref![data, OWNER_OFFSET, 32]);
+    let (sol_lock_account_key, _) =
+        Pubkey::find_program_address(&[owner.as_ref(), &acc_index.to_le_bytes()], program_id);
+    if sol_lock_account_key != *account_info.key {
+        return Err(SolLockError::PublicKeyMismatch.into());
+    }
+
+    let state = data[STATE_OFFSET];
+    if state != STATE_LOCKED && state != STATE_STAKED {
+        return Err(SolLockError::AccountNotLocked.into());
+    }
+
+    let lamports_src = array_ref![data, LAMPORTS_OFFSET, LAMPORTS_LEN];
+    let deadline_src = array_ref![data, DEADLINE_OFFSET, DEADLINE_LEN];
+    if lamports_src[0] != 1 || deadline_src[0] != 1 {
+        return Err(SolLockError::UnpackError.into());
+    }
+
+    Ok(LockReceipt {
+        owner,
+        lamports: u64::from_le_bytes(*array_ref![lamports_src, 1, 8]),
+        deadline: i64::from_le_bytes(*array_ref![deadline_src, 1, 8]),
+    })
+}
+
+/// Assert that an account is a SolLock account locked until at least `min_deadline`
+/// with at least `min_lamports` lamports
+pub fn assert_locked_until(
+    program_id: &Pubkey,
+    account_info: &AccountInfo,
+    acc_index: u64,
+    min_deadline: UnixTimestamp,
+    min_lamports: u64,
+) -> Result<LockReceipt, ProgramError> {
+    let receipt = read_lock_receipt(program_id, account_info, acc_index)?;
+    if receipt.deadline < min_deadline || receipt.lamports < min_lamports {
+        Err(SolLockError::LockRequirementsNotMet.into())
+    } else {
+        Ok(receipt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{Account, ACCOUNT_VERSION};
+    use solana_program::program_pack::Pack;
+
+    const ACC_INDEX: u64 = 3;
+
+    fn locked_account(owner: Pubkey) -> Account {
+        Account {
+            owner,
+            lamports: Some(5_000_000),
+            deadline: Some(1_700_000_000),
+            state: State::Locked,
+            ..Account::default()
+        }
+    }
+
+    fn packed(account: &Account) -> Vec<u8> {
+        let mut data = vec![0; Account::LEN];
+        Account::pack_into_slice(account, &mut data);
+        data
+    }
+
+    #[test]
+    fn test_offsets_are_pinned() {
+        assert_eq!(OWNER_OFFSET, 0);
+        assert_eq!(LAMPORTS_OFFSET, 32);
+        assert_eq!(DEADLINE_OFFSET, 41);
+        assert_eq!(STATE_OFFSET, 83);
+        assert_eq!(DISCRIMINATOR_OFFSET, 84);
+        assert_eq!(VERSION_OFFSET, 92);
+    }
+
+    #[test]
+    fn test_packed_account_matches_offsets() {
+        let owner = Pubkey::new_unique();
+        let data = packed(&locked_account(owner));
+
+        assert_eq!(data[OWNER_OFFSET..OWNER_OFFSET + 32], owner.to_bytes());
+        assert_eq!(data[STATE_OFFSET], STATE_LOCKED);
+        assert_eq!(
+            data[DISCRIMINATOR_OFFSET..VERSION_OFFSET],
+            SOL_LOCK_ACCOUNT_DISCRIMINATOR
+        );
+        assert_eq!(read_layout_version(&data), ACCOUNT_VERSION);
+    }
+
+    #[test]
+    fn test_read_lock_receipt_round_trip() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let (key, _) =
+            Pubkey::find_program_address(&[owner.as_ref(), &ACC_INDEX.to_le_bytes()], &program_id);
+        let mut data = packed(&locked_account(owner));
+        let mut lamports = 0;
+        let account_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+
+        assert_eq!(
+            read_lock_receipt(&program_id, &account_info, ACC_INDEX),
+            Ok(LockReceipt {
+                owner,
+                lamports: 5_000_000,
+                deadline: 1_700_000_000,
+            })
+        );
+        assert_eq!(account_info.data.borrow()[STATE_OFFSET], STATE_LOCKED);
+    }
+
+    #[test]
+    fn test_read_lock_receipt_rejects_unlocked_account() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let (key, _) =
+            Pubkey::find_program_address(&[owner.as_ref(), &ACC_INDEX.to_le_bytes()], &program_id);
+        let mut data = packed(&Account {
+            state: State::ReadyUnlocked,
+            ..locked_account(owner)
+        });
+        let mut lamports = 0;
+        let account_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+
+        assert_eq!(
+            read_lock_receipt(&program_id, &account_info, ACC_INDEX),
+            Err(SolLockError::AccountNotLocked.into())
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! The SPL Stake Pool instructions needed to convert locked SOL into a liquid staking token
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+    stake, system_program,
+    sysvar::{clock, stake_history},
+};
+
+use crate::token::TOKEN_PROGRAM_ID;
+
+/// The SPL Stake Pool program
+pub const STAKE_POOL_PROGRAM_ID: Pubkey = pubkey!("SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy");
+
+/// The instruction tags of the SPL Stake Pool program
+const DEPOSIT_SOL: u8 = 14;
+const WITHDRAW_SOL: u8 = 16;
+
+/// The accounts of a stake pool that depositing and withdrawing SOL go through
+pub struct StakePoolAccounts<'a> {
+    /// The stake pool
+    pub stake_pool: &'a Pubkey,
+    /// The withdraw authority of the stake pool
+    pub withdraw_authority: &'a Pubkey,
+    /// The reserve stake account of the stake pool
+    pub reserve_stake: &'a Pubkey,
+    /// The pool token account the manager fees are paid to
+    pub manager_fee_account: &'a Pubkey,
+    /// The pool token mint
+    pub pool_mint: &'a Pubkey,
+}
+
+/// Deposit `lamports` from a system account into a stake pool, minting pool tokens to
+/// the destination token account
+pub fn deposit_sol(
+    pool: &StakePoolAccounts,
+    funding_account: &Pubkey,
+    destination: &Pubkey,
+    lamports: u64,
+) -> Instruction {
+    let mut data = vec![DEPOSIT_SOL];
+    data.extend_from_slice(&lamports.to_le_bytes());
+
+    Instruction {
+        program_id: STAKE_POOL_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*pool.stake_pool, false),
+            AccountMeta::new_readonly(*pool.withdraw_authority, false),
+            AccountMeta::new(*pool.reserve_stake, false),
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new(*pool.manager_fee_account, false),
+            // No referrer, so the referral fee goes to the depositor
+            AccountMeta::new(*destination, false),
+            AccountMeta::new(*pool.pool_mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+/// Burn `pool_tokens` from the source token account to withdraw their value in SOL
+/// from the reserve of a stake pool to the destination account
+pub fn withdraw_sol(
+    pool: &StakePoolAccounts,
+    source_authority: &Pubkey,
+    source: &Pubkey,
+    destination: &Pubkey,
+    pool_tokens: u64,
+) -> Instruction {
+    let mut data = vec![WITHDRAW_SOL];
+    data.extend_from_slice(&pool_tokens.to_le_bytes());
+
+    Instruction {
+        program_id: STAKE_POOL_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*pool.stake_pool, false),
+            AccountMeta::new_readonly(*pool.withdraw_authority, false),
+            AccountMeta::new_readonly(*source_authority, true),
+            AccountMeta::new(*source, false),
+            AccountMeta::new(*pool.reserve_stake, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new(*pool.manager_fee_account, false),
+            AccountMeta::new(*pool.pool_mint, false),
+            AccountMeta::new_readonly(clock::id(), false),
+            AccountMeta::new_readonly(stake_history::id(), false),
+            AccountMeta::new_readonly(stake::program::id(), false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
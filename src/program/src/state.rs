@@ -1,23 +1,31 @@
 //! Program state
 #![deny(missing_docs)]
-
-use std::convert::TryInto;
+#![deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable
+)]
 
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 use borsh::{BorshDeserialize, BorshSerialize};
 use num_derive::FromPrimitive;
 use solana_program::{
-    clock::UnixTimestamp,
+    clock::{Epoch, UnixTimestamp},
     program_error::ProgramError,
-    program_memory::sol_memcpy,
+    program_memory::{sol_memcpy, sol_memset},
     program_pack::{IsInitialized, Pack, Sealed},
     pubkey::Pubkey,
 };
 
-use crate::{error::SolLockError, pack_utils::unpack_option};
+use crate::{
+    error::SolLockError,
+    pack_utils::{pack_option, unpack_option},
+};
 
 /// A SolLock account modeled as a state machine
-#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Account {
     // 32
@@ -35,17 +43,661 @@ pub struct Account {
     // 1
     /// The account state
     pub state: State,
+    // 1
+    /// The layout version the account was unpacked from, see ACCOUNT_VERSION
+    pub version: u8,
+    // 32
+    /// The owner proposed to take over the account, who must accept to complete the transfer
+    pub pending_owner: Option<Pubkey>,
+    // 32
+    /// The key allowed to unlock the account instead of the owner
+    pub unlock_authority: Option<Pubkey>,
+    // 32
+    /// The account unlocked lamports must be credited to, if set
+    pub receiver: Option<Pubkey>,
+    // 8
+    /// The nonce delegated instructions must include, incremented on every use
+    pub nonce: u64,
+    // 32
+    /// Hash or other pointer to off-chain recovery documentation for the account
+    pub recovery_pointer: Option<[u8; 32]>,
+    // 8
+    /// Lamports paid to whoever unlocks the account after its deadline,
+    /// or None if only the owner can unlock it
+    pub crank_tip: Option<u64>,
+    // 8
+    /// Period the deadline rolls forward by if the account is not unlocked
+    /// within AUTO_EXTEND_GRACE_PERIOD after its deadline
+    pub auto_extend: Option<UnixTimestamp>,
+    // 8
+    /// The time the lamports start vesting, linearly until the deadline,
+    /// or None if they are all released at the deadline
+    pub vesting_start: Option<UnixTimestamp>,
+    // 8
+    /// Lamports already withdrawn from the account while vesting
+    pub vested_withdrawn: u64,
+    // 8
+    /// The time before which no lamports of a vesting account can be withdrawn,
+    /// or None if they can be withdrawn as soon as they vest
+    pub vesting_cliff: Option<UnixTimestamp>,
+    // 8
+    /// The maximum number of lamports the account can hold, or None for no limit
+    pub deposit_cap: Option<u64>,
+    // 1
+    /// Whether deposits over the cap are reduced to fit it instead of failing
+    pub refund_excess: bool,
+    // 8
+    /// Lamports per second that become claimable by the receiver once the account is locked,
+    /// or None if the account does not stream
+    pub stream_rate: Option<u64>,
+    // 8
+    /// The time the account was locked and started streaming, if it streams
+    pub stream_start: Option<UnixTimestamp>,
+    // 8
+    /// Lamports already claimed from the account while streaming
+    pub stream_claimed: u64,
+    // 32
+    /// The hash whose preimage lets the counterparty claim the lamports before the deadline
+    pub hash_lock: Option<[u8; 32]>,
+    // 32
+    /// The account allowed to claim the lamports of a hash-locked or escrow account
+    pub counterparty: Option<Pubkey>,
+    // 32
+    /// The account allowed to direct the lamports of a disputed escrow account to either party
+    pub arbiter: Option<Pubkey>,
+    // 8
+    /// How long the arbiter has to resolve a dispute, if the escrow account has an arbiter
+    pub dispute_period: Option<UnixTimestamp>,
+    // 1
+    /// Whether anyone can contribute lamports to the account, not only its owner
+    pub open_deposits: bool,
+    // 8
+    /// The lamports contributors must raise by the deadline, below which they can get refunds
+    pub raise_target: Option<u64>,
+    // 8
+    /// How long after the deadline contributors can get refunds if the raise target was missed
+    pub refund_period: Option<UnixTimestamp>,
+    // 8
+    /// Lamports contributed to the account by contributors with Contribute
+    pub raised: u64,
+    // 32
+    /// The account allowed to claim the lamports if the owner stops sending heartbeats
+    pub beneficiary: Option<Pubkey>,
+    // 8
+    /// How long after the deadline or the last heartbeat the beneficiary can claim the lamports
+    pub heartbeat_interval: Option<UnixTimestamp>,
+    // 8
+    /// The time of the last heartbeat of the owner, if any
+    pub last_heartbeat: Option<UnixTimestamp>,
+    // 1 + 4 * 34
+    /// How the lamports claimed by the beneficiary are split, or empty if they all go to the beneficiary
+    pub beneficiary_shares: Vec<BeneficiaryShare>,
+    // 1 + 5 * 32
+    /// The accounts that co-sign unlocks if the account has a multisig threshold
+    pub multisig_signers: Vec<Pubkey>,
+    // 1
+    /// How many multisig signers must co-sign unlocks, or 0 if unlocks need no co-signers
+    pub multisig_threshold: u8,
+    // 1 + 5 * 32
+    /// The accounts that can recover the account by rotating its owner
+    pub guardians: Vec<Pubkey>,
+    // 1
+    /// How many guardians must sign a recovery, or 0 if the account has no guardians
+    pub guardian_threshold: u8,
+    // 8
+    /// How long the owner can veto a recovery started by the guardians
+    pub recovery_delay: Option<UnixTimestamp>,
+    // 8
+    /// The time from which the pending owner proposed by the guardians can accept the account
+    pub recovery_available_at: Option<UnixTimestamp>,
+    // 32
+    /// The Pyth price account that can unlock the account once its price reaches the price target
+    pub price_feed: Option<Pubkey>,
+    // 8
+    /// The price at which the account can be unlocked, in the units of the price feed
+    pub price_target: Option<i64>,
+    // 1
+    /// Whether the price target must be reached in addition to the deadline, instead of either one
+    pub price_requires_deadline: bool,
+    // 1
+    /// Whether the price feed was seen at or above the price target
+    pub price_target_reached: bool,
+    // 8
+    /// The epoch the account is locked until, in addition to the deadline
+    pub epoch_deadline: Option<Epoch>,
+    // 2
+    /// The protocol fee on unlocking in basis points, frozen from the config when locking
+    pub lock_fee_bps: u16,
+    // 33
+    /// The label the account index was derived from, or empty if the account is not labeled
+    pub label: String,
+    // 65
+    /// A name or memo for wallets and explorers to show, or empty
+    pub memo: String,
+    // 33
+    /// The hash of an off-chain document the lock commits to, set when locking
+    pub commitment: Option<[u8; 32]>,
+    // 8
+    /// The time the account was created
+    pub created_at: UnixTimestamp,
+    // 9
+    /// The time the account was last locked, or None if it was never locked
+    pub locked_at: Option<UnixTimestamp>,
+    // 8
+    /// The number of times the account was locked
+    pub lock_count: u64,
+    // 8
+    /// The total lamports ever locked in the account
+    pub lifetime_locked: u64,
+    // 8
+    /// The total lamports ever unlocked from the account
+    pub lifetime_withdrawn: u64,
+    // 8
+    /// The number of receipt tokens minted against the locked lamports, which must be burned before they leave the account
+    pub receipt_minted: u64,
+    // 33
+    /// The mint of the position NFT whose holder may unlock the account, while one is outstanding
+    pub position_mint: Option<Pubkey>,
+    // 8
+    /// The voting power of the account when it was last snapshotted with GetVotingPower
+    pub voting_power: u64,
+    // 8
+    /// The time of the last voting power snapshot, 0 if none was taken
+    pub voting_power_at: UnixTimestamp,
+    // 1 + 4 * 32
+    /// The vote accounts the locked lamports can be delegated to, or empty to allow any
+    pub allowed_validators: Vec<Pubkey>,
+    // 33
+    /// The stake account split off the stake account with SplitStake, until it is unstaked or merged back
+    pub split_stake_account: Option<Pubkey>,
+    // 8
+    /// The locked lamports already withdrawn from split stake accounts while the account is staked
+    pub unstaked_lamports: u64,
+    // 33
+    /// The stake pool the locked lamports are converted into with ConvertToLst, until they are redeemed
+    pub lst_pool: Option<Pubkey>,
+    // 8
+    /// The locked lamports deposited into the stake pool
+    pub lst_deposited: u64,
+    // 1
+    /// The yield backend the locked lamports are deposited into, if any
+    pub yield_backend: YieldBackend,
+    // 33
+    /// The lending reserve the locked lamports are deposited into with DepositToLending, until they are withdrawn
+    pub lending_reserve: Option<Pubkey>,
+    // 8
+    /// The locked lamports deposited into the lending reserve
+    pub lending_deposited: u64,
+    // 8
+    /// The collateral tokens received for the deposit into the lending reserve
+    pub lending_collateral: u64,
+    // 8
+    /// The lamports set aside to keep the account rent-exempt, which RemoveSol and Unlock only transfer out on close
+    pub rent_reserve: u64,
+    // 2
+    /// The number of NameRecord accounts of the owner that point at the account
+    pub name_records: u16,
+    // 1
+    /// The bump seed of the account address, so it can be derived without searching for it
+    pub bump_seed: u8,
 }
 
 /// The size of a SolLock account
-pub const SOL_LOCK_ACCOUNT_SIZE: usize =
-    OWNER_LEN + LAMPORTS_LEN + DEADLINE_LEN + STAKE_ACC_LEN + STATE_LEN;
+pub const SOL_LOCK_ACCOUNT_SIZE: usize = OWNER_LEN
+    + LAMPORTS_LEN
+    + DEADLINE_LEN
+    + STAKE_ACC_LEN
+    + STATE_LEN
+    + DISCRIMINATOR_LEN
+    + VERSION_LEN
+    + PENDING_OWNER_LEN
+    + UNLOCK_AUTHORITY_LEN
+    + RECEIVER_LEN
+    + NONCE_LEN
+    + RECOVERY_POINTER_LEN
+    + CRANK_TIP_LEN
+    + AUTO_EXTEND_LEN
+    + VESTING_START_LEN
+    + VESTED_WITHDRAWN_LEN
+    + VESTING_CLIFF_LEN
+    + DEPOSIT_CAP_LEN
+    + REFUND_EXCESS_LEN
+    + STREAM_RATE_LEN
+    + STREAM_START_LEN
+    + STREAM_CLAIMED_LEN
+    + HASH_LOCK_LEN
+    + COUNTERPARTY_LEN
+    + ARBITER_LEN
+    + DISPUTE_PERIOD_LEN
+    + OPEN_DEPOSITS_LEN
+    + RAISE_TARGET_LEN
+    + REFUND_PERIOD_LEN
+    + RAISED_LEN
+    + BENEFICIARY_LEN
+    + HEARTBEAT_INTERVAL_LEN
+    + LAST_HEARTBEAT_LEN
+    + BENEFICIARY_SHARES_LEN
+    + MULTISIG_SIGNERS_LEN
+    + MULTISIG_THRESHOLD_LEN
+    + GUARDIANS_LEN
+    + GUARDIAN_THRESHOLD_LEN
+    + RECOVERY_DELAY_LEN
+    + RECOVERY_AVAILABLE_AT_LEN
+    + PRICE_FEED_LEN
+    + PRICE_TARGET_LEN
+    + PRICE_REQUIRES_DEADLINE_LEN
+    + PRICE_TARGET_REACHED_LEN
+    + EPOCH_DEADLINE_LEN
+    + LOCK_FEE_BPS_LEN
+    + LABEL_LEN
+    + MEMO_LEN
+    + COMMITMENT_LEN
+    + CREATED_AT_LEN
+    + LOCKED_AT_LEN
+    + LOCK_COUNT_LEN
+    + LIFETIME_LOCKED_LEN
+    + LIFETIME_WITHDRAWN_LEN
+    + RECEIPT_MINTED_LEN
+    + POSITION_MINT_LEN
+    + VOTING_POWER_LEN
+    + VOTING_POWER_AT_LEN
+    + ALLOWED_VALIDATORS_LEN
+    + SPLIT_STAKE_ACCOUNT_LEN
+    + UNSTAKED_LAMPORTS_LEN
+    + LST_POOL_LEN
+    + LST_DEPOSITED_LEN
+    + YIELD_BACKEND_LEN
+    + LENDING_RESERVE_LEN
+    + LENDING_DEPOSITED_LEN
+    + LENDING_COLLATERAL_LEN
+    + RENT_RESERVE_LEN
+    + NAME_RECORDS_LEN
+    + BUMP_SEED_LEN;
+
+/// The maximum number of beneficiary shares of a SolLock account
+pub const MAX_BENEFICIARY_SHARES: usize = 4;
+
+/// The number of basis points all beneficiary shares must sum to
+pub const TOTAL_SHARE_BPS: u16 = 10_000;
+
+/// The size of a single beneficiary share
+pub const BENEFICIARY_SHARE_LEN: usize = 32 + 2;
+
+/// The maximum number of multisig signers of a SolLock account
+pub const MAX_MULTISIG_SIGNERS: usize = 5;
+
+/// The maximum number of guardians of a SolLock account
+pub const MAX_GUARDIANS: usize = 5;
+
+/// The maximum number of vote accounts a SolLock account can restrict staking to
+pub const MAX_ALLOWED_VALIDATORS: usize = 4;
+
+/// The maximum length of the label of a SolLock account, in bytes
+pub const MAX_LABEL_LEN: usize = 32;
+
+/// The seed prefix of the account index of a labeled SolLock account
+pub const LABEL_SEED: &[u8] = b"label";
+
+/// The discriminator identifying SolLock accounts among the accounts owned by the program
+///
+/// It directly follows the state field, so it stays at a fixed offset as the layout grows.
+/// Accounts from layouts before it was added must be migrated with the Migrate instruction.
+pub const SOL_LOCK_ACCOUNT_DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = *b"sol-lock";
+
+/// The version of the current SolLock account layout, stored after the discriminator
+///
+/// Version 0 refers to the layouts from before the discriminator was added.
+/// Fields appended to the end of the layout don't change the version: Realloc grows older
+/// accounts to SOL_LOCK_ACCOUNT_SIZE with zeroed bytes, which unpack as the new fields' defaults.
+/// Bump it only when existing fields move or change encoding, and teach the Migrate
+/// instruction to upgrade from it.
+pub const ACCOUNT_VERSION: u8 = 1;
+
+/// The maximum length of the memo of a SolLock account, in bytes
+pub const MAX_MEMO_LEN: usize = 64;
 
+/// The time after the deadline during which an auto-extending account can still be unlocked
+pub const AUTO_EXTEND_GRACE_PERIOD: UnixTimestamp = 3 * 24 * 60 * 60;
+
+/// The remaining lock time at which a locked lamport counts for one unit of voting power.
+/// Locking for longer doesn't add any more voting power.
+pub const MAX_VOTING_POWER_DURATION: UnixTimestamp = 4 * 365 * 24 * 60 * 60;
+
+/// The default of how far in the future a deadline can be set, so that a mistyped deadline
+/// can't strand funds
+pub const MAX_LOCK_DURATION: UnixTimestamp = 20 * 365 * 24 * 60 * 60;
+
+/// The default of the fewest lamports a SolLock account can hold or be locked with,
+/// so that no dust accounts are made
+pub const MIN_LOCK_LAMPORTS: u64 = 1_000_000;
+
+/// The size of the owner field
 pub const OWNER_LEN: usize = 32;
+/// The size of the lamports field
 pub const LAMPORTS_LEN: usize = 1 + 8;
+/// The size of the deadline field
 pub const DEADLINE_LEN: usize = 1 + 8;
+/// The size of the stake account field
 pub const STAKE_ACC_LEN: usize = 1 + 32;
+/// The size of the state field
 pub const STATE_LEN: usize = 1;
+/// The size of the account discriminator
+pub const DISCRIMINATOR_LEN: usize = 8;
+/// The size of the version field
+pub const VERSION_LEN: usize = 1;
+/// The size of the pending owner field
+pub const PENDING_OWNER_LEN: usize = 1 + 32;
+/// The size of the unlock authority field
+pub const UNLOCK_AUTHORITY_LEN: usize = 1 + 32;
+/// The size of the receiver field
+pub const RECEIVER_LEN: usize = 1 + 32;
+/// The size of the nonce field
+pub const NONCE_LEN: usize = 8;
+/// The size of the recovery_pointer field
+pub const RECOVERY_POINTER_LEN: usize = 1 + 32;
+/// The size of the crank_tip field
+pub const CRANK_TIP_LEN: usize = 1 + 8;
+/// The size of the auto_extend field
+pub const AUTO_EXTEND_LEN: usize = 1 + 8;
+/// The size of the vesting_start field
+pub const VESTING_START_LEN: usize = 1 + 8;
+/// The size of the vested_withdrawn field
+pub const VESTED_WITHDRAWN_LEN: usize = 8;
+/// The size of the vesting_cliff field
+pub const VESTING_CLIFF_LEN: usize = 1 + 8;
+/// The size of the deposit_cap field
+pub const DEPOSIT_CAP_LEN: usize = 1 + 8;
+/// The size of the refund_excess field
+pub const REFUND_EXCESS_LEN: usize = 1;
+/// The size of the stream_rate field
+pub const STREAM_RATE_LEN: usize = 1 + 8;
+/// The size of the stream_start field
+pub const STREAM_START_LEN: usize = 1 + 8;
+/// The size of the stream_claimed field
+pub const STREAM_CLAIMED_LEN: usize = 8;
+/// The size of the hash_lock field
+pub const HASH_LOCK_LEN: usize = 1 + 32;
+/// The size of the counterparty field
+pub const COUNTERPARTY_LEN: usize = 1 + 32;
+/// The size of the arbiter field
+pub const ARBITER_LEN: usize = 1 + 32;
+/// The size of the dispute_period field
+pub const DISPUTE_PERIOD_LEN: usize = 1 + 8;
+/// The size of the open_deposits field
+pub const OPEN_DEPOSITS_LEN: usize = 1;
+/// The size of the raise_target field
+pub const RAISE_TARGET_LEN: usize = 1 + 8;
+/// The size of the refund_period field
+pub const REFUND_PERIOD_LEN: usize = 1 + 8;
+/// The size of the raised field
+pub const RAISED_LEN: usize = 8;
+/// The size of the beneficiary field
+pub const BENEFICIARY_LEN: usize = 1 + 32;
+/// The size of the heartbeat_interval field
+pub const HEARTBEAT_INTERVAL_LEN: usize = 1 + 8;
+/// The size of the last_heartbeat field
+pub const LAST_HEARTBEAT_LEN: usize = 1 + 8;
+/// The size of the beneficiary_shares field
+pub const BENEFICIARY_SHARES_LEN: usize = 1 + MAX_BENEFICIARY_SHARES * BENEFICIARY_SHARE_LEN;
+/// The size of the multisig_signers field
+pub const MULTISIG_SIGNERS_LEN: usize = 1 + MAX_MULTISIG_SIGNERS * 32;
+/// The size of the multisig_threshold field
+pub const MULTISIG_THRESHOLD_LEN: usize = 1;
+/// The size of the guardians field
+pub const GUARDIANS_LEN: usize = 1 + MAX_GUARDIANS * 32;
+/// The size of the guardian_threshold field
+pub const GUARDIAN_THRESHOLD_LEN: usize = 1;
+/// The size of the recovery_delay field
+pub const RECOVERY_DELAY_LEN: usize = 1 + 8;
+/// The size of the recovery_available_at field
+pub const RECOVERY_AVAILABLE_AT_LEN: usize = 1 + 8;
+/// The size of the price_feed field
+pub const PRICE_FEED_LEN: usize = 1 + 32;
+/// The size of the price_target field
+pub const PRICE_TARGET_LEN: usize = 1 + 8;
+/// The size of the price_requires_deadline field
+pub const PRICE_REQUIRES_DEADLINE_LEN: usize = 1;
+/// The size of the price_target_reached field
+pub const PRICE_TARGET_REACHED_LEN: usize = 1;
+/// The size of the epoch_deadline field
+pub const EPOCH_DEADLINE_LEN: usize = 1 + 8;
+/// The size of the lock_fee_bps field
+pub const LOCK_FEE_BPS_LEN: usize = 2;
+/// The size of the label field
+pub const LABEL_LEN: usize = 1 + MAX_LABEL_LEN;
+/// The size of the memo field
+pub const MEMO_LEN: usize = 1 + MAX_MEMO_LEN;
+/// The size of the commitment field
+pub const COMMITMENT_LEN: usize = 1 + 32;
+/// The size of the created_at field
+pub const CREATED_AT_LEN: usize = 8;
+/// The size of the locked_at field
+pub const LOCKED_AT_LEN: usize = 1 + 8;
+/// The size of the lock_count field
+pub const LOCK_COUNT_LEN: usize = 8;
+/// The size of the lifetime_locked field
+pub const LIFETIME_LOCKED_LEN: usize = 8;
+/// The size of the lifetime_withdrawn field
+pub const LIFETIME_WITHDRAWN_LEN: usize = 8;
+/// The size of the receipt_minted field
+pub const RECEIPT_MINTED_LEN: usize = 8;
+/// The size of the position_mint field
+pub const POSITION_MINT_LEN: usize = 1 + 32;
+/// The size of the voting_power field
+pub const VOTING_POWER_LEN: usize = 8;
+/// The size of the voting_power_at field
+pub const VOTING_POWER_AT_LEN: usize = 8;
+/// The size of the allowed_validators field
+pub const ALLOWED_VALIDATORS_LEN: usize = 1 + MAX_ALLOWED_VALIDATORS * 32;
+/// The size of the split_stake_account field
+pub const SPLIT_STAKE_ACCOUNT_LEN: usize = 1 + 32;
+/// The size of the unstaked_lamports field
+pub const UNSTAKED_LAMPORTS_LEN: usize = 8;
+/// The size of the lst_pool field
+pub const LST_POOL_LEN: usize = 1 + 32;
+/// The size of the lst_deposited field
+pub const LST_DEPOSITED_LEN: usize = 8;
+/// The size of the yield_backend field
+pub const YIELD_BACKEND_LEN: usize = 1;
+/// The size of the lending_reserve field
+pub const LENDING_RESERVE_LEN: usize = 1 + 32;
+/// The size of the lending_deposited field
+pub const LENDING_DEPOSITED_LEN: usize = 8;
+/// The size of the lending_collateral field
+pub const LENDING_COLLATERAL_LEN: usize = 8;
+/// The size of the rent_reserve field
+pub const RENT_RESERVE_LEN: usize = 8;
+/// The size of the name_records field
+pub const NAME_RECORDS_LEN: usize = 2;
+/// The size of the bump_seed field
+pub const BUMP_SEED_LEN: usize = 1;
+/// The offset of the bump_seed field, the last field of a SolLock account
+pub const BUMP_SEED_OFFSET: usize = SOL_LOCK_ACCOUNT_SIZE - BUMP_SEED_LEN;
+
+impl Account {
+    /// Count a new lock of `lamports` in the lifetime counters
+    pub fn record_lock(&mut self, lamports: u64) {
+        self.lock_count = self.lock_count.saturating_add(1);
+        self.lifetime_locked = self.lifetime_locked.saturating_add(lamports);
+    }
+
+    /// Count `lamports` added to the account while it is locked
+    pub fn record_deposit(&mut self, lamports: u64) {
+        self.lifetime_locked = self.lifetime_locked.saturating_add(lamports);
+    }
+
+    /// Count `lamports` unlocked from the account
+    pub fn record_withdrawal(&mut self, lamports: u64) {
+        self.lifetime_withdrawn = self.lifetime_withdrawn.saturating_add(lamports);
+    }
+
+    /// The locked lamports held by the account itself, rather than by its yield backend
+    pub fn held_lamports(&self) -> u64 {
+        let lamports = self.lamports.unwrap_or(0);
+
+        match self.yield_backend {
+            YieldBackend::None => lamports,
+            YieldBackend::NativeStake => self.unstaked_lamports,
+            YieldBackend::StakePool => lamports.saturating_sub(self.lst_deposited),
+            YieldBackend::Lending => lamports.saturating_sub(self.lending_deposited),
+        }
+    }
+
+    /// The vote-escrow style voting power of the account at `now`
+    ///
+    /// The locked lamports weighted by the remaining lock time, relative to
+    /// MAX_VOTING_POWER_DURATION, so that it decays linearly to 0 at the deadline.
+    /// Accounts that are not locked or have no deadline have no voting power.
+    pub fn voting_power(&self, now: UnixTimestamp) -> u64 {
+        let deadline = match (self.state, self.deadline) {
+            (State::Locked, Some(deadline)) | (State::Staked, Some(deadline)) => deadline,
+            _ => return 0,
+        };
+
+        let remaining = deadline
+            .saturating_sub(now)
+            .clamp(0, MAX_VOTING_POWER_DURATION);
+
+        // remaining <= MAX_VOTING_POWER_DURATION, so the result is at most the locked lamports
+        (self.lamports.unwrap_or(0) as u128 * remaining as u128 / MAX_VOTING_POWER_DURATION as u128)
+            as u64
+    }
+
+    /// The deadline of a locked, auto-extending account after rolling it forward at `now`
+    ///
+    /// Returns None if the deadline does not need to roll, i.e. if the account is not locked,
+    /// does not auto-extend, or `now` is before the end of the grace period after its deadline.
+    pub fn rolled_deadline(
+        &self,
+        now: UnixTimestamp,
+    ) -> Result<Option<UnixTimestamp>, ProgramError> {
+        let (deadline, period) = match (self.state, self.deadline, self.auto_extend) {
+            (State::Locked, Some(deadline), Some(period)) => (deadline, period),
+            _ => return Ok(None),
+        };
+
+        let grace_end = deadline
+            .checked_add(AUTO_EXTEND_GRACE_PERIOD)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        if now < grace_end {
+            return Ok(None);
+        }
+
+        let periods = (now - grace_end) / period + 1;
+        periods
+            .checked_mul(period)
+            .and_then(|extension| deadline.checked_add(extension))
+            .map(Some)
+            .ok_or(ProgramError::InvalidAccountData)
+    }
+
+    /// The lamports of a vesting account that have vested at `now`, including withdrawn ones
+    ///
+    /// Vesting is linear from vesting_start to the deadline. Nothing vests before the cliff,
+    /// if any, and the lamports that vested until then all vest at the cliff.
+    /// Returns None if the account is not vesting or has no deadline.
+    pub fn vested_lamports(&self, now: UnixTimestamp) -> Option<u64> {
+        let (start, end) = match (self.vesting_start, self.deadline) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return None,
+        };
+
+        let total = self
+            .lamports
+            .unwrap_or(0)
+            .saturating_add(self.vested_withdrawn);
+
+        Some(if now >= end {
+            total
+        } else if now <= start || matches!(self.vesting_cliff, Some(cliff) if now < cliff) {
+            0
+        } else {
+            // start < now < end, so the division is safe and the result is less than total
+            (total as u128 * (now - start) as u128 / (end - start) as u128) as u64
+        })
+    }
+
+    /// The lamports of a streaming account that have streamed at `now`, including claimed ones
+    ///
+    /// Returns None if the account is not streaming.
+    pub fn streamed_lamports(&self, now: UnixTimestamp) -> Option<u64> {
+        let (rate, start) = match (self.stream_rate, self.stream_start) {
+            (Some(rate), Some(start)) => (rate, start),
+            _ => return None,
+        };
+
+        let total = self
+            .lamports
+            .unwrap_or(0)
+            .saturating_add(self.stream_claimed);
+        let elapsed = now.saturating_sub(start).max(0) as u64;
+
+        Some(rate.saturating_mul(elapsed).min(total))
+    }
+
+    /// The streamed lamports of a streaming account that can be claimed at `now`
+    pub fn claimable_streamed_lamports(&self, now: UnixTimestamp) -> Option<u64> {
+        self.streamed_lamports(now)
+            .map(|streamed| streamed.saturating_sub(self.stream_claimed))
+    }
+
+    /// The vested lamports of a vesting account that can be withdrawn at `now`
+    pub fn withdrawable_vested_lamports(&self, now: UnixTimestamp) -> Option<u64> {
+        self.vested_lamports(now)
+            .map(|vested| vested.saturating_sub(self.vested_withdrawn))
+    }
+
+    /// Whether contributors can get refunds at `now`, i.e. whether the account is locked,
+    /// missed its raise target, and `now` is within the refund period after its deadline
+    pub fn in_refund_period(&self, now: UnixTimestamp) -> bool {
+        match (
+            self.state,
+            self.deadline,
+            self.raise_target,
+            self.refund_period,
+        ) {
+            (State::Locked, Some(deadline), Some(raise_target), Some(refund_period)) => {
+                self.raised < raise_target
+                    && now >= deadline
+                    && now < deadline.saturating_add(refund_period)
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether contributions to the account may still be refunded at `now` or later,
+    /// so the Contribution records derived from its address must stay where they are
+    pub fn refunds_pending(&self, now: UnixTimestamp) -> bool {
+        match (self.deadline, self.raise_target, self.refund_period) {
+            (Some(deadline), Some(raise_target), Some(refund_period)) => {
+                self.raised > 0
+                    && self.raised < raise_target
+                    && now < deadline.saturating_add(refund_period)
+            }
+            _ => false,
+        }
+    }
+
+    /// The time from which the beneficiary can claim the lamports of a locked account,
+    /// one heartbeat interval after the deadline or the last heartbeat, whichever is later
+    ///
+    /// Returns None if the account is not locked or has no beneficiary.
+    pub fn beneficiary_claim_time(&self) -> Option<UnixTimestamp> {
+        match (
+            self.state,
+            self.deadline,
+            self.beneficiary,
+            self.heartbeat_interval,
+        ) {
+            (State::Locked, Some(deadline), Some(_), Some(heartbeat_interval)) => Some(
+                deadline
+                    .max(self.last_heartbeat.unwrap_or(deadline))
+                    .saturating_add(heartbeat_interval),
+            ),
+            _ => None,
+        }
+    }
+}
 
 impl IsInitialized for Account {
     fn is_initialized(&self) -> bool {
@@ -60,13 +712,149 @@ impl Pack for Account {
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let dst = array_mut_ref![dst, 0, SOL_LOCK_ACCOUNT_SIZE];
 
-        let (owner_dst, lamports_dst, deadline_dst, stake_account_dst, state_dst) = mut_array_refs![
+        let (
+            owner_dst,
+            lamports_dst,
+            deadline_dst,
+            stake_account_dst,
+            state_dst,
+            discriminator_dst,
+            version_dst,
+            pending_owner_dst,
+            unlock_authority_dst,
+            receiver_dst,
+            nonce_dst,
+            recovery_pointer_dst,
+            crank_tip_dst,
+            auto_extend_dst,
+            vesting_start_dst,
+            vested_withdrawn_dst,
+            vesting_cliff_dst,
+            deposit_cap_dst,
+            refund_excess_dst,
+            stream_rate_dst,
+            stream_start_dst,
+            stream_claimed_dst,
+            hash_lock_dst,
+            counterparty_dst,
+            arbiter_dst,
+            dispute_period_dst,
+            open_deposits_dst,
+            raise_target_dst,
+            refund_period_dst,
+            raised_dst,
+            beneficiary_dst,
+            heartbeat_interval_dst,
+            last_heartbeat_dst,
+            beneficiary_shares_dst,
+            multisig_signers_dst,
+            multisig_threshold_dst,
+            guardians_dst,
+            guardian_threshold_dst,
+            recovery_delay_dst,
+            recovery_available_at_dst,
+            price_feed_dst,
+            price_target_dst,
+            price_requires_deadline_dst,
+            price_target_reached_dst,
+            epoch_deadline_dst,
+            lock_fee_bps_dst,
+            label_dst,
+            memo_dst,
+            commitment_dst,
+            created_at_dst,
+            locked_at_dst,
+            lock_count_dst,
+            lifetime_locked_dst,
+            lifetime_withdrawn_dst,
+            receipt_minted_dst,
+            position_mint_dst,
+            voting_power_dst,
+            voting_power_at_dst,
+            allowed_validators_dst,
+            split_stake_account_dst,
+            unstaked_lamports_dst,
+            lst_pool_dst,
+            lst_deposited_dst,
+            yield_backend_dst,
+            lending_reserve_dst,
+            lending_deposited_dst,
+            lending_collateral_dst,
+            rent_reserve_dst,
+            name_records_dst,
+            bump_seed_dst,
+        ) = mut_array_refs![
             dst,
             OWNER_LEN,
             LAMPORTS_LEN,
             DEADLINE_LEN,
             STAKE_ACC_LEN,
-            STATE_LEN
+            STATE_LEN,
+            DISCRIMINATOR_LEN,
+            VERSION_LEN,
+            PENDING_OWNER_LEN,
+            UNLOCK_AUTHORITY_LEN,
+            RECEIVER_LEN,
+            NONCE_LEN,
+            RECOVERY_POINTER_LEN,
+            CRANK_TIP_LEN,
+            AUTO_EXTEND_LEN,
+            VESTING_START_LEN,
+            VESTED_WITHDRAWN_LEN,
+            VESTING_CLIFF_LEN,
+            DEPOSIT_CAP_LEN,
+            REFUND_EXCESS_LEN,
+            STREAM_RATE_LEN,
+            STREAM_START_LEN,
+            STREAM_CLAIMED_LEN,
+            HASH_LOCK_LEN,
+            COUNTERPARTY_LEN,
+            ARBITER_LEN,
+            DISPUTE_PERIOD_LEN,
+            OPEN_DEPOSITS_LEN,
+            RAISE_TARGET_LEN,
+            REFUND_PERIOD_LEN,
+            RAISED_LEN,
+            BENEFICIARY_LEN,
+            HEARTBEAT_INTERVAL_LEN,
+            LAST_HEARTBEAT_LEN,
+            BENEFICIARY_SHARES_LEN,
+            MULTISIG_SIGNERS_LEN,
+            MULTISIG_THRESHOLD_LEN,
+            GUARDIANS_LEN,
+            GUARDIAN_THRESHOLD_LEN,
+            RECOVERY_DELAY_LEN,
+            RECOVERY_AVAILABLE_AT_LEN,
+            PRICE_FEED_LEN,
+            PRICE_TARGET_LEN,
+            PRICE_REQUIRES_DEADLINE_LEN,
+            PRICE_TARGET_REACHED_LEN,
+            EPOCH_DEADLINE_LEN,
+            LOCK_FEE_BPS_LEN,
+            LABEL_LEN,
+            MEMO_LEN,
+            COMMITMENT_LEN,
+            CREATED_AT_LEN,
+            LOCKED_AT_LEN,
+            LOCK_COUNT_LEN,
+            LIFETIME_LOCKED_LEN,
+            LIFETIME_WITHDRAWN_LEN,
+            RECEIPT_MINTED_LEN,
+            POSITION_MINT_LEN,
+            VOTING_POWER_LEN,
+            VOTING_POWER_AT_LEN,
+            ALLOWED_VALIDATORS_LEN,
+            SPLIT_STAKE_ACCOUNT_LEN,
+            UNSTAKED_LAMPORTS_LEN,
+            LST_POOL_LEN,
+            LST_DEPOSITED_LEN,
+            YIELD_BACKEND_LEN,
+            LENDING_RESERVE_LEN,
+            LENDING_DEPOSITED_LEN,
+            LENDING_COLLATERAL_LEN,
+            RENT_RESERVE_LEN,
+            NAME_RECORDS_LEN,
+            BUMP_SEED_LEN
         ];
 
         sol_memcpy(owner_dst, &self.owner.to_bytes()[..], 32);
@@ -108,72 +896,1492 @@ impl Pack for Account {
         );
 
         state_dst[0] = self.state as u8;
-    }
 
-    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        let src = array_ref![src, 0, SOL_LOCK_ACCOUNT_SIZE];
+        *discriminator_dst = SOL_LOCK_ACCOUNT_DISCRIMINATOR;
+        version_dst[0] = ACCOUNT_VERSION;
 
-        let (owner_src, lamports_src, deadline_src, stake_account_src, state_src) = array_refs![
-            src,
-            OWNER_LEN,
-            LAMPORTS_LEN,
-            DEADLINE_LEN,
-            STAKE_ACC_LEN,
-            STATE_LEN
-        ];
+        pack_option(
+            &self.pending_owner,
+            pending_owner_dst,
+            |key| key.as_ref(),
+            32,
+        );
 
-        let owner = Pubkey::new(owner_src);
+        pack_option(
+            &self.unlock_authority,
+            unlock_authority_dst,
+            |key| key.as_ref(),
+            32,
+        );
 
-        let lamports = unpack_option(lamports_src, |src| {
-            u64::from_le_bytes(src.try_into().unwrap())
-        })?;
+        pack_option(&self.receiver, receiver_dst, |key| key.as_ref(), 32);
 
-        let deadline = unpack_option(deadline_src, |src| {
-            i64::from_le_bytes(src.try_into().unwrap())
-        })?;
+        *nonce_dst = self.nonce.to_le_bytes();
 
-        let stake_account = unpack_option(stake_account_src, |src| Pubkey::new(src))?;
+        pack_option(
+            &self.recovery_pointer,
+            recovery_pointer_dst,
+            |pointer| pointer.as_ref(),
+            32,
+        );
 
-        let state_opt: Option<State> = num::FromPrimitive::from_u8(state_src[0]);
-        if state_opt.is_none() {
-            return Err(SolLockError::UnpackError.into());
+        let mut crank_tip_bytes = [0; 8];
+        crank_tip_dst[0] = self.crank_tip.is_some() as u8;
+        sol_memcpy(
+            &mut crank_tip_dst[1..],
+            self.crank_tip.map_or(&bytes8_zero, |crank_tip| {
+                crank_tip_bytes = crank_tip.to_le_bytes();
+                &crank_tip_bytes
+            }),
+            8,
+        );
+
+        let mut auto_extend_bytes = [0; 8];
+        auto_extend_dst[0] = self.auto_extend.is_some() as u8;
+        sol_memcpy(
+            &mut auto_extend_dst[1..],
+            self.auto_extend.map_or(&bytes8_zero, |auto_extend| {
+                auto_extend_bytes = auto_extend.to_le_bytes();
+                &auto_extend_bytes
+            }),
+            8,
+        );
+
+        let mut vesting_start_bytes = [0; 8];
+        vesting_start_dst[0] = self.vesting_start.is_some() as u8;
+        sol_memcpy(
+            &mut vesting_start_dst[1..],
+            self.vesting_start.map_or(&bytes8_zero, |vesting_start| {
+                vesting_start_bytes = vesting_start.to_le_bytes();
+                &vesting_start_bytes
+            }),
+            8,
+        );
+
+        *vested_withdrawn_dst = self.vested_withdrawn.to_le_bytes();
+
+        let mut vesting_cliff_bytes = [0; 8];
+        vesting_cliff_dst[0] = self.vesting_cliff.is_some() as u8;
+        sol_memcpy(
+            &mut vesting_cliff_dst[1..],
+            self.vesting_cliff.map_or(&bytes8_zero, |vesting_cliff| {
+                vesting_cliff_bytes = vesting_cliff.to_le_bytes();
+                &vesting_cliff_bytes
+            }),
+            8,
+        );
+
+        let mut deposit_cap_bytes = [0; 8];
+        deposit_cap_dst[0] = self.deposit_cap.is_some() as u8;
+        sol_memcpy(
+            &mut deposit_cap_dst[1..],
+            self.deposit_cap.map_or(&bytes8_zero, |deposit_cap| {
+                deposit_cap_bytes = deposit_cap.to_le_bytes();
+                &deposit_cap_bytes
+            }),
+            8,
+        );
+
+        refund_excess_dst[0] = self.refund_excess as u8;
+
+        let mut stream_rate_bytes = [0; 8];
+        stream_rate_dst[0] = self.stream_rate.is_some() as u8;
+        sol_memcpy(
+            &mut stream_rate_dst[1..],
+            self.stream_rate.map_or(&bytes8_zero, |stream_rate| {
+                stream_rate_bytes = stream_rate.to_le_bytes();
+                &stream_rate_bytes
+            }),
+            8,
+        );
+
+        let mut stream_start_bytes = [0; 8];
+        stream_start_dst[0] = self.stream_start.is_some() as u8;
+        sol_memcpy(
+            &mut stream_start_dst[1..],
+            self.stream_start.map_or(&bytes8_zero, |stream_start| {
+                stream_start_bytes = stream_start.to_le_bytes();
+                &stream_start_bytes
+            }),
+            8,
+        );
+
+        *stream_claimed_dst = self.stream_claimed.to_le_bytes();
+
+        pack_option(&self.hash_lock, hash_lock_dst, |hash| hash.as_ref(), 32);
+
+        pack_option(&self.counterparty, counterparty_dst, |key| key.as_ref(), 32);
+
+        pack_option(&self.arbiter, arbiter_dst, |key| key.as_ref(), 32);
+
+        let mut dispute_period_bytes = [0; 8];
+        dispute_period_dst[0] = self.dispute_period.is_some() as u8;
+        sol_memcpy(
+            &mut dispute_period_dst[1..],
+            self.dispute_period.map_or(&bytes8_zero, |dispute_period| {
+                dispute_period_bytes = dispute_period.to_le_bytes();
+                &dispute_period_bytes
+            }),
+            8,
+        );
+
+        open_deposits_dst[0] = self.open_deposits as u8;
+
+        let mut raise_target_bytes = [0; 8];
+        raise_target_dst[0] = self.raise_target.is_some() as u8;
+        sol_memcpy(
+            &mut raise_target_dst[1..],
+            self.raise_target.map_or(&bytes8_zero, |raise_target| {
+                raise_target_bytes = raise_target.to_le_bytes();
+                &raise_target_bytes
+            }),
+            8,
+        );
+
+        let mut refund_period_bytes = [0; 8];
+        refund_period_dst[0] = self.refund_period.is_some() as u8;
+        sol_memcpy(
+            &mut refund_period_dst[1..],
+            self.refund_period.map_or(&bytes8_zero, |refund_period| {
+                refund_period_bytes = refund_period.to_le_bytes();
+                &refund_period_bytes
+            }),
+            8,
+        );
+
+        *raised_dst = self.raised.to_le_bytes();
+
+        pack_option(&self.beneficiary, beneficiary_dst, |key| key.as_ref(), 32);
+
+        let mut heartbeat_interval_bytes = [0; 8];
+        heartbeat_interval_dst[0] = self.heartbeat_interval.is_some() as u8;
+        sol_memcpy(
+            &mut heartbeat_interval_dst[1..],
+            self.heartbeat_interval
+                .map_or(&bytes8_zero, |heartbeat_interval| {
+                    heartbeat_interval_bytes = heartbeat_interval.to_le_bytes();
+                    &heartbeat_interval_bytes
+                }),
+            8,
+        );
+
+        let mut last_heartbeat_bytes = [0; 8];
+        last_heartbeat_dst[0] = self.last_heartbeat.is_some() as u8;
+        sol_memcpy(
+            &mut last_heartbeat_dst[1..],
+            self.last_heartbeat.map_or(&bytes8_zero, |last_heartbeat| {
+                last_heartbeat_bytes = last_heartbeat.to_le_bytes();
+                &last_heartbeat_bytes
+            }),
+            8,
+        );
+
+        beneficiary_shares_dst[0] = self.beneficiary_shares.len() as u8;
+        for (index, share_dst) in beneficiary_shares_dst[1..]
+            .chunks_exact_mut(BENEFICIARY_SHARE_LEN)
+            .enumerate()
+        {
+            match self.beneficiary_shares.get(index) {
+                Some(share) => {
+                    sol_memcpy(share_dst, share.beneficiary.as_ref(), 32);
+                    sol_memcpy(&mut share_dst[32..], &share.bps.to_le_bytes(), 2);
+                }
+                None => sol_memset(share_dst, 0, BENEFICIARY_SHARE_LEN),
+            }
         }
-        let state = state_opt.unwrap();
 
-        let account = Account {
-            owner,
-            lamports,
-            deadline,
-            stake_account,
-            state,
-        };
+        multisig_signers_dst[0] = self.multisig_signers.len() as u8;
+        for (index, signer_dst) in multisig_signers_dst[1..].chunks_exact_mut(32).enumerate() {
+            match self.multisig_signers.get(index) {
+                Some(signer) => sol_memcpy(signer_dst, signer.as_ref(), 32),
+                None => sol_memset(signer_dst, 0, 32),
+            }
+        }
 
-        Ok(account)
-    }
-}
+        multisig_threshold_dst[0] = self.multisig_threshold;
 
-/// Account state
-#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, FromPrimitive, Clone, Copy)]
-#[repr(C)]
-pub enum State {
-    /// The account is not yet initialized
-    Uninitialized,
-    /// The account is initialized. State after account creation or after fund withdrawal
-    Initialized,
-    /// The account knows how many lamports to lock and has received them, but doesn't know how long to lock for
-    HasFunds,
-    /// The account knows how long to lock the lamports for, but doesn't know how many lamports to lock
-    HasDeadline,
-    /// The account is ready to be locked, but is currently unlocked
-    ReadyUnlocked,
-    /// The account is currently locked, and cannot leave this state until the current time is >= deadline (except if going to Stake)
-    Locked,
-    /// The account is locked and its funds have been sent to a Stake account to delegate to validators
-    Staked,
-}
+        guardians_dst[0] = self.guardians.len() as u8;
+        for (index, guardian_dst) in guardians_dst[1..].chunks_exact_mut(32).enumerate() {
+            match self.guardians.get(index) {
+                Some(guardian) => sol_memcpy(guardian_dst, guardian.as_ref(), 32),
+                None => sol_memset(guardian_dst, 0, 32),
+            }
+        }
 
-impl Default for State {
-    fn default() -> Self {
-        State::Uninitialized
+        guardian_threshold_dst[0] = self.guardian_threshold;
+
+        let mut recovery_delay_bytes = [0; 8];
+        recovery_delay_dst[0] = self.recovery_delay.is_some() as u8;
+        sol_memcpy(
+            &mut recovery_delay_dst[1..],
+            self.recovery_delay.map_or(&bytes8_zero, |recovery_delay| {
+                recovery_delay_bytes = recovery_delay.to_le_bytes();
+                &recovery_delay_bytes
+            }),
+            8,
+        );
+
+        let mut recovery_available_at_bytes = [0; 8];
+        recovery_available_at_dst[0] = self.recovery_available_at.is_some() as u8;
+        sol_memcpy(
+            &mut recovery_available_at_dst[1..],
+            self.recovery_available_at
+                .map_or(&bytes8_zero, |recovery_available_at| {
+                    recovery_available_at_bytes = recovery_available_at.to_le_bytes();
+                    &recovery_available_at_bytes
+                }),
+            8,
+        );
+
+        pack_option(&self.price_feed, price_feed_dst, |key| key.as_ref(), 32);
+
+        let mut price_target_bytes = [0; 8];
+        price_target_dst[0] = self.price_target.is_some() as u8;
+        sol_memcpy(
+            &mut price_target_dst[1..],
+            self.price_target.map_or(&bytes8_zero, |price_target| {
+                price_target_bytes = price_target.to_le_bytes();
+                &price_target_bytes
+            }),
+            8,
+        );
+
+        price_requires_deadline_dst[0] = self.price_requires_deadline as u8;
+
+        price_target_reached_dst[0] = self.price_target_reached as u8;
+
+        let mut epoch_deadline_bytes = [0; 8];
+        epoch_deadline_dst[0] = self.epoch_deadline.is_some() as u8;
+        sol_memcpy(
+            &mut epoch_deadline_dst[1..],
+            self.epoch_deadline.map_or(&bytes8_zero, |epoch_deadline| {
+                epoch_deadline_bytes = epoch_deadline.to_le_bytes();
+                &epoch_deadline_bytes
+            }),
+            8,
+        );
+
+        *lock_fee_bps_dst = self.lock_fee_bps.to_le_bytes();
+
+        label_dst[0] = self.label.len() as u8;
+        sol_memset(&mut label_dst[1..], 0, MAX_LABEL_LEN);
+        sol_memcpy(&mut label_dst[1..], self.label.as_bytes(), self.label.len());
+
+        memo_dst[0] = self.memo.len() as u8;
+        sol_memset(&mut memo_dst[1..], 0, MAX_MEMO_LEN);
+        sol_memcpy(&mut memo_dst[1..], self.memo.as_bytes(), self.memo.len());
+
+        pack_option(&self.commitment, commitment_dst, |hash| hash.as_ref(), 32);
+
+        *created_at_dst = self.created_at.to_le_bytes();
+
+        let mut locked_at_bytes = [0; 8];
+        locked_at_dst[0] = self.locked_at.is_some() as u8;
+        sol_memcpy(
+            &mut locked_at_dst[1..],
+            self.locked_at.map_or(&bytes8_zero, |locked_at| {
+                locked_at_bytes = locked_at.to_le_bytes();
+                &locked_at_bytes
+            }),
+            8,
+        );
+
+        *lock_count_dst = self.lock_count.to_le_bytes();
+
+        *lifetime_locked_dst = self.lifetime_locked.to_le_bytes();
+
+        *lifetime_withdrawn_dst = self.lifetime_withdrawn.to_le_bytes();
+
+        *receipt_minted_dst = self.receipt_minted.to_le_bytes();
+
+        pack_option(
+            &self.position_mint,
+            position_mint_dst,
+            |key| key.as_ref(),
+            32,
+        );
+
+        *voting_power_dst = self.voting_power.to_le_bytes();
+
+        *voting_power_at_dst = self.voting_power_at.to_le_bytes();
+
+        allowed_validators_dst[0] = self.allowed_validators.len() as u8;
+        for (index, validator_dst) in allowed_validators_dst[1..].chunks_exact_mut(32).enumerate() {
+            match self.allowed_validators.get(index) {
+                Some(validator) => sol_memcpy(validator_dst, validator.as_ref(), 32),
+                None => sol_memset(validator_dst, 0, 32),
+            }
+        }
+
+        pack_option(
+            &self.split_stake_account,
+            split_stake_account_dst,
+            |key| key.as_ref(),
+            32,
+        );
+
+        *unstaked_lamports_dst = self.unstaked_lamports.to_le_bytes();
+
+        pack_option(&self.lst_pool, lst_pool_dst, |key| key.as_ref(), 32);
+
+        *lst_deposited_dst = self.lst_deposited.to_le_bytes();
+
+        yield_backend_dst[0] = self.yield_backend as u8;
+
+        pack_option(
+            &self.lending_reserve,
+            lending_reserve_dst,
+            |key| key.as_ref(),
+            32,
+        );
+
+        *lending_deposited_dst = self.lending_deposited.to_le_bytes();
+
+        *lending_collateral_dst = self.lending_collateral.to_le_bytes();
+
+        *rent_reserve_dst = self.rent_reserve.to_le_bytes();
+
+        *name_records_dst = self.name_records.to_le_bytes();
+
+        bump_seed_dst[0] = self.bump_seed;
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() < SOL_LOCK_ACCOUNT_SIZE {
+            return Err(SolLockError::UnpackError.into());
+        }
+        let src = array_ref![src, 0, SOL_LOCK_ACCOUNT_SIZE];
+
+        let (
+            owner_src,
+            lamports_src,
+            deadline_src,
+            stake_account_src,
+            state_src,
+            discriminator_src,
+            version_src,
+            pending_owner_src,
+            unlock_authority_src,
+            receiver_src,
+            nonce_src,
+            recovery_pointer_src,
+            crank_tip_src,
+            auto_extend_src,
+            vesting_start_src,
+            vested_withdrawn_src,
+            vesting_cliff_src,
+            deposit_cap_src,
+            refund_excess_src,
+            stream_rate_src,
+            stream_start_src,
+            stream_claimed_src,
+            hash_lock_src,
+            counterparty_src,
+            arbiter_src,
+            dispute_period_src,
+            open_deposits_src,
+            raise_target_src,
+            refund_period_src,
+            raised_src,
+            beneficiary_src,
+            heartbeat_interval_src,
+            last_heartbeat_src,
+            beneficiary_shares_src,
+            multisig_signers_src,
+            multisig_threshold_src,
+            guardians_src,
+            guardian_threshold_src,
+            recovery_delay_src,
+            recovery_available_at_src,
+            price_feed_src,
+            price_target_src,
+            price_requires_deadline_src,
+            price_target_reached_src,
+            epoch_deadline_src,
+            lock_fee_bps_src,
+            label_src,
+            memo_src,
+            commitment_src,
+            created_at_src,
+            locked_at_src,
+            lock_count_src,
+            lifetime_locked_src,
+            lifetime_withdrawn_src,
+            receipt_minted_src,
+            position_mint_src,
+            voting_power_src,
+            voting_power_at_src,
+            allowed_validators_src,
+            split_stake_account_src,
+            unstaked_lamports_src,
+            lst_pool_src,
+            lst_deposited_src,
+            yield_backend_src,
+            lending_reserve_src,
+            lending_deposited_src,
+            lending_collateral_src,
+            rent_reserve_src,
+            name_records_src,
+            bump_seed_src,
+        ) = array_refs![
+            src,
+            OWNER_LEN,
+            LAMPORTS_LEN,
+            DEADLINE_LEN,
+            STAKE_ACC_LEN,
+            STATE_LEN,
+            DISCRIMINATOR_LEN,
+            VERSION_LEN,
+            PENDING_OWNER_LEN,
+            UNLOCK_AUTHORITY_LEN,
+            RECEIVER_LEN,
+            NONCE_LEN,
+            RECOVERY_POINTER_LEN,
+            CRANK_TIP_LEN,
+            AUTO_EXTEND_LEN,
+            VESTING_START_LEN,
+            VESTED_WITHDRAWN_LEN,
+            VESTING_CLIFF_LEN,
+            DEPOSIT_CAP_LEN,
+            REFUND_EXCESS_LEN,
+            STREAM_RATE_LEN,
+            STREAM_START_LEN,
+            STREAM_CLAIMED_LEN,
+            HASH_LOCK_LEN,
+            COUNTERPARTY_LEN,
+            ARBITER_LEN,
+            DISPUTE_PERIOD_LEN,
+            OPEN_DEPOSITS_LEN,
+            RAISE_TARGET_LEN,
+            REFUND_PERIOD_LEN,
+            RAISED_LEN,
+            BENEFICIARY_LEN,
+            HEARTBEAT_INTERVAL_LEN,
+            LAST_HEARTBEAT_LEN,
+            BENEFICIARY_SHARES_LEN,
+            MULTISIG_SIGNERS_LEN,
+            MULTISIG_THRESHOLD_LEN,
+            GUARDIANS_LEN,
+            GUARDIAN_THRESHOLD_LEN,
+            RECOVERY_DELAY_LEN,
+            RECOVERY_AVAILABLE_AT_LEN,
+            PRICE_FEED_LEN,
+            PRICE_TARGET_LEN,
+            PRICE_REQUIRES_DEADLINE_LEN,
+            PRICE_TARGET_REACHED_LEN,
+            EPOCH_DEADLINE_LEN,
+            LOCK_FEE_BPS_LEN,
+            LABEL_LEN,
+            MEMO_LEN,
+            COMMITMENT_LEN,
+            CREATED_AT_LEN,
+            LOCKED_AT_LEN,
+            LOCK_COUNT_LEN,
+            LIFETIME_LOCKED_LEN,
+            LIFETIME_WITHDRAWN_LEN,
+            RECEIPT_MINTED_LEN,
+            POSITION_MINT_LEN,
+            VOTING_POWER_LEN,
+            VOTING_POWER_AT_LEN,
+            ALLOWED_VALIDATORS_LEN,
+            SPLIT_STAKE_ACCOUNT_LEN,
+            UNSTAKED_LAMPORTS_LEN,
+            LST_POOL_LEN,
+            LST_DEPOSITED_LEN,
+            YIELD_BACKEND_LEN,
+            LENDING_RESERVE_LEN,
+            LENDING_DEPOSITED_LEN,
+            LENDING_COLLATERAL_LEN,
+            RENT_RESERVE_LEN,
+            NAME_RECORDS_LEN,
+            BUMP_SEED_LEN
+        ];
+
+        let owner = Pubkey::new(owner_src);
+
+        let lamports = unpack_option(lamports_src, |src| {
+            u64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let deadline = unpack_option(deadline_src, |src| {
+            i64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let stake_account = unpack_option(stake_account_src, |src| Pubkey::new(src))?;
+
+        let state: State =
+            num::FromPrimitive::from_u8(state_src[0]).ok_or(SolLockError::UnpackError)?;
+
+        if *discriminator_src != SOL_LOCK_ACCOUNT_DISCRIMINATOR {
+            return Err(SolLockError::UnpackError.into());
+        }
+
+        let version = version_src[0];
+        if version < ACCOUNT_VERSION {
+            return Err(SolLockError::AccountNeedsMigration.into());
+        } else if version > ACCOUNT_VERSION {
+            return Err(SolLockError::UnpackError.into());
+        }
+
+        let pending_owner = unpack_option(pending_owner_src, |src| Pubkey::new(src))?;
+
+        let unlock_authority = unpack_option(unlock_authority_src, |src| Pubkey::new(src))?;
+
+        let receiver = unpack_option(receiver_src, |src| Pubkey::new(src))?;
+
+        let nonce = u64::from_le_bytes(*nonce_src);
+
+        let recovery_pointer = unpack_option(recovery_pointer_src, |src| *array_ref![src, 0, 32])?;
+
+        let crank_tip = unpack_option(crank_tip_src, |src| {
+            u64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let auto_extend = unpack_option(auto_extend_src, |src| {
+            i64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let vesting_start = unpack_option(vesting_start_src, |src| {
+            i64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let vested_withdrawn = u64::from_le_bytes(*vested_withdrawn_src);
+
+        let vesting_cliff = unpack_option(vesting_cliff_src, |src| {
+            i64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let deposit_cap = unpack_option(deposit_cap_src, |src| {
+            u64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let refund_excess = match refund_excess_src[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(SolLockError::UnpackError.into()),
+        };
+
+        let stream_rate = unpack_option(stream_rate_src, |src| {
+            u64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let stream_start = unpack_option(stream_start_src, |src| {
+            i64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let stream_claimed = u64::from_le_bytes(*stream_claimed_src);
+
+        let hash_lock = unpack_option(hash_lock_src, |src| *array_ref![src, 0, 32])?;
+
+        let counterparty = unpack_option(counterparty_src, |src| Pubkey::new(src))?;
+
+        let arbiter = unpack_option(arbiter_src, |src| Pubkey::new(src))?;
+
+        let dispute_period = unpack_option(dispute_period_src, |src| {
+            i64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let open_deposits = match open_deposits_src[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(SolLockError::UnpackError.into()),
+        };
+
+        let raise_target = unpack_option(raise_target_src, |src| {
+            u64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let refund_period = unpack_option(refund_period_src, |src| {
+            i64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let raised = u64::from_le_bytes(*raised_src);
+
+        let beneficiary = unpack_option(beneficiary_src, |src| Pubkey::new(src))?;
+
+        let heartbeat_interval = unpack_option(heartbeat_interval_src, |src| {
+            i64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let last_heartbeat = unpack_option(last_heartbeat_src, |src| {
+            i64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let beneficiary_shares_count = beneficiary_shares_src[0] as usize;
+        if beneficiary_shares_count > MAX_BENEFICIARY_SHARES {
+            return Err(SolLockError::UnpackError.into());
+        }
+        let beneficiary_shares = beneficiary_shares_src[1..]
+            .chunks_exact(BENEFICIARY_SHARE_LEN)
+            .take(beneficiary_shares_count)
+            .map(|share_src| BeneficiaryShare {
+                beneficiary: Pubkey::new(&share_src[..32]),
+                bps: u16::from_le_bytes([share_src[32], share_src[33]]),
+            })
+            .collect();
+
+        let multisig_signers_count = multisig_signers_src[0] as usize;
+        if multisig_signers_count > MAX_MULTISIG_SIGNERS {
+            return Err(SolLockError::UnpackError.into());
+        }
+        let multisig_signers = multisig_signers_src[1..]
+            .chunks_exact(32)
+            .take(multisig_signers_count)
+            .map(Pubkey::new)
+            .collect();
+
+        let multisig_threshold = multisig_threshold_src[0];
+
+        let guardians_count = guardians_src[0] as usize;
+        if guardians_count > MAX_GUARDIANS {
+            return Err(SolLockError::UnpackError.into());
+        }
+        let guardians = guardians_src[1..]
+            .chunks_exact(32)
+            .take(guardians_count)
+            .map(Pubkey::new)
+            .collect();
+
+        let guardian_threshold = guardian_threshold_src[0];
+
+        let recovery_delay = unpack_option(recovery_delay_src, |src| {
+            i64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let recovery_available_at = unpack_option(recovery_available_at_src, |src| {
+            i64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let price_feed = unpack_option(price_feed_src, |src| Pubkey::new(src))?;
+
+        let price_target = unpack_option(price_target_src, |src| {
+            i64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let price_requires_deadline = match price_requires_deadline_src[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(SolLockError::UnpackError.into()),
+        };
+
+        let price_target_reached = match price_target_reached_src[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(SolLockError::UnpackError.into()),
+        };
+
+        let epoch_deadline = unpack_option(epoch_deadline_src, |src| {
+            u64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let lock_fee_bps = u16::from_le_bytes(*lock_fee_bps_src);
+
+        let label_len = label_src[0] as usize;
+        if label_len > MAX_LABEL_LEN {
+            return Err(SolLockError::UnpackError.into());
+        }
+        let label = String::from_utf8(label_src[1..1 + label_len].to_vec())
+            .map_err(|_| SolLockError::UnpackError)?;
+
+        let memo_len = memo_src[0] as usize;
+        if memo_len > MAX_MEMO_LEN {
+            return Err(SolLockError::UnpackError.into());
+        }
+        let memo = String::from_utf8(memo_src[1..1 + memo_len].to_vec())
+            .map_err(|_| SolLockError::UnpackError)?;
+
+        let commitment = unpack_option(commitment_src, |src| *array_ref![src, 0, 32])?;
+
+        let created_at = i64::from_le_bytes(*created_at_src);
+
+        let locked_at = unpack_option(locked_at_src, |src| {
+            i64::from_le_bytes(*array_ref![src, 0, 8])
+        })?;
+
+        let lock_count = u64::from_le_bytes(*lock_count_src);
+
+        let lifetime_locked = u64::from_le_bytes(*lifetime_locked_src);
+
+        let lifetime_withdrawn = u64::from_le_bytes(*lifetime_withdrawn_src);
+
+        let receipt_minted = u64::from_le_bytes(*receipt_minted_src);
+
+        let position_mint = unpack_option(position_mint_src, |src| Pubkey::new(src))?;
+
+        let voting_power = u64::from_le_bytes(*voting_power_src);
+
+        let voting_power_at = i64::from_le_bytes(*voting_power_at_src);
+
+        let allowed_validators_count = allowed_validators_src[0] as usize;
+        if allowed_validators_count > MAX_ALLOWED_VALIDATORS {
+            return Err(SolLockError::UnpackError.into());
+        }
+        let allowed_validators = allowed_validators_src[1..]
+            .chunks_exact(32)
+            .take(allowed_validators_count)
+            .map(Pubkey::new)
+            .collect();
+
+        let split_stake_account = unpack_option(split_stake_account_src, |src| Pubkey::new(src))?;
+
+        let unstaked_lamports = u64::from_le_bytes(*unstaked_lamports_src);
+
+        let lst_pool = unpack_option(lst_pool_src, |src| Pubkey::new(src))?;
+
+        let lst_deposited = u64::from_le_bytes(*lst_deposited_src);
+
+        let yield_backend: YieldBackend =
+            num::FromPrimitive::from_u8(yield_backend_src[0]).ok_or(SolLockError::UnpackError)?;
+
+        let lending_reserve = unpack_option(lending_reserve_src, |src| Pubkey::new(src))?;
+
+        let lending_deposited = u64::from_le_bytes(*lending_deposited_src);
+
+        let lending_collateral = u64::from_le_bytes(*lending_collateral_src);
+
+        let rent_reserve = u64::from_le_bytes(*rent_reserve_src);
+
+        let name_records = u16::from_le_bytes(*name_records_src);
+
+        let bump_seed = bump_seed_src[0];
+
+        let account = Account {
+            owner,
+            lamports,
+            deadline,
+            stake_account,
+            state,
+            version,
+            pending_owner,
+            unlock_authority,
+            receiver,
+            nonce,
+            recovery_pointer,
+            crank_tip,
+            auto_extend,
+            vesting_start,
+            vested_withdrawn,
+            vesting_cliff,
+            deposit_cap,
+            refund_excess,
+            stream_rate,
+            stream_start,
+            stream_claimed,
+            hash_lock,
+            counterparty,
+            arbiter,
+            dispute_period,
+            open_deposits,
+            raise_target,
+            refund_period,
+            raised,
+            beneficiary,
+            heartbeat_interval,
+            last_heartbeat,
+            beneficiary_shares,
+            multisig_signers,
+            multisig_threshold,
+            guardians,
+            guardian_threshold,
+            recovery_delay,
+            recovery_available_at,
+            price_feed,
+            price_target,
+            price_requires_deadline,
+            price_target_reached,
+            epoch_deadline,
+            lock_fee_bps,
+            label,
+            memo,
+            commitment,
+            created_at,
+            locked_at,
+            lock_count,
+            lifetime_locked,
+            lifetime_withdrawn,
+            receipt_minted,
+            position_mint,
+            voting_power,
+            voting_power_at,
+            allowed_validators,
+            split_stake_account,
+            unstaked_lamports,
+            lst_pool,
+            lst_deposited,
+            yield_backend,
+            lending_reserve,
+            lending_deposited,
+            lending_collateral,
+            rent_reserve,
+            name_records,
+            bump_seed,
+        };
+
+        Ok(account)
+    }
+}
+
+/// A part of the lamports claimed by the beneficiary of a SolLock account
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BeneficiaryShare {
+    /// The account receiving the share
+    pub beneficiary: Pubkey,
+    /// The share of the lamports, in basis points
+    pub bps: u16,
+}
+
+/// Account state
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, FromPrimitive, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub enum State {
+    /// The account is not yet initialized
+    Uninitialized,
+    /// The account is initialized. State after account creation or after fund withdrawal
+    Initialized,
+    /// The account knows how many lamports to lock and has received them, but doesn't know how long to lock for
+    HasFunds,
+    /// The account knows how long to lock the lamports for, but doesn't know how many lamports to lock
+    HasDeadline,
+    /// The account is ready to be locked, but is currently unlocked
+    ReadyUnlocked,
+    /// The account is currently locked, and cannot leave this state until the current time is >= deadline (except if going to Stake)
+    Locked,
+    /// The account is locked and its funds have been sent to a Stake account to delegate to validators
+    Staked,
+    /// The account is a disputed escrow, and only its arbiter can release the funds until the deadline
+    Disputed,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State::Uninitialized
+    }
+}
+
+/// The yield backend the locked lamports of an account are deposited into
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, FromPrimitive, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub enum YieldBackend {
+    /// The locked lamports stay in the account
+    None,
+    /// The locked lamports are delegated from a stake account, see Stake
+    NativeStake,
+    /// The locked lamports are deposited into an SPL stake pool, see ConvertToLst
+    StakePool,
+    /// The locked lamports are deposited into a lending reserve, see DepositToLending
+    Lending,
+}
+
+impl Default for YieldBackend {
+    fn default() -> Self {
+        YieldBackend::None
+    }
+}
+
+/// The seed prefix of a NameRecord account
+pub const NAME_RECORD_SEED: &[u8] = b"name";
+
+/// A reverse-lookup record mapping a label hash to a SolLock account of an owner
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct NameRecord {
+    // 32
+    /// The owner of the record and of the SolLock account
+    pub owner: Pubkey,
+    // 32
+    /// The hash of the label
+    pub label_hash: [u8; 32],
+    // 32
+    /// The SolLock account the label refers to
+    pub sol_lock_account: Pubkey,
+}
+
+/// The size of a NameRecord account
+pub const NAME_RECORD_SIZE: usize = OWNER_LEN + LABEL_HASH_LEN + SOL_LOCK_ACC_LEN;
+
+/// The size of the label hash field
+pub const LABEL_HASH_LEN: usize = 32;
+/// The size of the SolLock account field
+pub const SOL_LOCK_ACC_LEN: usize = 32;
+
+impl IsInitialized for NameRecord {
+    fn is_initialized(&self) -> bool {
+        self.owner != Pubkey::default()
+    }
+}
+
+impl Sealed for NameRecord {}
+impl Pack for NameRecord {
+    const LEN: usize = NAME_RECORD_SIZE;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, NAME_RECORD_SIZE];
+
+        let (owner_dst, label_hash_dst, sol_lock_account_dst) =
+            mut_array_refs![dst, OWNER_LEN, LABEL_HASH_LEN, SOL_LOCK_ACC_LEN];
+
+        sol_memcpy(owner_dst, &self.owner.to_bytes()[..], 32);
+        sol_memcpy(label_hash_dst, &self.label_hash[..], 32);
+        sol_memcpy(
+            sol_lock_account_dst,
+            &self.sol_lock_account.to_bytes()[..],
+            32,
+        );
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, NAME_RECORD_SIZE];
+
+        let (owner_src, label_hash_src, sol_lock_account_src) =
+            array_refs![src, OWNER_LEN, LABEL_HASH_LEN, SOL_LOCK_ACC_LEN];
+
+        Ok(NameRecord {
+            owner: Pubkey::new(owner_src),
+            label_hash: *label_hash_src,
+            sol_lock_account: Pubkey::new(sol_lock_account_src),
+        })
+    }
+}
+
+/// The seed prefix of ClaimTicket accounts
+pub const CLAIM_TICKET_SEED: &[u8] = b"claim";
+
+/// Lamports unlocked for a receiver, waiting for the receiver to claim them
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct ClaimTicket {
+    // 32
+    /// The receiver allowed to claim the lamports
+    pub receiver: Pubkey,
+    // 8
+    /// The number of lamports that can be claimed
+    pub lamports: u64,
+}
+
+/// The size of a ClaimTicket account
+pub const CLAIM_TICKET_SIZE: usize = RECEIVER_KEY_LEN + CLAIMABLE_LAMPORTS_LEN;
+
+/// The size of the receiver field
+pub const RECEIVER_KEY_LEN: usize = 32;
+/// The size of the claimable lamports field
+pub const CLAIMABLE_LAMPORTS_LEN: usize = 8;
+
+impl IsInitialized for ClaimTicket {
+    fn is_initialized(&self) -> bool {
+        self.receiver != Pubkey::default()
+    }
+}
+
+impl Sealed for ClaimTicket {}
+impl Pack for ClaimTicket {
+    const LEN: usize = CLAIM_TICKET_SIZE;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, CLAIM_TICKET_SIZE];
+
+        let (receiver_dst, lamports_dst) =
+            mut_array_refs![dst, RECEIVER_KEY_LEN, CLAIMABLE_LAMPORTS_LEN];
+
+        sol_memcpy(receiver_dst, &self.receiver.to_bytes()[..], 32);
+        *lamports_dst = self.lamports.to_le_bytes();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, CLAIM_TICKET_SIZE];
+
+        let (receiver_src, lamports_src) =
+            array_refs![src, RECEIVER_KEY_LEN, CLAIMABLE_LAMPORTS_LEN];
+
+        Ok(ClaimTicket {
+            receiver: Pubkey::new(receiver_src),
+            lamports: u64::from_le_bytes(*lamports_src),
+        })
+    }
+}
+
+/// The seed prefix of a Contribution account
+pub const CONTRIBUTION_SEED: &[u8] = b"contribution";
+
+/// The lamports a contributor has deposited into an open-deposit SolLock account
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Contribution {
+    // 32
+    /// The SolLock account the lamports were deposited into
+    pub sol_lock_account: Pubkey,
+    // 32
+    /// The account that deposited the lamports
+    pub contributor: Pubkey,
+    // 8
+    /// The total number of lamports deposited by the contributor
+    pub lamports: u64,
+}
+
+/// The size of a Contribution account
+pub const CONTRIBUTION_SIZE: usize =
+    SOL_LOCK_ACCOUNT_KEY_LEN + CONTRIBUTOR_KEY_LEN + CONTRIBUTED_LAMPORTS_LEN;
+
+/// The size of the sol_lock_account field
+pub const SOL_LOCK_ACCOUNT_KEY_LEN: usize = 32;
+/// The size of the contributor field
+pub const CONTRIBUTOR_KEY_LEN: usize = 32;
+/// The size of the contributed lamports field
+pub const CONTRIBUTED_LAMPORTS_LEN: usize = 8;
+
+impl IsInitialized for Contribution {
+    fn is_initialized(&self) -> bool {
+        self.contributor != Pubkey::default()
+    }
+}
+
+impl Sealed for Contribution {}
+impl Pack for Contribution {
+    const LEN: usize = CONTRIBUTION_SIZE;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, CONTRIBUTION_SIZE];
+
+        let (sol_lock_account_dst, contributor_dst, lamports_dst) = mut_array_refs![
+            dst,
+            SOL_LOCK_ACCOUNT_KEY_LEN,
+            CONTRIBUTOR_KEY_LEN,
+            CONTRIBUTED_LAMPORTS_LEN
+        ];
+
+        sol_memcpy(
+            sol_lock_account_dst,
+            &self.sol_lock_account.to_bytes()[..],
+            32,
+        );
+        sol_memcpy(contributor_dst, &self.contributor.to_bytes()[..], 32);
+        *lamports_dst = self.lamports.to_le_bytes();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, CONTRIBUTION_SIZE];
+
+        let (sol_lock_account_src, contributor_src, lamports_src) = array_refs![
+            src,
+            SOL_LOCK_ACCOUNT_KEY_LEN,
+            CONTRIBUTOR_KEY_LEN,
+            CONTRIBUTED_LAMPORTS_LEN
+        ];
+
+        Ok(Contribution {
+            sol_lock_account: Pubkey::new(sol_lock_account_src),
+            contributor: Pubkey::new(contributor_src),
+            lamports: u64::from_le_bytes(*lamports_src),
+        })
+    }
+}
+
+/// The seed of the Config account
+pub const CONFIG_SEED: &[u8] = b"config";
+
+/// The seed of the treasury account, which collects the protocol fees
+pub const TREASURY_SEED: &[u8] = b"treasury";
+
+/// The seed of the receipt token mint, which is also its own mint authority
+pub const RECEIPT_MINT_SEED: &[u8] = b"receipt_mint";
+
+/// The decimals of the receipt token, so that one token represents one locked SOL
+pub const RECEIPT_MINT_DECIMALS: u8 = 9;
+
+/// The seed prefix of the position NFT mint of a SolLock account, which is its own mint authority
+pub const POSITION_MINT_SEED: &[u8] = b"position_mint";
+
+/// The seed prefix of the stake account of a SolLock account, which it stakes its locked lamports in
+pub const STAKE_ACCOUNT_SEED: &[u8] = b"stake";
+
+/// The seed prefix of the stake account a SolLock account splits part of its stake into
+pub const SPLIT_STAKE_ACCOUNT_SEED: &[u8] = b"split_stake";
+
+/// The seed prefix of the system account a SolLock account deposits its locked lamports
+/// into a stake pool from, since the stake pool only takes SOL from system accounts
+pub const LST_FUNDING_SEED: &[u8] = b"lst_funding";
+
+/// The program-wide settings of SolLock, managed by an admin
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Config {
+    // 32
+    /// The account that can update the config
+    pub admin: Pubkey,
+    // 2
+    /// The protocol fee, in basis points
+    pub fee_bps: u16,
+    // 8
+    /// How far in the future a deadline can be set
+    pub max_lock_duration: UnixTimestamp,
+    // 8
+    /// The fewest lamports a SolLock account can hold or be locked with
+    pub min_lock_lamports: u64,
+    // 1
+    /// Whether SolLock accounts can't be funded or locked for now
+    pub paused: bool,
+}
+
+/// The size of a Config account
+pub const CONFIG_SIZE: usize =
+    ADMIN_LEN + FEE_BPS_LEN + MAX_LOCK_DURATION_LEN + MIN_LOCK_LAMPORTS_LEN + PAUSED_LEN;
+
+/// The size of the admin field
+pub const ADMIN_LEN: usize = 32;
+/// The size of the fee_bps field
+pub const FEE_BPS_LEN: usize = 2;
+/// The size of the max_lock_duration field
+pub const MAX_LOCK_DURATION_LEN: usize = 8;
+/// The size of the min_lock_lamports field
+pub const MIN_LOCK_LAMPORTS_LEN: usize = 8;
+/// The size of the paused field
+pub const PAUSED_LEN: usize = 1;
+
+/// The settings used until the Config account is initialized
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            admin: Pubkey::default(),
+            fee_bps: 0,
+            max_lock_duration: MAX_LOCK_DURATION,
+            min_lock_lamports: MIN_LOCK_LAMPORTS,
+            paused: false,
+        }
+    }
+}
+
+impl IsInitialized for Config {
+    fn is_initialized(&self) -> bool {
+        self.admin != Pubkey::default()
+    }
+}
+
+impl Sealed for Config {}
+impl Pack for Config {
+    const LEN: usize = CONFIG_SIZE;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, CONFIG_SIZE];
+
+        let (admin_dst, fee_bps_dst, max_lock_duration_dst, min_lock_lamports_dst, paused_dst) = mut_array_refs![
+            dst,
+            ADMIN_LEN,
+            FEE_BPS_LEN,
+            MAX_LOCK_DURATION_LEN,
+            MIN_LOCK_LAMPORTS_LEN,
+            PAUSED_LEN
+        ];
+
+        sol_memcpy(admin_dst, &self.admin.to_bytes()[..], 32);
+        *fee_bps_dst = self.fee_bps.to_le_bytes();
+        *max_lock_duration_dst = self.max_lock_duration.to_le_bytes();
+        *min_lock_lamports_dst = self.min_lock_lamports.to_le_bytes();
+        paused_dst[0] = self.paused as u8;
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, CONFIG_SIZE];
+
+        let (admin_src, fee_bps_src, max_lock_duration_src, min_lock_lamports_src, paused_src) = array_refs![
+            src,
+            ADMIN_LEN,
+            FEE_BPS_LEN,
+            MAX_LOCK_DURATION_LEN,
+            MIN_LOCK_LAMPORTS_LEN,
+            PAUSED_LEN
+        ];
+
+        let paused = match paused_src[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(SolLockError::UnpackError.into()),
+        };
+
+        Ok(Config {
+            admin: Pubkey::new(admin_src),
+            fee_bps: u16::from_le_bytes(*fee_bps_src),
+            max_lock_duration: i64::from_le_bytes(*max_lock_duration_src),
+            min_lock_lamports: u64::from_le_bytes(*min_lock_lamports_src),
+            paused,
+        })
+    }
+}
+
+/// The seed prefix of AllowlistEntry accounts
+pub const ALLOWLIST_SEED: &[u8] = b"allowlist";
+
+/// An owner allowed to create SolLock accounts when the program is built with the allowlist feature
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct AllowlistEntry {
+    // 32
+    /// The allowed owner
+    pub owner: Pubkey,
+}
+
+/// The size of an AllowlistEntry account
+pub const ALLOWLIST_ENTRY_SIZE: usize = ALLOWED_OWNER_LEN;
+
+/// The size of the allowed owner field
+pub const ALLOWED_OWNER_LEN: usize = 32;
+
+impl IsInitialized for AllowlistEntry {
+    fn is_initialized(&self) -> bool {
+        self.owner != Pubkey::default()
+    }
+}
+
+impl Sealed for AllowlistEntry {}
+impl Pack for AllowlistEntry {
+    const LEN: usize = ALLOWLIST_ENTRY_SIZE;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let owner_dst = array_mut_ref![dst, 0, ALLOWLIST_ENTRY_SIZE];
+
+        sol_memcpy(owner_dst, &self.owner.to_bytes()[..], 32);
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let owner_src = array_ref![src, 0, ALLOWLIST_ENTRY_SIZE];
+
+        Ok(AllowlistEntry {
+            owner: Pubkey::new(owner_src),
+        })
+    }
+}
+
+/// The seed of the Stats account
+pub const STATS_SEED: &[u8] = b"stats";
+
+/// Program-wide totals of locked lamports, kept up to date by the instructions that deposit
+/// into, lock and unlock SolLock accounts
+///
+/// Only locks made after the Stats account was created are counted.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Stats {
+    // 8
+    /// The lamports held by locked SolLock accounts
+    pub total_locked: u64,
+    // 8
+    /// The number of locked SolLock accounts
+    pub active_locks: u64,
+    // 8
+    /// The lamports ever locked, including deposits into locked accounts
+    pub total_volume: u64,
+}
+
+/// The size of a Stats account
+pub const STATS_SIZE: usize = TOTAL_LOCKED_LEN + ACTIVE_LOCKS_LEN + TOTAL_VOLUME_LEN;
+
+/// The size of the total_locked field
+pub const TOTAL_LOCKED_LEN: usize = 8;
+/// The size of the active_locks field
+pub const ACTIVE_LOCKS_LEN: usize = 8;
+/// The size of the total_volume field
+pub const TOTAL_VOLUME_LEN: usize = 8;
+
+impl Stats {
+    /// Record a SolLock account being locked with `lamports`
+    pub fn record_lock(&mut self, lamports: u64) {
+        self.active_locks = self.active_locks.saturating_add(1);
+        self.record_deposit(lamports);
+    }
+
+    /// Record `lamports` being deposited into a locked SolLock account
+    pub fn record_deposit(&mut self, lamports: u64) {
+        self.total_locked = self.total_locked.saturating_add(lamports);
+        self.total_volume = self.total_volume.saturating_add(lamports);
+    }
+
+    /// Record `lamports` being unlocked, and the account no longer being locked if `unlocked`
+    ///
+    /// Saturates at zero, as locks made before the Stats account was created were never counted.
+    pub fn record_unlock(&mut self, lamports: u64, unlocked: bool) {
+        self.total_locked = self.total_locked.saturating_sub(lamports);
+        if unlocked {
+            self.active_locks = self.active_locks.saturating_sub(1);
+        }
+    }
+}
+
+impl Sealed for Stats {}
+impl Pack for Stats {
+    const LEN: usize = STATS_SIZE;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, STATS_SIZE];
+
+        let (total_locked_dst, active_locks_dst, total_volume_dst) =
+            mut_array_refs![dst, TOTAL_LOCKED_LEN, ACTIVE_LOCKS_LEN, TOTAL_VOLUME_LEN];
+
+        *total_locked_dst = self.total_locked.to_le_bytes();
+        *active_locks_dst = self.active_locks.to_le_bytes();
+        *total_volume_dst = self.total_volume.to_le_bytes();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, STATS_SIZE];
+
+        let (total_locked_src, active_locks_src, total_volume_src) =
+            array_refs![src, TOTAL_LOCKED_LEN, ACTIVE_LOCKS_LEN, TOTAL_VOLUME_LEN];
+
+        Ok(Stats {
+            total_locked: u64::from_le_bytes(*total_locked_src),
+            active_locks: u64::from_le_bytes(*active_locks_src),
+            total_volume: u64::from_le_bytes(*total_volume_src),
+        })
+    }
+}
+
+/// The seed prefix of IndexCounter accounts
+pub const INDEX_COUNTER_SEED: &[u8] = b"counter";
+
+/// The next account index CreateNextAccount assigns to the SolLock accounts of an owner
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct IndexCounter {
+    // 32
+    /// The owner of the SolLock accounts
+    pub owner: Pubkey,
+    // 8
+    /// The index of the next SolLock account
+    pub next_index: u64,
+}
+
+/// The size of an IndexCounter account
+pub const INDEX_COUNTER_SIZE: usize = COUNTER_OWNER_LEN + NEXT_INDEX_LEN;
+
+/// The size of the owner field
+pub const COUNTER_OWNER_LEN: usize = 32;
+/// The size of the next_index field
+pub const NEXT_INDEX_LEN: usize = 8;
+
+impl IsInitialized for IndexCounter {
+    fn is_initialized(&self) -> bool {
+        self.owner != Pubkey::default()
+    }
+}
+
+impl Sealed for IndexCounter {}
+impl Pack for IndexCounter {
+    const LEN: usize = INDEX_COUNTER_SIZE;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, INDEX_COUNTER_SIZE];
+
+        let (owner_dst, next_index_dst) = mut_array_refs![dst, COUNTER_OWNER_LEN, NEXT_INDEX_LEN];
+
+        sol_memcpy(owner_dst, &self.owner.to_bytes()[..], 32);
+        *next_index_dst = self.next_index.to_le_bytes();
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, INDEX_COUNTER_SIZE];
+
+        let (owner_src, next_index_src) = array_refs![src, COUNTER_OWNER_LEN, NEXT_INDEX_LEN];
+
+        Ok(IndexCounter {
+            owner: Pubkey::new(owner_src),
+            next_index: u64::from_le_bytes(*next_index_src),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipt::{
+        DISCRIMINATOR_OFFSET, LAMPORTS_OFFSET, MIN_ACCOUNT_LEN, STATE_OFFSET, VERSION_OFFSET,
+    };
+
+    fn packed(account: &Account) -> Vec<u8> {
+        let mut data = vec![0; Account::LEN];
+        account.pack_into_slice(&mut data);
+        data
+    }
+
+    fn unpack_error() -> Result<Account, ProgramError> {
+        Err(SolLockError::UnpackError.into())
+    }
+
+    fn locked_account() -> Account {
+        Account {
+            owner: Pubkey::new_unique(),
+            lamports: Some(1_000_000),
+            deadline: Some(1_700_000_000),
+            state: State::Locked,
+            version: ACCOUNT_VERSION,
+            ..Account::default()
+        }
+    }
+
+    #[test]
+    fn test_pack_round_trip() {
+        let account = locked_account();
+
+        assert_eq!(Account::unpack(&packed(&account)), Ok(account));
+    }
+
+    #[test]
+    fn test_zeroed_appended_fields_unpack_as_defaults() {
+        let account = Account {
+            receiver: Some(Pubkey::new_unique()),
+            crank_tip: Some(5_000),
+            ..locked_account()
+        };
+        let mut data = packed(&account);
+        // The layout of an account grown by Realloc, from before anything after the version
+        data[VERSION_OFFSET + VERSION_LEN..].fill(0);
+
+        assert_eq!(
+            Account::unpack(&data),
+            Ok(Account {
+                owner: account.owner,
+                ..locked_account()
+            })
+        );
+    }
+
+    #[test]
+    fn test_malformed_data_is_rejected() {
+        let data = packed(&locked_account());
+
+        assert_eq!(
+            Account::unpack_from_slice(&data[..MIN_ACCOUNT_LEN]),
+            unpack_error()
+        );
+
+        let mut bad_state = data.clone();
+        bad_state[STATE_OFFSET] = u8::MAX;
+        assert_eq!(Account::unpack(&bad_state), unpack_error());
+
+        let mut bad_option_tag = data.clone();
+        bad_option_tag[LAMPORTS_OFFSET] = 2;
+        assert_eq!(Account::unpack(&bad_option_tag), unpack_error());
+
+        let mut bad_discriminator = data.clone();
+        bad_discriminator[DISCRIMINATOR_OFFSET] ^= 1;
+        assert_eq!(Account::unpack(&bad_discriminator), unpack_error());
+
+        let mut newer_version = data.clone();
+        newer_version[VERSION_OFFSET] = ACCOUNT_VERSION + 1;
+        assert_eq!(Account::unpack(&newer_version), unpack_error());
+
+        let mut older_version = data;
+        older_version[VERSION_OFFSET] = 0;
+        assert_eq!(
+            Account::unpack(&older_version),
+            Err(SolLockError::AccountNeedsMigration.into())
+        );
     }
 }
@@ -0,0 +1,32 @@
+//! Helpers for testing programs and clients that use SolLock
+
+use solana_program::{clock::UnixTimestamp, program_pack::Pack, pubkey::Pubkey};
+
+use crate::state::{Account, State, SOL_LOCK_ACCOUNT_SIZE};
+
+/// Build the data of a SolLock account in state Initialized
+pub fn initialized_account(owner: Pubkey) -> Account {
+    Account {
+        owner,
+        state: State::Initialized,
+        ..Account::default()
+    }
+}
+
+/// Build the data of a SolLock account in state Locked
+pub fn locked_account(owner: Pubkey, lamports: u64, deadline: UnixTimestamp) -> Account {
+    Account {
+        owner,
+        lamports: Some(lamports),
+        deadline: Some(deadline),
+        state: State::Locked,
+        ..Account::default()
+    }
+}
+
+/// Pack SolLock account data into account data bytes
+pub fn pack_account(account: &Account) -> Vec<u8> {
+    let mut data = vec![0; SOL_LOCK_ACCOUNT_SIZE];
+    account.pack_into_slice(&mut data);
+    data
+}
@@ -0,0 +1,167 @@
+//! The few SPL Token instructions and accounts needed for the receipt token, position NFTs
+//! and wrapped SOL
+use arrayref::array_ref;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey,
+    pubkey::Pubkey,
+    system_program,
+};
+
+use crate::error::SolLockError;
+
+/// The SPL Token program
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+/// The SPL Associated Token Account program
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// The mint of wrapped SOL
+pub const NATIVE_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+
+/// The size of an SPL Token mint account
+pub const MINT_LEN: usize = 82;
+
+/// The size of an SPL Token account
+const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// The fields of an SPL Token account that the program reads
+pub struct TokenAccount {
+    /// The mint of the tokens held
+    pub mint: Pubkey,
+    /// The owner of the token account, who can transfer and burn its tokens
+    pub owner: Pubkey,
+    /// The number of tokens held
+    pub amount: u64,
+    /// The delegate allowed to transfer and burn some of the tokens, if any
+    pub delegate: Option<Pubkey>,
+}
+
+/// Read the mint, owner, amount and delegate of an SPL Token account
+pub fn unpack_token_account(data: &[u8]) -> Result<TokenAccount, ProgramError> {
+    if data.len() != TOKEN_ACCOUNT_LEN {
+        return Err(SolLockError::UnpackError.into());
+    }
+
+    Ok(TokenAccount {
+        mint: Pubkey::new_from_array(*array_ref![data, 0, 32]),
+        owner: Pubkey::new_from_array(*array_ref![data, 32, 32]),
+        amount: u64::from_le_bytes(*array_ref![data, 64, 8]),
+        delegate: match u32::from_le_bytes(*array_ref![data, 72, 4]) {
+            0 => None,
+            _ => Some(Pubkey::new_from_array(*array_ref![data, 76, 32])),
+        },
+    })
+}
+
+/// Get the associated token account of a wallet for a mint
+pub fn get_associated_token_address(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), TOKEN_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Create the associated token account of a wallet for a mint
+pub fn create_associated_token_account(
+    payer: &Pubkey,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: ASSOCIATED_TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(get_associated_token_address(wallet, mint), false),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        ],
+        data: vec![],
+    }
+}
+
+/// The instruction tags of the SPL Token program
+const MINT_TO: u8 = 7;
+const BURN: u8 = 8;
+const CLOSE_ACCOUNT: u8 = 9;
+const SYNC_NATIVE: u8 = 17;
+const INITIALIZE_MINT2: u8 = 20;
+
+/// Initialize a mint without a freeze authority
+pub fn initialize_mint2(mint: &Pubkey, mint_authority: &Pubkey, decimals: u8) -> Instruction {
+    let mut data = vec![INITIALIZE_MINT2, decimals];
+    data.extend_from_slice(mint_authority.as_ref());
+    // No freeze authority
+    data.push(0);
+
+    Instruction {
+        program_id: TOKEN_PROGRAM_ID,
+        accounts: vec![AccountMeta::new(*mint, false)],
+        data,
+    }
+}
+
+/// Mint `amount` tokens to the destination token account
+pub fn mint_to(
+    mint: &Pubkey,
+    destination: &Pubkey,
+    mint_authority: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let mut data = vec![MINT_TO];
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    Instruction {
+        program_id: TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*mint, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*mint_authority, true),
+        ],
+        data,
+    }
+}
+
+/// Burn `amount` tokens from the source token account
+pub fn burn(source: &Pubkey, mint: &Pubkey, authority: &Pubkey, amount: u64) -> Instruction {
+    let mut data = vec![BURN];
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    Instruction {
+        program_id: TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*source, false),
+            AccountMeta::new(*mint, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+        data,
+    }
+}
+
+/// Close a token account, moving its lamports to the destination account
+pub fn close_account(account: &Pubkey, destination: &Pubkey, owner: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*account, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data: vec![CLOSE_ACCOUNT],
+    }
+}
+
+/// Update the amount of a wrapped SOL token account to the lamports it holds above its
+/// rent-exempt reserve
+pub fn sync_native(account: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: TOKEN_PROGRAM_ID,
+        accounts: vec![AccountMeta::new(*account, false)],
+        data: vec![SYNC_NATIVE],
+    }
+}
@@ -1,15 +1,36 @@
+#![deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unreachable
+)]
+
 use solana_program::{
     account_info::AccountInfo,
-    clock::UnixTimestamp,
+    bpf_loader_upgradeable,
+    clock::{Epoch, UnixTimestamp},
     entrypoint::ProgramResult,
+    hash::hash,
+    msg,
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack},
     pubkey::Pubkey,
+    stake::{self, state::Delegation},
+    stake_history::StakeHistory,
+    system_program,
+    sysvar::{self, rent::Rent},
 };
 
 use crate::{
     error::SolLockError,
-    state::{Account, State},
+    lending::Reserve,
+    oracle::{PythPrice, MAX_PRICE_AGE},
+    state::{
+        Account, AllowlistEntry, BeneficiaryShare, Config, Contribution, State, YieldBackend,
+        MAX_ALLOWED_VALIDATORS, MAX_BENEFICIARY_SHARES, MAX_GUARDIANS, MAX_LABEL_LEN, MAX_MEMO_LEN,
+        MAX_MULTISIG_SIGNERS, TOTAL_SHARE_BPS,
+    },
+    token::{unpack_token_account, NATIVE_MINT, TOKEN_PROGRAM_ID},
 };
 
 #[must_use]
@@ -24,12 +45,45 @@ pub fn assert_is_signer(account: &AccountInfo) -> ProgramResult {
 #[must_use]
 pub fn assert_keys_equal(key1: Pubkey, key2: Pubkey) -> ProgramResult {
     if key1 != key2 {
+        msg!("Expected key {}, found {}", key1, key2);
         Err(SolLockError::PublicKeyMismatch.into())
     } else {
         Ok(())
     }
 }
 
+/// Check that an account is the system program, so CPIs to it cannot be redirected
+#[must_use]
+pub fn assert_system_program(account: &AccountInfo) -> ProgramResult {
+    assert_keys_equal(system_program::id(), *account.key)
+}
+
+/// Check that an account is the stake program, so CPIs signed by a SolLock account cannot be redirected
+#[must_use]
+pub fn assert_stake_program(account: &AccountInfo) -> ProgramResult {
+    assert_keys_equal(stake::program::id(), *account.key)
+}
+
+#[must_use]
+pub fn assert_stake_config(account: &AccountInfo) -> ProgramResult {
+    assert_keys_equal(stake::config::id(), *account.key)
+}
+
+#[must_use]
+pub fn assert_clock_sysvar(account: &AccountInfo) -> ProgramResult {
+    assert_keys_equal(sysvar::clock::id(), *account.key)
+}
+
+#[must_use]
+pub fn assert_rent_sysvar(account: &AccountInfo) -> ProgramResult {
+    assert_keys_equal(sysvar::rent::id(), *account.key)
+}
+
+#[must_use]
+pub fn assert_stake_history_sysvar(account: &AccountInfo) -> ProgramResult {
+    assert_keys_equal(sysvar::stake_history::id(), *account.key)
+}
+
 #[must_use]
 pub fn assert_keys_unequal(key1: Pubkey, key2: Pubkey) -> ProgramResult {
     if key1 == key2 {
@@ -62,27 +116,96 @@ pub fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
     }
 }
 
+#[must_use]
+pub fn assert_not_owned_by(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
+    if account.owner == owner {
+        Err(SolLockError::IncorrectOwner.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that an account is in one of the expected states, logging both states otherwise
+#[must_use]
+pub fn assert_state(account: &Account, expected: &[State], error: SolLockError) -> ProgramResult {
+    if expected.contains(&account.state) {
+        Ok(())
+    } else {
+        msg!(
+            "Expected state in {:?}, found {:?}",
+            expected,
+            account.state
+        );
+        Err(error.into())
+    }
+}
+
 #[must_use]
 pub fn assert_has_funds(account: &Account) -> ProgramResult {
-    if account.lamports.is_some() && account.lamports.unwrap() > 0 {
+    if account.lamports.unwrap_or(0) > 0 {
         Ok(())
     } else {
         Err(SolLockError::NoFunds.into())
     }
 }
 
+#[must_use]
+pub fn assert_min_lock_amount(account: &Account, config: &Config) -> ProgramResult {
+    if account.lamports.unwrap_or(0) < config.min_lock_lamports {
+        Err(SolLockError::AmountTooSmall.into())
+    } else {
+        Ok(())
+    }
+}
+
 #[must_use]
 pub fn assert_sufficient_funds(account: &Account, lamports_to_remove: u64) -> ProgramResult {
-    if account.lamports.unwrap() < lamports_to_remove {
+    let lamports = account.lamports.ok_or(SolLockError::AccountFieldNotSet)?;
+    if lamports < lamports_to_remove {
+        msg!(
+            "Requested {} lamports, but only {} are available",
+            lamports_to_remove,
+            lamports
+        );
         Err(SolLockError::InsufficientFunds.into())
     } else {
         Ok(())
     }
 }
 
+/// Check that removing lamports from an account with the given balance leaves its
+/// rent-exempt reserve untouched
+#[must_use]
+pub fn assert_rent_reserve_kept(
+    account: &Account,
+    balance: u64,
+    lamports_to_remove: u64,
+) -> ProgramResult {
+    if balance.saturating_sub(account.rent_reserve) < lamports_to_remove {
+        Err(SolLockError::RentReserveNotKept.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that an account stays rent-exempt at its size after removing lamports from it
+#[must_use]
+pub fn assert_remains_rent_exempt(
+    rent: &Rent,
+    account_info: &AccountInfo,
+    lamports_to_remove: u64,
+) -> ProgramResult {
+    let balance = account_info.lamports().saturating_sub(lamports_to_remove);
+    if rent.is_exempt(balance, account_info.data_len()) {
+        Ok(())
+    } else {
+        Err(SolLockError::NotRentExempt.into())
+    }
+}
+
 #[must_use]
 pub fn assert_valid_new_deadline(account: &Account, deadline: UnixTimestamp) -> ProgramResult {
-    if account.deadline.is_some() && account.deadline.unwrap() > deadline {
+    if matches!(account.deadline, Some(current_deadline) if current_deadline > deadline) {
         Err(SolLockError::NewDeadlineTooEarly.into())
     } else {
         Ok(())
@@ -90,8 +213,91 @@ pub fn assert_valid_new_deadline(account: &Account, deadline: UnixTimestamp) ->
 }
 
 #[must_use]
-pub fn assert_can_lock(account: &Account) -> ProgramResult {
-    if account.state != State::ReadyUnlocked {
+pub fn assert_deadline_not_too_far(
+    deadline: UnixTimestamp,
+    now: UnixTimestamp,
+    config: &Config,
+) -> ProgramResult {
+    if deadline.saturating_sub(now) > config.max_lock_duration {
+        Err(SolLockError::DeadlineTooFar.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_can_lock(account: &Account, config: &Config) -> ProgramResult {
+    assert_state(
+        account,
+        &[State::ReadyUnlocked],
+        SolLockError::InvalidStateForLock,
+    )?;
+    assert_min_lock_amount(account, config)
+}
+
+/// Check that the commitment of an account can be set, which it cannot while it is locked
+#[must_use]
+pub fn assert_commitment_mutable(account: &Account) -> ProgramResult {
+    match account.state {
+        State::Locked | State::Staked | State::Disputed => {
+            Err(SolLockError::CommitmentLocked.into())
+        }
+        State::Uninitialized
+        | State::Initialized
+        | State::HasFunds
+        | State::HasDeadline
+        | State::ReadyUnlocked => Ok(()),
+    }
+}
+
+/// Check that an AllowlistEntry account exists, so its owner may create SolLock accounts
+#[must_use]
+pub fn assert_allowlisted(
+    allowlist_entry_info: &AccountInfo,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    if allowlist_entry_info.owner != program_id
+        || !AllowlistEntry::unpack_unchecked(&allowlist_entry_info.data.borrow())?.is_initialized()
+    {
+        Err(SolLockError::NotAllowlisted.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_valid_label(label: &str) -> ProgramResult {
+    if label.is_empty() || label.len() > MAX_LABEL_LEN {
+        Err(SolLockError::InvalidLabel.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_valid_memo(memo: &str) -> ProgramResult {
+    if memo.len() > MAX_MEMO_LEN {
+        Err(SolLockError::InvalidMemo.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_not_paused(config: &Config) -> ProgramResult {
+    if config.paused {
+        Err(SolLockError::ProgramPaused.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_valid_config(config: &Config) -> ProgramResult {
+    if config.admin == Pubkey::default()
+        || config.fee_bps > TOTAL_SHARE_BPS
+        || config.max_lock_duration <= 0
+    {
         Err(ProgramError::InvalidInstructionData)
     } else {
         Ok(())
@@ -99,31 +305,996 @@ pub fn assert_can_lock(account: &Account) -> ProgramResult {
 }
 
 #[must_use]
-pub fn assert_can_unlock(account: &Account, now: UnixTimestamp) -> ProgramResult {
+pub fn assert_can_withdraw_treasury(lamports: u64, withdrawable: u64) -> ProgramResult {
+    if lamports == 0 {
+        Err(SolLockError::NoFunds.into())
+    } else if lamports > withdrawable {
+        msg!(
+            "Requested {} lamports, but only {} are available",
+            lamports,
+            withdrawable
+        );
+        Err(SolLockError::InsufficientFunds.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that a signer is the upgrade authority of the program, given its ProgramData account
+#[must_use]
+pub fn assert_upgrade_authority(
+    program_id: &Pubkey,
+    program_data_info: &AccountInfo,
+    authority_info: &AccountInfo,
+) -> ProgramResult {
+    let program_data_key =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id()).0;
+    assert_keys_equal(program_data_key, *program_data_info.key)?;
+    assert_owned_by(program_data_info, &bpf_loader_upgradeable::id())?;
+    assert_is_signer(authority_info)?;
+
+    // A ProgramData account starts with its enum tag (3), the deployment slot,
+    // and the optional upgrade authority
+    let data = program_data_info.data.borrow();
+    if data.len() < 45 || data[..4] != 3u32.to_le_bytes() || data[12] != 1 {
+        return Err(SolLockError::NotUpgradeAuthority.into());
+    }
+    if data[13..45] != authority_info.key.to_bytes() {
+        return Err(SolLockError::NotUpgradeAuthority.into());
+    }
+
+    Ok(())
+}
+
+#[must_use]
+pub fn assert_can_add_sol_and_lock(account: &Account) -> ProgramResult {
+    assert_state(
+        account,
+        &[State::HasDeadline, State::ReadyUnlocked],
+        SolLockError::InvalidStateForAddSolAndLock,
+    )
+}
+
+#[must_use]
+pub fn assert_can_unlock(account: &Account, now: UnixTimestamp, epoch: Epoch) -> ProgramResult {
+    assert_state(
+        account,
+        &[State::Locked],
+        SolLockError::InvalidStateForUnlock,
+    )?;
+    if now < account.deadline.ok_or(SolLockError::AccountFieldNotSet)?
+        && (!account.price_target_reached || account.price_requires_deadline)
+    {
+        Err(SolLockError::PrematureUnlock.into())
+    } else if account.price_requires_deadline && !account.price_target_reached {
+        Err(SolLockError::PriceTargetNotReached.into())
+    } else if matches!(account.epoch_deadline, Some(epoch_deadline) if epoch < epoch_deadline) {
+        Err(SolLockError::PrematureUnlock.into())
+    } else if account.in_refund_period(now) {
+        Err(SolLockError::RefundPeriodActive.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that a receipt token can be minted against the locked lamports of an account
+#[must_use]
+pub fn assert_can_mint_receipt(account: &Account) -> ProgramResult {
+    if account.state != State::Locked && account.state != State::Staked {
+        Err(SolLockError::AccountNotLocked.into())
+    } else if account.receipt_minted > 0 {
+        Err(SolLockError::ReceiptAlreadyMinted.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that no receipt token or position NFT is outstanding against the lamports
+/// of an account, and that they are not converted into a liquid staking token or
+/// lent out, before any of them leave it
+#[must_use]
+pub fn assert_no_outstanding_tokens(account: &Account) -> ProgramResult {
+    if account.receipt_minted > 0 {
+        Err(SolLockError::ReceiptNotBurned.into())
+    } else if account.position_mint.is_some() {
+        Err(SolLockError::PositionOutstanding.into())
+    } else if account.lst_pool.is_some() {
+        Err(SolLockError::LstNotRedeemed.into())
+    } else if account.lending_reserve.is_some() {
+        Err(SolLockError::LendingNotWithdrawn.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that a position NFT can be minted for an account
+#[must_use]
+pub fn assert_can_mint_position(account: &Account) -> ProgramResult {
+    if account.state != State::Locked && account.state != State::Staked {
+        Err(SolLockError::AccountNotLocked.into())
+    } else if account.position_mint.is_some() {
+        Err(SolLockError::PositionAlreadyMinted.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that the holder holds the position NFT of an account in the token account
+#[must_use]
+pub fn assert_position_holder(
+    account: &Account,
+    holder_info: &AccountInfo,
+    holder_token_account_info: &AccountInfo,
+) -> ProgramResult {
+    let position_mint = account
+        .position_mint
+        .ok_or(SolLockError::NoPositionMinted)?;
+    assert_owned_by(holder_token_account_info, &TOKEN_PROGRAM_ID)?;
+    let token_account = unpack_token_account(&holder_token_account_info.data.borrow())?;
+
+    if token_account.mint != position_mint
+        || token_account.owner != *holder_info.key
+        || token_account.amount != 1
+    {
+        Err(SolLockError::NotPositionHolder.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_can_stake(account: &Account) -> ProgramResult {
     if account.state != State::Locked {
+        Err(SolLockError::AccountNotLocked.into())
+    } else if account.lst_pool.is_some() {
+        Err(SolLockError::LstNotRedeemed.into())
+    } else if account.lending_reserve.is_some() {
+        Err(SolLockError::LendingNotWithdrawn.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that the locked lamports of an account are not deposited into a yield backend
+#[must_use]
+pub fn assert_no_yield_backend(account: &Account) -> ProgramResult {
+    if account.yield_backend != YieldBackend::None {
+        Err(SolLockError::YieldBackendActive.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that the collateral tokens of a lending deposit are still held by the SolLock
+/// account alone, so they were never pledged to borrow against and cannot be liquidated
+#[must_use]
+pub fn assert_lending_collateral_held(
+    account: &Account,
+    collateral_account: &AccountInfo,
+    sol_lock_account_key: &Pubkey,
+) -> ProgramResult {
+    if *collateral_account.owner != TOKEN_PROGRAM_ID {
+        return Err(SolLockError::LendingCollateralNotHeld.into());
+    }
+
+    let collateral = unpack_token_account(&collateral_account.data.borrow())?;
+    if collateral.owner != *sol_lock_account_key
+        || collateral.delegate.is_some()
+        || collateral.amount < account.lending_collateral
+    {
+        Err(SolLockError::LendingCollateralNotHeld.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that a lending reserve lends out wrapped SOL from the accounts passed
+#[must_use]
+pub fn assert_lending_reserve(
+    reserve: &Reserve,
+    liquidity_supply: &Pubkey,
+    collateral_mint: &Pubkey,
+    lending_market: &Pubkey,
+) -> ProgramResult {
+    if reserve.liquidity_mint != NATIVE_MINT
+        || reserve.liquidity_supply != *liquidity_supply
+        || reserve.collateral_mint != *collateral_mint
+        || reserve.lending_market != *lending_market
+    {
+        Err(SolLockError::InvalidLendingReserve.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_valid_validator_allowlist(validators: &[Pubkey]) -> ProgramResult {
+    let has_duplicates = validators
+        .iter()
+        .enumerate()
+        .any(|(index, validator)| validators[..index].contains(validator));
+
+    if validators.len() > MAX_ALLOWED_VALIDATORS || has_duplicates {
+        Err(SolLockError::InvalidValidatorAllowlist.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that the locked lamports of an account can be delegated to a vote account
+#[must_use]
+pub fn assert_validator_allowed(account: &Account, vote_account: &Pubkey) -> ProgramResult {
+    if account.allowed_validators.is_empty() || account.allowed_validators.contains(vote_account) {
+        Ok(())
+    } else {
+        Err(SolLockError::ValidatorNotAllowed.into())
+    }
+}
+
+#[must_use]
+pub fn assert_can_unstake(account: &Account) -> ProgramResult {
+    assert_state(
+        account,
+        &[State::Staked],
+        SolLockError::InvalidStateForUnstake,
+    )
+}
+
+#[must_use]
+pub fn assert_stake_deactivated(
+    delegation: &Delegation,
+    epoch: Epoch,
+    stake_history: &StakeHistory,
+) -> ProgramResult {
+    let status = delegation.stake_activating_and_deactivating(epoch, Some(stake_history));
+    if status.effective != 0 || status.activating != 0 || status.deactivating != 0 {
+        Err(SolLockError::StakeNotDeactivated.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_can_accept_owner(
+    account: &Account,
+    new_owner: &Pubkey,
+    now: UnixTimestamp,
+) -> ProgramResult {
+    if account.pending_owner != Some(*new_owner) {
+        return Err(SolLockError::PendingOwnerMismatch.into());
+    }
+    assert_state(
+        account,
+        &[
+            State::Uninitialized,
+            State::Initialized,
+            State::HasFunds,
+            State::HasDeadline,
+            State::ReadyUnlocked,
+            State::Locked,
+        ],
+        SolLockError::InvalidStateForOwnerChange,
+    )?;
+    if matches!(account.recovery_available_at, Some(available_at) if now < available_at) {
+        return Err(SolLockError::RecoveryPending.into());
+    }
+
+    // These accounts are derived from the SolLock address, which changes with the owner
+    assert_no_yield_backend(account)?;
+    if account.position_mint.is_some() {
+        Err(SolLockError::PositionOutstanding.into())
+    } else if account.split_stake_account.is_some() {
+        Err(SolLockError::SplitStakeOutstanding.into())
+    } else if account.name_records > 0 {
+        Err(SolLockError::NameRecordsOutstanding.into())
+    } else if account.refunds_pending(now) {
+        Err(SolLockError::RefundsPending.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_unlock_authorized(
+    account: &Account,
+    owner_info: &AccountInfo,
+    unlock_authority_res: Result<&AccountInfo, ProgramError>,
+) -> ProgramResult {
+    match account.unlock_authority {
+        Some(unlock_authority) => {
+            let unlock_authority_info =
+                unlock_authority_res.map_err(|_| SolLockError::MissingUnlockAuthority)?;
+            assert_keys_equal(unlock_authority, *unlock_authority_info.key)?;
+            assert_is_signer(unlock_authority_info)
+        }
+        None => assert_is_signer(owner_info),
+    }
+}
+
+/// Check that an unlock is authorized and co-signed by enough multisig signers
+///
+/// `signer_infos` are the accounts after the fixed accounts of the instruction: the unlock
+/// authority first if the account has one, then the multisig co-signers.
+#[must_use]
+pub fn assert_unlock_approved(
+    account: &Account,
+    owner_info: &AccountInfo,
+    signer_infos: &[AccountInfo],
+) -> ProgramResult {
+    let cosigner_infos = match account.unlock_authority {
+        Some(_) => {
+            let (unlock_authority_info, cosigner_infos) = signer_infos
+                .split_first()
+                .ok_or(SolLockError::MissingUnlockAuthority)?;
+            assert_unlock_authorized(account, owner_info, Ok(unlock_authority_info))?;
+            cosigner_infos
+        }
+        None => {
+            assert_is_signer(owner_info)?;
+            signer_infos
+        }
+    };
+
+    assert_multisig_approved(account, cosigner_infos)
+}
+
+#[must_use]
+pub fn assert_multisig_approved(
+    account: &Account,
+    cosigner_infos: &[AccountInfo],
+) -> ProgramResult {
+    if account.multisig_threshold == 0 {
+        return Ok(());
+    }
+
+    let approvals = account
+        .multisig_signers
+        .iter()
+        .filter(|signer| {
+            cosigner_infos
+                .iter()
+                .any(|info| info.is_signer && info.key == *signer)
+        })
+        .count();
+
+    if approvals < account.multisig_threshold as usize {
+        Err(SolLockError::MultisigThresholdNotMet.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_valid_multisig(signers: &[Pubkey], threshold: u8) -> ProgramResult {
+    let has_duplicates = signers
+        .iter()
+        .enumerate()
+        .any(|(index, signer)| signers[..index].contains(signer));
+
+    if signers.is_empty() && threshold == 0 {
+        Ok(())
+    } else if signers.len() > MAX_MULTISIG_SIGNERS
+        || threshold == 0
+        || threshold as usize > signers.len()
+        || has_duplicates
+    {
+        Err(SolLockError::InvalidMultisig.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_valid_guardians(
+    guardians: &[Pubkey],
+    threshold: u8,
+    recovery_delay: Option<UnixTimestamp>,
+) -> ProgramResult {
+    let has_duplicates = guardians
+        .iter()
+        .enumerate()
+        .any(|(index, guardian)| guardians[..index].contains(guardian));
+
+    if guardians.is_empty() && threshold == 0 && recovery_delay.is_none() {
+        Ok(())
+    } else if guardians.len() > MAX_GUARDIANS
+        || threshold == 0
+        || threshold as usize > guardians.len()
+        || has_duplicates
+        || !matches!(recovery_delay, Some(delay) if delay > 0)
+    {
+        Err(SolLockError::InvalidGuardians.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_guardians_approved(
+    account: &Account,
+    guardian_infos: &[AccountInfo],
+) -> ProgramResult {
+    let approvals = account
+        .guardians
+        .iter()
+        .filter(|guardian| {
+            guardian_infos
+                .iter()
+                .any(|info| info.is_signer && info.key == *guardian)
+        })
+        .count();
+
+    if account.guardian_threshold == 0 || approvals < account.guardian_threshold as usize {
+        Err(SolLockError::GuardianThresholdNotMet.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_no_pending_recovery(account: &Account) -> ProgramResult {
+    if account.recovery_available_at.is_some() {
+        Err(SolLockError::RecoveryPending.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_stored_receiver(account: &Account, receiver_info: &AccountInfo) -> ProgramResult {
+    match account.receiver {
+        Some(receiver) if receiver != *receiver_info.key => {
+            Err(SolLockError::ReceiverMismatch.into())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[must_use]
+pub fn assert_not_locked(account: &Account) -> ProgramResult {
+    if account.state == State::Locked
+        || account.state == State::Staked
+        || account.state == State::Disputed
+    {
+        Err(SolLockError::FundsLocked.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_can_crank(account: &Account) -> Result<u64, ProgramError> {
+    account
+        .crank_tip
+        .ok_or_else(|| SolLockError::CrankingDisabled.into())
+}
+
+/// Check that the beneficiary of the account cannot claim it yet, as cranking it then would
+/// send the lamports meant for the beneficiary to the owner
+#[must_use]
+pub fn assert_beneficiary_claim_closed(account: &Account, now: UnixTimestamp) -> ProgramResult {
+    match account.beneficiary_claim_time() {
+        Some(claim_time) if now >= claim_time => Err(SolLockError::BeneficiaryClaimOpen.into()),
+        _ => Ok(()),
+    }
+}
+
+#[must_use]
+pub fn assert_valid_auto_extend(auto_extend: Option<UnixTimestamp>) -> ProgramResult {
+    match auto_extend {
+        Some(period) if period <= 0 => Err(SolLockError::InvalidAutoExtendPeriod.into()),
+        _ => Ok(()),
+    }
+}
+
+#[must_use]
+pub fn assert_valid_vesting(
+    account: &Account,
+    vesting_start: Option<UnixTimestamp>,
+    vesting_cliff: Option<UnixTimestamp>,
+) -> ProgramResult {
+    let cliff = match vesting_cliff {
+        Some(cliff) => cliff,
+        None => return Ok(()),
+    };
+
+    match (vesting_start, account.deadline) {
+        (Some(start), _) if cliff < start => Err(SolLockError::InvalidVestingSchedule.into()),
+        (Some(_), Some(deadline)) if cliff > deadline => {
+            Err(SolLockError::InvalidVestingSchedule.into())
+        }
+        (Some(_), _) => Ok(()),
+        (None, _) => Err(SolLockError::InvalidVestingSchedule.into()),
+    }
+}
+
+#[must_use]
+pub fn assert_single_schedule(vesting: bool, streaming: bool) -> ProgramResult {
+    if vesting && streaming {
+        Err(SolLockError::ConflictingSchedules.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_can_claim_streamed(account: &Account, receiver_info: &AccountInfo) -> ProgramResult {
+    if account.state != State::Locked || account.stream_start.is_none() {
+        return Err(SolLockError::FundsLocked.into());
+    }
+
+    assert_keys_equal(
+        account.receiver.unwrap_or(account.owner),
+        *receiver_info.key,
+    )
+}
+
+#[must_use]
+pub fn assert_valid_hash_lock(
+    hash_lock: Option<[u8; 32]>,
+    counterparty: Option<Pubkey>,
+) -> ProgramResult {
+    if hash_lock.is_some() != counterparty.is_some() {
         Err(ProgramError::InvalidInstructionData)
-    } else if now < account.deadline.unwrap() {
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_can_claim_hash_lock(
+    account: &Account,
+    counterparty_info: &AccountInfo,
+    preimage: &[u8],
+    now: UnixTimestamp,
+) -> ProgramResult {
+    let (hash_lock, counterparty) = match (account.state, account.hash_lock, account.counterparty) {
+        (State::Locked, Some(hash_lock), Some(counterparty)) => (hash_lock, counterparty),
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+
+    assert_keys_equal(counterparty, *counterparty_info.key)?;
+
+    if now >= account.deadline.ok_or(SolLockError::AccountFieldNotSet)? {
+        Err(SolLockError::HashLockExpired.into())
+    } else if hash(preimage).to_bytes() != hash_lock {
+        Err(SolLockError::InvalidPreimage.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_can_claim_escrow(
+    account: &Account,
+    counterparty_info: &AccountInfo,
+    now: UnixTimestamp,
+) -> ProgramResult {
+    let counterparty = match (account.state, account.hash_lock, account.counterparty) {
+        (State::Locked, None, Some(counterparty)) => counterparty,
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+
+    assert_keys_equal(counterparty, *counterparty_info.key)?;
+
+    if now >= account.deadline.ok_or(SolLockError::AccountFieldNotSet)? {
+        Err(SolLockError::EscrowExpired.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_can_refund_escrow(account: &Account, now: UnixTimestamp) -> ProgramResult {
+    match (account.state, account.hash_lock, account.counterparty) {
+        (State::Locked, None, Some(_)) | (State::Disputed, None, Some(_)) => {}
+        _ => return Err(ProgramError::InvalidInstructionData),
+    }
+
+    if now < account.deadline.ok_or(SolLockError::AccountFieldNotSet)? {
         Err(SolLockError::PrematureUnlock.into())
     } else {
         Ok(())
     }
 }
 
+#[must_use]
+pub fn assert_valid_arbiter(
+    counterparty: Option<Pubkey>,
+    arbiter: Option<Pubkey>,
+    dispute_period: Option<UnixTimestamp>,
+) -> ProgramResult {
+    match (counterparty, arbiter, dispute_period) {
+        (_, None, None) => Ok(()),
+        (Some(_), Some(_), Some(dispute_period)) if dispute_period > 0 => Ok(()),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+#[must_use]
+pub fn assert_can_raise_dispute(
+    account: &Account,
+    party_info: &AccountInfo,
+    now: UnixTimestamp,
+) -> ProgramResult {
+    let counterparty = match (account.state, account.counterparty, account.arbiter) {
+        (State::Locked, Some(counterparty), Some(_)) => counterparty,
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+
+    if *party_info.key != account.owner && *party_info.key != counterparty {
+        Err(SolLockError::PublicKeyMismatch.into())
+    } else if now >= account.deadline.ok_or(SolLockError::AccountFieldNotSet)? {
+        Err(SolLockError::EscrowExpired.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_can_resolve_dispute(
+    account: &Account,
+    arbiter_info: &AccountInfo,
+    counterparty_info: &AccountInfo,
+    now: UnixTimestamp,
+) -> ProgramResult {
+    let (counterparty, arbiter) = match (account.state, account.counterparty, account.arbiter) {
+        (State::Disputed, Some(counterparty), Some(arbiter)) => (counterparty, arbiter),
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+
+    assert_keys_equal(arbiter, *arbiter_info.key)?;
+    assert_keys_equal(counterparty, *counterparty_info.key)?;
+
+    if now >= account.deadline.ok_or(SolLockError::AccountFieldNotSet)? {
+        Err(SolLockError::DisputeExpired.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_open_deposits(account: &Account) -> ProgramResult {
+    if !account.open_deposits {
+        Err(SolLockError::DepositsClosed.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_valid_raise_target(
+    raise_target: Option<u64>,
+    refund_period: Option<UnixTimestamp>,
+) -> ProgramResult {
+    match (raise_target, refund_period) {
+        (None, None) => Ok(()),
+        (Some(_), Some(refund_period)) if refund_period > 0 => Ok(()),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+#[must_use]
+pub fn assert_valid_price_condition(
+    price_feed: Option<Pubkey>,
+    price_target: Option<i64>,
+    requires_deadline: bool,
+) -> ProgramResult {
+    match (price_feed, price_target) {
+        (None, None) if !requires_deadline => Ok(()),
+        (Some(_), Some(_)) => Ok(()),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+#[must_use]
+pub fn assert_price_target_reached(
+    account: &Account,
+    price: &PythPrice,
+    now: UnixTimestamp,
+) -> ProgramResult {
+    assert_state(
+        account,
+        &[State::Locked],
+        SolLockError::InvalidStateForPriceCheck,
+    )?;
+    if now.saturating_sub(price.publish_time) > MAX_PRICE_AGE {
+        Err(SolLockError::StalePrice.into())
+    } else if price.price
+        < account
+            .price_target
+            .ok_or(SolLockError::AccountFieldNotSet)?
+    {
+        Err(SolLockError::PriceTargetNotReached.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_crowdfund_open(account: &Account, now: UnixTimestamp) -> ProgramResult {
+    match (account.raise_target, account.deadline) {
+        (Some(_), Some(deadline)) if now >= deadline => Err(SolLockError::CrowdfundEnded.into()),
+        _ => Ok(()),
+    }
+}
+
+#[must_use]
+pub fn assert_can_refund_contribution(
+    account: &Account,
+    contribution: &Contribution,
+    now: UnixTimestamp,
+) -> ProgramResult {
+    if !account.in_refund_period(now) {
+        Err(SolLockError::RefundUnavailable.into())
+    } else if contribution.lamports == 0 {
+        Err(SolLockError::NoFunds.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_valid_beneficiary(
+    beneficiary: Option<Pubkey>,
+    heartbeat_interval: Option<UnixTimestamp>,
+) -> ProgramResult {
+    match (beneficiary, heartbeat_interval) {
+        (None, None) => Ok(()),
+        (Some(_), Some(heartbeat_interval)) if heartbeat_interval > 0 => Ok(()),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+#[must_use]
+pub fn assert_valid_beneficiary_shares(shares: &[BeneficiaryShare]) -> ProgramResult {
+    if shares.is_empty() {
+        return Ok(());
+    }
+
+    let total_bps = shares
+        .iter()
+        .try_fold(0u16, |total, share| total.checked_add(share.bps));
+    let has_duplicates = shares.iter().enumerate().any(|(index, share)| {
+        shares[..index]
+            .iter()
+            .any(|other| other.beneficiary == share.beneficiary)
+    });
+
+    if shares.len() > MAX_BENEFICIARY_SHARES
+        || shares.iter().any(|share| share.bps == 0)
+        || total_bps != Some(TOTAL_SHARE_BPS)
+        || has_duplicates
+    {
+        Err(SolLockError::InvalidBeneficiaryShares.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_can_claim_as_beneficiary(
+    account: &Account,
+    beneficiary_info: &AccountInfo,
+    now: UnixTimestamp,
+) -> ProgramResult {
+    let claim_time = account
+        .beneficiary_claim_time()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    let beneficiary = account
+        .beneficiary
+        .ok_or(SolLockError::AccountFieldNotSet)?;
+    assert_keys_equal(beneficiary, *beneficiary_info.key)?;
+
+    if now < claim_time {
+        Err(SolLockError::OwnerStillActive.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_no_unlock_authority(account: &Account) -> ProgramResult {
+    if account.unlock_authority.is_some() {
+        Err(SolLockError::MissingUnlockAuthority.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_can_withdraw_vested(
+    account: &Account,
+    lamports_to_remove: u64,
+    now: UnixTimestamp,
+) -> ProgramResult {
+    if account.state != State::Locked || account.vesting_start.is_none() {
+        return Err(SolLockError::FundsLocked.into());
+    }
+    match account.withdrawable_vested_lamports(now) {
+        Some(withdrawable) if withdrawable >= lamports_to_remove && lamports_to_remove > 0 => {
+            Ok(())
+        }
+        _ => Err(SolLockError::InsufficientVestedFunds.into()),
+    }
+}
+
+#[must_use]
+pub fn assert_valid_nonce(account: &Account, nonce: Option<u64>) -> ProgramResult {
+    if nonce != Some(account.nonce) {
+        Err(SolLockError::NonceMismatch.into())
+    } else {
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn assert_payer_validity<'a, 'b>(
+    owner_info: &'a AccountInfo<'b>,
+    sol_payer_account_res: Result<&'a AccountInfo<'b>, ProgramError>,
+    has_payer: bool,
+) -> Result<&'a AccountInfo<'b>, ProgramError> {
+    match sol_payer_account_res {
+        Ok(sol_payer_account_info) if has_payer => Ok(sol_payer_account_info),
+        Err(_) if !has_payer => Ok(owner_info),
+        _ => Err(SolLockError::ConflictingPayerInfo.into()),
+    }
+}
+
 #[must_use]
 pub fn assert_receiver_validity<'a, 'b>(
     owner_info: &'a AccountInfo<'b>,
     sol_receiver_account_res: Result<&'a AccountInfo<'b>, ProgramError>,
     has_receiver: bool,
 ) -> Result<&'a AccountInfo<'b>, ProgramError> {
-    if sol_receiver_account_res.is_ok() {
-        if !has_receiver {
-            return Err(SolLockError::ConflictingPayerInfo.into());
-        }
-        Ok(sol_receiver_account_res.unwrap())
-    } else {
-        if has_receiver {
-            return Err(SolLockError::ConflictingPayerInfo.into());
+    match sol_receiver_account_res {
+        Ok(sol_receiver_account_info) if has_receiver => Ok(sol_receiver_account_info),
+        Err(_) if !has_receiver => Ok(owner_info),
+        _ => Err(SolLockError::ConflictingPayerInfo.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: UnixTimestamp = 1_700_000_000;
+
+    fn field_not_set() -> ProgramResult {
+        Err(SolLockError::AccountFieldNotSet.into())
+    }
+
+    /// A locked account of a party and a counterparty, with no deadline
+    fn locked_without_deadline(counterparty: Pubkey) -> Account {
+        Account {
+            state: State::Locked,
+            lamports: Some(1_000_000),
+            deadline: None,
+            counterparty: Some(counterparty),
+            ..Account::default()
         }
-        Ok(owner_info)
+    }
+
+    fn test_account_info<'a>(key: &'a Pubkey, lamports: &'a mut u64) -> AccountInfo<'a> {
+        AccountInfo::new(key, true, false, lamports, &mut [], key, false, 0)
+    }
+
+    #[test]
+    fn test_missing_lamports_are_rejected() {
+        let account = Account {
+            state: State::HasFunds,
+            lamports: None,
+            ..Account::default()
+        };
+
+        assert_eq!(
+            assert_has_funds(&account),
+            Err(SolLockError::NoFunds.into())
+        );
+        assert_eq!(assert_sufficient_funds(&account, 1), field_not_set());
+    }
+
+    #[test]
+    fn test_missing_deadline_is_rejected() {
+        let counterparty = Pubkey::new_unique();
+        let mut lamports = 0;
+        let counterparty_info = test_account_info(&counterparty, &mut lamports);
+
+        let account = locked_without_deadline(counterparty);
+        assert_eq!(assert_can_unlock(&account, NOW, 0), field_not_set());
+        assert_eq!(
+            assert_can_claim_escrow(&account, &counterparty_info, NOW),
+            field_not_set()
+        );
+        assert_eq!(assert_can_refund_escrow(&account, NOW), field_not_set());
+        assert_eq!(assert_valid_new_deadline(&account, NOW), Ok(()));
+
+        let account = Account {
+            hash_lock: Some(hash(b"preimage").to_bytes()),
+            ..locked_without_deadline(counterparty)
+        };
+        assert_eq!(
+            assert_can_claim_hash_lock(&account, &counterparty_info, b"preimage", NOW),
+            field_not_set()
+        );
+
+        let account = Account {
+            arbiter: Some(counterparty),
+            ..locked_without_deadline(counterparty)
+        };
+        assert_eq!(
+            assert_can_raise_dispute(&account, &counterparty_info, NOW),
+            field_not_set()
+        );
+
+        let account = Account {
+            state: State::Disputed,
+            ..account
+        };
+        assert_eq!(
+            assert_can_resolve_dispute(&account, &counterparty_info, &counterparty_info, NOW),
+            field_not_set()
+        );
+    }
+
+    #[test]
+    fn test_missing_price_target_is_rejected() {
+        let account = Account {
+            state: State::Locked,
+            price_target: None,
+            ..Account::default()
+        };
+        let price = PythPrice {
+            price: 100,
+            expo: 0,
+            publish_time: NOW,
+        };
+
+        assert_eq!(
+            assert_price_target_reached(&account, &price, NOW),
+            field_not_set()
+        );
+    }
+
+    #[test]
+    fn test_missing_beneficiary_is_rejected() {
+        let beneficiary = Pubkey::new_unique();
+        let mut lamports = 0;
+        let beneficiary_info = test_account_info(&beneficiary, &mut lamports);
+        let account = Account {
+            state: State::Locked,
+            deadline: Some(NOW),
+            heartbeat_interval: Some(60),
+            beneficiary: None,
+            ..Account::default()
+        };
+
+        assert!(assert_can_claim_as_beneficiary(&account, &beneficiary_info, NOW).is_err());
+    }
+
+    #[test]
+    fn test_receiver_validity() {
+        let owner = Pubkey::new_unique();
+        let receiver = Pubkey::new_unique();
+        let (mut owner_lamports, mut receiver_lamports) = (0, 0);
+        let owner_info = test_account_info(&owner, &mut owner_lamports);
+        let receiver_info = test_account_info(&receiver, &mut receiver_lamports);
+        let conflicting: Result<Pubkey, ProgramError> =
+            Err(SolLockError::ConflictingPayerInfo.into());
+
+        let resolve = |receiver_res, has_receiver| {
+            assert_receiver_validity(&owner_info, receiver_res, has_receiver).map(|info| *info.key)
+        };
+        assert_eq!(resolve(Ok(&receiver_info), true), Ok(receiver));
+        assert_eq!(
+            resolve(Err(ProgramError::NotEnoughAccountKeys), false),
+            Ok(owner)
+        );
+        assert_eq!(resolve(Ok(&receiver_info), false), conflicting);
+        assert_eq!(
+            resolve(Err(ProgramError::NotEnoughAccountKeys), true),
+            conflicting
+        );
     }
 }
@@ -0,0 +1,628 @@
+//! The yield backends the locked lamports of a SolLock account can be deposited into
+//!
+//! Each backend implements YieldAdapter, so the processor only decides when the locked
+//! lamports move and which state the account transitions to, and a new backend only needs
+//! an adapter and the instructions that build it.
+use solana_program::{
+    account_info::AccountInfo,
+    borsh::try_from_slice_unchecked,
+    clock::Epoch,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    stake::{
+        instruction as stake_instruction, program as stake_program,
+        state::{Authorized, Lockup, StakeState},
+    },
+    stake_history::StakeHistory,
+    system_instruction,
+    sysvar::{clock::Clock, Sysvar},
+};
+
+use crate::{
+    error::SolLockError,
+    lamport_utils::transfer_lamports,
+    lending::{self, LendingReserveAccounts},
+    stake_pool::{self, StakePoolAccounts},
+    state::YieldBackend,
+    token::{self, unpack_token_account, TOKEN_PROGRAM_ID},
+    validation_utils::assert_stake_deactivated,
+};
+
+/// A yield backend the locked lamports of a SolLock account can be deposited into
+pub trait YieldAdapter {
+    /// The backend, recorded in the SolLock account while lamports are deposited into it
+    fn backend(&self) -> YieldBackend;
+
+    /// Move `lamports` out of the SolLock account into the backend
+    fn deposit(&self, lamports: u64) -> ProgramResult;
+
+    /// Prepare to withdraw everything from the backend
+    ///
+    /// Returns whether it can be withdrawn right away, or only later, e.g. after a stake cooldown.
+    fn start_withdrawal(&self) -> Result<bool, ProgramError>;
+
+    /// Move everything out of the backend back into the SolLock account
+    ///
+    /// Returns the lamports received, including any rewards.
+    fn withdraw(&self) -> Result<u64, ProgramError>;
+}
+
+/// The SolLock account, which signs for the lamports it deposits
+pub struct SolLockSigner<'a, 'b> {
+    /// The SolLock account
+    pub info: &'b AccountInfo<'a>,
+    /// The seeds of the SolLock account, including its bump seed
+    pub seeds: &'b [&'b [u8]],
+}
+
+/// The backend of accounts whose locked lamports stay in the account
+pub struct NoYield;
+
+impl YieldAdapter for NoYield {
+    fn backend(&self) -> YieldBackend {
+        YieldBackend::None
+    }
+
+    fn deposit(&self, _lamports: u64) -> ProgramResult {
+        Ok(())
+    }
+
+    fn start_withdrawal(&self) -> Result<bool, ProgramError> {
+        Ok(true)
+    }
+
+    fn withdraw(&self) -> Result<u64, ProgramError> {
+        Ok(0)
+    }
+}
+
+/// Delegating the locked lamports from a stake account the SolLock account is the authority of
+pub struct NativeStake<'a, 'b> {
+    /// The SolLock account
+    pub sol_lock: SolLockSigner<'a, 'b>,
+    /// The stake account
+    pub stake_account_info: &'b AccountInfo<'a>,
+    /// The clock sysvar account
+    pub clock_info: &'b AccountInfo<'a>,
+    /// The stake history sysvar account
+    pub stake_history_info: &'b AccountInfo<'a>,
+    /// The stake program account
+    pub stake_program_info: &'b AccountInfo<'a>,
+    /// The accounts only needed to deposit
+    pub delegation: Option<NativeStakeDelegation<'a, 'b>>,
+}
+
+/// The accounts needed to create a stake account and delegate it
+pub struct NativeStakeDelegation<'a, 'b> {
+    /// The seeds of the stake account, including its bump seed
+    pub stake_account_seeds: &'b [&'b [u8]],
+    /// The vote account to delegate to
+    pub vote_account_info: &'b AccountInfo<'a>,
+    /// The rent sysvar account
+    pub rent_info: &'b AccountInfo<'a>,
+    /// The stake config account
+    pub stake_config_info: &'b AccountInfo<'a>,
+    /// The system program account
+    pub system_account_info: &'b AccountInfo<'a>,
+}
+
+impl YieldAdapter for NativeStake<'_, '_> {
+    fn backend(&self) -> YieldBackend {
+        YieldBackend::NativeStake
+    }
+
+    fn deposit(&self, lamports: u64) -> ProgramResult {
+        let delegation = self
+            .delegation
+            .as_ref()
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+        verbose_msg!(
+            "Staking {} lamports in stake account {:#?} with vote account {:#?}",
+            lamports,
+            self.stake_account_info.key,
+            delegation.vote_account_info.key
+        );
+
+        // The stake account is funded with the locked lamports alone, so its rent-exempt
+        // reserve comes out of the principal and is returned when unstaking
+        invoke_signed(
+            &system_instruction::allocate(
+                self.stake_account_info.key,
+                std::mem::size_of::<StakeState>() as u64,
+            ),
+            &[
+                self.stake_account_info.clone(),
+                delegation.system_account_info.clone(),
+            ],
+            &[delegation.stake_account_seeds],
+        )?;
+        invoke_signed(
+            &system_instruction::assign(self.stake_account_info.key, &stake_program::id()),
+            &[
+                self.stake_account_info.clone(),
+                delegation.system_account_info.clone(),
+            ],
+            &[delegation.stake_account_seeds],
+        )?;
+
+        transfer_lamports(self.sol_lock.info, self.stake_account_info, lamports)?;
+
+        invoke(
+            &stake_instruction::initialize(
+                self.stake_account_info.key,
+                &Authorized {
+                    staker: *self.sol_lock.info.key,
+                    withdrawer: *self.sol_lock.info.key,
+                },
+                &Lockup::default(),
+            ),
+            &[
+                self.stake_account_info.clone(),
+                delegation.rent_info.clone(),
+                self.stake_program_info.clone(),
+            ],
+        )?;
+        invoke_signed(
+            &stake_instruction::delegate_stake(
+                self.stake_account_info.key,
+                self.sol_lock.info.key,
+                delegation.vote_account_info.key,
+            ),
+            &[
+                self.stake_account_info.clone(),
+                delegation.vote_account_info.clone(),
+                self.clock_info.clone(),
+                self.stake_history_info.clone(),
+                delegation.stake_config_info.clone(),
+                self.sol_lock.info.clone(),
+                self.stake_program_info.clone(),
+            ],
+            &[self.sol_lock.seeds],
+        )
+    }
+
+    fn start_withdrawal(&self) -> Result<bool, ProgramError> {
+        let stake_state =
+            try_from_slice_unchecked::<StakeState>(&self.stake_account_info.data.borrow())
+                .map_err(|_| SolLockError::InvalidStakeAccount)?;
+        let delegation = stake_state
+            .delegation()
+            .ok_or(SolLockError::InvalidStakeAccount)?;
+
+        if delegation.deactivation_epoch == Epoch::MAX {
+            verbose_msg!(
+                "Deactivating stake account {:#?}",
+                self.stake_account_info.key
+            );
+
+            invoke_signed(
+                &stake_instruction::deactivate_stake(
+                    self.stake_account_info.key,
+                    self.sol_lock.info.key,
+                ),
+                &[
+                    self.stake_account_info.clone(),
+                    self.clock_info.clone(),
+                    self.sol_lock.info.clone(),
+                    self.stake_program_info.clone(),
+                ],
+                &[self.sol_lock.seeds],
+            )?;
+
+            return Ok(false);
+        }
+
+        let clock = Clock::from_account_info(self.clock_info)?;
+        let stake_history = StakeHistory::from_account_info(self.stake_history_info)?;
+        assert_stake_deactivated(&delegation, clock.epoch, &stake_history)?;
+
+        Ok(true)
+    }
+
+    fn withdraw(&self) -> Result<u64, ProgramError> {
+        let lamports = self.stake_account_info.lamports();
+
+        verbose_msg!(
+            "Withdrawing {} lamports from stake account {:#?}",
+            lamports,
+            self.stake_account_info.key
+        );
+
+        invoke_signed(
+            &stake_instruction::withdraw(
+                self.stake_account_info.key,
+                self.sol_lock.info.key,
+                self.sol_lock.info.key,
+                lamports,
+                None,
+            ),
+            &[
+                self.stake_account_info.clone(),
+                self.sol_lock.info.clone(),
+                self.clock_info.clone(),
+                self.stake_history_info.clone(),
+                self.stake_program_info.clone(),
+            ],
+            &[self.sol_lock.seeds],
+        )?;
+
+        Ok(lamports)
+    }
+}
+
+/// Depositing the locked lamports into an SPL stake pool for its pool token, which the
+/// SolLock account holds in its associated token account
+pub struct StakePool<'a, 'b> {
+    /// The SolLock account
+    pub sol_lock: SolLockSigner<'a, 'b>,
+    /// The stake pool account
+    pub stake_pool_info: &'b AccountInfo<'a>,
+    /// The withdraw authority of the stake pool
+    pub withdraw_authority_info: &'b AccountInfo<'a>,
+    /// The reserve stake account of the stake pool
+    pub reserve_stake_info: &'b AccountInfo<'a>,
+    /// The pool token account the manager fees are paid to
+    pub manager_fee_account_info: &'b AccountInfo<'a>,
+    /// The pool token mint
+    pub pool_mint_info: &'b AccountInfo<'a>,
+    /// The associated token account of the SolLock account for the pool mint
+    pub pool_token_account_info: &'b AccountInfo<'a>,
+    /// The SPL Stake Pool program account
+    pub stake_pool_program_info: &'b AccountInfo<'a>,
+    /// The SPL Token program account
+    pub token_program_info: &'b AccountInfo<'a>,
+    /// The accounts only needed to deposit
+    pub deposit: Option<StakePoolDeposit<'a, 'b>>,
+    /// The accounts only needed to withdraw
+    pub withdrawal: Option<StakePoolWithdrawal<'a, 'b>>,
+}
+
+/// The accounts needed to deposit into a stake pool
+pub struct StakePoolDeposit<'a, 'b> {
+    /// The account that pays for the pool token account if it does not exist yet
+    pub payer_info: &'b AccountInfo<'a>,
+    /// The system account the lamports are deposited from
+    pub funding_account: SolLockSigner<'a, 'b>,
+    /// The SPL Associated Token Account program account
+    pub associated_token_program_info: &'b AccountInfo<'a>,
+    /// The system program account
+    pub system_account_info: &'b AccountInfo<'a>,
+}
+
+/// The accounts needed to withdraw from a stake pool
+pub struct StakePoolWithdrawal<'a, 'b> {
+    /// The clock sysvar account
+    pub clock_info: &'b AccountInfo<'a>,
+    /// The stake history sysvar account
+    pub stake_history_info: &'b AccountInfo<'a>,
+    /// The stake program account
+    pub stake_program_info: &'b AccountInfo<'a>,
+}
+
+impl StakePool<'_, '_> {
+    fn pool_accounts(&self) -> StakePoolAccounts<'_> {
+        StakePoolAccounts {
+            stake_pool: self.stake_pool_info.key,
+            withdraw_authority: self.withdraw_authority_info.key,
+            reserve_stake: self.reserve_stake_info.key,
+            manager_fee_account: self.manager_fee_account_info.key,
+            pool_mint: self.pool_mint_info.key,
+        }
+    }
+}
+
+impl YieldAdapter for StakePool<'_, '_> {
+    fn backend(&self) -> YieldBackend {
+        YieldBackend::StakePool
+    }
+
+    fn deposit(&self, lamports: u64) -> ProgramResult {
+        let deposit = self
+            .deposit
+            .as_ref()
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+        if *self.pool_token_account_info.owner != TOKEN_PROGRAM_ID {
+            invoke(
+                &token::create_associated_token_account(
+                    deposit.payer_info.key,
+                    self.sol_lock.info.key,
+                    self.pool_mint_info.key,
+                ),
+                &[
+                    deposit.payer_info.clone(),
+                    self.pool_token_account_info.clone(),
+                    self.sol_lock.info.clone(),
+                    self.pool_mint_info.clone(),
+                    deposit.system_account_info.clone(),
+                    self.token_program_info.clone(),
+                    deposit.associated_token_program_info.clone(),
+                ],
+            )?;
+        }
+
+        verbose_msg!(
+            "Depositing {} lamports into stake pool {:#?}",
+            lamports,
+            self.stake_pool_info.key
+        );
+
+        // The stake pool transfers the lamports with the system program, which only
+        // debits system accounts
+        transfer_lamports(self.sol_lock.info, deposit.funding_account.info, lamports)?;
+
+        invoke_signed(
+            &stake_pool::deposit_sol(
+                &self.pool_accounts(),
+                deposit.funding_account.info.key,
+                self.pool_token_account_info.key,
+                lamports,
+            ),
+            &[
+                self.stake_pool_info.clone(),
+                self.withdraw_authority_info.clone(),
+                self.reserve_stake_info.clone(),
+                deposit.funding_account.info.clone(),
+                self.pool_token_account_info.clone(),
+                self.manager_fee_account_info.clone(),
+                self.pool_mint_info.clone(),
+                deposit.system_account_info.clone(),
+                self.token_program_info.clone(),
+                self.stake_pool_program_info.clone(),
+            ],
+            &[deposit.funding_account.seeds],
+        )
+    }
+
+    fn start_withdrawal(&self) -> Result<bool, ProgramError> {
+        Ok(true)
+    }
+
+    fn withdraw(&self) -> Result<u64, ProgramError> {
+        let withdrawal = self
+            .withdrawal
+            .as_ref()
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+        let pool_tokens = unpack_token_account(&self.pool_token_account_info.data.borrow())?.amount;
+        let lamports_before = self.sol_lock.info.lamports();
+
+        verbose_msg!(
+            "Redeeming {} pool tokens from stake pool {:#?}",
+            pool_tokens,
+            self.stake_pool_info.key
+        );
+
+        invoke_signed(
+            &stake_pool::withdraw_sol(
+                &self.pool_accounts(),
+                self.sol_lock.info.key,
+                self.pool_token_account_info.key,
+                self.sol_lock.info.key,
+                pool_tokens,
+            ),
+            &[
+                self.stake_pool_info.clone(),
+                self.withdraw_authority_info.clone(),
+                self.sol_lock.info.clone(),
+                self.pool_token_account_info.clone(),
+                self.reserve_stake_info.clone(),
+                self.manager_fee_account_info.clone(),
+                self.pool_mint_info.clone(),
+                withdrawal.clock_info.clone(),
+                withdrawal.stake_history_info.clone(),
+                withdrawal.stake_program_info.clone(),
+                self.token_program_info.clone(),
+                self.stake_pool_program_info.clone(),
+            ],
+            &[self.sol_lock.seeds],
+        )?;
+
+        Ok(self.sol_lock.info.lamports() - lamports_before)
+    }
+}
+
+/// Lending the locked lamports out as wrapped SOL from a lending reserve, for its collateral
+/// tokens, which the SolLock account holds in its associated token account
+///
+/// The collateral tokens are never deposited into an obligation, so nothing can be borrowed
+/// against them and they cannot be liquidated.
+pub struct Lending<'a, 'b> {
+    /// The SolLock account
+    pub sol_lock: SolLockSigner<'a, 'b>,
+    /// The account that pays for the wrapped SOL and collateral token accounts, and is
+    /// refunded the rent of the wrapped SOL account once it is closed
+    pub payer_info: &'b AccountInfo<'a>,
+    /// The reserve account
+    pub reserve_info: &'b AccountInfo<'a>,
+    /// The token account holding the liquidity of the reserve
+    pub reserve_liquidity_supply_info: &'b AccountInfo<'a>,
+    /// The collateral token mint of the reserve
+    pub reserve_collateral_mint_info: &'b AccountInfo<'a>,
+    /// The lending market of the reserve
+    pub lending_market_info: &'b AccountInfo<'a>,
+    /// The authority of the lending market
+    pub lending_market_authority_info: &'b AccountInfo<'a>,
+    /// The associated token account of the SolLock account for wrapped SOL
+    pub liquidity_account_info: &'b AccountInfo<'a>,
+    /// The associated token account of the SolLock account for the collateral mint
+    pub collateral_account_info: &'b AccountInfo<'a>,
+    /// The wrapped SOL mint
+    pub native_mint_info: &'b AccountInfo<'a>,
+    /// The clock sysvar account
+    pub clock_info: &'b AccountInfo<'a>,
+    /// The lending program account
+    pub lending_program_info: &'b AccountInfo<'a>,
+    /// The SPL Token program account
+    pub token_program_info: &'b AccountInfo<'a>,
+    /// The SPL Associated Token Account program account
+    pub associated_token_program_info: &'b AccountInfo<'a>,
+    /// The system program account
+    pub system_account_info: &'b AccountInfo<'a>,
+}
+
+impl<'a> Lending<'a, '_> {
+    fn reserve_accounts(&self) -> LendingReserveAccounts<'_> {
+        LendingReserveAccounts {
+            reserve: self.reserve_info.key,
+            reserve_liquidity_supply: self.reserve_liquidity_supply_info.key,
+            reserve_collateral_mint: self.reserve_collateral_mint_info.key,
+            lending_market: self.lending_market_info.key,
+            lending_market_authority: self.lending_market_authority_info.key,
+        }
+    }
+
+    fn create_token_account_if_needed(
+        &self,
+        token_account_info: &AccountInfo<'a>,
+        mint_info: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        if *token_account_info.owner == TOKEN_PROGRAM_ID {
+            return Ok(());
+        }
+
+        invoke(
+            &token::create_associated_token_account(
+                self.payer_info.key,
+                self.sol_lock.info.key,
+                mint_info.key,
+            ),
+            &[
+                self.payer_info.clone(),
+                token_account_info.clone(),
+                self.sol_lock.info.clone(),
+                mint_info.clone(),
+                self.system_account_info.clone(),
+                self.token_program_info.clone(),
+                self.associated_token_program_info.clone(),
+            ],
+        )
+    }
+
+    fn close_liquidity_account(&self, destination_info: &AccountInfo<'a>) -> ProgramResult {
+        invoke_signed(
+            &token::close_account(
+                self.liquidity_account_info.key,
+                destination_info.key,
+                self.sol_lock.info.key,
+            ),
+            &[
+                self.liquidity_account_info.clone(),
+                destination_info.clone(),
+                self.sol_lock.info.clone(),
+                self.token_program_info.clone(),
+            ],
+            &[self.sol_lock.seeds],
+        )
+    }
+}
+
+impl YieldAdapter for Lending<'_, '_> {
+    fn backend(&self) -> YieldBackend {
+        YieldBackend::Lending
+    }
+
+    fn deposit(&self, lamports: u64) -> ProgramResult {
+        self.create_token_account_if_needed(self.liquidity_account_info, self.native_mint_info)?;
+        self.create_token_account_if_needed(
+            self.collateral_account_info,
+            self.reserve_collateral_mint_info,
+        )?;
+
+        verbose_msg!(
+            "Lending {} lamports out from reserve {:#?}",
+            lamports,
+            self.reserve_info.key
+        );
+
+        // Wrap the locked lamports, as the reserve lends out wrapped SOL
+        transfer_lamports(self.sol_lock.info, self.liquidity_account_info, lamports)?;
+
+        invoke(
+            &token::sync_native(self.liquidity_account_info.key),
+            &[
+                self.liquidity_account_info.clone(),
+                self.token_program_info.clone(),
+            ],
+        )?;
+        invoke_signed(
+            &lending::deposit_reserve_liquidity(
+                &self.reserve_accounts(),
+                self.liquidity_account_info.key,
+                self.collateral_account_info.key,
+                self.sol_lock.info.key,
+                lamports,
+            ),
+            &[
+                self.liquidity_account_info.clone(),
+                self.collateral_account_info.clone(),
+                self.reserve_info.clone(),
+                self.reserve_liquidity_supply_info.clone(),
+                self.reserve_collateral_mint_info.clone(),
+                self.lending_market_info.clone(),
+                self.lending_market_authority_info.clone(),
+                self.sol_lock.info.clone(),
+                self.clock_info.clone(),
+                self.token_program_info.clone(),
+                self.lending_program_info.clone(),
+            ],
+            &[self.sol_lock.seeds],
+        )?;
+
+        // The wrapped SOL account is empty again, so refund its rent to the payer
+        self.close_liquidity_account(self.payer_info)
+    }
+
+    fn start_withdrawal(&self) -> Result<bool, ProgramError> {
+        Ok(true)
+    }
+
+    fn withdraw(&self) -> Result<u64, ProgramError> {
+        self.create_token_account_if_needed(self.liquidity_account_info, self.native_mint_info)?;
+
+        let collateral = unpack_token_account(&self.collateral_account_info.data.borrow())?.amount;
+
+        verbose_msg!(
+            "Redeeming {} collateral tokens from reserve {:#?}",
+            collateral,
+            self.reserve_info.key
+        );
+
+        invoke_signed(
+            &lending::redeem_reserve_collateral(
+                &self.reserve_accounts(),
+                self.collateral_account_info.key,
+                self.liquidity_account_info.key,
+                self.sol_lock.info.key,
+                collateral,
+            ),
+            &[
+                self.collateral_account_info.clone(),
+                self.liquidity_account_info.clone(),
+                self.reserve_info.clone(),
+                self.reserve_collateral_mint_info.clone(),
+                self.reserve_liquidity_supply_info.clone(),
+                self.lending_market_info.clone(),
+                self.lending_market_authority_info.clone(),
+                self.sol_lock.info.clone(),
+                self.clock_info.clone(),
+                self.token_program_info.clone(),
+                self.lending_program_info.clone(),
+            ],
+            &[self.sol_lock.seeds],
+        )?;
+
+        let redeemed = unpack_token_account(&self.liquidity_account_info.data.borrow())?.amount;
+        let rent = self.liquidity_account_info.lamports() - redeemed;
+
+        // Unwrap the redeemed lamports into the SolLock account, and refund the rent of the
+        // wrapped SOL account to the payer
+        self.close_liquidity_account(self.sol_lock.info)?;
+
+        transfer_lamports(self.sol_lock.info, self.payer_info, rent)?;
+
+        Ok(redeemed)
+    }
+}
@@ -0,0 +1,73 @@
+use solana_program::pubkey::Pubkey;
+use std::{collections::HashMap, error::Error, fs, path::Path, str::FromStr};
+
+/// Prefix marking a name to look up in the address book instead of a base58 pubkey
+pub const NAME_PREFIX: char = '@';
+
+/// Named pubkeys per cluster, such as treasuries, beneficiaries or validators
+///
+/// Stored as JSON, mapping cluster names to maps of names to base58 pubkeys:
+///
+/// ```json
+/// {
+///     "devnet": { "treasury": "..." },
+///     "mainnet-beta": { "treasury": "...", "validator": "..." }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct AddressBook {
+    clusters: HashMap<String, HashMap<String, Pubkey>>,
+}
+
+impl AddressBook {
+    /// Load an address book from a JSON file, checking that every address is a valid pubkey
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        let raw: HashMap<String, HashMap<String, String>> = serde_json::from_str(json)?;
+
+        let mut clusters = HashMap::new();
+        for (cluster, entries) in raw {
+            let mut names = HashMap::new();
+            for (name, address) in entries {
+                let pubkey = Pubkey::from_str(&address).map_err(|err| {
+                    format!("invalid address for @{} on {}: {}", name, cluster, err)
+                })?;
+                names.insert(name, pubkey);
+            }
+            clusters.insert(cluster, names);
+        }
+
+        Ok(Self { clusters })
+    }
+
+    /// Look up a name on a cluster, without the `@` prefix
+    pub fn get(&self, cluster: &str, name: &str) -> Option<&Pubkey> {
+        self.clusters.get(cluster)?.get(name)
+    }
+
+    /// Parse a pubkey argument, which is either a base58 pubkey or `@name`
+    pub fn resolve(&self, cluster: &str, value: &str) -> Result<Pubkey, Box<dyn Error>> {
+        match value.strip_prefix(NAME_PREFIX) {
+            Some(name) => self.get(cluster, name).copied().ok_or_else(|| {
+                format!("@{} is not in the address book for {}", name, cluster).into()
+            }),
+            None => Ok(Pubkey::from_str(value)?),
+        }
+    }
+}
+
+/// The address book name of the cluster behind an RPC URL
+pub fn cluster_name(url: &str) -> &'static str {
+    if url.contains("devnet") {
+        "devnet"
+    } else if url.contains("testnet") {
+        "testnet"
+    } else if url.contains("mainnet") {
+        "mainnet-beta"
+    } else {
+        "localnet"
+    }
+}
@@ -0,0 +1,178 @@
+use crate::scan::get_raw_lock_accounts_with_owner_prefix;
+use sol_lock::{
+    processor::get_sol_lock_account,
+    state::{Account, State, SOL_LOCK_ACCOUNT_SIZE, TOTAL_SHARE_BPS},
+};
+use solana_client::rpc_client::RpcClient;
+use solana_program::{
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+};
+use std::error::Error;
+
+/// A broken invariant of a SolLock account
+#[derive(Debug, Clone)]
+pub struct AuditViolation {
+    /// The SolLock account breaking the invariant
+    pub sol_lock_account: Pubkey,
+    /// What is wrong with it
+    pub message: String,
+}
+
+/// Check the invariants of every SolLock account of an owner
+///
+/// Account indexes are not stored in the accounts, so the PDA derivation of every account
+/// is checked against the indexes below `max_index`.
+pub fn audit_owner_locks(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    max_index: u64,
+) -> Result<Vec<AuditViolation>, Box<dyn Error>> {
+    audit_locks_with_owner_prefix(rpc_client, program_id, owner.as_ref(), max_index)
+}
+
+/// Check the invariants of every SolLock account of the program, one owner byte at a time
+pub fn audit_all_locks(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    max_index: u64,
+) -> Result<Vec<AuditViolation>, Box<dyn Error>> {
+    let mut violations = vec![];
+    for chunk in 0..=u8::MAX {
+        violations.extend(audit_locks_with_owner_prefix(
+            rpc_client,
+            program_id,
+            &[chunk],
+            max_index,
+        )?);
+    }
+
+    Ok(violations)
+}
+
+fn audit_locks_with_owner_prefix(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    owner_prefix: &[u8],
+    max_index: u64,
+) -> Result<Vec<AuditViolation>, Box<dyn Error>> {
+    let rent_exempt_balance =
+        rpc_client.get_minimum_balance_for_rent_exemption(SOL_LOCK_ACCOUNT_SIZE)?;
+    let accounts = get_raw_lock_accounts_with_owner_prefix(rpc_client, program_id, owner_prefix)?;
+
+    let mut violations = vec![];
+    for (key, account) in accounts {
+        let messages = match Account::unpack_unchecked(&account.data) {
+            Ok(sol_lock_account) if sol_lock_account.is_initialized() => audit_account(
+                program_id,
+                &key,
+                &sol_lock_account,
+                account.lamports.saturating_sub(rent_exempt_balance),
+                max_index,
+            ),
+            Ok(_) => continue,
+            Err(err) => vec![format!("data can't be unpacked: {}", err)],
+        };
+
+        violations.extend(messages.into_iter().map(|message| AuditViolation {
+            sol_lock_account: key,
+            message,
+        }));
+    }
+
+    Ok(violations)
+}
+
+/// The invariants a single initialized SolLock account breaks
+fn audit_account(
+    program_id: &Pubkey,
+    key: &Pubkey,
+    account: &Account,
+    spendable_balance: u64,
+    max_index: u64,
+) -> Vec<String> {
+    let mut messages = vec![];
+
+    if !(0..max_index)
+        .any(|acc_index| get_sol_lock_account(program_id, &account.owner, acc_index).0 == *key)
+    {
+        messages.push(format!(
+            "not derived from owner {} and any index below {}",
+            account.owner, max_index
+        ));
+    }
+
+    // The lamports of a staked account are held by its stake account
+    let tracked_lamports = account.lamports.unwrap_or(0);
+    if account.state != State::Staked && tracked_lamports > spendable_balance {
+        messages.push(format!(
+            "tracks {} lamports but only holds {} above the rent-exempt minimum",
+            tracked_lamports, spendable_balance
+        ));
+    }
+
+    let (has_lamports, has_deadline) = match account.state {
+        State::Uninitialized | State::Initialized => (false, false),
+        State::HasFunds => (true, false),
+        State::HasDeadline => (false, true),
+        State::ReadyUnlocked | State::Locked | State::Staked | State::Disputed => (true, true),
+    };
+    if account.lamports.is_some() != has_lamports {
+        messages.push(format!(
+            "state {:?} but lamports is {:?}",
+            account.state, account.lamports
+        ));
+    }
+    if account.deadline.is_some() != has_deadline {
+        messages.push(format!(
+            "state {:?} but deadline is {:?}",
+            account.state, account.deadline
+        ));
+    }
+
+    if account.state == State::Staked && account.stake_account.is_none() {
+        messages.push("state Staked but no stake account".to_string());
+    }
+    if account.state == State::Disputed && account.arbiter.is_none() {
+        messages.push("state Disputed but no arbiter".to_string());
+    }
+    if account.hash_lock.is_some() && account.counterparty.is_none() {
+        messages.push("hash lock without a counterparty".to_string());
+    }
+    if account.arbiter.is_some() != account.dispute_period.is_some() {
+        messages.push("arbiter and dispute period are not set together".to_string());
+    }
+    if account.arbiter.is_some() && account.counterparty.is_none() {
+        messages.push("arbiter without a counterparty".to_string());
+    }
+    if account.raise_target.is_some() != account.refund_period.is_some() {
+        messages.push("raise target and refund period are not set together".to_string());
+    }
+    if account.beneficiary.is_some() != account.heartbeat_interval.is_some() {
+        messages.push("beneficiary and heartbeat interval are not set together".to_string());
+    }
+    if (account.guardian_threshold > 0) != account.recovery_delay.is_some() {
+        messages.push("guardians and recovery delay are not set together".to_string());
+    }
+    if account.recovery_available_at.is_some() && account.pending_owner.is_none() {
+        messages.push("recovery pending without a pending owner".to_string());
+    }
+    if account.price_feed.is_some() != account.price_target.is_some() {
+        messages.push("price feed and price target are not set together".to_string());
+    }
+    if account.lock_fee_bps > TOTAL_SHARE_BPS {
+        messages.push(format!(
+            "unlock fee of {} basis points is more than the total",
+            account.lock_fee_bps
+        ));
+    }
+    if account.vesting_start.is_some() && account.stream_rate.is_some() {
+        messages.push("both vests and streams".to_string());
+    }
+    if account.stream_start.is_some() && account.stream_rate.is_none() {
+        messages.push("stream started without a stream rate".to_string());
+    }
+
+    messages
+}
@@ -0,0 +1,259 @@
+use sol_lock::state::Account;
+use solana_client::rpc_client::RpcClient;
+use solana_program::{
+    instruction::Instruction,
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+};
+use solana_sdk::{
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+use std::{collections::VecDeque, error::Error, thread::sleep, time::Duration};
+
+/// Default maximum number of transactions of a batch waiting for confirmation at once
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 32;
+
+/// Default number of times a transaction is rebuilt after its blockhash expired
+pub const DEFAULT_MAX_REBUILDS: usize = 3;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Maximum number of signatures the RPC accepts in a single `getSignatureStatuses` request
+const MAX_SIGNATURE_STATUSES: usize = 256;
+
+/// Maximum number of accounts the RPC returns in a single `getMultipleAccounts` request
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Whether a SolLock account reached the intended state of a batch item,
+/// given None if the account is closed or uninitialized
+pub type IsDone = Box<dyn Fn(Option<&Account>) -> bool>;
+
+/// A transaction of a batch, and the state it should bring its SolLock account to
+pub struct BatchItem {
+    /// The SolLock account the transaction acts on
+    pub sol_lock_account: Pubkey,
+    /// The instructions of the transaction
+    pub instructions: Vec<Instruction>,
+    /// Whether the SolLock account reached the intended state
+    pub is_done: IsDone,
+}
+
+/// The last known status of a transaction of a batch
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactionStatus {
+    /// Not sent yet
+    Pending,
+    /// Sent and waiting for confirmation
+    Sent(Signature),
+    /// Confirmed at the commitment of the RPC client
+    Confirmed(Signature),
+    /// Rejected by the RPC or failed on chain
+    Failed(String),
+    /// The blockhash expired more times than allowed before confirmation
+    Expired,
+}
+
+/// The outcome of a batch, in the order of its items
+#[derive(Debug)]
+pub struct BatchReport {
+    /// The final status of the transaction of every item
+    pub statuses: Vec<(Pubkey, TransactionStatus)>,
+    /// The SolLock accounts that reached the intended state, whatever their transaction status
+    pub reached: Vec<Pubkey>,
+    /// The SolLock accounts that did not reach the intended state
+    pub not_reached: Vec<Pubkey>,
+}
+
+/// Sends large batches of transactions without flooding the RPC
+///
+/// At most `max_in_flight` transactions wait for confirmation at once. Transactions whose
+/// blockhash expires before they are confirmed are rebuilt with a new blockhash and sent
+/// again, up to `max_rebuilds` times. Once every transaction is settled, the SolLock
+/// accounts are read back to report which ones reached their intended state.
+pub struct BatchSender<'a> {
+    rpc_client: &'a RpcClient,
+    max_in_flight: usize,
+    max_rebuilds: usize,
+}
+
+#[derive(Clone, Copy)]
+struct InFlight {
+    item: usize,
+    signature: Signature,
+    last_valid_block_height: u64,
+}
+
+impl<'a> BatchSender<'a> {
+    pub fn new(rpc_client: &'a RpcClient) -> Self {
+        Self {
+            rpc_client,
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+            max_rebuilds: DEFAULT_MAX_REBUILDS,
+        }
+    }
+
+    /// Set the maximum number of transactions waiting for confirmation at once
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
+
+    /// Set the number of times a transaction is rebuilt after its blockhash expired
+    pub fn with_max_rebuilds(mut self, max_rebuilds: usize) -> Self {
+        self.max_rebuilds = max_rebuilds;
+        self
+    }
+
+    /// Send the transactions of all items, paid by `payer` and signed by `payer` and `signers`
+    ///
+    /// `on_status` is called every time the status of an item changes.
+    pub fn send(
+        &self,
+        items: &[BatchItem],
+        payer: &Keypair,
+        signers: &[&Keypair],
+        mut on_status: impl FnMut(usize, &TransactionStatus),
+    ) -> Result<BatchReport, Box<dyn Error>> {
+        let mut all_signers = vec![payer];
+        all_signers.extend(
+            signers
+                .iter()
+                .filter(|signer| signer.pubkey() != payer.pubkey()),
+        );
+
+        let mut statuses = vec![TransactionStatus::Pending; items.len()];
+        let mut rebuilds = vec![0; items.len()];
+        let mut queue = (0..items.len()).collect::<VecDeque<_>>();
+        let mut in_flight: Vec<InFlight> = vec![];
+
+        let mut set_status = |statuses: &mut Vec<TransactionStatus>, item, status| {
+            on_status(item, &status);
+            statuses[item] = status;
+        };
+
+        while !queue.is_empty() || !in_flight.is_empty() {
+            if !queue.is_empty() && in_flight.len() < self.max_in_flight {
+                let (blockhash, last_valid_block_height) = self
+                    .rpc_client
+                    .get_latest_blockhash_with_commitment(self.rpc_client.commitment())?;
+
+                while in_flight.len() < self.max_in_flight {
+                    let item = match queue.pop_front() {
+                        Some(item) => item,
+                        None => break,
+                    };
+
+                    let transaction = Transaction::new_signed_with_payer(
+                        &items[item].instructions,
+                        Some(&payer.pubkey()),
+                        &all_signers,
+                        blockhash,
+                    );
+
+                    match self.rpc_client.send_transaction(&transaction) {
+                        Ok(signature) => {
+                            set_status(&mut statuses, item, TransactionStatus::Sent(signature));
+                            in_flight.push(InFlight {
+                                item,
+                                signature,
+                                last_valid_block_height,
+                            });
+                        }
+                        Err(err) => set_status(
+                            &mut statuses,
+                            item,
+                            TransactionStatus::Failed(err.to_string()),
+                        ),
+                    }
+                }
+            }
+
+            sleep(POLL_INTERVAL);
+
+            let block_height = self.rpc_client.get_block_height()?;
+            let mut still_in_flight = vec![];
+            for chunk in in_flight.chunks(MAX_SIGNATURE_STATUSES) {
+                let signatures = chunk.iter().map(|sent| sent.signature).collect::<Vec<_>>();
+                let responses = self.rpc_client.get_signature_statuses(&signatures)?.value;
+
+                for (sent, response) in chunk.iter().zip(responses) {
+                    if let Some(err) = response.as_ref().and_then(|status| status.err.as_ref()) {
+                        set_status(
+                            &mut statuses,
+                            sent.item,
+                            TransactionStatus::Failed(err.to_string()),
+                        );
+                        continue;
+                    }
+
+                    match response {
+                        Some(status)
+                            if status.satisfies_commitment(self.rpc_client.commitment()) =>
+                        {
+                            set_status(
+                                &mut statuses,
+                                sent.item,
+                                TransactionStatus::Confirmed(sent.signature),
+                            )
+                        }
+                        None if block_height > sent.last_valid_block_height => {
+                            if rebuilds[sent.item] < self.max_rebuilds {
+                                rebuilds[sent.item] += 1;
+                                set_status(&mut statuses, sent.item, TransactionStatus::Pending);
+                                queue.push_back(sent.item);
+                            } else {
+                                set_status(&mut statuses, sent.item, TransactionStatus::Expired);
+                            }
+                        }
+                        _ => still_in_flight.push(*sent),
+                    }
+                }
+            }
+            in_flight = still_in_flight;
+        }
+
+        self.reconcile(items, statuses)
+    }
+
+    /// Read back the SolLock accounts of a settled batch and check their state
+    fn reconcile(
+        &self,
+        items: &[BatchItem],
+        statuses: Vec<TransactionStatus>,
+    ) -> Result<BatchReport, Box<dyn Error>> {
+        let keys = items
+            .iter()
+            .map(|item| item.sol_lock_account)
+            .collect::<Vec<_>>();
+
+        let mut accounts = Vec::with_capacity(keys.len());
+        for chunk in keys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            accounts.extend(self.rpc_client.get_multiple_accounts(chunk)?);
+        }
+
+        let mut reached = vec![];
+        let mut not_reached = vec![];
+        for (item, account) in items.iter().zip(accounts) {
+            let sol_lock_account = match account {
+                Some(account) => Account::unpack_unchecked(&account.data)
+                    .ok()
+                    .filter(IsInitialized::is_initialized),
+                None => None,
+            };
+
+            if (item.is_done)(sol_lock_account.as_ref()) {
+                reached.push(item.sol_lock_account);
+            } else {
+                not_reached.push(item.sol_lock_account);
+            }
+        }
+
+        Ok(BatchReport {
+            statuses: keys.into_iter().zip(statuses).collect(),
+            reached,
+            not_reached,
+        })
+    }
+}
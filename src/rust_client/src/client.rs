@@ -0,0 +1,301 @@
+use crate::scan::get_lock_accounts_with_owner_prefix;
+use sol_lock::{
+    processor::{get_index_counter, get_stats},
+    state::{Account, IndexCounter, State, Stats},
+};
+use solana_client::rpc_client::RpcClient;
+use solana_program::{
+    clock::{Clock, Slot},
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+    sysvar,
+};
+use std::{collections::VecDeque, error::Error, thread::sleep, time::Duration};
+
+/// Default number of attempts for every RPC request made by a [`SolLockClient`]
+pub const DEFAULT_MAX_ATTEMPTS: usize = 3;
+
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Maximum number of accounts the RPC returns in a single `getMultipleAccounts` request
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Read-only access to the SolLock accounts of a program
+pub struct SolLockClient {
+    rpc_client: RpcClient,
+    program_id: Pubkey,
+    max_attempts: usize,
+}
+
+impl SolLockClient {
+    pub fn new(rpc_client: RpcClient, program_id: Pubkey) -> Self {
+        Self {
+            rpc_client,
+            program_id,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+        }
+    }
+
+    /// Set the number of attempts for every RPC request before giving up
+    pub fn with_max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn rpc_client(&self) -> &RpcClient {
+        &self.rpc_client
+    }
+
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    /// Iterate over all initialized SolLock accounts of all owners, decoded
+    ///
+    /// Accounts are fetched lazily in the same chunks as [`crate::scan::scan_lock_accounts`],
+    /// retrying failed requests. If a chunk still fails after the last attempt, the error is
+    /// yielded and iteration stops.
+    pub fn iter_locks(&self) -> LockIter<'_> {
+        LockIter {
+            client: self,
+            owner: None,
+            next_chunk: Some(0),
+            buffer: VecDeque::new(),
+        }
+    }
+
+    /// Iterate over all initialized SolLock accounts of a single owner, decoded
+    pub fn iter_owner_locks(&self, owner: &Pubkey) -> LockIter<'_> {
+        LockIter {
+            client: self,
+            owner: Some(*owner),
+            next_chunk: Some(0),
+            buffer: VecDeque::new(),
+        }
+    }
+
+    /// Fetch a set of SolLock accounts and the clock sysvar as of a single slot
+    ///
+    /// The clock is fetched in the same `getMultipleAccounts` request as the first accounts.
+    /// If more accounts are requested than fit in one request, every request must report the
+    /// same slot. Inconsistent or failed reads are retried, up to the maximum number of attempts.
+    pub fn get_snapshot(
+        &self,
+        sol_lock_accounts: &[Pubkey],
+    ) -> Result<LockSnapshot, Box<dyn Error>> {
+        let mut attempt = 1;
+        loop {
+            match self.try_get_snapshot(sol_lock_accounts) {
+                Ok(Some(snapshot)) => return Ok(snapshot),
+                Ok(None) if attempt >= self.max_attempts => {
+                    return Err("accounts were read at different slots".into())
+                }
+                Err(err) if attempt >= self.max_attempts => return Err(err),
+                Ok(None) | Err(_) => {
+                    attempt += 1;
+                    sleep(RETRY_DELAY);
+                }
+            }
+        }
+    }
+
+    /// Fetch the program-wide Stats account, or None if it was not created yet
+    pub fn get_stats(&self) -> Result<Option<Stats>, Box<dyn Error>> {
+        let stats_key = get_stats(&self.program_id).0;
+        let account = self
+            .rpc_client
+            .get_account_with_commitment(&stats_key, self.rpc_client.commitment())?
+            .value;
+
+        match account {
+            Some(account) if account.owner == self.program_id => {
+                Ok(Some(Stats::unpack_unchecked(&account.data)?))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Fetch the index CreateNextAccount assigns to the next SolLock account of an owner
+    pub fn get_next_acc_index(&self, owner: &Pubkey) -> Result<u64, Box<dyn Error>> {
+        let index_counter_key = get_index_counter(&self.program_id, owner).0;
+        let account = self
+            .rpc_client
+            .get_account_with_commitment(&index_counter_key, self.rpc_client.commitment())?
+            .value;
+
+        match account {
+            Some(account) if account.owner == self.program_id => {
+                Ok(IndexCounter::unpack(&account.data)?.next_index)
+            }
+            _ => Ok(0),
+        }
+    }
+
+    /// Read a snapshot once, returning None if the reads spanned several slots
+    fn try_get_snapshot(
+        &self,
+        sol_lock_accounts: &[Pubkey],
+    ) -> Result<Option<LockSnapshot>, Box<dyn Error>> {
+        let mut keys = vec![sysvar::clock::id()];
+        keys.extend_from_slice(sol_lock_accounts);
+
+        let mut slot = None;
+        let mut accounts = Vec::with_capacity(keys.len());
+        for chunk in keys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let response = self
+                .rpc_client
+                .get_multiple_accounts_with_commitment(chunk, self.rpc_client.commitment())?;
+
+            match slot {
+                Some(slot) if slot != response.context.slot => return Ok(None),
+                _ => slot = Some(response.context.slot),
+            }
+
+            accounts.extend(response.value);
+        }
+
+        let mut accounts = accounts.into_iter();
+        let clock = accounts
+            .next()
+            .flatten()
+            .ok_or("clock sysvar account not found")?;
+        let clock: Clock = bincode::deserialize(&clock.data)?;
+
+        let mut locks = Vec::with_capacity(sol_lock_accounts.len());
+        for (key, account) in sol_lock_accounts.iter().zip(accounts) {
+            let sol_lock_account = match account {
+                Some(account) if account.owner == self.program_id => {
+                    let sol_lock_account = Account::unpack_unchecked(&account.data)?;
+                    Some(sol_lock_account).filter(IsInitialized::is_initialized)
+                }
+                _ => None,
+            };
+            locks.push((*key, sol_lock_account));
+        }
+
+        Ok(Some(LockSnapshot {
+            slot: slot.ok_or("no accounts were read")?,
+            clock,
+            locks,
+        }))
+    }
+
+    fn get_lock_accounts_with_retries(
+        &self,
+        owner_prefix: &[u8],
+    ) -> Result<Vec<(Pubkey, Account)>, Box<dyn Error>> {
+        let mut attempt = 1;
+        loop {
+            match get_lock_accounts_with_owner_prefix(
+                &self.rpc_client,
+                &self.program_id,
+                owner_prefix,
+            ) {
+                Ok(accounts) => return Ok(accounts),
+                Err(err) if attempt >= self.max_attempts => return Err(err),
+                Err(_) => {
+                    attempt += 1;
+                    sleep(RETRY_DELAY);
+                }
+            }
+        }
+    }
+}
+
+/// SolLock accounts and the clock sysvar read at the same slot, see [`SolLockClient::get_snapshot`]
+#[derive(Debug)]
+pub struct LockSnapshot {
+    /// The slot all accounts were read at
+    pub slot: Slot,
+    /// The clock sysvar at that slot
+    pub clock: Clock,
+    /// The requested accounts, in order, or None for accounts that aren't initialized SolLock accounts
+    pub locks: Vec<(Pubkey, Option<Account>)>,
+}
+
+impl LockSnapshot {
+    /// Total lamports held by the accounts of the snapshot, locked or not
+    pub fn total_lamports(&self) -> u64 {
+        self.locks
+            .iter()
+            .filter_map(|(_, account)| account.as_ref()?.lamports)
+            .sum()
+    }
+
+    /// Total lamports held by the accounts of the snapshot in states {Locked, Staked, Disputed}
+    pub fn total_locked_lamports(&self) -> u64 {
+        self.locks
+            .iter()
+            .filter_map(|(_, account)| account.as_ref())
+            .filter(|account| {
+                matches!(
+                    account.state,
+                    State::Locked | State::Staked | State::Disputed
+                )
+            })
+            .filter_map(|account| account.lamports)
+            .sum()
+    }
+
+    /// Keys of the accounts that can be unlocked at the time of the snapshot
+    pub fn unlockable_now(&self) -> Vec<Pubkey> {
+        let now = self.clock.unix_timestamp;
+        self.locks
+            .iter()
+            .filter_map(|(key, account)| Some((key, account.as_ref()?)))
+            .filter(|(_, account)| account.state == State::Locked)
+            .filter(|(_, account)| {
+                let deadline = account
+                    .rolled_deadline(now)
+                    .ok()
+                    .flatten()
+                    .or(account.deadline);
+                matches!(deadline, Some(deadline) if now >= deadline)
+            })
+            .filter(|(_, account)| {
+                !matches!(account.epoch_deadline, Some(epoch) if self.clock.epoch < epoch)
+            })
+            .map(|(key, _)| *key)
+            .collect()
+    }
+}
+
+/// Iterator over decoded SolLock accounts, see [`SolLockClient::iter_locks`]
+pub struct LockIter<'a> {
+    client: &'a SolLockClient,
+    owner: Option<Pubkey>,
+    next_chunk: Option<u8>,
+    buffer: VecDeque<(Pubkey, Account)>,
+}
+
+impl Iterator for LockIter<'_> {
+    type Item = Result<(Pubkey, Account), Box<dyn Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(lock) = self.buffer.pop_front() {
+                return Some(Ok(lock));
+            }
+
+            let chunk = self.next_chunk?;
+            let result = match self.owner {
+                Some(owner) => {
+                    self.next_chunk = None;
+                    self.client.get_lock_accounts_with_retries(owner.as_ref())
+                }
+                None => {
+                    self.next_chunk = chunk.checked_add(1);
+                    self.client.get_lock_accounts_with_retries(&[chunk])
+                }
+            };
+
+            match result {
+                Ok(accounts) => self.buffer.extend(accounts),
+                Err(err) => {
+                    self.next_chunk = None;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
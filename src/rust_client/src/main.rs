@@ -3,22 +3,284 @@
 use chrono::prelude::*;
 use solana_client::rpc_client::RpcClient;
 use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::read_keypair_file;
 use solana_sdk::signature::Keypair;
 use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
 use std::thread::sleep;
 use std::time::Duration;
 use std::{error::Error, str::FromStr};
 use transaction::{check_balance, create_and_lock, unlock};
 
+use address_book::{cluster_name, AddressBook};
+use audit::{audit_all_locks, audit_owner_locks};
+use cli::Args;
+use review::review_transaction;
+use signing::{
+    decode_transaction, encode_transaction, merge_signatures, missing_signers, partial_sign,
+};
+use unlock_kit::build_unlock_kit;
+
+mod address_book;
+mod audit;
+mod batch;
+mod cli;
+mod client;
+mod receiver_check;
+mod review;
+mod scan;
+mod signing;
 mod transaction;
+mod unlock_kit;
 
 const URL_TESTNET: &str = "https://api.testnet.solana.com";
 const URL_DEVNET: &str = "https://api.devnet.solana.com";
 const URL_LOCAL: &str = "http://127.0.0.1:8899";
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let rpc_client = RpcClient::new(URL_LOCAL);
+const PROGRAM_ID: &str = "DBqu2qa8B43uzVqrNJJcXeFW2y91os6xwpraoN5D43rP";
+
+const DEFAULT_AUDIT_MAX_INDEX: u64 = 1024;
+
+const USAGE: &str = "\
+usage: rust_client [--url <rpc-url>] [--address-book <path>] [command]
+
+With no command, creates, locks and unlocks a demo SolLock account,
+unlocking to --receiver <pubkey> if given.
+
+Pubkeys can be given as @name to look them up in the address book for the cluster.
+
+commands:
+    review <base64-tx>
+        Describe what a proposed transaction does to which SolLock accounts
+    audit [--owner <pubkey>] [--max-index <n>]
+        Check the invariants of every lock of an owner, or of the whole program,
+        deriving account addresses from indexes below --max-index (default 1024)
+    unlock-kit <acc-index> --owner <pubkey> --nonce-account <pubkey>
+            [--nonce-authority <pubkey>] [--receiver <pubkey>]
+        Print a cold-storage kit for unlocking a lock from an offline machine
+    sign <base64-tx> --signer <keypair-file>...
+        Add the signatures of the given keypairs to a partially signed transaction
+    send <base64-tx>...
+        Merge the signatures of copies of a transaction and broadcast it once fully signed
+    withdraw-treasury <lamports> --admin <keypair-file> [--destination <pubkey>]
+        Withdraw collected protocol fees from the treasury, to the admin by default";
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse(std::env::args().skip(1))?;
+    let url = args.value("url").unwrap_or(URL_LOCAL);
+    let rpc_client = RpcClient::new(url);
+    let program_id = Pubkey::from_str(PROGRAM_ID).unwrap();
+
+    let cluster = cluster_name(url);
+    let address_book = match args.value("address-book") {
+        Some(path) => AddressBook::load(path)?,
+        None => AddressBook::default(),
+    };
+    let pubkey_option = |name: &str| {
+        args.value(name)
+            .map(|value| address_book.resolve(cluster, value))
+            .transpose()
+    };
+
+    match args.command() {
+        None => run_demo(
+            &rpc_client,
+            &program_id,
+            pubkey_option("receiver")?.as_ref(),
+        ),
+        Some("review") => review(&program_id, &args),
+        Some("audit") => audit(
+            &rpc_client,
+            &program_id,
+            &args,
+            pubkey_option("owner")?.as_ref(),
+        ),
+        Some("unlock-kit") => unlock_kit(&rpc_client, &program_id, &args, pubkey_option),
+        Some("sign") => sign(&args),
+        Some("send") => send(&rpc_client, &args),
+        Some("withdraw-treasury") => {
+            withdraw_treasury(&rpc_client, &program_id, &args, pubkey_option)
+        }
+        Some(command) => Err(format!("unknown command {}\n\n{}", command, USAGE).into()),
+    }
+}
+
+/// Print what a proposed transaction does, for approvers to check before signing
+fn review(program_id: &Pubkey, args: &Args) -> Result<(), Box<dyn Error>> {
+    let transaction = match args.operands() {
+        [encoded] => decode_transaction(encoded)?,
+        _ => return Err(USAGE.into()),
+    };
+
+    for line in review_transaction(&transaction, program_id) {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
+
+/// Print every broken invariant of the locks of an owner, or of the whole program
+fn audit(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    args: &Args,
+    owner: Option<&Pubkey>,
+) -> Result<(), Box<dyn Error>> {
+    let max_index = match args.value("max-index") {
+        Some(max_index) => max_index.parse()?,
+        None => DEFAULT_AUDIT_MAX_INDEX,
+    };
+
+    let violations = match owner {
+        Some(owner) => audit_owner_locks(rpc_client, program_id, owner, max_index)?,
+        None => audit_all_locks(rpc_client, program_id, max_index)?,
+    };
+
+    for violation in &violations {
+        println!("{}: {}", violation.sol_lock_account, violation.message);
+    }
+    if !violations.is_empty() {
+        return Err(format!("Found {} violations", violations.len()).into());
+    }
+
+    println!("No violations found");
+    Ok(())
+}
+
+/// Print a cold-storage unlock kit for a lock as JSON
+fn unlock_kit(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    args: &Args,
+    pubkey_option: impl Fn(&str) -> Result<Option<Pubkey>, Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+    let acc_index = match args.operands() {
+        [acc_index] => acc_index.parse()?,
+        _ => return Err(USAGE.into()),
+    };
+    let owner = pubkey_option("owner")?.ok_or("--owner is required")?;
+    let nonce_account = pubkey_option("nonce-account")?.ok_or("--nonce-account is required")?;
+    let nonce_authority = pubkey_option("nonce-authority")?.unwrap_or(owner);
+
+    let kit = build_unlock_kit(
+        rpc_client,
+        program_id,
+        &owner,
+        acc_index,
+        pubkey_option("receiver")?.as_ref(),
+        &nonce_account,
+        &nonce_authority,
+    )?;
+    println!("{}", serde_json::to_string_pretty(&kit)?);
+
+    Ok(())
+}
+
+/// Partially sign a transaction with every `--signer` keypair and print it for the next signer
+fn sign(args: &Args) -> Result<(), Box<dyn Error>> {
+    let encoded = match args.operands() {
+        [encoded] => encoded,
+        _ => return Err(USAGE.into()),
+    };
+    let mut transaction = decode_transaction(encoded)?;
+
+    let signers = args
+        .values("signer")
+        .into_iter()
+        .map(|path| read_keypair_file(path).map_err(|err| format!("{}: {}", path, err)))
+        .collect::<Result<Vec<_>, _>>()?;
+    if signers.is_empty() {
+        return Err("at least one --signer is required".into());
+    }
+
+    let blockhash = transaction.message.recent_blockhash;
+    partial_sign(
+        &mut transaction,
+        &signers.iter().collect::<Vec<_>>(),
+        blockhash,
+    )?;
+
+    for missing in missing_signers(&transaction) {
+        eprintln!("Still needs a signature from {}", missing);
+    }
+    println!("{}", encode_transaction(&transaction)?);
+
+    Ok(())
+}
+
+/// Merge the signatures of every copy of a transaction and broadcast it
+fn send(rpc_client: &RpcClient, args: &Args) -> Result<(), Box<dyn Error>> {
+    let (encoded, others) = match args.operands() {
+        [encoded, others @ ..] => (encoded, others),
+        _ => return Err(USAGE.into()),
+    };
+    let mut transaction = decode_transaction(encoded)?;
+    let others = others
+        .iter()
+        .map(|other| decode_transaction(other))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    merge_signatures(&mut transaction, &others)?;
+    let missing = missing_signers(&transaction);
+    if !missing.is_empty() {
+        return Err(format!("Transaction still needs signatures from {:?}", missing).into());
+    }
+
+    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    println!("Sent transaction {}", signature);
+
+    Ok(())
+}
+
+/// Withdraw protocol fees from the treasury with the admin keypair
+fn withdraw_treasury(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    args: &Args,
+    pubkey_option: impl Fn(&str) -> Result<Option<Pubkey>, Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+    let lamports: u64 = match args.operands() {
+        [lamports] => lamports.parse()?,
+        _ => return Err(USAGE.into()),
+    };
+    let admin_path = args.value("admin").ok_or("--admin is required")?;
+    let admin = read_keypair_file(admin_path).map_err(|err| format!("{}: {}", admin_path, err))?;
+    let destination = pubkey_option("destination")?.unwrap_or_else(|| admin.pubkey());
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[sol_lock::instruction::withdraw_treasury(
+            program_id,
+            &admin.pubkey(),
+            &destination,
+            lamports,
+        )],
+        Some(&admin.pubkey()),
+        &[&admin],
+        rpc_client.get_latest_blockhash()?,
+    );
+
+    let signature = rpc_client.send_and_confirm_transaction(&transaction)?;
+    println!(
+        "Withdrew {} lamports from the treasury to {} in transaction {}",
+        lamports, destination, signature
+    );
+
+    Ok(())
+}
 
+/// Create, lock and unlock a SolLock account with the bundled demo keypair
+fn run_demo(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    receiver: Option<&Pubkey>,
+) -> Result<(), Box<dyn Error>> {
     let keypair_secret_json: serde_json::Value =
         serde_json::from_str(include_str!("../keys/key.json"))?;
     let keypair_secret = keypair_secret_json
@@ -33,37 +295,34 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     println!("Sender: {:?}", sender_key);
 
-    let program_id = Pubkey::from_str("DBqu2qa8B43uzVqrNJJcXeFW2y91os6xwpraoN5D43rP").unwrap();
     let system_program = Pubkey::from_str("11111111111111111111111111111111").unwrap();
 
     let acc_index = 100u64;
-    let sol_lock_account = Pubkey::find_program_address(
-        &[sender_key.as_ref(), &acc_index.to_le_bytes()],
-        &program_id,
-    )
-    .0;
+    let sol_lock_account =
+        Pubkey::find_program_address(&[sender_key.as_ref(), &acc_index.to_le_bytes()], program_id)
+            .0;
 
     let now = Utc::now().timestamp();
     let deadline = now + 20;
 
     println!("creating and locking...");
     create_and_lock(
-        &rpc_client,
-        &program_id,
+        rpc_client,
+        program_id,
         &sender_key,
         &sol_lock_account,
         &system_program,
         acc_index,
         deadline.try_into().unwrap(),
-        10000000000,
+        4000000000,
         &sender,
     )?;
 
     println!("Account: {:?} created successfully.", sol_lock_account);
-    let pda_balance = check_balance(&rpc_client, &sol_lock_account)?;
+    let pda_balance = check_balance(rpc_client, &sol_lock_account)?;
     println!("SolLock account balance: {:?}", pda_balance);
 
-    let balance = check_balance(&rpc_client, &sender_key)?;
+    let balance = check_balance(rpc_client, &sender_key)?;
     println!("Sender balance: {:?}", balance);
 
     let now = Utc::now().timestamp();
@@ -75,20 +334,21 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     println!("Unlocking Sol.");
     unlock(
-        &rpc_client,
-        &program_id,
+        rpc_client,
+        program_id,
         &sender_key,
         &sol_lock_account,
         acc_index,
+        receiver,
         &sender,
     )?;
 
     println!("Unlocked successfully!");
 
-    let pda_balance = check_balance(&rpc_client, &sol_lock_account)?;
+    let pda_balance = check_balance(rpc_client, &sol_lock_account)?;
     println!("SolLock account balance: {:?}", pda_balance);
 
-    let balance = check_balance(&rpc_client, &sender_key)?;
+    let balance = check_balance(rpc_client, &sender_key)?;
     println!("Sender balance: {:?}", balance);
 
     Ok(())
@@ -0,0 +1,51 @@
+use solana_client::rpc_client::RpcClient;
+use solana_program::{pubkey::Pubkey, system_program};
+use std::{error::Error, str::FromStr};
+
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// Look for common mistakes in the choice of an unlock receiver before broadcasting
+///
+/// Returns a human-readable warning for every problem found. An empty list means
+/// the receiver looks like a regular wallet (or does not exist yet, which is fine for SOL).
+pub fn check_unlock_receiver(
+    rpc_client: &RpcClient,
+    receiver: &Pubkey,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut warnings = vec![];
+
+    let account = match rpc_client
+        .get_account_with_commitment(receiver, rpc_client.commitment())?
+        .value
+    {
+        Some(account) => account,
+        None => return Ok(warnings),
+    };
+
+    let token_program_ids = [
+        Pubkey::from_str(TOKEN_PROGRAM_ID)?,
+        Pubkey::from_str(TOKEN_2022_PROGRAM_ID)?,
+    ];
+
+    if token_program_ids.contains(&account.owner) {
+        warnings.push(format!(
+            "Receiver {} is a token account. SOL sent to it cannot be used as tokens \
+             and is usually unrecoverable. Use the wallet address that owns it instead.",
+            receiver
+        ));
+    } else if account.executable {
+        warnings.push(format!(
+            "Receiver {} is a program. SOL sent to it is usually unrecoverable.",
+            receiver
+        ));
+    } else if account.owner != system_program::id() {
+        warnings.push(format!(
+            "Receiver {} is owned by program {}, not by a wallet. \
+             Make sure that program can move the SOL it receives.",
+            receiver, account.owner
+        ));
+    }
+
+    Ok(warnings)
+}
@@ -0,0 +1,577 @@
+use borsh::BorshDeserialize;
+use sol_lock::{
+    instruction::SolLockInstruction,
+    processor::{get_label_acc_index, get_sol_lock_account},
+};
+use solana_program::{instruction::CompiledInstruction, message::Message, pubkey::Pubkey};
+use solana_sdk::transaction::Transaction;
+
+/// Describe what a proposed transaction does, one line per fact, for approvers to check
+///
+/// Every instruction is listed with its program, its decoded SolLock instruction data and
+/// the role of every account it touches. SolLock accounts that are not derived from the
+/// owner and index in the instruction are flagged, as are instructions the tool can't decode.
+pub fn review_transaction(transaction: &Transaction, program_id: &Pubkey) -> Vec<String> {
+    let message = &transaction.message;
+    let mut lines = vec![format!(
+        "Fee payer: {}",
+        message
+            .account_keys
+            .first()
+            .map_or("none".to_string(), |key| key.to_string())
+    )];
+
+    for (index, instruction) in message.instructions.iter().enumerate() {
+        let instruction_program_id = message.account_keys[instruction.program_id_index as usize];
+        let accounts = instruction
+            .accounts
+            .iter()
+            .map(|account_index| *account_index as usize)
+            .collect::<Vec<_>>();
+
+        if instruction_program_id != *program_id {
+            lines.push(format!(
+                "Instruction {}: program {} (not SolLock)",
+                index + 1,
+                instruction_program_id
+            ));
+            push_accounts(&mut lines, message, &accounts, &[]);
+            continue;
+        }
+
+        let sol_lock_instruction = match SolLockInstruction::try_from_slice(&instruction.data) {
+            Ok(sol_lock_instruction) => sol_lock_instruction,
+            Err(_) => {
+                lines.push(format!(
+                    "Instruction {}: WARNING: SolLock instruction data could not be decoded",
+                    index + 1
+                ));
+                push_accounts(&mut lines, message, &accounts, &[]);
+                continue;
+            }
+        };
+
+        lines.push(format!(
+            "Instruction {}: SolLock {:?}",
+            index + 1,
+            sol_lock_instruction
+        ));
+        push_accounts(
+            &mut lines,
+            message,
+            &accounts,
+            account_labels(&sol_lock_instruction),
+        );
+
+        if let Some(warning) =
+            check_sol_lock_account(program_id, message, instruction, &sol_lock_instruction)
+        {
+            lines.push(format!("  WARNING: {}", warning));
+        }
+    }
+
+    lines
+}
+
+fn push_accounts(lines: &mut Vec<String>, message: &Message, accounts: &[usize], labels: &[&str]) {
+    for (position, account_index) in accounts.iter().enumerate() {
+        let mut flags = vec![];
+        if message.is_signer(*account_index) {
+            flags.push("signer");
+        }
+        if message.is_writable(*account_index) {
+            flags.push("writable");
+        }
+
+        lines.push(format!(
+            "  {}. {} {} [{}]",
+            position,
+            labels.get(position).unwrap_or(&"Account"),
+            message.account_keys[*account_index],
+            flags.join(", ")
+        ));
+    }
+}
+
+/// The roles of the accounts of a SolLock instruction, in order
+fn account_labels(instruction: &SolLockInstruction) -> &'static [&'static str] {
+    use SolLockInstruction::*;
+
+    match instruction {
+        #[cfg(feature = "allowlist")]
+        CreateAccount(_) | CreateLabeledAccount(_) => &[
+            "Owner",
+            "SolLock",
+            "System program",
+            "AllowlistEntry",
+            "Payer",
+        ],
+        #[cfg(not(feature = "allowlist"))]
+        CreateAccount(_) | CreateLabeledAccount(_) => {
+            &["Owner", "SolLock", "System program", "Payer"]
+        }
+        #[cfg(feature = "allowlist")]
+        CreateAndLock(_) => &[
+            "Owner",
+            "SolLock",
+            "System program",
+            "Config",
+            "Stats",
+            "AllowlistEntry",
+            "Payer",
+        ],
+        #[cfg(not(feature = "allowlist"))]
+        CreateAndLock(_) => &[
+            "Owner",
+            "SolLock",
+            "System program",
+            "Config",
+            "Stats",
+            "Payer",
+        ],
+        AddSol(_) => &[
+            "Owner",
+            "SolLock",
+            "System program",
+            "Config",
+            "Stats",
+            "Payer",
+        ],
+        RemoveSol(remove) if remove.has_receiver && remove.memo.is_some() => &[
+            "Owner",
+            "SolLock",
+            "Treasury",
+            "Stats",
+            "Receiver",
+            "Memo program",
+        ],
+        RemoveSol(remove) if remove.memo.is_some() => {
+            &["Owner", "SolLock", "Treasury", "Stats", "Memo program"]
+        }
+        RemoveSol(_) => &["Owner", "SolLock", "Treasury", "Stats", "Receiver"],
+        SetDeadline(_) | SetDeadlineRelative(_) => &["Owner", "SolLock", "Config"],
+        Lock(_) => &["Owner", "SolLock", "Config", "Stats"],
+        ProposeOwner(_)
+        | SetUnlockAuthority(_)
+        | SetReceiver(_)
+        | SetRecoveryPointer(_)
+        | SetCrankTip(_)
+        | SetAutoExtend(_)
+        | SetVesting(_)
+        | SetDepositCap(_)
+        | SetStream(_)
+        | SetHashLock(_)
+        | SetEscrow(_)
+        | SetOpenDeposits(_)
+        | SetRaiseTarget(_)
+        | SetBeneficiary(_)
+        | SetBeneficiaryShares(_)
+        | SetMultisig(_)
+        | SetGuardians(_)
+        | SetPriceCondition(_)
+        | SetEpochDeadline(_)
+        | VetoRecovery(_)
+        | Heartbeat(_) => &["Owner", "SolLock"],
+        RefundEscrow(_) => &["Owner", "SolLock", "Treasury", "Stats"],
+        Unlock(unlock) if unlock.has_receiver && unlock.memo.is_some() => &[
+            "Owner",
+            "SolLock",
+            "Treasury",
+            "Stats",
+            "Receiver",
+            "Memo program",
+            "Unlock authority",
+        ],
+        Unlock(unlock) if unlock.has_receiver => &[
+            "Owner",
+            "SolLock",
+            "Treasury",
+            "Stats",
+            "Receiver",
+            "Unlock authority",
+        ],
+        Unlock(unlock) if unlock.memo.is_some() => &[
+            "Owner",
+            "SolLock",
+            "Treasury",
+            "Stats",
+            "Memo program",
+            "Unlock authority",
+        ],
+        Unlock(_) => &["Owner", "SolLock", "Treasury", "Stats", "Unlock authority"],
+        UnlockAndClose(unlock) if unlock.has_receiver => &[
+            "Owner",
+            "SolLock",
+            "Treasury",
+            "Stats",
+            "Receiver",
+            "Unlock authority",
+        ],
+        UnlockAndClose(_) => &["Owner", "SolLock", "Treasury", "Stats", "Unlock authority"],
+        Stake(_) => &[
+            "Owner",
+            "SolLock",
+            "Stake account",
+            "Vote account",
+            "Clock sysvar",
+            "Rent sysvar",
+            "Stake history sysvar",
+            "Stake config",
+            "Stake program",
+            "System program",
+        ],
+        Unstake(_) => &[
+            "Owner",
+            "SolLock",
+            "Stake account",
+            "Clock sysvar",
+            "Stake history sysvar",
+            "Stake program",
+        ],
+        TopUpRent(_) => &["Payer", "SolLock", "System program"],
+        SetDeadlineMany(_) => &["Owner", "Config"],
+        AddSolAndLock(_) | Relock(_) => &["Owner", "SolLock", "System program", "Config", "Stats"],
+        SetName(set_name) if set_name.has_payer => &[
+            "Owner",
+            "SolLock",
+            "NameRecord",
+            "System program",
+            "Payer",
+            "Previous SolLock",
+        ],
+        SetName(_) => &[
+            "Owner",
+            "SolLock",
+            "NameRecord",
+            "System program",
+            "Previous SolLock",
+        ],
+        AcceptOwner(_) => &[
+            "Owner",
+            "SolLock",
+            "New owner",
+            "New SolLock",
+            "System program",
+        ],
+        CrankUnlock(_) => &[
+            "Cranker", "Owner", "SolLock", "Treasury", "Stats", "Receiver",
+        ],
+        UnlockToClaim(_) => &[
+            "Owner",
+            "SolLock",
+            "Receiver",
+            "Claim ticket",
+            "Payer",
+            "System program",
+            "Treasury",
+            "Stats",
+            "Unlock authority",
+        ],
+        ClaimUnlocked(_) => &["Receiver", "Claim ticket"],
+        Claim(_) => &["Receiver", "Owner", "SolLock", "Treasury", "Stats"],
+        ClaimWithPreimage(_) | ClaimEscrow(_) => {
+            &["Counterparty", "Owner", "SolLock", "Treasury", "Stats"]
+        }
+        RaiseDispute(_) => &["Disputing party", "Owner", "SolLock"],
+        Resolve(_) => &[
+            "Arbiter",
+            "Owner",
+            "SolLock",
+            "Counterparty",
+            "Treasury",
+            "Stats",
+        ],
+        ClaimAsBeneficiary(_) => &["Beneficiary", "Owner", "SolLock", "Treasury", "Stats"],
+        InitiateRecovery(_) => &["Owner", "SolLock", "Guardian"],
+        CheckPrice(_) => &["Owner", "SolLock", "Price feed"],
+        RefundContribution(_) => &["Contributor", "Owner", "SolLock", "Contribution", "Stats"],
+        Contribute(_) => &[
+            "Contributor",
+            "Owner",
+            "SolLock",
+            "Contribution",
+            "System program",
+            "Config",
+            "Stats",
+        ],
+        InitializeConfig(_) => &[
+            "Upgrade authority",
+            "Config",
+            "Treasury",
+            "Stats",
+            "ProgramData",
+            "System program",
+        ],
+        UpdateConfig(_) => &["Admin", "Config"],
+        WithdrawTreasury(_) => &["Admin", "Config", "Treasury", "Destination"],
+        AddToAllowlist(_) => &["Admin", "Config", "AllowlistEntry", "System program"],
+        RemoveFromAllowlist(_) => &["Admin", "Config", "AllowlistEntry"],
+        Migrate(_) => &["Payer", "Owner", "SolLock", "System program"],
+        Realloc(_) => &["Owner", "SolLock", "System program"],
+        GetVersion(_) => &[],
+        InitializeReceiptMint(_) => &[
+            "Admin",
+            "Config",
+            "Receipt mint",
+            "Token program",
+            "System program",
+        ],
+        MintReceipt(_) => &[
+            "Owner",
+            "SolLock",
+            "Receipt mint",
+            "Destination",
+            "Token program",
+        ],
+        BurnReceipt(_) => &[
+            "Owner",
+            "SolLock",
+            "Receipt mint",
+            "Source",
+            "Token program",
+        ],
+        MintPosition(_) => &[
+            "Owner",
+            "SolLock",
+            "Position mint",
+            "Owner token account",
+            "Token program",
+            "Associated token program",
+            "System program",
+            "Unlock authority",
+        ],
+        UnlockPosition(_) => &[
+            "Holder",
+            "Owner",
+            "SolLock",
+            "Position mint",
+            "Holder token account",
+            "Token program",
+            "Treasury",
+            "Stats",
+        ],
+        GetVotingPower(_) => &["Owner", "SolLock"],
+        CreateVoterWeightRecord(_) => &["Owner", "Voter weight record", "System program"],
+        UpdateVoterWeightRecord(_) => &["Owner", "SolLock", "Voter weight record"],
+        WithdrawRewards(_) => &[
+            "Owner",
+            "SolLock",
+            "Stake account",
+            "Clock sysvar",
+            "Stake history sysvar",
+            "Stake program",
+        ],
+        Compound(_) => &[
+            "Cranker",
+            "Owner",
+            "SolLock",
+            "Stake account",
+            "Clock sysvar",
+            "Stake history sysvar",
+            "Stake program",
+            "Stats",
+        ],
+        SetValidatorAllowlist(_) => &["Owner", "SolLock"],
+        SplitStake(_) => &[
+            "Owner",
+            "SolLock",
+            "Stake account",
+            "Split stake account",
+            "Stake program",
+            "System program",
+        ],
+        MergeStake(_) => &[
+            "Owner",
+            "SolLock",
+            "Stake account",
+            "Source stake account",
+            "Clock sysvar",
+            "Stake history sysvar",
+            "Stake program",
+        ],
+        ConvertToLst(_) => &[
+            "Owner",
+            "SolLock",
+            "Funding account",
+            "Stake pool",
+            "Stake pool withdraw authority",
+            "Reserve stake",
+            "Manager fee account",
+            "Pool mint",
+            "Pool token account",
+            "Stake pool program",
+            "Token program",
+            "Associated token program",
+            "System program",
+        ],
+        RedeemLst(_) => &[
+            "Owner",
+            "SolLock",
+            "Stake pool",
+            "Stake pool withdraw authority",
+            "Reserve stake",
+            "Manager fee account",
+            "Pool mint",
+            "Pool token account",
+            "Clock sysvar",
+            "Stake history sysvar",
+            "Stake program",
+            "Stake pool program",
+            "Token program",
+        ],
+        Reconcile(_) => &["Owner", "SolLock", "Config", "Stats"],
+        DepositToLending(_) | WithdrawFromLending(_) => &[
+            "Owner",
+            "SolLock",
+            "Reserve",
+            "Reserve liquidity supply",
+            "Reserve collateral mint",
+            "Lending market",
+            "Lending market authority",
+            "Wrapped SOL account",
+            "Collateral token account",
+            "Wrapped SOL mint",
+            "Clock sysvar",
+            "Lending program",
+            "Token program",
+            "Associated token program",
+            "System program",
+        ],
+        #[cfg(feature = "allowlist")]
+        CreateNextAccount(_) => &[
+            "Owner",
+            "IndexCounter",
+            "SolLock",
+            "System program",
+            "AllowlistEntry",
+            "Payer",
+        ],
+        #[cfg(not(feature = "allowlist"))]
+        CreateNextAccount(_) => &[
+            "Owner",
+            "IndexCounter",
+            "SolLock",
+            "System program",
+            "Payer",
+        ],
+    }
+}
+
+/// Check that the SolLock account of an instruction is derived from its owner and index
+fn check_sol_lock_account(
+    program_id: &Pubkey,
+    message: &Message,
+    instruction: &CompiledInstruction,
+    sol_lock_instruction: &SolLockInstruction,
+) -> Option<String> {
+    use SolLockInstruction::*;
+
+    let (owner_position, sol_lock_position, acc_index) = match sol_lock_instruction {
+        CreateAccount(ctx) => (0, 1, ctx.acc_index),
+        CreateLabeledAccount(ctx) => (0, 1, get_label_acc_index(&ctx.label)),
+        AddSol(ctx) => (0, 1, ctx.acc_index),
+        RemoveSol(ctx) => (0, 1, ctx.acc_index),
+        SetDeadline(ctx) => (0, 1, ctx.acc_index),
+        Lock(ctx) => (0, 1, ctx.acc_index),
+        Unlock(ctx) => (0, 1, ctx.acc_index),
+        Stake(ctx) => (0, 1, ctx.acc_index),
+        Unstake(ctx) => (0, 1, ctx.acc_index),
+        CreateAndLock(ctx) => (0, 1, ctx.acc_index),
+        AddSolAndLock(ctx) => (0, 1, ctx.acc_index),
+        SetName(ctx) => (0, 1, ctx.acc_index),
+        UnlockAndClose(ctx) => (0, 1, ctx.acc_index),
+        ProposeOwner(ctx) => (0, 1, ctx.acc_index),
+        AcceptOwner(ctx) => (0, 1, ctx.acc_index),
+        SetUnlockAuthority(ctx) => (0, 1, ctx.acc_index),
+        SetReceiver(ctx) => (0, 1, ctx.acc_index),
+        SetRecoveryPointer(ctx) => (0, 1, ctx.acc_index),
+        SetCrankTip(ctx) => (0, 1, ctx.acc_index),
+        CrankUnlock(ctx) => (1, 2, ctx.acc_index),
+        Relock(ctx) => (0, 1, ctx.acc_index),
+        SetAutoExtend(ctx) => (0, 1, ctx.acc_index),
+        SetVesting(ctx) => (0, 1, ctx.acc_index),
+        SetDepositCap(ctx) => (0, 1, ctx.acc_index),
+        UnlockToClaim(ctx) => (0, 1, ctx.acc_index),
+        SetStream(ctx) => (0, 1, ctx.acc_index),
+        Claim(ctx) => (1, 2, ctx.acc_index),
+        SetHashLock(ctx) => (0, 1, ctx.acc_index),
+        ClaimWithPreimage(ctx) => (1, 2, ctx.acc_index),
+        SetEscrow(ctx) => (0, 1, ctx.acc_index),
+        ClaimEscrow(ctx) => (1, 2, ctx.acc_index),
+        RefundEscrow(ctx) => (0, 1, ctx.acc_index),
+        RaiseDispute(ctx) => (1, 2, ctx.acc_index),
+        Resolve(ctx) => (1, 2, ctx.acc_index),
+        SetOpenDeposits(ctx) => (0, 1, ctx.acc_index),
+        Contribute(ctx) => (1, 2, ctx.acc_index),
+        SetRaiseTarget(ctx) => (0, 1, ctx.acc_index),
+        RefundContribution(ctx) => (1, 2, ctx.acc_index),
+        SetBeneficiary(ctx) => (0, 1, ctx.acc_index),
+        Heartbeat(ctx) => (0, 1, ctx.acc_index),
+        ClaimAsBeneficiary(ctx) => (1, 2, ctx.acc_index),
+        SetBeneficiaryShares(ctx) => (0, 1, ctx.acc_index),
+        SetMultisig(ctx) => (0, 1, ctx.acc_index),
+        SetGuardians(ctx) => (0, 1, ctx.acc_index),
+        InitiateRecovery(ctx) => (0, 1, ctx.acc_index),
+        VetoRecovery(ctx) => (0, 1, ctx.acc_index),
+        SetPriceCondition(ctx) => (0, 1, ctx.acc_index),
+        CheckPrice(ctx) => (0, 1, ctx.acc_index),
+        SetEpochDeadline(ctx) => (0, 1, ctx.acc_index),
+        SetDeadlineRelative(ctx) => (0, 1, ctx.acc_index),
+        Migrate(ctx) => (1, 2, ctx.acc_index),
+        Realloc(ctx) => (0, 1, ctx.acc_index),
+        MintReceipt(ctx) => (0, 1, ctx.acc_index),
+        BurnReceipt(ctx) => (0, 1, ctx.acc_index),
+        MintPosition(ctx) => (0, 1, ctx.acc_index),
+        UnlockPosition(ctx) => (1, 2, ctx.acc_index),
+        GetVotingPower(ctx) => (0, 1, ctx.acc_index),
+        UpdateVoterWeightRecord(ctx) => (0, 1, ctx.acc_index),
+        WithdrawRewards(ctx) => (0, 1, ctx.acc_index),
+        Compound(ctx) => (1, 2, ctx.acc_index),
+        SetValidatorAllowlist(ctx) => (0, 1, ctx.acc_index),
+        SplitStake(ctx) => (0, 1, ctx.acc_index),
+        MergeStake(ctx) => (0, 1, ctx.acc_index),
+        ConvertToLst(ctx) => (0, 1, ctx.acc_index),
+        RedeemLst(ctx) => (0, 1, ctx.acc_index),
+        DepositToLending(ctx) => (0, 1, ctx.acc_index),
+        WithdrawFromLending(ctx) => (0, 1, ctx.acc_index),
+        Reconcile(ctx) => (0, 1, ctx.acc_index),
+        // The voter weight record is not a SolLock account
+        CreateVoterWeightRecord(_) => return None,
+        // The payer of TopUpRent need not be the owner, and SetDeadlineMany has several
+        // SolLock accounts, so their SolLock accounts can't be checked from the instruction alone
+        TopUpRent(_) | SetDeadlineMany(_) | ClaimUnlocked(_) => return None,
+        // The index of CreateNextAccount is only known on chain
+        CreateNextAccount(_) => return None,
+        // The admin instructions have no SolLock account
+        InitializeConfig(_)
+        | UpdateConfig(_)
+        | WithdrawTreasury(_)
+        | AddToAllowlist(_)
+        | RemoveFromAllowlist(_)
+        | InitializeReceiptMint(_) => return None,
+        // GetVersion has no accounts at all
+        GetVersion(_) => return None,
+    };
+
+    let key_at = |position: usize| {
+        instruction
+            .accounts
+            .get(position)
+            .map(|account_index| message.account_keys[*account_index as usize])
+    };
+
+    let (owner, sol_lock_account) = match (key_at(owner_position), key_at(sol_lock_position)) {
+        (Some(owner), Some(sol_lock_account)) => (owner, sol_lock_account),
+        _ => return Some("missing accounts".to_string()),
+    };
+
+    let expected = get_sol_lock_account(program_id, &owner, acc_index).0;
+    if expected != sol_lock_account {
+        Some(format!(
+            "SolLock account {} is not account {} of owner {} (expected {})",
+            sol_lock_account, acc_index, owner, expected
+        ))
+    } else {
+        None
+    }
+}
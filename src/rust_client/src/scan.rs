@@ -0,0 +1,117 @@
+use sol_lock::state::{Account, SOL_LOCK_ACCOUNT_SIZE};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
+};
+use solana_program::{
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+};
+use std::error::Error;
+
+/// Number of chunks a full scan is split into, one per value of the first owner byte
+pub const SCAN_CHUNKS: usize = 256;
+
+/// Progress of a chunked scan, reported after every chunk
+#[derive(Debug, Clone, Copy)]
+pub struct ScanProgress {
+    /// Number of chunks scanned so far, including skipped ones when resuming
+    pub chunks_done: usize,
+    /// Total number of chunks in the scan
+    pub chunks_total: usize,
+    /// Number of SolLock accounts found so far in this run
+    pub accounts_found: usize,
+    /// Cursor to pass as `start_cursor` to resume the scan after this chunk,
+    /// or None if the scan is complete
+    pub next_cursor: Option<u8>,
+}
+
+/// Fetch all initialized SolLock accounts of all owners, one chunk at a time
+///
+/// Instead of a single `getProgramAccounts` call over the whole program, which times out
+/// on large deployments, accounts are fetched in [`SCAN_CHUNKS`] chunks, each matching
+/// the accounts whose owner key starts with a given byte.
+///
+/// `start_cursor` allows resuming an interrupted scan from the `next_cursor` of the last
+/// reported progress. `on_progress` is called after every chunk.
+pub fn scan_lock_accounts(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    start_cursor: u8,
+    mut on_progress: impl FnMut(&ScanProgress),
+) -> Result<Vec<(Pubkey, Account)>, Box<dyn Error>> {
+    let mut found = vec![];
+
+    for chunk in start_cursor..=u8::MAX {
+        found.extend(get_lock_accounts_with_owner_prefix(
+            rpc_client,
+            program_id,
+            &[chunk],
+        )?);
+
+        on_progress(&ScanProgress {
+            chunks_done: chunk as usize + 1,
+            chunks_total: SCAN_CHUNKS,
+            accounts_found: found.len(),
+            next_cursor: chunk.checked_add(1),
+        });
+    }
+
+    Ok(found)
+}
+
+/// Fetch all initialized SolLock accounts of a single owner
+pub fn get_owner_lock_accounts(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    owner: &Pubkey,
+) -> Result<Vec<(Pubkey, Account)>, Box<dyn Error>> {
+    get_lock_accounts_with_owner_prefix(rpc_client, program_id, owner.as_ref())
+}
+
+pub(crate) fn get_lock_accounts_with_owner_prefix(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    owner_prefix: &[u8],
+) -> Result<Vec<(Pubkey, Account)>, Box<dyn Error>> {
+    let accounts = get_raw_lock_accounts_with_owner_prefix(rpc_client, program_id, owner_prefix)?;
+
+    let mut found = vec![];
+    for (key, account) in accounts {
+        let sol_lock_account = Account::unpack_unchecked(&account.data)?;
+        if sol_lock_account.is_initialized() {
+            found.push((key, sol_lock_account));
+        }
+    }
+
+    Ok(found)
+}
+
+/// Fetch the raw accounts of the program that have the size of a SolLock account
+/// and whose owner key starts with `owner_prefix`, initialized or not
+pub(crate) fn get_raw_lock_accounts_with_owner_prefix(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    owner_prefix: &[u8],
+) -> Result<Vec<(Pubkey, solana_sdk::account::Account)>, Box<dyn Error>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::DataSize(SOL_LOCK_ACCOUNT_SIZE as u64),
+            RpcFilterType::Memcmp(Memcmp {
+                offset: 0,
+                bytes: MemcmpEncodedBytes::Bytes(owner_prefix.to_vec()),
+                encoding: None,
+            }),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(rpc_client.commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        with_context: None,
+    };
+
+    Ok(rpc_client.get_program_accounts_with_config(program_id, config)?)
+}
@@ -0,0 +1,78 @@
+use solana_program::{hash::Hash, pubkey::Pubkey};
+use solana_sdk::{
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+use std::error::Error;
+
+/// Encode a (possibly partially signed) transaction so it can be moved to another machine
+pub fn encode_transaction(transaction: &Transaction) -> Result<String, Box<dyn Error>> {
+    Ok(base64::encode(bincode::serialize(transaction)?))
+}
+
+pub fn decode_transaction(encoded: &str) -> Result<Transaction, Box<dyn Error>> {
+    Ok(bincode::deserialize(&base64::decode(encoded)?)?)
+}
+
+/// The signers that still have to sign the transaction
+pub fn missing_signers(transaction: &Transaction) -> Vec<Pubkey> {
+    let num_required_signatures = transaction.message.header.num_required_signatures as usize;
+    transaction.message.account_keys[..num_required_signatures]
+        .iter()
+        .zip(transaction.signatures.iter())
+        .filter(|(_, signature)| **signature == Signature::default())
+        .map(|(key, _)| *key)
+        .collect()
+}
+
+/// Add the signatures of `signers` to the transaction, keeping existing signatures
+///
+/// Every machine must sign over the same blockhash, so use a durable nonce
+/// if signing may take longer than a blockhash stays valid.
+pub fn partial_sign(
+    transaction: &mut Transaction,
+    signers: &[&Keypair],
+    blockhash: Hash,
+) -> Result<(), Box<dyn Error>> {
+    let missing = missing_signers(transaction);
+    for signer in signers {
+        if !missing.contains(&signer.pubkey()) {
+            return Err(format!(
+                "{} is not a missing signer of the transaction",
+                signer.pubkey()
+            )
+            .into());
+        }
+    }
+
+    transaction.try_partial_sign(signers, blockhash)?;
+    Ok(())
+}
+
+/// Combine the signatures of copies of the same transaction signed by different machines
+pub fn merge_signatures(
+    transaction: &mut Transaction,
+    others: &[Transaction],
+) -> Result<(), Box<dyn Error>> {
+    for other in others {
+        if other.message != transaction.message {
+            return Err("Cannot merge signatures of different transactions".into());
+        }
+
+        for (signature, other_signature) in transaction
+            .signatures
+            .iter_mut()
+            .zip(other.signatures.iter())
+        {
+            if *signature == Signature::default() {
+                *signature = *other_signature;
+            }
+        }
+    }
+
+    if missing_signers(transaction).is_empty() {
+        transaction.verify()?;
+    }
+    Ok(())
+}
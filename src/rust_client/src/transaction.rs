@@ -1,6 +1,13 @@
-use sol_lock::instruction::*;
+use crate::receiver_check::check_unlock_receiver;
+use sol_lock::{
+    instruction::*,
+    processor::{get_config, get_stats, get_treasury},
+    state::{NameRecord, NAME_RECORD_SEED},
+};
 use solana_client::rpc_client::RpcClient;
-use solana_program::{clock::UnixTimestamp, instruction::Instruction, pubkey::Pubkey};
+use solana_program::{
+    clock::UnixTimestamp, hash::hash, instruction::Instruction, program_pack::Pack, pubkey::Pubkey,
+};
 use solana_sdk::{
     instruction::AccountMeta,
     signature::{Keypair, Signature},
@@ -35,19 +42,33 @@ pub fn unlock(
     sender_key: &Pubkey,
     sol_lock_account: &Pubkey,
     acc_index: u64,
+    receiver: Option<&Pubkey>,
     sender: &Keypair,
 ) -> core::result::Result<(), Box<dyn Error>> {
+    let mut accounts = vec![
+        AccountMeta::new(sender_key.clone(), true),
+        AccountMeta::new(sol_lock_account.clone(), false),
+        AccountMeta::new(get_treasury(program_id).0, false),
+        AccountMeta::new(get_stats(program_id).0, false),
+    ];
+
+    if let Some(receiver) = receiver {
+        for warning in check_unlock_receiver(rpc_client, receiver)? {
+            println!("Warning: {}", warning);
+        }
+        accounts.push(AccountMeta::new(*receiver, false));
+    }
+
     let mut transaction = Transaction::new_with_payer(
         &[Instruction::new_with_borsh(
             *program_id,
             &SolLockInstruction::Unlock(Unlock {
                 acc_index,
-                has_receiver: false,
+                has_receiver: receiver.is_some(),
+                nonce: None,
+                memo: None,
             }),
-            vec![
-                AccountMeta::new(sender_key.clone(), true),
-                AccountMeta::new(sol_lock_account.clone(), false),
-            ],
+            accounts,
         )],
         Some(&sender_key),
     );
@@ -75,62 +96,87 @@ pub fn create_and_lock(
     sender: &Keypair,
 ) -> core::result::Result<(), Box<dyn Error>> {
     let mut transaction = Transaction::new_with_payer(
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &SolLockInstruction::CreateAndLock(CreateAndLock {
+                acc_index,
+                lamports,
+                deadline,
+                has_payer: false,
+                memo: String::new(),
+                commitment: None,
+            }),
+            vec![
+                AccountMeta::new(sender_key.clone(), true),
+                AccountMeta::new(sol_lock_account.clone(), false),
+                AccountMeta::new(system_program.clone(), false),
+                AccountMeta::new_readonly(get_config(program_id).0, false),
+                AccountMeta::new(get_stats(program_id).0, false),
+            ],
+        )],
+        Some(&sender_key),
+    );
+
+    let blockhash = rpc_client.get_latest_blockhash()?;
+    transaction.sign(&[sender], blockhash);
+
+    if let Err(err) = rpc_client.send_and_confirm_transaction(&transaction) {
+        println!("{:#?}", err);
+        panic!();
+    };
+
+    Ok(())
+}
+
+pub fn get_name_record_address(program_id: &Pubkey, owner: &Pubkey, label: &str) -> Pubkey {
+    Pubkey::find_program_address(
         &[
-            Instruction::new_with_borsh(
-                *program_id,
-                &SolLockInstruction::CreateAccount(CreateAccount { acc_index }),
-                vec![
-                    AccountMeta::new(sender_key.clone(), true),
-                    AccountMeta::new(sol_lock_account.clone(), false),
-                    AccountMeta::new(system_program.clone(), false),
-                ],
-            ),
-            Instruction::new_with_borsh(
-                *program_id,
-                &SolLockInstruction::SetDeadline(SetDeadline {
-                    acc_index,
-                    deadline,
-                }),
-                vec![
-                    AccountMeta::new(sender_key.clone(), true),
-                    AccountMeta::new(sol_lock_account.clone(), false),
-                ],
-            ),
-            Instruction::new_with_borsh(
-                *program_id,
-                &SolLockInstruction::AddSol(AddSol {
-                    acc_index,
-                    lamports,
-                    has_payer: false,
-                }),
-                vec![
-                    AccountMeta::new(sender_key.clone(), true),
-                    AccountMeta::new(sol_lock_account.clone(), false),
-                    AccountMeta::new(system_program.clone(), false),
-                ],
-            ),
-            Instruction::new_with_borsh(
-                *program_id,
-                &SolLockInstruction::RemoveSol(RemoveSol {
-                    acc_index,
-                    lamports: 6000000000,
-                    has_receiver: false,
-                }),
-                vec![
-                    AccountMeta::new(sender_key.clone(), true),
-                    AccountMeta::new(sol_lock_account.clone(), false),
-                ],
-            ),
-            Instruction::new_with_borsh(
-                *program_id,
-                &SolLockInstruction::Lock(Lock { acc_index }),
-                vec![
-                    AccountMeta::new(sender_key.clone(), true),
-                    AccountMeta::new(sol_lock_account.clone(), false),
-                ],
-            ),
+            NAME_RECORD_SEED,
+            owner.as_ref(),
+            hash(label.as_bytes()).as_ref(),
         ],
-        Some(&sender_key),
+        program_id,
+    )
+    .0
+}
+
+pub fn set_name(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    sender_key: &Pubkey,
+    sol_lock_account: &Pubkey,
+    system_program: &Pubkey,
+    acc_index: u64,
+    label: &str,
+    sender: &Keypair,
+) -> core::result::Result<(), Box<dyn Error>> {
+    let name_record = get_name_record_address(program_id, sender_key, label);
+
+    let mut accounts = vec![
+        AccountMeta::new(*sender_key, true),
+        AccountMeta::new(*sol_lock_account, false),
+        AccountMeta::new(name_record, false),
+        AccountMeta::new_readonly(*system_program, false),
+    ];
+
+    // Repointing an existing name releases it from the SolLock account it pointed at
+    if let Ok(previous_sol_lock_account) = resolve_name(rpc_client, program_id, sender_key, label) {
+        if previous_sol_lock_account != *sol_lock_account {
+            accounts.push(AccountMeta::new(previous_sol_lock_account, false));
+        }
+    }
+
+    let mut transaction = Transaction::new_with_payer(
+        &[Instruction::new_with_borsh(
+            *program_id,
+            &SolLockInstruction::SetName(SetName {
+                acc_index,
+                label_hash: hash(label.as_bytes()).to_bytes(),
+                has_payer: false,
+            }),
+            accounts,
+        )],
+        Some(sender_key),
     );
 
     let blockhash = rpc_client.get_latest_blockhash()?;
@@ -143,3 +189,14 @@ pub fn create_and_lock(
 
     Ok(())
 }
+
+pub fn resolve_name(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    label: &str,
+) -> Result<Pubkey, Box<dyn Error>> {
+    let name_record = get_name_record_address(program_id, owner, label);
+    let data = rpc_client.get_account_data(&name_record)?;
+    Ok(NameRecord::unpack(&data)?.sol_lock_account)
+}
@@ -0,0 +1,96 @@
+use crate::signing::encode_transaction;
+use sol_lock::{instruction::unlock, processor::get_sol_lock_account, state::Account};
+use solana_client::{nonce_utils, rpc_client::RpcClient};
+use solana_program::{message::Message, program_pack::Pack, pubkey::Pubkey};
+use solana_sdk::transaction::Transaction;
+use std::error::Error;
+
+/// Build a cold-storage unlock kit for a lock, as a single JSON document
+///
+/// The kit holds everything needed to unlock the lock from an offline machine after the
+/// deadline: an unsigned Unlock transaction that uses a durable nonce instead of a recent
+/// blockhash, so it stays valid until signed, the accounts it touches, and step-by-step
+/// instructions for signing and broadcasting it.
+pub fn build_unlock_kit(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    acc_index: u64,
+    receiver: Option<&Pubkey>,
+    nonce_account: &Pubkey,
+    nonce_authority: &Pubkey,
+) -> Result<serde_json::Value, Box<dyn Error>> {
+    let sol_lock_account = get_sol_lock_account(program_id, owner, acc_index).0;
+    let account = Account::unpack(&rpc_client.get_account_data(&sol_lock_account)?)?;
+
+    if account.unlock_authority.is_some() {
+        return Err("Lock has an unlock authority, which can't be signed for in advance".into());
+    }
+    if account.multisig_threshold > 0 {
+        return Err("Lock needs multisig co-signers, which the kit does not support".into());
+    }
+    if account.receiver.is_some() && account.receiver.as_ref() != receiver {
+        return Err("Lock only unlocks to its stored receiver".into());
+    }
+
+    let nonce_data =
+        nonce_utils::data_from_account(&nonce_utils::get_account(rpc_client, nonce_account)?)?;
+    if nonce_data.authority != *nonce_authority {
+        return Err(format!(
+            "Nonce account {} is not authorized by {}",
+            nonce_account, nonce_authority
+        )
+        .into());
+    }
+
+    let mut message = Message::new_with_nonce(
+        vec![unlock(program_id, owner, acc_index, receiver, None, None)],
+        Some(owner),
+        nonce_account,
+        nonce_authority,
+    );
+    message.recent_blockhash = nonce_data.blockhash;
+    let transaction = Transaction::new_unsigned(message);
+
+    let accounts = transaction
+        .message
+        .account_keys
+        .iter()
+        .enumerate()
+        .map(|(index, key)| {
+            serde_json::json!({
+                "pubkey": key.to_string(),
+                "signer": transaction.message.is_signer(index),
+                "writable": transaction.message.is_writable(index),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let signers = transaction.message.account_keys
+        [..transaction.message.header.num_required_signatures as usize]
+        .iter()
+        .map(|key| key.to_string())
+        .collect::<Vec<_>>();
+
+    Ok(serde_json::json!({
+        "program_id": program_id.to_string(),
+        "sol_lock_account": sol_lock_account.to_string(),
+        "owner": owner.to_string(),
+        "acc_index": acc_index,
+        "receiver": receiver.unwrap_or(owner).to_string(),
+        "deadline": account.deadline,
+        "nonce_account": nonce_account.to_string(),
+        "nonce_authority": nonce_authority.to_string(),
+        "nonce_blockhash": nonce_data.blockhash.to_string(),
+        "required_signers": signers,
+        "accounts": accounts,
+        "transaction": encode_transaction(&transaction)?,
+        "instructions": [
+            "Keep this file together with the keys of the required signers.",
+            "Do not advance or close the nonce account, or the transaction will no longer be valid.",
+            "After the deadline, decode the transaction on the offline machine and sign it with every required signer, over the nonce blockhash.",
+            "Move the signed transaction to an online machine and broadcast it.",
+            "Do not broadcast before the deadline: a failed transaction still advances the nonce, and a new kit must be built.",
+        ],
+    }))
+}